@@ -4,7 +4,7 @@ use std::collections::HashSet;
 use std::io::Write;
 
 use anoma::types::intent::{Exchange, FungibleTokenIntent};
-use anoma::types::key::ed25519::Signed;
+use anoma::types::key::common::Signed;
 use anoma_apps::cli::{args, cmds};
 use anoma_apps::client::{rpc, tx};
 use anoma_apps::proto::services::rpc_service_client::RpcServiceClient;
@@ -22,15 +22,145 @@ pub async fn main() -> Result<()> {
         cmds::AnomaClient::TxTransfer(cmds::TxTransfer(args)) => {
             tx::submit_transfer(args).await;
         }
+        cmds::AnomaClient::TxMint(cmds::TxMint(args)) => {
+            tx::submit_mint(args).await;
+        }
+        cmds::AnomaClient::TxBurn(cmds::TxBurn(args)) => {
+            tx::submit_burn(args).await;
+        }
+        cmds::AnomaClient::TxApprove(cmds::TxApprove(args)) => {
+            tx::submit_approve(args).await;
+        }
+        cmds::AnomaClient::TxRevoke(cmds::TxRevoke(args)) => {
+            tx::submit_revoke(args).await;
+        }
+        cmds::AnomaClient::TxTransferFrom(cmds::TxTransferFrom(args)) => {
+            tx::submit_transfer_from(args).await;
+        }
+        #[cfg(feature = "shielded-pool")]
+        cmds::AnomaClient::TxShield(cmds::TxShield(args)) => {
+            tx::submit_shield(args).await;
+        }
+        #[cfg(feature = "shielded-pool")]
+        cmds::AnomaClient::TxUnshield(cmds::TxUnshield(args)) => {
+            tx::submit_unshield(args).await;
+        }
+        #[cfg(feature = "shielded-pool")]
+        cmds::AnomaClient::TxShieldedTransfer(cmds::TxShieldedTransfer(
+            args,
+        )) => {
+            tx::submit_shielded_transfer(args).await;
+        }
         cmds::AnomaClient::TxUpdateVp(cmds::TxUpdateVp(args)) => {
             tx::submit_update_vp(args).await;
         }
+        cmds::AnomaClient::TxUpdateKey(cmds::TxUpdateKey(args)) => {
+            tx::submit_update_key(args).await;
+        }
+        cmds::AnomaClient::TxUpdateRecovery(cmds::TxUpdateRecovery(args)) => {
+            tx::submit_update_recovery(args).await;
+        }
+        cmds::AnomaClient::TxProposeRecovery(cmds::TxProposeRecovery(args)) => {
+            tx::submit_propose_recovery(args).await;
+        }
+        cmds::AnomaClient::TxVetoRecovery(cmds::TxVetoRecovery(args)) => {
+            tx::submit_veto_recovery(args).await;
+        }
+        cmds::AnomaClient::TxRecover(cmds::TxRecover(args)) => {
+            tx::submit_recover(args).await;
+        }
         cmds::AnomaClient::TxInitAccount(cmds::TxInitAccount(args)) => {
             tx::submit_init_account(args).await;
         }
+        cmds::AnomaClient::TxInitMultisigAccount(
+            cmds::TxInitMultisigAccount(args),
+        ) => {
+            tx::submit_init_multisig_account(args).await;
+        }
+        cmds::AnomaClient::TxInitVestingAccount(
+            cmds::TxInitVestingAccount(args),
+        ) => {
+            tx::submit_init_vesting_account(args).await;
+        }
+        cmds::AnomaClient::TxInitToken(cmds::TxInitToken(args)) => {
+            tx::submit_init_token(args).await;
+        }
+        cmds::AnomaClient::TxInitProposal(cmds::TxInitProposal(args)) => {
+            tx::submit_init_proposal(args).await;
+        }
+        cmds::AnomaClient::TxVoteProposal(cmds::TxVoteProposal(args)) => {
+            tx::submit_vote_proposal(args).await;
+        }
+        cmds::AnomaClient::TxClaimRewards(cmds::TxClaimRewards(args)) => {
+            tx::submit_claim_rewards(args).await;
+        }
+        cmds::AnomaClient::TxBecomeValidator(cmds::TxBecomeValidator(args)) => {
+            tx::submit_become_validator(args).await;
+        }
+        cmds::AnomaClient::TxBond(cmds::TxBond(args)) => {
+            tx::submit_bond(args).await;
+        }
+        cmds::AnomaClient::TxUnbond(cmds::TxUnbond(args)) => {
+            tx::submit_unbond(args).await;
+        }
+        cmds::AnomaClient::TxWithdraw(cmds::TxWithdraw(args)) => {
+            tx::submit_withdraw(args).await;
+        }
+        cmds::AnomaClient::TxChangeCommission(cmds::TxChangeCommission(
+            args,
+        )) => {
+            tx::submit_change_commission(args).await;
+        }
+        cmds::AnomaClient::TxUnjail(cmds::TxUnjail(args)) => {
+            tx::submit_unjail(args).await;
+        }
+        cmds::AnomaClient::TxBuild(cmds::TxBuild(args)) => {
+            tx::build_tx(args);
+        }
+        cmds::AnomaClient::TxBuildTreasurySpend(
+            cmds::TxBuildTreasurySpend(args),
+        ) => {
+            tx::build_treasury_spend(args).await;
+        }
+        cmds::AnomaClient::TxSign(cmds::TxSign(args)) => {
+            tx::sign_tx(args);
+        }
+        cmds::AnomaClient::TxCombineSignatures(cmds::TxCombineSignatures(
+            args,
+        )) => {
+            tx::combine_signatures(args);
+        }
+        cmds::AnomaClient::TxBroadcast(cmds::TxBroadcast(args)) => {
+            tx::submit_tx_from_file(args).await;
+        }
         cmds::AnomaClient::QueryBalance(cmds::QueryBalance(args)) => {
             rpc::query_balance(args).await;
         }
+        cmds::AnomaClient::QueryProposal(cmds::QueryProposal(args)) => {
+            rpc::query_proposal(args).await;
+        }
+        cmds::AnomaClient::QueryParameters(cmds::QueryParameters(args)) => {
+            rpc::query_parameters(args).await;
+        }
+        cmds::AnomaClient::QueryTreasury(cmds::QueryTreasury(args)) => {
+            rpc::query_treasury(args).await;
+        }
+        cmds::AnomaClient::QueryRewards(cmds::QueryRewards(args)) => {
+            rpc::query_rewards(args).await;
+        }
+        cmds::AnomaClient::QueryValidator(cmds::QueryValidator(args)) => {
+            rpc::query_validator(args).await;
+        }
+        cmds::AnomaClient::QueryEpoch(cmds::QueryEpoch(args)) => {
+            rpc::query_epoch(args).await;
+        }
+        cmds::AnomaClient::QueryNextEpoch(cmds::QueryNextEpoch(args)) => {
+            rpc::query_next_epoch(args).await;
+        }
+        #[cfg(feature = "shielded-pool")]
+        cmds::AnomaClient::ShieldedGenKey(cmds::ShieldedGenKey) => {
+            tx::shielded_gen_key();
+        }
         cmds::AnomaClient::Intent(cmds::Intent(args)) => {
             gossip_intent(args).await;
         }
@@ -3,6 +3,7 @@
 #[cfg(feature = "dev")]
 use std::path::Path;
 
+use anoma::types::pos;
 use anoma_apps::config::Config;
 use anoma_apps::node::{gossip, ledger};
 use anoma_apps::{cli, config};
@@ -24,6 +25,14 @@ pub fn main() -> Result<()> {
                 ledger::reset(ledger_cfg)
                     .wrap_err("Failed to reset Anoma node")?;
             }
+            cli::cmds::Ledger::GenValidatorKey(_) => {
+                let config = get_cfg(base_dir);
+                let ledger_cfg = config.ledger.unwrap_or_default();
+                let pk = ledger::gen_validator_key(ledger_cfg)
+                    .wrap_err("Failed to generate a validator key")?;
+                println!("Consensus key: {}", pk);
+                println!("Tendermint address: {}", pos::consensus_address(&pk));
+            }
         },
         cli::cmds::AnomaNode::Gossip(sub) => match *sub {
             cli::cmds::Gossip::Run(cli::cmds::GossipRun(args)) => {
@@ -44,6 +53,26 @@ pub fn main() -> Result<()> {
                 tracing::debug!("Generated config {:?}", gen_config);
             }
         },
+        cli::cmds::AnomaNode::Utils(sub) => match sub {
+            cli::cmds::Utils::InitNetwork(cli::cmds::InitNetwork(args)) => {
+                let nodes = ledger::init_network(
+                    &args.output_dir,
+                    args.num_validators,
+                    args.stake,
+                    args.balance,
+                )
+                .wrap_err("Failed to initialize the network")?;
+                for node in nodes {
+                    println!(
+                        "Validator {} in {}, with the ledger address \
+                         127.0.0.1:{}",
+                        node.account.encode(),
+                        node.base_dir.display(),
+                        node.rpc_port
+                    );
+                }
+            }
+        },
     }
     Ok(())
 }
@@ -38,10 +38,13 @@ fn handle_command(cmd: cli::cmds::Anoma, raw_sub_cmd: String) -> Result<()> {
     match cmd {
         cli::cmds::Anoma::Node(_)
         | cli::cmds::Anoma::Ledger(_)
-        | cli::cmds::Anoma::Gossip(_) => handle_subcommand("anoman", sub_args),
+        | cli::cmds::Anoma::Gossip(_)
+        | cli::cmds::Anoma::Utils(_) => handle_subcommand("anoman", sub_args),
         cli::cmds::Anoma::Client(_)
         | cli::cmds::Anoma::TxCustom(_)
         | cli::cmds::Anoma::TxTransfer(_)
+        | cli::cmds::Anoma::TxMint(_)
+        | cli::cmds::Anoma::TxBurn(_)
         | cli::cmds::Anoma::TxUpdateVp(_)
         | cli::cmds::Anoma::Intent(_) => handle_subcommand("anomac", sub_args),
     }
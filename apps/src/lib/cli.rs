@@ -39,8 +39,11 @@ pub mod cmds {
         Gossip(Gossip),
         TxCustom(TxCustom),
         TxTransfer(TxTransfer),
+        TxMint(TxMint),
+        TxBurn(TxBurn),
         TxUpdateVp(TxUpdateVp),
         Intent(Intent),
+        Utils(Utils),
     }
 
     impl Cmd for Anoma {
@@ -51,8 +54,11 @@ pub mod cmds {
                 .subcommand(Gossip::def())
                 .subcommand(TxCustom::def())
                 .subcommand(TxTransfer::def())
+                .subcommand(TxMint::def())
+                .subcommand(TxBurn::def())
                 .subcommand(TxUpdateVp::def())
                 .subcommand(Intent::def())
+                .subcommand(Utils::def())
         }
 
         fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)> {
@@ -62,15 +68,21 @@ pub mod cmds {
             let gossip = SubCmd::parse(matches).map_fst(Self::Gossip);
             let tx_custom = SubCmd::parse(matches).map_fst(Self::TxCustom);
             let tx_transfer = SubCmd::parse(matches).map_fst(Self::TxTransfer);
+            let tx_mint = SubCmd::parse(matches).map_fst(Self::TxMint);
+            let tx_burn = SubCmd::parse(matches).map_fst(Self::TxBurn);
             let tx_update_vp = SubCmd::parse(matches).map_fst(Self::TxUpdateVp);
             let intent = SubCmd::parse(matches).map_fst(Self::Intent);
+            let utils = SubCmd::parse(matches).map_fst(Self::Utils);
             node.or(client)
                 .or(ledger)
                 .or(gossip)
                 .or(tx_custom)
                 .or(tx_transfer)
+                .or(tx_mint)
+                .or(tx_burn)
                 .or(tx_update_vp)
                 .or(intent)
+                .or(utils)
         }
     }
 
@@ -82,6 +94,7 @@ pub mod cmds {
         // Boxed, because it's larger than other variants
         Gossip(Box<Gossip>),
         Config(Config),
+        Utils(Utils),
     }
 
     impl Cmd for AnomaNode {
@@ -89,6 +102,7 @@ pub mod cmds {
             app.subcommand(Ledger::def())
                 .subcommand(Gossip::def())
                 .subcommand(Config::def())
+                .subcommand(Utils::def())
         }
 
         fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)> {
@@ -96,7 +110,8 @@ pub mod cmds {
             let gossip = SubCmd::parse(matches)
                 .map_fst(|gossip| Self::Gossip(Box::new(gossip)));
             let config = SubCmd::parse(matches).map_fst(Self::Config);
-            ledger.or(gossip).or(config)
+            let utils = SubCmd::parse(matches).map_fst(Self::Utils);
+            ledger.or(gossip).or(config).or(utils)
         }
     }
     impl SubCmd for AnomaNode {
@@ -126,20 +141,98 @@ pub mod cmds {
     pub enum AnomaClient {
         TxCustom(TxCustom),
         TxTransfer(TxTransfer),
+        TxMint(TxMint),
+        TxBurn(TxBurn),
+        TxApprove(TxApprove),
+        TxRevoke(TxRevoke),
+        TxTransferFrom(TxTransferFrom),
+        #[cfg(feature = "shielded-pool")]
+        TxShield(TxShield),
+        #[cfg(feature = "shielded-pool")]
+        TxUnshield(TxUnshield),
+        #[cfg(feature = "shielded-pool")]
+        TxShieldedTransfer(TxShieldedTransfer),
         TxUpdateVp(TxUpdateVp),
+        TxUpdateKey(TxUpdateKey),
+        TxUpdateRecovery(TxUpdateRecovery),
+        TxProposeRecovery(TxProposeRecovery),
+        TxVetoRecovery(TxVetoRecovery),
+        TxRecover(TxRecover),
         TxInitAccount(TxInitAccount),
+        TxInitMultisigAccount(TxInitMultisigAccount),
+        TxInitVestingAccount(TxInitVestingAccount),
+        TxInitToken(TxInitToken),
+        TxInitProposal(TxInitProposal),
+        TxVoteProposal(TxVoteProposal),
+        TxClaimRewards(TxClaimRewards),
+        TxBecomeValidator(TxBecomeValidator),
+        TxBond(TxBond),
+        TxUnbond(TxUnbond),
+        TxWithdraw(TxWithdraw),
+        TxChangeCommission(TxChangeCommission),
+        TxUnjail(TxUnjail),
+        TxBuild(TxBuild),
+        TxBuildTreasurySpend(TxBuildTreasurySpend),
+        TxSign(TxSign),
+        TxCombineSignatures(TxCombineSignatures),
+        TxBroadcast(TxBroadcast),
         QueryBalance(QueryBalance),
+        QueryProposal(QueryProposal),
+        QueryParameters(QueryParameters),
+        QueryTreasury(QueryTreasury),
+        QueryRewards(QueryRewards),
+        QueryValidator(QueryValidator),
+        QueryEpoch(QueryEpoch),
+        QueryNextEpoch(QueryNextEpoch),
+        #[cfg(feature = "shielded-pool")]
+        ShieldedGenKey(ShieldedGenKey),
         Intent(Intent),
         SubscribeTopic(SubscribeTopic),
     }
 
     impl Cmd for AnomaClient {
         fn add_sub(app: App) -> App {
-            app.subcommand(TxCustom::def())
+            let app = app
+                .subcommand(TxCustom::def())
                 .subcommand(TxTransfer::def())
-                .subcommand(TxUpdateVp::def())
+                .subcommand(TxMint::def())
+                .subcommand(TxBurn::def())
+                .subcommand(TxApprove::def())
+                .subcommand(TxRevoke::def())
+                .subcommand(TxTransferFrom::def());
+            let app = Self::add_shielded_sub(app);
+            app.subcommand(TxUpdateVp::def())
+                .subcommand(TxUpdateKey::def())
+                .subcommand(TxUpdateRecovery::def())
+                .subcommand(TxProposeRecovery::def())
+                .subcommand(TxVetoRecovery::def())
+                .subcommand(TxRecover::def())
                 .subcommand(TxInitAccount::def())
+                .subcommand(TxInitMultisigAccount::def())
+                .subcommand(TxInitVestingAccount::def())
+                .subcommand(TxInitToken::def())
+                .subcommand(TxInitProposal::def())
+                .subcommand(TxVoteProposal::def())
+                .subcommand(TxClaimRewards::def())
+                .subcommand(TxBecomeValidator::def())
+                .subcommand(TxBond::def())
+                .subcommand(TxUnbond::def())
+                .subcommand(TxWithdraw::def())
+                .subcommand(TxChangeCommission::def())
+                .subcommand(TxUnjail::def())
+                .subcommand(TxBuild::def())
+                .subcommand(TxBuildTreasurySpend::def())
+                .subcommand(TxSign::def())
+                .subcommand(TxCombineSignatures::def())
+                .subcommand(TxBroadcast::def())
                 .subcommand(QueryBalance::def())
+                .subcommand(QueryProposal::def())
+                .subcommand(QueryParameters::def())
+                .subcommand(QueryTreasury::def())
+                .subcommand(QueryRewards::def())
+                .subcommand(QueryValidator::def())
+                .subcommand(QueryEpoch::def())
+                .subcommand(QueryNextEpoch::def())
                 .subcommand(Intent::def())
                 .subcommand(SubscribeTopic::def())
         }
@@ -147,21 +240,150 @@ pub mod cmds {
         fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)> {
             let tx_custom = SubCmd::parse(matches).map_fst(Self::TxCustom);
             let tx_transfer = SubCmd::parse(matches).map_fst(Self::TxTransfer);
+            let tx_mint = SubCmd::parse(matches).map_fst(Self::TxMint);
+            let tx_burn = SubCmd::parse(matches).map_fst(Self::TxBurn);
+            let tx_approve = SubCmd::parse(matches).map_fst(Self::TxApprove);
+            let tx_revoke = SubCmd::parse(matches).map_fst(Self::TxRevoke);
+            let tx_transfer_from =
+                SubCmd::parse(matches).map_fst(Self::TxTransferFrom);
             let tx_update_vp = SubCmd::parse(matches).map_fst(Self::TxUpdateVp);
+            let tx_update_key =
+                SubCmd::parse(matches).map_fst(Self::TxUpdateKey);
+            let tx_update_recovery =
+                SubCmd::parse(matches).map_fst(Self::TxUpdateRecovery);
+            let tx_propose_recovery =
+                SubCmd::parse(matches).map_fst(Self::TxProposeRecovery);
+            let tx_veto_recovery =
+                SubCmd::parse(matches).map_fst(Self::TxVetoRecovery);
+            let tx_recover = SubCmd::parse(matches).map_fst(Self::TxRecover);
             let tx_init_account =
                 SubCmd::parse(matches).map_fst(Self::TxInitAccount);
+            let tx_init_multisig_account =
+                SubCmd::parse(matches).map_fst(Self::TxInitMultisigAccount);
+            let tx_init_vesting_account =
+                SubCmd::parse(matches).map_fst(Self::TxInitVestingAccount);
+            let tx_init_token =
+                SubCmd::parse(matches).map_fst(Self::TxInitToken);
+            let tx_init_proposal =
+                SubCmd::parse(matches).map_fst(Self::TxInitProposal);
+            let tx_vote_proposal =
+                SubCmd::parse(matches).map_fst(Self::TxVoteProposal);
+            let tx_claim_rewards =
+                SubCmd::parse(matches).map_fst(Self::TxClaimRewards);
+            let tx_become_validator =
+                SubCmd::parse(matches).map_fst(Self::TxBecomeValidator);
+            let tx_bond = SubCmd::parse(matches).map_fst(Self::TxBond);
+            let tx_unbond = SubCmd::parse(matches).map_fst(Self::TxUnbond);
+            let tx_withdraw = SubCmd::parse(matches).map_fst(Self::TxWithdraw);
+            let tx_change_commission =
+                SubCmd::parse(matches).map_fst(Self::TxChangeCommission);
+            let tx_unjail = SubCmd::parse(matches).map_fst(Self::TxUnjail);
+            let tx_build = SubCmd::parse(matches).map_fst(Self::TxBuild);
+            let tx_build_treasury_spend =
+                SubCmd::parse(matches).map_fst(Self::TxBuildTreasurySpend);
+            let tx_sign = SubCmd::parse(matches).map_fst(Self::TxSign);
+            let tx_combine_signatures =
+                SubCmd::parse(matches).map_fst(Self::TxCombineSignatures);
+            let tx_broadcast =
+                SubCmd::parse(matches).map_fst(Self::TxBroadcast);
             let query_balance =
                 SubCmd::parse(matches).map_fst(Self::QueryBalance);
+            let query_proposal =
+                SubCmd::parse(matches).map_fst(Self::QueryProposal);
+            let query_parameters =
+                SubCmd::parse(matches).map_fst(Self::QueryParameters);
+            let query_treasury =
+                SubCmd::parse(matches).map_fst(Self::QueryTreasury);
+            let query_rewards =
+                SubCmd::parse(matches).map_fst(Self::QueryRewards);
+            let query_validator =
+                SubCmd::parse(matches).map_fst(Self::QueryValidator);
+            let query_epoch = SubCmd::parse(matches).map_fst(Self::QueryEpoch);
+            let query_next_epoch =
+                SubCmd::parse(matches).map_fst(Self::QueryNextEpoch);
             let intent = SubCmd::parse(matches).map_fst(Self::Intent);
             let subscribe_topic =
                 SubCmd::parse(matches).map_fst(Self::SubscribeTopic);
             tx_custom
                 .or(tx_transfer)
+                .or(tx_mint)
+                .or(tx_burn)
+                .or(tx_approve)
+                .or(tx_revoke)
+                .or(tx_transfer_from)
                 .or(tx_update_vp)
+                .or(tx_update_key)
+                .or(tx_update_recovery)
+                .or(tx_propose_recovery)
+                .or(tx_veto_recovery)
+                .or(tx_recover)
                 .or(tx_init_account)
+                .or(tx_init_multisig_account)
+                .or(tx_init_vesting_account)
+                .or(tx_init_token)
+                .or(tx_init_proposal)
+                .or(tx_vote_proposal)
+                .or(tx_claim_rewards)
+                .or(tx_become_validator)
+                .or(tx_bond)
+                .or(tx_unbond)
+                .or(tx_withdraw)
+                .or(tx_change_commission)
+                .or(tx_unjail)
+                .or(tx_build)
+                .or(tx_build_treasury_spend)
+                .or(tx_sign)
+                .or(tx_combine_signatures)
+                .or(tx_broadcast)
                 .or(query_balance)
+                .or(query_proposal)
+                .or(query_parameters)
+                .or(query_treasury)
+                .or(query_rewards)
+                .or(query_validator)
+                .or(query_epoch)
+                .or(query_next_epoch)
                 .or(intent)
                 .or(subscribe_topic)
+                .or_else(|| Self::parse_shielded(matches))
+        }
+    }
+
+    impl AnomaClient {
+        /// Add the shielded pool's commands, which are only available with
+        /// the `shielded-pool` feature.
+        #[cfg(feature = "shielded-pool")]
+        fn add_shielded_sub(app: App) -> App {
+            app.subcommand(TxShield::def())
+                .subcommand(TxUnshield::def())
+                .subcommand(TxShieldedTransfer::def())
+                .subcommand(ShieldedGenKey::def())
+        }
+
+        #[cfg(not(feature = "shielded-pool"))]
+        fn add_shielded_sub(app: App) -> App {
+            app
+        }
+
+        #[cfg(feature = "shielded-pool")]
+        fn parse_shielded(matches: &ArgMatches) -> Option<(Self, &ArgMatches)> {
+            let tx_shield = SubCmd::parse(matches).map_fst(Self::TxShield);
+            let tx_unshield = SubCmd::parse(matches).map_fst(Self::TxUnshield);
+            let tx_shielded_transfer =
+                SubCmd::parse(matches).map_fst(Self::TxShieldedTransfer);
+            let shielded_gen_key =
+                SubCmd::parse(matches).map_fst(Self::ShieldedGenKey);
+            tx_shield
+                .or(tx_unshield)
+                .or(tx_shielded_transfer)
+                .or(shielded_gen_key)
+        }
+
+        #[cfg(not(feature = "shielded-pool"))]
+        fn parse_shielded(
+            _matches: &ArgMatches,
+        ) -> Option<(Self, &ArgMatches)> {
+            None
         }
     }
     impl SubCmd for AnomaClient {
@@ -189,6 +411,7 @@ pub mod cmds {
     pub enum Ledger {
         Run(LedgerRun),
         Reset(LedgerReset),
+        GenValidatorKey(LedgerGenValidatorKey),
     }
 
     impl SubCmd for Ledger {
@@ -198,7 +421,10 @@ pub mod cmds {
             matches.subcommand_matches(Self::CMD).and_then(|matches| {
                 let run = SubCmd::parse(matches).map_fst(Ledger::Run);
                 let reset = SubCmd::parse(matches).map_fst(Ledger::Reset);
+                let gen_validator_key =
+                    SubCmd::parse(matches).map_fst(Ledger::GenValidatorKey);
                 run.or(reset)
+                    .or(gen_validator_key)
                     // The `run` command is the default if no sub-command given
                     .or(Some((Ledger::Run(LedgerRun), matches)))
             })
@@ -212,6 +438,7 @@ pub mod cmds {
                 )
                 .subcommand(LedgerRun::def())
                 .subcommand(LedgerReset::def())
+                .subcommand(LedgerGenValidatorKey::def())
         }
     }
 
@@ -252,6 +479,27 @@ pub mod cmds {
         }
     }
 
+    #[derive(Debug)]
+    pub struct LedgerGenValidatorKey;
+
+    impl SubCmd for LedgerGenValidatorKey {
+        const CMD: &'static str = "gen-validator-key";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| (LedgerGenValidatorKey, matches))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD).about(
+                "Generate a new validator consensus key for the Tendermint \
+                 node. The key is written into Tendermint's private validator \
+                 key file, which must not exist yet.",
+            )
+        }
+    }
+
     #[derive(Debug)]
     pub enum Gossip {
         Run(GossipRun),
@@ -358,6 +606,59 @@ pub mod cmds {
         }
     }
 
+    #[derive(Debug)]
+    pub enum Utils {
+        InitNetwork(InitNetwork),
+    }
+
+    impl SubCmd for Utils {
+        const CMD: &'static str = "utils";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).and_then(|matches| {
+                let init_network =
+                    SubCmd::parse(matches).map_fst(Self::InitNetwork);
+                init_network
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .about("Utilities sub-commands")
+                .subcommand(InitNetwork::def())
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct InitNetwork(pub args::InitNetwork);
+
+    impl SubCmd for InitNetwork {
+        const CMD: &'static str = "init-network";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (InitNetwork(args::InitNetwork::parse(matches)), matches)
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Generate the base directories of a network of validator \
+                     nodes that run on the same machine. The nodes share the \
+                     same genesis and are peers of each other.",
+                )
+                .add_args::<args::InitNetwork>()
+        }
+    }
+
     #[derive(Debug)]
     pub struct TxCustom(pub args::TxCustom);
 
@@ -400,104 +701,106 @@ pub mod cmds {
     }
 
     #[derive(Debug)]
-    pub struct TxUpdateVp(pub args::TxUpdateVp);
+    pub struct TxMint(pub args::TxMint);
 
-    impl SubCmd for TxUpdateVp {
-        const CMD: &'static str = "update";
+    impl SubCmd for TxMint {
+        const CMD: &'static str = "mint";
 
         fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
         where
             Self: Sized,
         {
-            matches.subcommand_matches(Self::CMD).map(|matches| {
-                (TxUpdateVp(args::TxUpdateVp::parse(matches)), matches)
-            })
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| (TxMint(args::TxMint::parse(matches)), matches))
         }
 
         fn def() -> App {
             App::new(Self::CMD)
                 .about(
-                    "Send a signed transaction to update account's validity \
-                     predicate",
+                    "Send a signed transaction to mint new tokens by the \
+                     token's minter",
                 )
-                .add_args::<args::TxUpdateVp>()
+                .add_args::<args::TxMint>()
         }
     }
 
     #[derive(Debug)]
-    pub struct TxInitAccount(pub args::TxInitAccount);
+    pub struct TxBurn(pub args::TxBurn);
 
-    impl SubCmd for TxInitAccount {
-        const CMD: &'static str = "init-account";
+    impl SubCmd for TxBurn {
+        const CMD: &'static str = "burn";
 
         fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
         where
             Self: Sized,
         {
-            matches.subcommand_matches(Self::CMD).map(|matches| {
-                (TxInitAccount(args::TxInitAccount::parse(matches)), matches)
-            })
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| (TxBurn(args::TxBurn::parse(matches)), matches))
         }
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about(
-                    "Send a signed transaction to create a new established \
-                     account",
-                )
-                .add_args::<args::TxInitAccount>()
+                .about("Send a signed transaction to burn tokens")
+                .add_args::<args::TxBurn>()
         }
     }
 
     #[derive(Debug)]
-    pub struct QueryBalance(pub args::QueryBalance);
+    pub struct TxApprove(pub args::TxApprove);
 
-    impl SubCmd for QueryBalance {
-        const CMD: &'static str = "balance";
+    impl SubCmd for TxApprove {
+        const CMD: &'static str = "approve";
 
         fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
         where
             Self: Sized,
         {
             matches.subcommand_matches(Self::CMD).map(|matches| {
-                (QueryBalance(args::QueryBalance::parse(matches)), matches)
+                (TxApprove(args::TxApprove::parse(matches)), matches)
             })
         }
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("Query balance(s) of tokens")
-                .add_args::<args::QueryBalance>()
+                .about(
+                    "Send a signed transaction to allow a spender to transfer \
+                     the owner's tokens",
+                )
+                .add_args::<args::TxApprove>()
         }
     }
 
     #[derive(Debug)]
-    pub struct Intent(pub args::Intent);
+    pub struct TxRevoke(pub args::TxRevoke);
 
-    impl SubCmd for Intent {
-        const CMD: &'static str = "intent";
+    impl SubCmd for TxRevoke {
+        const CMD: &'static str = "revoke";
 
         fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
         where
             Self: Sized,
         {
-            matches
-                .subcommand_matches(Self::CMD)
-                .map(|matches| (Intent(args::Intent::parse(matches)), matches))
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (TxRevoke(args::TxRevoke::parse(matches)), matches)
+            })
         }
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("Send an intent.")
-                .add_args::<args::Intent>()
+                .about(
+                    "Send a signed transaction to revoke a spender's allowance",
+                )
+                .add_args::<args::TxRevoke>()
         }
     }
 
     #[derive(Debug)]
-    pub struct SubscribeTopic(pub args::SubscribeTopic);
+    pub struct TxTransferFrom(pub args::TxTransferFrom);
 
-    impl SubCmd for SubscribeTopic {
-        const CMD: &'static str = "subscribe-topic";
+    impl SubCmd for TxTransferFrom {
+        const CMD: &'static str = "transfer-from";
 
         fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
         where
@@ -505,7 +808,7 @@ pub mod cmds {
         {
             matches.subcommand_matches(Self::CMD).map(|matches| {
                 (
-                    SubscribeTopic(args::SubscribeTopic::parse(matches)),
+                    TxTransferFrom(args::TxTransferFrom::parse(matches)),
                     matches,
                 )
             })
@@ -513,253 +816,2664 @@ pub mod cmds {
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("subscribe to a topic.")
-                .add_args::<args::SubscribeTopic>()
+                .about(
+                    "Send a signed transfer transaction of the owner's tokens \
+                     using the spender's allowance",
+                )
+                .add_args::<args::TxTransferFrom>()
         }
     }
-}
 
-pub mod args {
-
-    use std::convert::TryFrom;
-    use std::fs::File;
-    use std::net::SocketAddr;
-    use std::path::PathBuf;
-    use std::str::FromStr;
+    #[derive(Debug)]
+    pub struct TxShield(pub args::TxShield);
 
-    use anoma::types::address::Address;
-    use anoma::types::intent::{DecimalWrapper, Exchange};
-    use anoma::types::key::ed25519::PublicKey;
-    use anoma::types::token;
-    use libp2p::Multiaddr;
-    use serde::Deserialize;
+    impl SubCmd for TxShield {
+        const CMD: &'static str = "shield";
 
-    use super::utils::*;
-    use super::ArgMatches;
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (TxShield(args::TxShield::parse(matches)), matches)
+            })
+        }
 
-    const ADDRESS: Arg<Address> = arg("address");
-    const AMOUNT: Arg<token::Amount> = arg("amount");
-    const BASE_DIR: ArgDefault<PathBuf> =
-        arg_default("base-dir", DefaultFn(|| ".anoma".into()));
-    const CODE_PATH: Arg<PathBuf> = arg("code-path");
-    const CODE_PATH_OPT: ArgOpt<PathBuf> = CODE_PATH.opt();
-    const DATA_PATH_OPT: ArgOpt<PathBuf> = arg_opt("data-path");
-    const DATA_PATH: Arg<PathBuf> = arg("data-path");
-    const DRY_RUN_TX: ArgFlag = flag("dry-run");
-    const FILTER_PATH: ArgOpt<PathBuf> = arg_opt("filter-path");
-    const LEDGER_ADDRESS_ABOUT: &str =
-        "Address of a ledger node as \"{scheme}://{host}:{port}\". If the \
-         scheme is not supplied, it is assumed to be TCP.";
-    const LEDGER_ADDRESS_DEFAULT: ArgDefault<tendermint::net::Address> =
-        LEDGER_ADDRESS.default(DefaultFn(|| {
-            let raw = "127.0.0.1:26657";
-            tendermint::net::Address::from_str(raw).unwrap()
-        }));
-    const LEDGER_ADDRESS_OPT: ArgOpt<tendermint::net::Address> =
-        LEDGER_ADDRESS.opt();
-    const PEERS: ArgMulti<String> = arg_multi("peers");
-    const TOPIC: Arg<String> = arg("topic");
-    const TOPIC_OPT: ArgOpt<String> = arg_opt("topic");
-    const TOPICS: ArgMulti<String> = TOPIC.multi();
-    // TODO: once we have a wallet, we should also allow to use a key alias
-    // <https://github.com/anoma/anoma/issues/167>
-    const SIGNING_KEY: Arg<Address> = arg("key");
-    const RPC_SOCKET_ADDR: ArgOpt<SocketAddr> = arg_opt("rpc");
-    const LEDGER_ADDRESS: Arg<tendermint::net::Address> = arg("ledger-address");
-    const MATCHMAKER_PATH: ArgOpt<PathBuf> = arg_opt("matchmaker-path");
-    const MULTIADDR_OPT: ArgOpt<Multiaddr> = arg_opt("address");
-    const NODE: Arg<String> = arg("node");
-    const NODE_OPT: ArgOpt<String> = arg_opt("node");
-    const TO_STDOUT: ArgFlag = flag("stdout");
-    const OWNER: ArgOpt<Address> = arg_opt("owner");
-    // TODO: once we have a wallet, we should also allow to use a key alias
-    // <https://github.com/anoma/anoma/issues/167>
-    const PUBLIC_KEY: Arg<PublicKey> = arg("public-key");
-    const SOURCE: Arg<Address> = arg("source");
-    const TARGET: Arg<Address> = arg("target");
-    const TOKEN: Arg<Address> = arg("token");
-    const TOKEN_OPT: ArgOpt<Address> = TOKEN.opt();
-    const TX_CODE_PATH: ArgOpt<PathBuf> = arg_opt("tx-code-path");
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to shield tokens into a new \
+                     note of the shielded pool",
+                )
+                .add_args::<args::TxShield>()
+        }
+    }
 
-    /// Global command arguments
     #[derive(Debug)]
-    pub struct Global {
-        pub base_dir: PathBuf,
-    }
+    pub struct TxUnshield(pub args::TxUnshield);
 
-    impl Args for Global {
-        fn parse(matches: &ArgMatches) -> Self {
-            let base_dir = BASE_DIR.parse(matches);
-            Global { base_dir }
+    impl SubCmd for TxUnshield {
+        const CMD: &'static str = "unshield";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (TxUnshield(args::TxUnshield::parse(matches)), matches)
+            })
         }
 
-        fn def(app: App) -> App {
-            app.arg(BASE_DIR.def().about(
-                "The base directory is where the client and nodes \
-                 configuration and state is stored.",
-            ))
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a transaction to unshield tokens from notes of the \
+                     shielded pool to a transparent account",
+                )
+                .add_args::<args::TxUnshield>()
         }
     }
 
-    /// Custom transaction arguments
     #[derive(Debug)]
-    pub struct TxCustom {
-        /// Common tx arguments
-        pub tx: Tx,
-        /// Path to the tx WASM code file
-        pub code_path: PathBuf,
-        /// Path to the data file
-        pub data_path: Option<PathBuf>,
-    }
+    pub struct TxShieldedTransfer(pub args::TxShieldedTransfer);
 
-    impl Args for TxCustom {
-        fn parse(matches: &ArgMatches) -> Self {
-            let tx = Tx::parse(matches);
-            let code_path = CODE_PATH.parse(matches);
-            let data_path = DATA_PATH_OPT.parse(matches);
-            Self {
-                tx,
-                code_path,
-                data_path,
-            }
+    impl SubCmd for TxShieldedTransfer {
+        const CMD: &'static str = "shielded-transfer";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (
+                    TxShieldedTransfer(args::TxShieldedTransfer::parse(
+                        matches,
+                    )),
+                    matches,
+                )
+            })
         }
 
-        fn def(app: App) -> App {
-            app.add_args::<Tx>()
-                .arg(
-                    CODE_PATH
-                        .def()
-                        .about("The path to the transaction's WASM code."),
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a transaction to transfer tokens from notes of the \
+                     shielded pool to a new note of a payment address",
                 )
-                .arg(DATA_PATH_OPT.def().about(
-                    "The data file at this path containing arbitrary bytes \
-                     will be passed to the transaction code when it's \
-                     executed.",
-                ))
+                .add_args::<args::TxShieldedTransfer>()
         }
     }
 
-    /// Transfer transaction arguments
     #[derive(Debug)]
-    pub struct TxTransfer {
-        /// Common tx arguments
-        pub tx: Tx,
-        /// Transfer source address
-        pub source: Address,
-        /// Transfer target address
-        pub target: Address,
-        /// Transferred token address
-        pub token: Address,
-        /// Transferred token amount
-        pub amount: token::Amount,
-    }
+    pub struct TxUpdateVp(pub args::TxUpdateVp);
+
+    impl SubCmd for TxUpdateVp {
+        const CMD: &'static str = "update";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (TxUpdateVp(args::TxUpdateVp::parse(matches)), matches)
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to update account's validity \
+                     predicate",
+                )
+                .add_args::<args::TxUpdateVp>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TxUpdateKey(pub args::TxUpdateKey);
+
+    impl SubCmd for TxUpdateKey {
+        const CMD: &'static str = "update-key";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (TxUpdateKey(args::TxUpdateKey::parse(matches)), matches)
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to replace account's public key",
+                )
+                .add_args::<args::TxUpdateKey>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TxUpdateRecovery(pub args::TxUpdateRecovery);
+
+    impl SubCmd for TxUpdateRecovery {
+        const CMD: &'static str = "update-recovery";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (
+                    TxUpdateRecovery(args::TxUpdateRecovery::parse(matches)),
+                    matches,
+                )
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to set or remove account's \
+                     recovery guardians",
+                )
+                .add_args::<args::TxUpdateRecovery>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TxProposeRecovery(pub args::TxProposeRecovery);
+
+    impl SubCmd for TxProposeRecovery {
+        const CMD: &'static str = "propose-recovery";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (
+                    TxProposeRecovery(args::TxProposeRecovery::parse(matches)),
+                    matches,
+                )
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a transaction signed by a guardian to approve a new \
+                     public key for an account",
+                )
+                .add_args::<args::TxProposeRecovery>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TxVetoRecovery(pub args::TxRecovery);
+
+    impl SubCmd for TxVetoRecovery {
+        const CMD: &'static str = "veto-recovery";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (TxVetoRecovery(args::TxRecovery::parse(matches)), matches)
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a transaction signed by the account's current key \
+                     to veto its recovery proposal",
+                )
+                .add_args::<args::TxRecovery>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TxRecover(pub args::TxRecovery);
+
+    impl SubCmd for TxRecover {
+        const CMD: &'static str = "recover";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (TxRecover(args::TxRecovery::parse(matches)), matches)
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a transaction to replace account's public key with \
+                     the key approved by its guardians",
+                )
+                .add_args::<args::TxRecovery>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TxInitAccount(pub args::TxInitAccount);
+
+    impl SubCmd for TxInitAccount {
+        const CMD: &'static str = "init-account";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (TxInitAccount(args::TxInitAccount::parse(matches)), matches)
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to create a new established \
+                     account",
+                )
+                .add_args::<args::TxInitAccount>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TxInitMultisigAccount(pub args::TxInitMultisigAccount);
+
+    impl SubCmd for TxInitMultisigAccount {
+        const CMD: &'static str = "init-multisig-account";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (
+                    TxInitMultisigAccount(args::TxInitMultisigAccount::parse(
+                        matches,
+                    )),
+                    matches,
+                )
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to create a new established \
+                     multisig account",
+                )
+                .add_args::<args::TxInitMultisigAccount>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TxInitVestingAccount(pub args::TxInitVestingAccount);
+
+    impl SubCmd for TxInitVestingAccount {
+        const CMD: &'static str = "init-vesting-account";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (
+                    TxInitVestingAccount(args::TxInitVestingAccount::parse(
+                        matches,
+                    )),
+                    matches,
+                )
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to create a new established \
+                     account with a vesting schedule",
+                )
+                .add_args::<args::TxInitVestingAccount>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TxInitToken(pub args::TxInitToken);
+
+    impl SubCmd for TxInitToken {
+        const CMD: &'static str = "init-token";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (TxInitToken(args::TxInitToken::parse(matches)), matches)
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to create a new token and add \
+                     it to the token registry",
+                )
+                .add_args::<args::TxInitToken>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TxInitProposal(pub args::TxInitProposal);
+
+    impl SubCmd for TxInitProposal {
+        const CMD: &'static str = "submit-proposal";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (
+                    TxInitProposal(args::TxInitProposal::parse(matches)),
+                    matches,
+                )
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to submit a new governance \
+                     proposal",
+                )
+                .add_args::<args::TxInitProposal>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TxVoteProposal(pub args::TxVoteProposal);
+
+    impl SubCmd for TxVoteProposal {
+        const CMD: &'static str = "vote-proposal";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (
+                    TxVoteProposal(args::TxVoteProposal::parse(matches)),
+                    matches,
+                )
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to vote on a governance \
+                     proposal",
+                )
+                .add_args::<args::TxVoteProposal>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TxClaimRewards(pub args::TxClaimRewards);
+
+    impl SubCmd for TxClaimRewards {
+        const CMD: &'static str = "claim-rewards";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (
+                    TxClaimRewards(args::TxClaimRewards::parse(matches)),
+                    matches,
+                )
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to claim the rewards of a bond \
+                     to a validator",
+                )
+                .add_args::<args::TxClaimRewards>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TxBecomeValidator(pub args::TxBecomeValidator);
+
+    impl SubCmd for TxBecomeValidator {
+        const CMD: &'static str = "become-validator";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (
+                    TxBecomeValidator(args::TxBecomeValidator::parse(matches)),
+                    matches,
+                )
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to register a new validator \
+                     with a consensus key",
+                )
+                .add_args::<args::TxBecomeValidator>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TxBond(pub args::TxBond);
+
+    impl SubCmd for TxBond {
+        const CMD: &'static str = "bond";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| (TxBond(args::TxBond::parse(matches)), matches))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Send a signed transaction to bond XAN to a validator")
+                .add_args::<args::TxBond>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TxUnbond(pub args::TxUnbond);
+
+    impl SubCmd for TxUnbond {
+        const CMD: &'static str = "unbond";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (TxUnbond(args::TxUnbond::parse(matches)), matches)
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to unbond XAN from a validator",
+                )
+                .add_args::<args::TxUnbond>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TxWithdraw(pub args::TxWithdraw);
+
+    impl SubCmd for TxWithdraw {
+        const CMD: &'static str = "withdraw";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (TxWithdraw(args::TxWithdraw::parse(matches)), matches)
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to withdraw the XAN unbonded \
+                     from a validator",
+                )
+                .add_args::<args::TxWithdraw>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TxChangeCommission(pub args::TxChangeCommission);
+
+    impl SubCmd for TxChangeCommission {
+        const CMD: &'static str = "change-commission";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (
+                    TxChangeCommission(args::TxChangeCommission::parse(
+                        matches,
+                    )),
+                    matches,
+                )
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to change a validator's \
+                     commission rate",
+                )
+                .add_args::<args::TxChangeCommission>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TxUnjail(pub args::TxUnjail);
+
+    impl SubCmd for TxUnjail {
+        const CMD: &'static str = "unjail";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (TxUnjail(args::TxUnjail::parse(matches)), matches)
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Send a signed transaction to unjail a validator")
+                .add_args::<args::TxUnjail>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TxBuild(pub args::TxBuild);
+
+    impl SubCmd for TxBuild {
+        const CMD: &'static str = "build-tx";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (TxBuild(args::TxBuild::parse(matches)), matches)
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Build an unsigned transaction to be signed offline by \
+                     the keys of a multisig account",
+                )
+                .add_args::<args::TxBuild>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TxBuildTreasurySpend(pub args::TxBuildTreasurySpend);
+
+    impl SubCmd for TxBuildTreasurySpend {
+        const CMD: &'static str = "build-treasury-spend";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (
+                    TxBuildTreasurySpend(args::TxBuildTreasurySpend::parse(
+                        matches,
+                    )),
+                    matches,
+                )
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Build an unsigned transaction that spends the treasury \
+                     funds, to be signed offline by the keys of the \
+                     treasury's admin multisig account",
+                )
+                .add_args::<args::TxBuildTreasurySpend>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TxSign(pub args::TxSign);
+
+    impl SubCmd for TxSign {
+        const CMD: &'static str = "sign-tx";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| (TxSign(args::TxSign::parse(matches)), matches))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Sign an unsigned transaction offline")
+                .add_args::<args::TxSign>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TxCombineSignatures(pub args::TxCombineSignatures);
+
+    impl SubCmd for TxCombineSignatures {
+        const CMD: &'static str = "combine-signatures";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (
+                    TxCombineSignatures(args::TxCombineSignatures::parse(
+                        matches,
+                    )),
+                    matches,
+                )
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Combine the signatures collected offline into a multisig \
+                     transaction",
+                )
+                .add_args::<args::TxCombineSignatures>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct TxBroadcast(pub args::TxBroadcast);
+
+    impl SubCmd for TxBroadcast {
+        const CMD: &'static str = "broadcast-tx";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (TxBroadcast(args::TxBroadcast::parse(matches)), matches)
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Send a transaction from a file")
+                .add_args::<args::TxBroadcast>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct QueryBalance(pub args::QueryBalance);
+
+    impl SubCmd for QueryBalance {
+        const CMD: &'static str = "balance";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (QueryBalance(args::QueryBalance::parse(matches)), matches)
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Query balance(s) of tokens")
+                .add_args::<args::QueryBalance>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct QueryProposal(pub args::QueryProposal);
+
+    impl SubCmd for QueryProposal {
+        const CMD: &'static str = "proposal";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (QueryProposal(args::QueryProposal::parse(matches)), matches)
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Query a governance proposal, its votes and result")
+                .add_args::<args::QueryProposal>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct QueryParameters(pub args::Query);
+
+    impl SubCmd for QueryParameters {
+        const CMD: &'static str = "parameters";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (QueryParameters(args::Query::parse(matches)), matches)
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Query the current protocol parameters and the scheduled \
+                     parameter changes",
+                )
+                .add_args::<args::Query>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct QueryTreasury(pub args::Query);
+
+    impl SubCmd for QueryTreasury {
+        const CMD: &'static str = "treasury";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (QueryTreasury(args::Query::parse(matches)), matches)
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Query the treasury's admin and balances")
+                .add_args::<args::Query>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct QueryRewards(pub args::QueryRewards);
+
+    impl SubCmd for QueryRewards {
+        const CMD: &'static str = "rewards";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (QueryRewards(args::QueryRewards::parse(matches)), matches)
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Query the claimable rewards of a bond to a validator")
+                .add_args::<args::QueryRewards>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct QueryValidator(pub args::QueryValidator);
+
+    impl SubCmd for QueryValidator {
+        const CMD: &'static str = "validator";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (
+                    QueryValidator(args::QueryValidator::parse(matches)),
+                    matches,
+                )
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Query a validator's state")
+                .add_args::<args::QueryValidator>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct QueryEpoch(pub args::QueryEpoch);
+
+    impl SubCmd for QueryEpoch {
+        const CMD: &'static str = "epoch";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (QueryEpoch(args::QueryEpoch::parse(matches)), matches)
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Query the current epoch or the epoch of a block height")
+                .add_args::<args::QueryEpoch>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct QueryNextEpoch(pub args::Query);
+
+    impl SubCmd for QueryNextEpoch {
+        const CMD: &'static str = "next-epoch";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (QueryNextEpoch(args::Query::parse(matches)), matches)
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Query the earliest start of the next epoch")
+                .add_args::<args::Query>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct ShieldedGenKey;
+
+    impl SubCmd for ShieldedGenKey {
+        const CMD: &'static str = "shielded-gen-key";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| (ShieldedGenKey, matches))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD).about(
+                "Generate a new shielded spending key and print it with its \
+                 payment address",
+            )
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct Intent(pub args::Intent);
+
+    impl SubCmd for Intent {
+        const CMD: &'static str = "intent";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| (Intent(args::Intent::parse(matches)), matches))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Send an intent.")
+                .add_args::<args::Intent>()
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct SubscribeTopic(pub args::SubscribeTopic);
+
+    impl SubCmd for SubscribeTopic {
+        const CMD: &'static str = "subscribe-topic";
+
+        fn parse(matches: &ArgMatches) -> Option<(Self, &ArgMatches)>
+        where
+            Self: Sized,
+        {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                (
+                    SubscribeTopic(args::SubscribeTopic::parse(matches)),
+                    matches,
+                )
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("subscribe to a topic.")
+                .add_args::<args::SubscribeTopic>()
+        }
+    }
+}
+
+pub mod args {
+
+    use std::convert::TryFrom;
+    use std::fs::File;
+    use std::net::SocketAddr;
+    use std::path::PathBuf;
+    use std::str::FromStr;
+
+    use anoma::types::address::Address;
+    use anoma::types::governance::Vote;
+    use anoma::types::intent::{DecimalWrapper, Exchange};
+    use anoma::types::key::common::PublicKey;
+    use anoma::types::key::ed25519;
+    use anoma::types::shielded::{PaymentAddress, SpendingKey};
+    use anoma::types::storage::{BlockHeight, Epoch};
+    use anoma::types::time::DateTimeUtc;
+    use anoma::types::token;
+    use libp2p::Multiaddr;
+    use serde::Deserialize;
+
+    use super::utils::*;
+    use super::ArgMatches;
+
+    const ADDRESS: Arg<Address> = arg("address");
+    const AUTHOR: Arg<Address> = arg("author");
+    const AMOUNT: Arg<token::DecimalAmount> = arg("amount");
+    const BALANCE: ArgDefault<token::Amount> =
+        arg_default("balance", DefaultFn(|| token::Amount::whole(1_000_000)));
+    const BASE_DIR: ArgDefault<PathBuf> =
+        arg_default("base-dir", DefaultFn(|| ".anoma".into()));
+    const CHANGE_PATH: ArgOpt<PathBuf> = arg_opt("change-path");
+    const CLIFF_EPOCH: ArgOpt<u64> = arg_opt("cliff-epoch");
+    const CLIFF_TIME: ArgOpt<DateTimeUtc> = arg_opt("cliff-time");
+    const CODE_PATH: Arg<PathBuf> = arg("code-path");
+    const CODE_PATH_OPT: ArgOpt<PathBuf> = CODE_PATH.opt();
+    const COMMISSION_RATE: Arg<u64> = arg("commission-rate");
+    const CONSENSUS_KEY: Arg<ed25519::PublicKey> = arg("consensus-key");
+    const DATA_PATH_OPT: ArgOpt<PathBuf> = arg_opt("data-path");
+    const DATA_PATH: Arg<PathBuf> = arg("data-path");
+    const DECIMALS: Arg<u8> = arg("decimals");
+    const DELAY: ArgDefault<u64> = arg_default("delay", DefaultFn(|| 0));
+    const DELEGATOR: Arg<Address> = arg("delegator");
+    const DEPOSIT: ArgOpt<token::DecimalAmount> = arg_opt("deposit");
+    const DRY_RUN_TX: ArgFlag = flag("dry-run");
+    const END_EPOCH: Arg<u64> = arg("end-epoch");
+    const EXPIRY: ArgOpt<u64> = arg_opt("expiry");
+    const FILTER_PATH: ArgOpt<PathBuf> = arg_opt("filter-path");
+    const GUARDIAN: Arg<Address> = arg("guardian");
+    const GUARDIANS: ArgMulti<Address> = GUARDIAN.multi();
+    const HEIGHT_OPT: ArgOpt<u64> = arg_opt("height");
+    const LEDGER_ADDRESS_ABOUT: &str =
+        "Address of a ledger node as \"{scheme}://{host}:{port}\". If the \
+         scheme is not supplied, it is assumed to be TCP.";
+    const LEDGER_ADDRESS_DEFAULT: ArgDefault<tendermint::net::Address> =
+        LEDGER_ADDRESS.default(DefaultFn(|| {
+            let raw = "127.0.0.1:26657";
+            tendermint::net::Address::from_str(raw).unwrap()
+        }));
+    const LEDGER_ADDRESS_OPT: ArgOpt<tendermint::net::Address> =
+        LEDGER_ADDRESS.opt();
+    const PEERS: ArgMulti<String> = arg_multi("peers");
+    const PROPOSAL_ID: Arg<u64> = arg("proposal-id");
+    const TOPIC: Arg<String> = arg("topic");
+    const TOPIC_OPT: ArgOpt<String> = arg_opt("topic");
+    const TOPICS: ArgMulti<String> = TOPIC.multi();
+    // TODO: once we have a wallet, we should also allow to use a key alias
+    // <https://github.com/anoma/anoma/issues/167>
+    const SIGNING_KEY: Arg<Address> = arg("key");
+    const RPC_SOCKET_ADDR: ArgOpt<SocketAddr> = arg_opt("rpc");
+    const LEDGER_ADDRESS: Arg<tendermint::net::Address> = arg("ledger-address");
+    const MATCHMAKER_PATH: ArgOpt<PathBuf> = arg_opt("matchmaker-path");
+    const MAX_COMMISSION_RATE_CHANGE: Arg<u64> =
+        arg("max-commission-rate-change");
+    const MINTER: ArgOpt<Address> = arg_opt("minter");
+    const MULTIADDR_OPT: ArgOpt<Multiaddr> = arg_opt("address");
+    const NAME: Arg<String> = arg("name");
+    const NODE: Arg<String> = arg("node");
+    const NODE_OPT: ArgOpt<String> = arg_opt("node");
+    const NOTE_PATHS: ArgMulti<PathBuf> = arg_multi("note-paths");
+    const NUM_VALIDATORS: ArgDefault<u16> =
+        arg_default("num-validators", DefaultFn(|| 4));
+    const TO_STDOUT: ArgFlag = flag("stdout");
+    const OUTPUT_DIR: Arg<PathBuf> = arg("output-dir");
+    const OUTPUT_PATH: Arg<PathBuf> = arg("output-path");
+    const OWNER: Arg<Address> = arg("owner");
+    const OWNER_OPT: ArgOpt<Address> = OWNER.opt();
+    const PAYMENT_ADDRESS: Arg<PaymentAddress> = arg("payment-address");
+    // TODO: once we have a wallet, we should also allow to use a key alias
+    // <https://github.com/anoma/anoma/issues/167>
+    const PUBLIC_KEY: Arg<PublicKey> = arg("public-key");
+    const PUBLIC_KEYS: ArgMulti<PublicKey> = arg_multi("public-keys");
+    const SIGNATURE_PATHS: ArgMulti<PathBuf> = arg_multi("signature-paths");
+    // TODO: once we have a wallet, we should also allow to use a key alias
+    // <https://github.com/anoma/anoma/issues/167>
+    const SIGNER: Arg<Address> = arg("signer");
+    const SOURCE: Arg<Address> = arg("source");
+    const SPENDER: Arg<Address> = arg("spender");
+    const SPENDING_KEY: Arg<SpendingKey> = arg("spending-key");
+    const STAKE: ArgDefault<token::Amount> =
+        arg_default("stake", DefaultFn(|| token::Amount::whole(1_000_000)));
+    const START_EPOCH: Arg<u64> = arg("start-epoch");
+    const SYMBOL: Arg<String> = arg("symbol");
+    const TARGET: Arg<Address> = arg("target");
+    const TOKEN: Arg<Address> = arg("token");
+    const THRESHOLD: Arg<u64> = arg("threshold");
+    const THRESHOLD_OPT: ArgOpt<u64> = THRESHOLD.opt();
+    const TOKEN_OPT: ArgOpt<Address> = TOKEN.opt();
+    const TX_CODE_PATH: ArgOpt<PathBuf> = arg_opt("tx-code-path");
+    const TX_PATH: Arg<PathBuf> = arg("tx-path");
+    const VALIDATOR: Arg<Address> = arg("validator");
+    const VOTE: Arg<Vote> = arg("vote");
+    const VOTER: Arg<Address> = arg("voter");
+
+    /// Global command arguments
+    #[derive(Debug)]
+    pub struct Global {
+        pub base_dir: PathBuf,
+    }
+
+    impl Args for Global {
+        fn parse(matches: &ArgMatches) -> Self {
+            let base_dir = BASE_DIR.parse(matches);
+            Global { base_dir }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(BASE_DIR.def().about(
+                "The base directory is where the client and nodes \
+                 configuration and state is stored.",
+            ))
+        }
+    }
+
+    /// Custom transaction arguments
+    #[derive(Debug)]
+    pub struct TxCustom {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Path to the tx WASM code file
+        pub code_path: PathBuf,
+        /// Path to the data file
+        pub data_path: Option<PathBuf>,
+    }
+
+    impl Args for TxCustom {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let code_path = CODE_PATH.parse(matches);
+            let data_path = DATA_PATH_OPT.parse(matches);
+            Self {
+                tx,
+                code_path,
+                data_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(
+                    CODE_PATH
+                        .def()
+                        .about("The path to the transaction's WASM code."),
+                )
+                .arg(DATA_PATH_OPT.def().about(
+                    "The data file at this path containing arbitrary bytes \
+                     will be passed to the transaction code when it's \
+                     executed.",
+                ))
+        }
+    }
+
+    /// Transfer transaction arguments
+    #[derive(Debug)]
+    pub struct TxTransfer {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Transfer source address
+        pub source: Address,
+        /// Transfer target address
+        pub target: Address,
+        /// Transferred token address
+        pub token: Address,
+        /// Transferred token amount in decimal notation, converted using the
+        /// token's decimals from the token registry
+        pub amount: token::DecimalAmount,
+    }
 
     impl Args for TxTransfer {
         fn parse(matches: &ArgMatches) -> Self {
             let tx = Tx::parse(matches);
-            let source = SOURCE.parse(matches);
-            let target = TARGET.parse(matches);
-            let token = TOKEN.parse(matches);
-            let amount = AMOUNT.parse(matches);
+            let source = SOURCE.parse(matches);
+            let target = TARGET.parse(matches);
+            let token = TOKEN.parse(matches);
+            let amount = AMOUNT.parse(matches);
+            Self {
+                tx,
+                source,
+                target,
+                token,
+                amount,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(SOURCE.def().about(
+                    "The source account address. The source's key is used to \
+                     produce the signature.",
+                ))
+                .arg(TARGET.def().about("The target account address."))
+                .arg(TOKEN.def().about("The transfer token."))
+                .arg(AMOUNT.def().about("The amount to transfer in decimal."))
+        }
+    }
+
+    /// Mint transaction arguments
+    #[derive(Debug)]
+    pub struct TxMint {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Mint target address
+        pub target: Address,
+        /// Minted token address
+        pub token: Address,
+        /// Minted token amount in decimal notation, converted using the
+        /// token's decimals from the token registry
+        pub amount: token::DecimalAmount,
+    }
+
+    impl Args for TxMint {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let target = TARGET.parse(matches);
+            let token = TOKEN.parse(matches);
+            let amount = AMOUNT.parse(matches);
+            Self {
+                tx,
+                target,
+                token,
+                amount,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(TARGET.def().about("The target account address."))
+                .arg(TOKEN.def().about(
+                    "The token to mint. The key of the token's minter from \
+                     the token registry is used to produce the signature.",
+                ))
+                .arg(AMOUNT.def().about("The amount to mint in decimal."))
+        }
+    }
+
+    /// Burn transaction arguments
+    #[derive(Debug)]
+    pub struct TxBurn {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Burn source address
+        pub source: Address,
+        /// Burned token address
+        pub token: Address,
+        /// Burned token amount in decimal notation, converted using the
+        /// token's decimals from the token registry
+        pub amount: token::DecimalAmount,
+    }
+
+    impl Args for TxBurn {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let source = SOURCE.parse(matches);
+            let token = TOKEN.parse(matches);
+            let amount = AMOUNT.parse(matches);
+            Self {
+                tx,
+                source,
+                token,
+                amount,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(SOURCE.def().about(
+                    "The source account address. The source's key is used to \
+                     produce the signature.",
+                ))
+                .arg(TOKEN.def().about("The token to burn."))
+                .arg(AMOUNT.def().about("The amount to burn in decimal."))
+        }
+    }
+
+    /// Approve transaction arguments
+    #[derive(Debug)]
+    pub struct TxApprove {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Owner of the tokens
+        pub owner: Address,
+        /// Address allowed to transfer the owner's tokens
+        pub spender: Address,
+        /// Token address
+        pub token: Address,
+        /// Maximum amount of tokens in decimal notation
+        pub amount: token::DecimalAmount,
+        /// The first epoch in which the allowance can no longer be used
+        pub expiry: Option<Epoch>,
+    }
+
+    impl Args for TxApprove {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let owner = OWNER.parse(matches);
+            let spender = SPENDER.parse(matches);
+            let token = TOKEN.parse(matches);
+            let amount = AMOUNT.parse(matches);
+            let expiry = EXPIRY.parse(matches).map(Epoch);
+            Self {
+                tx,
+                owner,
+                spender,
+                token,
+                amount,
+                expiry,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(OWNER.def().about(
+                    "The owner of the tokens. The owner's key is used to \
+                     produce the signature.",
+                ))
+                .arg(SPENDER.def().about(
+                    "The address allowed to transfer the owner's tokens.",
+                ))
+                .arg(TOKEN.def().about("The token to approve."))
+                .arg(AMOUNT.def().about(
+                    "The maximum amount in decimal that the spender may \
+                     transfer. It replaces any previous allowance.",
+                ))
+                .arg(EXPIRY.def().about(
+                    "The first epoch in which the allowance can no longer be \
+                     used. Without it, the allowance doesn't expire.",
+                ))
+        }
+    }
+
+    /// Revoke transaction arguments
+    #[derive(Debug)]
+    pub struct TxRevoke {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Owner of the tokens
+        pub owner: Address,
+        /// Address whose allowance is revoked
+        pub spender: Address,
+        /// Token address
+        pub token: Address,
+    }
+
+    impl Args for TxRevoke {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let owner = OWNER.parse(matches);
+            let spender = SPENDER.parse(matches);
+            let token = TOKEN.parse(matches);
+            Self {
+                tx,
+                owner,
+                spender,
+                token,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(OWNER.def().about(
+                    "The owner of the tokens. The owner's key is used to \
+                     produce the signature.",
+                ))
+                .arg(
+                    SPENDER
+                        .def()
+                        .about("The address whose allowance to revoke."),
+                )
+                .arg(TOKEN.def().about("The token of the allowance."))
+        }
+    }
+
+    /// Transfer from transaction arguments
+    #[derive(Debug)]
+    pub struct TxTransferFrom {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Address spending its allowance
+        pub spender: Address,
+        /// Owner of the tokens
+        pub owner: Address,
+        /// Transfer target address
+        pub target: Address,
+        /// Transferred token address
+        pub token: Address,
+        /// Transferred token amount in decimal notation
+        pub amount: token::DecimalAmount,
+    }
+
+    impl Args for TxTransferFrom {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let spender = SPENDER.parse(matches);
+            let owner = OWNER.parse(matches);
+            let target = TARGET.parse(matches);
+            let token = TOKEN.parse(matches);
+            let amount = AMOUNT.parse(matches);
+            Self {
+                tx,
+                spender,
+                owner,
+                target,
+                token,
+                amount,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(SPENDER.def().about(
+                    "The address spending its allowance. The spender's key is \
+                     used to produce the signature.",
+                ))
+                .arg(OWNER.def().about("The owner of the tokens."))
+                .arg(TARGET.def().about("The target account address."))
+                .arg(TOKEN.def().about("The transfer token."))
+                .arg(AMOUNT.def().about("The amount to transfer in decimal."))
+        }
+    }
+
+    /// Shield transaction arguments
+    #[derive(Debug)]
+    pub struct TxShield {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Source of the shielded tokens
+        pub source: Address,
+        /// Shielded token address
+        pub token: Address,
+        /// Shielded token amount in decimal notation
+        pub amount: token::DecimalAmount,
+        /// Owner of the new note
+        pub payment_address: PaymentAddress,
+        /// Path to the file to which the new note is written
+        pub output_path: PathBuf,
+    }
+
+    impl Args for TxShield {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let source = SOURCE.parse(matches);
+            let token = TOKEN.parse(matches);
+            let amount = AMOUNT.parse(matches);
+            let payment_address = PAYMENT_ADDRESS.parse(matches);
+            let output_path = OUTPUT_PATH.parse(matches);
+            Self {
+                tx,
+                source,
+                token,
+                amount,
+                payment_address,
+                output_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(SOURCE.def().about(
+                    "The source account address. The source's key is used to \
+                     produce the signature.",
+                ))
+                .arg(TOKEN.def().about("The token to shield."))
+                .arg(AMOUNT.def().about("The amount to shield in decimal."))
+                .arg(
+                    PAYMENT_ADDRESS
+                        .def()
+                        .about("The payment address of the new note's owner."),
+                )
+                .arg(OUTPUT_PATH.def().about(
+                    "The path to the file to which the new note is written. \
+                     The note has to be sent to its owner.",
+                ))
+        }
+    }
+
+    /// Unshield transaction arguments
+    #[derive(Debug)]
+    pub struct TxUnshield {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Spending key of the spent notes
+        pub spending_key: SpendingKey,
+        /// Paths to the files of the spent notes
+        pub note_paths: Vec<PathBuf>,
+        /// Target of the unshielded tokens
+        pub target: Address,
+        /// Unshielded token amount in decimal notation
+        pub amount: token::DecimalAmount,
+        /// Path to the file to which the change note is written
+        pub change_path: Option<PathBuf>,
+    }
+
+    impl Args for TxUnshield {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let spending_key = SPENDING_KEY.parse(matches);
+            let note_paths = NOTE_PATHS.parse(matches);
+            let target = TARGET.parse(matches);
+            let amount = AMOUNT.parse(matches);
+            let change_path = CHANGE_PATH.parse(matches);
+            Self {
+                tx,
+                spending_key,
+                note_paths,
+                target,
+                amount,
+                change_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(
+                    SPENDING_KEY
+                        .def()
+                        .about("The spending key of the spent notes."),
+                )
+                .arg(NOTE_PATHS.def().about(
+                    "The paths to the files of at most 2 spent notes of the \
+                     same token.",
+                ))
+                .arg(TARGET.def().about("The target account address."))
+                .arg(AMOUNT.def().about("The amount to unshield in decimal."))
+                .arg(CHANGE_PATH.def().about(
+                    "The path to the file to which the change note is \
+                     written. Required if the notes are worth more than the \
+                     amount.",
+                ))
+        }
+    }
+
+    /// Shielded transfer transaction arguments
+    #[derive(Debug)]
+    pub struct TxShieldedTransfer {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Spending key of the spent notes
+        pub spending_key: SpendingKey,
+        /// Paths to the files of the spent notes
+        pub note_paths: Vec<PathBuf>,
+        /// Owner of the new note
+        pub payment_address: PaymentAddress,
+        /// Transferred token amount in decimal notation
+        pub amount: token::DecimalAmount,
+        /// Path to the file to which the new note is written
+        pub output_path: PathBuf,
+        /// Path to the file to which the change note is written
+        pub change_path: Option<PathBuf>,
+    }
+
+    impl Args for TxShieldedTransfer {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let spending_key = SPENDING_KEY.parse(matches);
+            let note_paths = NOTE_PATHS.parse(matches);
+            let payment_address = PAYMENT_ADDRESS.parse(matches);
+            let amount = AMOUNT.parse(matches);
+            let output_path = OUTPUT_PATH.parse(matches);
+            let change_path = CHANGE_PATH.parse(matches);
+            Self {
+                tx,
+                spending_key,
+                note_paths,
+                payment_address,
+                amount,
+                output_path,
+                change_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(
+                    SPENDING_KEY
+                        .def()
+                        .about("The spending key of the spent notes."),
+                )
+                .arg(NOTE_PATHS.def().about(
+                    "The paths to the files of at most 2 spent notes of the \
+                     same token.",
+                ))
+                .arg(
+                    PAYMENT_ADDRESS
+                        .def()
+                        .about("The payment address of the new note's owner."),
+                )
+                .arg(AMOUNT.def().about("The amount to transfer in decimal."))
+                .arg(OUTPUT_PATH.def().about(
+                    "The path to the file to which the new note is written. \
+                     The note has to be sent to its owner.",
+                ))
+                .arg(CHANGE_PATH.def().about(
+                    "The path to the file to which the change note is \
+                     written. Required if the notes are worth more than the \
+                     amount.",
+                ))
+        }
+    }
+
+    /// Transaction to initialize a new account
+    #[derive(Debug)]
+    pub struct TxInitAccount {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Address of the source account
+        pub source: Address,
+        /// Path to the VP WASM code file for the new account
+        pub vp_code_path: Option<PathBuf>,
+        /// Public key for the new account
+        pub public_key: PublicKey,
+    }
+
+    impl Args for TxInitAccount {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let source = SOURCE.parse(matches);
+            let vp_code_path = CODE_PATH_OPT.parse(matches);
+            let public_key = PUBLIC_KEY.parse(matches);
+            Self {
+                tx,
+                source,
+                vp_code_path,
+                public_key,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(SOURCE.def().about(
+                    "The source account's address that signs the transaction.",
+                ))
+                .arg(CODE_PATH_OPT.def().about(
+                    "The path to the validity predicate WASM code to be used \
+                     for the new account. Uses the default user VP if none \
+                     specified.",
+                ))
+                .arg(PUBLIC_KEY.def().about(
+                    "A public key to be used for the new account in \
+                     hexadecimal encoding.",
+                ))
+        }
+    }
+
+    /// Transaction to initialize a new multisig account
+    #[derive(Debug)]
+    pub struct TxInitMultisigAccount {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Address of the source account
+        pub source: Address,
+        /// Path to the VP WASM code file for the new account
+        pub vp_code_path: Option<PathBuf>,
+        /// Public keys for the new account
+        pub public_keys: Vec<PublicKey>,
+        /// The minimum number of signatures required by the new account
+        pub threshold: u64,
+    }
+
+    impl Args for TxInitMultisigAccount {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let source = SOURCE.parse(matches);
+            let vp_code_path = CODE_PATH_OPT.parse(matches);
+            let public_keys = PUBLIC_KEYS.parse(matches);
+            let threshold = THRESHOLD.parse(matches);
+            Self {
+                tx,
+                source,
+                vp_code_path,
+                public_keys,
+                threshold,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(SOURCE.def().about(
+                    "The source account's address that signs the transaction.",
+                ))
+                .arg(CODE_PATH_OPT.def().about(
+                    "The path to the validity predicate WASM code to be used \
+                     for the new account. Uses the default multisig VP if \
+                     none specified.",
+                ))
+                .arg(PUBLIC_KEYS.def().required(true).about(
+                    "The public keys to be used for the new account in \
+                     hexadecimal encoding.",
+                ))
+                .arg(THRESHOLD.def().about(
+                    "The minimum number of signatures from the public keys \
+                     required to authorize a transaction.",
+                ))
+        }
+    }
+
+    /// Transaction to initialize a new vesting account
+    #[derive(Debug)]
+    pub struct TxInitVestingAccount {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Address of the source account
+        pub source: Address,
+        /// Path to the VP WASM code file for the new account
+        pub vp_code_path: Option<PathBuf>,
+        /// Public key for the new account
+        pub public_key: PublicKey,
+        /// The vested token
+        pub token: Address,
+        /// The vested amount in decimal notation
+        pub amount: token::DecimalAmount,
+        /// The epoch from which the amount starts to unlock linearly
+        pub start: Epoch,
+        /// The first epoch in which any tokens are unlocked
+        pub cliff: Epoch,
+        /// The first epoch in which all the tokens are unlocked
+        pub end: Epoch,
+        /// The block time before which all the tokens are locked
+        pub cliff_time: Option<DateTimeUtc>,
+    }
+
+    impl Args for TxInitVestingAccount {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let source = SOURCE.parse(matches);
+            let vp_code_path = CODE_PATH_OPT.parse(matches);
+            let public_key = PUBLIC_KEY.parse(matches);
+            let token = TOKEN.parse(matches);
+            let amount = AMOUNT.parse(matches);
+            let start = Epoch(START_EPOCH.parse(matches));
+            let cliff = CLIFF_EPOCH.parse(matches).map(Epoch).unwrap_or(start);
+            let end = Epoch(END_EPOCH.parse(matches));
+            let cliff_time = CLIFF_TIME.parse(matches);
+            Self {
+                tx,
+                source,
+                vp_code_path,
+                public_key,
+                token,
+                amount,
+                start,
+                cliff,
+                end,
+                cliff_time,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(SOURCE.def().about(
+                    "The source account's address that signs the transaction.",
+                ))
+                .arg(CODE_PATH_OPT.def().about(
+                    "The path to the validity predicate WASM code to be used \
+                     for the new account. Uses the default vesting VP if none \
+                     specified.",
+                ))
+                .arg(PUBLIC_KEY.def().about(
+                    "A public key to be used for the new account in \
+                     hexadecimal encoding.",
+                ))
+                .arg(TOKEN.def().about("The vested token."))
+                .arg(AMOUNT.def().about(
+                    "The amount in decimal that is locked by the vesting \
+                     schedule. The tokens have to be transferred to the new \
+                     account separately.",
+                ))
+                .arg(START_EPOCH.def().about(
+                    "The epoch from which the amount starts to unlock \
+                     linearly.",
+                ))
+                .arg(CLIFF_EPOCH.def().about(
+                    "The first epoch in which any tokens are unlocked. \
+                     Defaults to the start epoch.",
+                ))
+                .arg(END_EPOCH.def().about(
+                    "The first epoch in which all the tokens are unlocked. \
+                     Set it equal to the cliff epoch to unlock all the tokens \
+                     at once.",
+                ))
+                .arg(CLIFF_TIME.def().about(
+                    "The block time in RFC 3339 format, e.g. \
+                     2022-01-01T00:00:00Z, before which all the tokens are \
+                     locked, regardless of the epoch.",
+                ))
+        }
+    }
+
+    /// Transaction to initialize a new token
+    #[derive(Debug)]
+    pub struct TxInitToken {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Address of the source account
+        pub source: Address,
+        /// Path to the VP WASM code file for the new token
+        pub vp_code_path: Option<PathBuf>,
+        /// The token's ticker symbol
+        pub symbol: String,
+        /// The token's name
+        pub name: String,
+        /// The number of decimal places of the token's amounts
+        pub decimals: u8,
+        /// The address allowed to mint the token, if any
+        pub minter: Option<Address>,
+    }
+
+    impl Args for TxInitToken {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let source = SOURCE.parse(matches);
+            let vp_code_path = CODE_PATH_OPT.parse(matches);
+            let symbol = SYMBOL.parse(matches);
+            let name = NAME.parse(matches);
+            let decimals = DECIMALS.parse(matches);
+            let minter = MINTER.parse(matches);
+            Self {
+                tx,
+                source,
+                vp_code_path,
+                symbol,
+                name,
+                decimals,
+                minter,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(SOURCE.def().about(
+                    "The source account's address that signs the transaction.",
+                ))
+                .arg(CODE_PATH_OPT.def().about(
+                    "The path to the validity predicate WASM code to be used \
+                     for the new token's account. Uses the default token VP \
+                     if none specified. Note that the tokens in the registry \
+                     are validated by the native token VP instead.",
+                ))
+                .arg(SYMBOL.def().about(
+                    "The token's ticker symbol, up to 12 alphanumeric \
+                     characters. It must not be used by any other token, \
+                     regardless of its case.",
+                ))
+                .arg(NAME.def().about("The token's name."))
+                .arg(DECIMALS.def().about(
+                    "The number of decimal places of the token's amounts, at \
+                     most 6.",
+                ))
+                .arg(MINTER.def().about(
+                    "The address allowed to mint new tokens. No one can mint \
+                     the token if none specified.",
+                ))
+        }
+    }
+
+    /// Build an unsigned transaction arguments
+    #[derive(Debug)]
+    pub struct TxBuild {
+        /// Path to the tx WASM code file
+        pub code_path: PathBuf,
+        /// Path to the data file
+        pub data_path: Option<PathBuf>,
+        /// Path to the file to write the unsigned transaction to
+        pub output_path: PathBuf,
+    }
+
+    impl Args for TxBuild {
+        fn parse(matches: &ArgMatches) -> Self {
+            let code_path = CODE_PATH.parse(matches);
+            let data_path = DATA_PATH_OPT.parse(matches);
+            let output_path = OUTPUT_PATH.parse(matches);
+            Self {
+                code_path,
+                data_path,
+                output_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(
+                CODE_PATH
+                    .def()
+                    .about("The path to the transaction's WASM code."),
+            )
+            .arg(DATA_PATH_OPT.def().about(
+                "The data file at this path containing arbitrary bytes will \
+                 be passed to the transaction code when it's executed.",
+            ))
+            .arg(OUTPUT_PATH.def().about(
+                "The path to the file to write the unsigned transaction to.",
+            ))
+        }
+    }
+
+    /// Build an unsigned treasury spend transaction arguments
+    #[derive(Debug)]
+    pub struct TxBuildTreasurySpend {
+        /// The address of the ledger node as host:port, used to look-up the
+        /// token's decimals
+        pub ledger_address: tendermint::net::Address,
+        /// The recipient of the funds
+        pub target: Address,
+        /// The transferred token
+        pub token: Address,
+        /// The transferred amount in decimal notation, converted using the
+        /// token's decimals from the token registry
+        pub amount: token::DecimalAmount,
+        /// Path to the file to write the unsigned transaction to
+        pub output_path: PathBuf,
+    }
+
+    impl Args for TxBuildTreasurySpend {
+        fn parse(matches: &ArgMatches) -> Self {
+            let ledger_address = LEDGER_ADDRESS_DEFAULT.parse(matches);
+            let target = TARGET.parse(matches);
+            let token = TOKEN.parse(matches);
+            let amount = AMOUNT.parse(matches);
+            let output_path = OUTPUT_PATH.parse(matches);
+            Self {
+                ledger_address,
+                target,
+                token,
+                amount,
+                output_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(LEDGER_ADDRESS_DEFAULT.def().about(LEDGER_ADDRESS_ABOUT))
+                .arg(TARGET.def().about("The recipient of the funds."))
+                .arg(TOKEN.def().about("The spent token."))
+                .arg(AMOUNT.def().about("The amount to spend in decimal."))
+                .arg(OUTPUT_PATH.def().about(
+                    "The path to the file to write the unsigned transaction \
+                     to.",
+                ))
+        }
+    }
+
+    /// Sign an unsigned transaction offline arguments
+    #[derive(Debug)]
+    pub struct TxSign {
+        /// Path to the unsigned transaction file
+        pub tx_path: PathBuf,
+        /// Address whose key is used to sign the transaction
+        pub signer: Address,
+        /// Path to the file to write the public key and signature to
+        pub output_path: PathBuf,
+    }
+
+    impl Args for TxSign {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx_path = TX_PATH.parse(matches);
+            let signer = SIGNER.parse(matches);
+            let output_path = OUTPUT_PATH.parse(matches);
+            Self {
+                tx_path,
+                signer,
+                output_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(
+                TX_PATH
+                    .def()
+                    .about("The path to the unsigned transaction file."),
+            )
+            .arg(SIGNER.def().about("The key to sign the transaction with."))
+            .arg(OUTPUT_PATH.def().about(
+                "The path to the file to write the public key and the \
+                 signature to.",
+            ))
+        }
+    }
+
+    /// Combine signatures collected offline arguments
+    #[derive(Debug)]
+    pub struct TxCombineSignatures {
+        /// Path to the unsigned transaction file
+        pub tx_path: PathBuf,
+        /// Paths to the signature files produced by `sign-tx`
+        pub signature_paths: Vec<PathBuf>,
+        /// Path to the file to write the signed transaction to
+        pub output_path: PathBuf,
+    }
+
+    impl Args for TxCombineSignatures {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx_path = TX_PATH.parse(matches);
+            let signature_paths = SIGNATURE_PATHS.parse(matches);
+            let output_path = OUTPUT_PATH.parse(matches);
+            Self {
+                tx_path,
+                signature_paths,
+                output_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(
+                TX_PATH
+                    .def()
+                    .about("The path to the unsigned transaction file."),
+            )
+            .arg(SIGNATURE_PATHS.def().required(true).about(
+                "The paths to the signature files produced by the `sign-tx` \
+                 command.",
+            ))
+            .arg(OUTPUT_PATH.def().about(
+                "The path to the file to write the signed transaction to.",
+            ))
+        }
+    }
+
+    /// Broadcast a transaction from a file arguments
+    #[derive(Debug)]
+    pub struct TxBroadcast {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Path to the transaction file
+        pub tx_path: PathBuf,
+    }
+
+    impl Args for TxBroadcast {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let tx_path = TX_PATH.parse(matches);
+            Self { tx, tx_path }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>().arg(
+                TX_PATH
+                    .def()
+                    .about("The path to the signed transaction file."),
+            )
+        }
+    }
+
+    /// Transaction to update a VP arguments
+    #[derive(Debug)]
+    pub struct TxUpdateVp {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Path to the VP WASM code file
+        pub vp_code_path: PathBuf,
+        /// Address of the account whose VP is to be updated
+        pub addr: Address,
+    }
+
+    impl Args for TxUpdateVp {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let vp_code_path = CODE_PATH.parse(matches);
+            let addr = ADDRESS.parse(matches);
+            Self {
+                tx,
+                vp_code_path,
+                addr,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(
+                    CODE_PATH.def().about(
+                        "The path to the new validity predicate WASM code.",
+                    ),
+                )
+                .arg(ADDRESS.def().about(
+                    "The account's address. It's key is used to produce the \
+                     signature.",
+                ))
+        }
+    }
+
+    /// Transaction to update an account's public key
+    #[derive(Debug)]
+    pub struct TxUpdateKey {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Address of the account whose public key is to be updated
+        pub addr: Address,
+        /// The new public key
+        pub public_key: PublicKey,
+    }
+
+    impl Args for TxUpdateKey {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let addr = ADDRESS.parse(matches);
+            let public_key = PUBLIC_KEY.parse(matches);
+            Self {
+                tx,
+                addr,
+                public_key,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(ADDRESS.def().about(
+                    "The account's address. Its current key is used to \
+                     produce the signature.",
+                ))
+                .arg(
+                    PUBLIC_KEY
+                        .def()
+                        .about("The new public key of the account."),
+                )
+        }
+    }
+
+    /// Transaction to set or remove an account's recovery configuration
+    #[derive(Debug)]
+    pub struct TxUpdateRecovery {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Address of the account
+        pub addr: Address,
+        /// The guardians of the account, empty to remove the recovery
+        pub guardians: Vec<Address>,
+        /// The number of guardians required to approve a new key
+        pub threshold: Option<u64>,
+        /// The number of epochs after the last approval before the new key
+        /// can be used
+        pub delay: u64,
+    }
+
+    impl Args for TxUpdateRecovery {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let addr = ADDRESS.parse(matches);
+            let guardians = GUARDIANS.parse(matches);
+            let threshold = THRESHOLD_OPT.parse(matches);
+            let delay = DELAY.parse(matches);
+            Self {
+                tx,
+                addr,
+                guardians,
+                threshold,
+                delay,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(ADDRESS.def().about(
+                    "The account's address. Its key is used to produce the \
+                     signature.",
+                ))
+                .arg(GUARDIANS.def().about(
+                    "The addresses of the guardians. Without any guardians, \
+                     the recovery of the account is disabled.",
+                ))
+                .arg(THRESHOLD_OPT.def().about(
+                    "The number of guardians required to approve a new key. \
+                     Required with guardians.",
+                ))
+                .arg(DELAY.def().about(
+                    "The number of epochs after the last approval before the \
+                     new key can replace the account's key. Defaults to 0.",
+                ))
+        }
+    }
+
+    /// Transaction of a guardian's approval of a new key for an account
+    #[derive(Debug)]
+    pub struct TxProposeRecovery {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Address of the recovered account
+        pub owner: Address,
+        /// Address of the approving guardian
+        pub guardian: Address,
+        /// The proposed public key
+        pub public_key: PublicKey,
+    }
+
+    impl Args for TxProposeRecovery {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let owner = OWNER.parse(matches);
+            let guardian = GUARDIAN.parse(matches);
+            let public_key = PUBLIC_KEY.parse(matches);
+            Self {
+                tx,
+                owner,
+                guardian,
+                public_key,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(OWNER.def().about("The address of the recovered account."))
+                .arg(GUARDIAN.def().about(
+                    "The guardian's address. Its key is used to produce the \
+                     signature.",
+                ))
+                .arg(
+                    PUBLIC_KEY
+                        .def()
+                        .about("The proposed public key of the account."),
+                )
+        }
+    }
+
+    /// Transaction to veto or execute an account's recovery proposal
+    #[derive(Debug)]
+    pub struct TxRecovery {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Address of the recovered account
+        pub owner: Address,
+        /// Address whose key is used to sign the transaction
+        pub signer: Address,
+    }
+
+    impl Args for TxRecovery {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let owner = OWNER.parse(matches);
+            let signer = SIGNER.parse(matches);
+            Self { tx, owner, signer }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(OWNER.def().about("The address of the recovered account."))
+                .arg(
+                    SIGNER.def().about("The key to sign the transaction with."),
+                )
+        }
+    }
+
+    /// Transaction to submit a new governance proposal
+    #[derive(Debug)]
+    pub struct TxInitProposal {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Address of the proposal's author
+        pub author: Address,
+        /// Path to the JSON file with the proposal's content
+        pub data_path: PathBuf,
+        /// The deposit in decimal notation. The minimum deposit is used if
+        /// not specified.
+        pub deposit: Option<token::DecimalAmount>,
+    }
+
+    impl Args for TxInitProposal {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let author = AUTHOR.parse(matches);
+            let data_path = DATA_PATH.parse(matches);
+            let deposit = DEPOSIT.parse(matches);
             Self {
                 tx,
-                source,
-                target,
-                token,
-                amount,
+                author,
+                data_path,
+                deposit,
             }
         }
 
         fn def(app: App) -> App {
             app.add_args::<Tx>()
-                .arg(SOURCE.def().about(
-                    "The source account address. The source's key is used to \
+                .arg(AUTHOR.def().about(
+                    "The author's address. Its key is used to produce the \
+                     signature and it pays the deposit.",
+                ))
+                .arg(DATA_PATH.def().about(
+                    "The path to a JSON file with the proposal's content.",
+                ))
+                .arg(DEPOSIT.def().about(
+                    "The deposit of XAN in decimal. Defaults to the minimum \
+                     proposal deposit.",
+                ))
+        }
+    }
+
+    /// Transaction to vote on a governance proposal
+    #[derive(Debug)]
+    pub struct TxVoteProposal {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// The proposal's identifier
+        pub id: u64,
+        /// Address of the voter
+        pub voter: Address,
+        /// The vote
+        pub vote: Vote,
+    }
+
+    impl Args for TxVoteProposal {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let id = PROPOSAL_ID.parse(matches);
+            let voter = VOTER.parse(matches);
+            let vote = VOTE.parse(matches);
+            Self {
+                tx,
+                id,
+                voter,
+                vote,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(PROPOSAL_ID.def().about("The proposal's identifier."))
+                .arg(VOTER.def().about(
+                    "The voter's address. Its key is used to produce the \
+                     signature.",
+                ))
+                .arg(VOTE.def().about("The vote, either \"yay\" or \"nay\"."))
+        }
+    }
+
+    /// Transaction to claim the rewards of a bond to a validator
+    #[derive(Debug)]
+    pub struct TxClaimRewards {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Address of the delegator
+        pub delegator: Address,
+        /// Address of the validator
+        pub validator: Address,
+    }
+
+    impl Args for TxClaimRewards {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let delegator = DELEGATOR.parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            Self {
+                tx,
+                delegator,
+                validator,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(DELEGATOR.def().about(
+                    "The delegator's address, which gets the rewards. Its key \
+                     is used to produce the signature.",
+                ))
+                .arg(VALIDATOR.def().about("The validator's address."))
+        }
+    }
+
+    /// Transaction to register a new validator
+    #[derive(Debug)]
+    pub struct TxBecomeValidator {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Address of the validator's account
+        pub address: Address,
+        /// The validator's consensus key
+        pub consensus_key: ed25519::PublicKey,
+        /// The commission rate in basis points
+        pub commission_rate: u64,
+        /// The maximum change of the commission rate in an epoch
+        pub max_commission_rate_change: u64,
+    }
+
+    impl Args for TxBecomeValidator {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let address = ADDRESS.parse(matches);
+            let consensus_key = CONSENSUS_KEY.parse(matches);
+            let commission_rate = COMMISSION_RATE.parse(matches);
+            let max_commission_rate_change =
+                MAX_COMMISSION_RATE_CHANGE.parse(matches);
+            Self {
+                tx,
+                address,
+                consensus_key,
+                commission_rate,
+                max_commission_rate_change,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(ADDRESS.def().about(
+                    "The validator's account address. Its key is used to \
                      produce the signature.",
                 ))
-                .arg(TARGET.def().about("The target account address."))
-                .arg(TOKEN.def().about("The transfer token."))
-                .arg(AMOUNT.def().about("The amount to transfer in decimal."))
+                .arg(CONSENSUS_KEY.def().about(
+                    "The validator's consensus key, as printed by `anoma node \
+                     ledger gen-validator-key`.",
+                ))
+                .arg(COMMISSION_RATE.def().about(
+                    "The commission rate in basis points, at most 10000.",
+                ))
+                .arg(MAX_COMMISSION_RATE_CHANGE.def().about(
+                    "The maximum change of the commission rate in an epoch, \
+                     in basis points. It cannot be changed later.",
+                ))
         }
     }
 
-    /// Transaction to initialize a new account
+    /// Transaction to bond XAN to a validator
     #[derive(Debug)]
-    pub struct TxInitAccount {
+    pub struct TxBond {
         /// Common tx arguments
         pub tx: Tx,
-        /// Address of the source account
-        pub source: Address,
-        /// Path to the VP WASM code file for the new account
-        pub vp_code_path: Option<PathBuf>,
-        /// Public key for the new account
-        pub public_key: PublicKey,
+        /// Address of the delegator
+        pub delegator: Address,
+        /// Address of the validator
+        pub validator: Address,
+        /// The amount of XAN
+        pub amount: token::DecimalAmount,
     }
 
-    impl Args for TxInitAccount {
+    impl Args for TxBond {
         fn parse(matches: &ArgMatches) -> Self {
             let tx = Tx::parse(matches);
-            let source = SOURCE.parse(matches);
-            let vp_code_path = CODE_PATH_OPT.parse(matches);
-            let public_key = PUBLIC_KEY.parse(matches);
+            let delegator = DELEGATOR.parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            let amount = AMOUNT.parse(matches);
             Self {
                 tx,
-                source,
-                vp_code_path,
-                public_key,
+                delegator,
+                validator,
+                amount,
             }
         }
 
         fn def(app: App) -> App {
             app.add_args::<Tx>()
-                .arg(SOURCE.def().about(
-                    "The source account's address that signs the transaction.",
+                .arg(DELEGATOR.def().about(
+                    "The delegator's address, which pays the bonded XAN. Its \
+                     key is used to produce the signature.",
                 ))
-                .arg(CODE_PATH_OPT.def().about(
-                    "The path to the validity predicate WASM code to be used \
-                     for the new account. Uses the default user VP if none \
-                     specified.",
+                .arg(VALIDATOR.def().about("The validator's address."))
+                .arg(AMOUNT.def().about("The amount of XAN in decimal."))
+        }
+    }
+
+    /// Transaction to unbond XAN from a validator
+    #[derive(Debug)]
+    pub struct TxUnbond {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Address of the delegator
+        pub delegator: Address,
+        /// Address of the validator
+        pub validator: Address,
+        /// The amount of XAN
+        pub amount: token::DecimalAmount,
+    }
+
+    impl Args for TxUnbond {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let delegator = DELEGATOR.parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            let amount = AMOUNT.parse(matches);
+            Self {
+                tx,
+                delegator,
+                validator,
+                amount,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(DELEGATOR.def().about(
+                    "The delegator's address. Its key is used to produce the \
+                     signature.",
                 ))
-                .arg(PUBLIC_KEY.def().about(
-                    "A public key to be used for the new account in \
-                     hexadecimal encoding.",
+                .arg(VALIDATOR.def().about("The validator's address."))
+                .arg(AMOUNT.def().about("The amount of XAN in decimal."))
+        }
+    }
+
+    /// Transaction to withdraw the XAN unbonded from a validator
+    #[derive(Debug)]
+    pub struct TxWithdraw {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Address of the delegator
+        pub delegator: Address,
+        /// Address of the validator
+        pub validator: Address,
+    }
+
+    impl Args for TxWithdraw {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let delegator = DELEGATOR.parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            Self {
+                tx,
+                delegator,
+                validator,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(DELEGATOR.def().about(
+                    "The delegator's address, which gets the withdrawn XAN. \
+                     Its key is used to produce the signature.",
                 ))
+                .arg(VALIDATOR.def().about("The validator's address."))
         }
     }
 
-    /// Transaction to update a VP arguments
+    /// Transaction to change a validator's commission rate
     #[derive(Debug)]
-    pub struct TxUpdateVp {
+    pub struct TxChangeCommission {
         /// Common tx arguments
         pub tx: Tx,
-        /// Path to the VP WASM code file
-        pub vp_code_path: PathBuf,
-        /// Address of the account whose VP is to be updated
-        pub addr: Address,
+        /// Address of the validator
+        pub validator: Address,
+        /// The new commission rate in basis points
+        pub commission_rate: u64,
     }
 
-    impl Args for TxUpdateVp {
+    impl Args for TxChangeCommission {
         fn parse(matches: &ArgMatches) -> Self {
             let tx = Tx::parse(matches);
-            let vp_code_path = CODE_PATH.parse(matches);
-            let addr = ADDRESS.parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            let commission_rate = COMMISSION_RATE.parse(matches);
             Self {
                 tx,
-                vp_code_path,
-                addr,
+                validator,
+                commission_rate,
             }
         }
 
         fn def(app: App) -> App {
             app.add_args::<Tx>()
-                .arg(
-                    CODE_PATH.def().about(
-                        "The path to the new validity predicate WASM code.",
-                    ),
-                )
-                .arg(ADDRESS.def().about(
-                    "The account's address. It's key is used to produce the \
+                .arg(VALIDATOR.def().about(
+                    "The validator's address. Its key is used to produce the \
                      signature.",
                 ))
+                .arg(COMMISSION_RATE.def().about(
+                    "The new commission rate in basis points. It can change \
+                     at most by the validator's maximum commission rate \
+                     change once per epoch.",
+                ))
+        }
+    }
+
+    /// Transaction to unjail a validator
+    #[derive(Debug)]
+    pub struct TxUnjail {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Address of the validator
+        pub validator: Address,
+    }
+
+    impl Args for TxUnjail {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            Self { tx, validator }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>().arg(VALIDATOR.def().about(
+                "The validator's address. Its key is used to produce the \
+                 signature.",
+            ))
+        }
+    }
+
+    /// Query the claimable rewards of a bond
+    #[derive(Debug)]
+    pub struct QueryRewards {
+        /// Common query args
+        pub query: Query,
+        /// Address of the delegator
+        pub delegator: Address,
+        /// Address of the validator
+        pub validator: Address,
+    }
+
+    impl Args for QueryRewards {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let delegator = DELEGATOR.parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            Self {
+                query,
+                delegator,
+                validator,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query>()
+                .arg(DELEGATOR.def().about("The delegator's address."))
+                .arg(VALIDATOR.def().about("The validator's address."))
+        }
+    }
+
+    /// Query a validator's state
+    #[derive(Debug)]
+    pub struct QueryValidator {
+        /// Common query args
+        pub query: Query,
+        /// Address of the validator
+        pub validator: Address,
+    }
+
+    impl Args for QueryValidator {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            Self { query, validator }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query>()
+                .arg(VALIDATOR.def().about("The validator's address."))
+        }
+    }
+
+    /// Query a governance proposal
+    #[derive(Debug)]
+    pub struct QueryProposal {
+        /// Common query args
+        pub query: Query,
+        /// The proposal's identifier
+        pub id: u64,
+    }
+
+    impl Args for QueryProposal {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let id = PROPOSAL_ID.parse(matches);
+            Self { query, id }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query>()
+                .arg(PROPOSAL_ID.def().about("The proposal's identifier."))
+        }
+    }
+
+    /// Query an epoch
+    #[derive(Debug)]
+    pub struct QueryEpoch {
+        /// Common query args
+        pub query: Query,
+        /// The block height whose epoch to query, the current epoch if `None`
+        pub height: Option<BlockHeight>,
+    }
+
+    impl Args for QueryEpoch {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let height = HEIGHT_OPT.parse(matches).map(BlockHeight);
+            Self { query, height }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query>().arg(HEIGHT_OPT.def().about(
+                "The height of a committed block whose epoch to query. The \
+                 current epoch is queried if not set.",
+            ))
         }
     }
 
@@ -777,7 +3491,7 @@ pub mod args {
     impl Args for QueryBalance {
         fn parse(matches: &ArgMatches) -> Self {
             let query = Query::parse(matches);
-            let owner = OWNER.parse(matches);
+            let owner = OWNER_OPT.parse(matches);
             let token = TOKEN_OPT.parse(matches);
             Self {
                 query,
@@ -789,7 +3503,7 @@ pub mod args {
         fn def(app: App) -> App {
             app.add_args::<Query>()
                 .arg(
-                    OWNER
+                    OWNER_OPT
                         .def()
                         .about("The account address whose balance to query"),
                 )
@@ -1026,6 +3740,53 @@ pub mod args {
         }
     }
 
+    /// Arguments for generating a network of validator nodes
+    #[derive(Debug)]
+    pub struct InitNetwork {
+        /// The directory in which the nodes' base directories are generated
+        pub output_dir: PathBuf,
+        /// The number of validator nodes
+        pub num_validators: u16,
+        /// The self-bond of each validator
+        pub stake: token::Amount,
+        /// The genesis balance of XAN of each validator's account
+        pub balance: token::Amount,
+    }
+
+    impl Args for InitNetwork {
+        fn parse(matches: &ArgMatches) -> Self {
+            let output_dir = OUTPUT_DIR.parse(matches);
+            let num_validators = NUM_VALIDATORS.parse(matches);
+            let stake = STAKE.parse(matches);
+            let balance = BALANCE.parse(matches);
+            Self {
+                output_dir,
+                num_validators,
+                stake,
+                balance,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(OUTPUT_DIR.def().about(
+                "The directory in which the nodes' base directories are \
+                 generated.",
+            ))
+            .arg(
+                NUM_VALIDATORS
+                    .def()
+                    .about("The number of validator nodes. Defaults to 4."),
+            )
+            .arg(STAKE.def().about(
+                "The self-bond of XAN of each validator. Defaults to 1000000.",
+            ))
+            .arg(BALANCE.def().about(
+                "The genesis balance of XAN of each validator's account. \
+                 Defaults to 1000000.",
+            ))
+        }
+    }
+
     /// Common transaction arguments
     #[derive(Debug)]
     pub struct Tx {
@@ -1,14 +1,22 @@
 //! Client RPC queries
 
-use std::borrow::Cow;
+use std::collections::HashMap;
 use std::io::{self, Write};
 
-use anoma::types::{address, storage, token};
+use anoma::ledger::parameters::{self, Parameters};
+use anoma::types::address::{self, Address};
+use anoma::types::governance::{
+    self, GovernanceParameters, Proposal, ProposalResult, Vote,
+};
+use anoma::types::key::ed25519;
+use anoma::types::shielded::{self, NoteCommitment};
+use anoma::types::token::TokenInfo;
+use anoma::types::{pos, storage, token, treasury};
 use borsh::BorshDeserialize;
 use tendermint_rpc::{Client, HttpClient};
 
 use crate::cli::args;
-use crate::node::ledger::rpc::{Path, PrefixValue};
+use crate::node::ledger::rpc::{NextEpoch, Path, PrefixValue};
 
 /// Dry run a transaction
 pub async fn dry_run_tx(
@@ -24,55 +32,69 @@ pub async fn dry_run_tx(
     println!("{:#?}", response);
 }
 
-/// Query token balance(s)
+/// Query token balance(s). The tokens, their symbols and decimals are read
+/// from the token registry.
 pub async fn query_balance(args: args::QueryBalance) {
     let client = HttpClient::new(args.query.ledger_address).unwrap();
-    let tokens = address::tokens();
+    let tokens = query_tokens(client.clone()).await;
+    let symbol = |token: &Address| -> String {
+        tokens
+            .get(token)
+            .map(|info| info.symbol.clone())
+            .unwrap_or_else(|| token.to_string())
+    };
+    let format_balance = |token: &Address, balance: token::Amount| -> String {
+        match tokens.get(token) {
+            Some(info) => balance.to_string_with_decimals(info.decimals),
+            None => balance.to_string(),
+        }
+    };
     match (args.token.as_ref(), args.owner.as_ref()) {
         (Some(token), Some(owner)) => {
             let key = token::balance_key(token, owner);
-            let balance: token::Amount = query_storage_value(client, key).await;
-            let currency_code = tokens
-                .get(token)
-                .map(|c| Cow::Borrowed(*c))
-                .unwrap_or_else(|| Cow::Owned(token.to_string()));
-            println!("{}: {}", currency_code, balance);
+            let balance: token::Amount =
+                query_storage_value(client, key).await.unwrap_or_default();
+            println!("{}: {}", symbol(token), format_balance(token, balance));
         }
         (None, Some(owner)) => {
-            for (token, currency_code) in tokens {
-                let key = token::balance_key(&token, owner);
+            for token in tokens.keys() {
+                let key = token::balance_key(token, owner);
                 let balance: token::Amount =
-                    query_storage_value(client.clone(), key).await;
-                println!("{}: {}", currency_code, balance);
+                    query_storage_value(client.clone(), key)
+                        .await
+                        .unwrap_or_default();
+                println!(
+                    "{}: {}",
+                    symbol(token),
+                    format_balance(token, balance)
+                );
             }
         }
         (Some(token), None) => {
             let key = token::balance_prefix(token);
             let balances =
                 query_storage_prefix::<token::Amount>(client, key).await;
-            let currency_code = tokens
-                .get(token)
-                .map(|c| Cow::Borrowed(*c))
-                .unwrap_or_else(|| Cow::Owned(token.to_string()));
             let stdout = io::stdout();
             let mut w = stdout.lock();
-            writeln!(w, "Token {}:", currency_code).unwrap();
+            writeln!(w, "Token {}:", symbol(token)).unwrap();
             for (key, balance) in balances {
                 let owner = token::is_any_token_balance_key(&key).unwrap();
+                let balance = format_balance(token, balance);
                 writeln!(w, "  {}, owned by {}", balance, owner).unwrap();
             }
         }
         (None, None) => {
             let stdout = io::stdout();
             let mut w = stdout.lock();
-            for (token, currency_code) in tokens {
-                let key = token::balance_prefix(&token);
+            for token in tokens.keys() {
+                let key = token::balance_prefix(token);
                 let balances =
                     query_storage_prefix::<token::Amount>(client.clone(), key)
                         .await;
-                writeln!(w, "Token {}:", currency_code).unwrap();
+                writeln!(w, "Token {}:", symbol(token)).unwrap();
                 for (key, balance) in balances {
                     let owner = token::is_any_token_balance_key(&key).unwrap();
+                    let balance = format_balance(token, balance);
                     writeln!(w, "  {}, owned by {}", balance, owner).unwrap();
                 }
             }
@@ -80,12 +102,335 @@ pub async fn query_balance(args: args::QueryBalance) {
     }
 }
 
-/// Query a storage value and decode it with [`BorshDeserialize`].
-async fn query_storage_value<T>(client: HttpClient, key: storage::Key) -> T
+/// Query the treasury's admin, if any, and its balances of the tokens from the
+/// token registry.
+pub async fn query_treasury(args: args::Query) {
+    let client = HttpClient::new(args.ledger_address.clone()).unwrap();
+    let admin: Option<Address> =
+        query_storage_value(client, treasury::admin_key()).await;
+    println!("Treasury {}", treasury::treasury_address());
+    match admin {
+        Some(admin) => println!("Admin: {}", admin),
+        None => println!("No admin, only governance can spend the funds"),
+    }
+    query_balance(args::QueryBalance {
+        query: args,
+        owner: Some(treasury::treasury_address()),
+        token: None,
+    })
+    .await
+}
+
+/// Query the rewards of a bond to a validator that can be claimed, including
+/// the validator's commission when the delegator is the validator itself.
+pub async fn query_rewards(args: args::QueryRewards) {
+    let client = HttpClient::new(args.query.ledger_address.clone()).unwrap();
+    let bond: pos::Bond = match query_storage_value(
+        client.clone(),
+        pos::bond_key(&args.delegator, &args.validator),
+    )
+    .await
+    {
+        Some(bond) => bond,
+        None => {
+            eprintln!(
+                "No bond found from {} to {}",
+                args.delegator, args.validator
+            );
+            return;
+        }
+    };
+    let reward_acc: u128 = query_storage_value(
+        client.clone(),
+        pos::reward_acc_key(&args.validator),
+    )
+    .await
+    .unwrap_or_default();
+    let rewards = bond
+        .accrued(reward_acc)
+        .and_then(|accrued| accrued.checked_add(&bond.unclaimed))
+        .expect("The rewards overflowed");
+    println!("Bonded: {}", bond.amount);
+    println!("Rewards: {}", rewards);
+    if args.delegator == args.validator {
+        let commission: token::Amount =
+            query_storage_value(client, pos::commission_key(&args.validator))
+                .await
+                .unwrap_or_default();
+        println!("Commission: {}", commission);
+    }
+}
+
+/// Query a validator's consensus key, commission rate, stake, voting power and
+/// whether it's jailed.
+pub async fn query_validator(args: args::QueryValidator) {
+    let client = HttpClient::new(args.query.ledger_address.clone()).unwrap();
+    let validator = &args.validator;
+    let consensus_key: ed25519::PublicKey = match query_storage_value(
+        client.clone(),
+        pos::consensus_key_key(validator),
+    )
+    .await
+    {
+        Some(consensus_key) => consensus_key,
+        None => {
+            eprintln!("No validator found with address {}", validator);
+            return;
+        }
+    };
+    let commission_rate: u64 = query_storage_value(
+        client.clone(),
+        pos::commission_rate_key(validator),
+    )
+    .await
+    .unwrap_or_default();
+    let max_commission_rate_change: u64 = query_storage_value(
+        client.clone(),
+        pos::max_commission_rate_change_key(validator),
+    )
+    .await
+    .unwrap_or_default();
+    let stake: token::Amount =
+        query_storage_value(client.clone(), pos::stake_key(validator))
+            .await
+            .unwrap_or_default();
+    let voting_power: u64 =
+        query_storage_value(client.clone(), pos::voting_power_key(validator))
+            .await
+            .unwrap_or_default();
+    let jailed: Option<storage::Epoch> =
+        query_storage_value(client, pos::jailed_key(validator)).await;
+    println!("Consensus key: {}", consensus_key);
+    println!(
+        "Tendermint address: {}",
+        pos::consensus_address(&consensus_key)
+    );
+    println!("Commission rate: {} bps", commission_rate);
+    println!(
+        "Maximum commission rate change: {} bps per epoch",
+        max_commission_rate_change
+    );
+    println!("Stake: {}", stake);
+    println!("Voting power: {}", voting_power);
+    if let Some(epoch) = jailed {
+        println!("Jailed in epoch {}", epoch);
+    }
+}
+
+/// Query a governance proposal, its votes and its result if it has been
+/// tallied.
+pub async fn query_proposal(args: args::QueryProposal) {
+    let client = HttpClient::new(args.query.ledger_address.clone()).unwrap();
+    let key = governance::proposal_key(args.id);
+    let proposal: Proposal =
+        match query_storage_value(client.clone(), key).await {
+            Some(proposal) => proposal,
+            None => {
+                eprintln!("No proposal found with identifier {}", args.id);
+                return;
+            }
+        };
+    let decimals =
+        query_token_info(&args.query.ledger_address, &address::xan())
+            .await
+            .map(|info| info.decimals);
+    let format_amount = |amount: token::Amount| -> String {
+        match decimals {
+            Some(decimals) => amount.to_string_with_decimals(decimals),
+            None => amount.to_string(),
+        }
+    };
+    let stdout = io::stdout();
+    let mut w = stdout.lock();
+    writeln!(w, "Proposal {} by {}", proposal.id, proposal.author).unwrap();
+    writeln!(w, "  content: {:?}", proposal.content).unwrap();
+    writeln!(w, "  deposit: {}", format_amount(proposal.deposit)).unwrap();
+    writeln!(
+        w,
+        "  voting period: epochs {} to {}",
+        proposal.voting_start_epoch, proposal.voting_end_epoch
+    )
+    .unwrap();
+    let key = governance::vote_prefix(args.id);
+    let votes = query_storage_prefix::<Vote>(client.clone(), key).await;
+    writeln!(w, "Votes:").unwrap();
+    for (key, vote) in votes {
+        if let Some((_, voter)) = governance::is_vote_key(&key) {
+            writeln!(w, "  {}: {}", voter, vote).unwrap();
+        }
+    }
+    let key = governance::result_key(args.id);
+    match query_storage_value::<ProposalResult>(client, key).await {
+        Some(result) => {
+            writeln!(
+                w,
+                "Result in epoch {}: {}, yay {}, nay {}{}",
+                result.epoch,
+                if result.passed { "passed" } else { "rejected" },
+                format_amount(result.tally.yay),
+                format_amount(result.tally.nay),
+                if result.passed && !result.applied {
+                    ", failed to apply"
+                } else {
+                    ""
+                }
+            )
+            .unwrap();
+        }
+        None => writeln!(w, "Not tallied yet").unwrap(),
+    }
+}
+
+/// Query the current protocol parameters and the parameters that are scheduled
+/// to become active in the future epochs.
+pub async fn query_parameters(args: args::Query) {
+    let client = HttpClient::new(args.ledger_address).unwrap();
+    let key = parameters::storage_key();
+    let current: Option<Parameters> =
+        query_storage_value(client.clone(), key).await;
+    let stdout = io::stdout();
+    let mut w = stdout.lock();
+    match current {
+        Some(current) => writeln!(w, "Current: {:#?}", current).unwrap(),
+        None => writeln!(w, "The protocol parameters are not set").unwrap(),
+    }
+    let key = parameters::pending_prefix();
+    let mut scheduled: Vec<(storage::Epoch, Parameters)> =
+        query_storage_prefix::<Parameters>(client, key)
+            .await
+            .filter_map(|(key, parameters)| {
+                parameters::is_pending_key(&key)
+                    .map(|epoch| (epoch, parameters))
+            })
+            .collect();
+    scheduled.sort_by_key(|(epoch, _)| *epoch);
+    if scheduled.is_empty() {
+        writeln!(w, "No scheduled changes").unwrap();
+    }
+    for (epoch, parameters) in scheduled {
+        writeln!(w, "From epoch {}: {:#?}", epoch, parameters).unwrap();
+    }
+}
+
+/// Query the current epoch or the epoch of a committed block height.
+pub async fn query_epoch(args: args::QueryEpoch) {
+    let client = HttpClient::new(args.query.ledger_address).unwrap();
+    match args.height {
+        None => {
+            let epoch: Option<storage::Epoch> =
+                query_path(client, Path::Epoch).await;
+            match epoch {
+                Some(epoch) => println!("Current epoch: {}", epoch),
+                None => eprintln!("The current epoch is not known"),
+            }
+        }
+        Some(height) => {
+            let epoch: Option<storage::Epoch> =
+                query_path(client, Path::EpochAtHeight(height)).await;
+            match epoch {
+                Some(epoch) => {
+                    println!("Epoch of block height {}: {}", height, epoch)
+                }
+                None => eprintln!("No epoch found for block height {}", height),
+            }
+        }
+    }
+}
+
+/// Query the earliest start of the next epoch.
+pub async fn query_next_epoch(args: args::Query) {
+    let client = HttpClient::new(args.ledger_address).unwrap();
+    let next_epoch: Option<NextEpoch> =
+        query_path(client, Path::NextEpoch).await;
+    match next_epoch {
+        Some(NextEpoch {
+            epoch,
+            min_start_height,
+            min_start_time,
+        }) => println!(
+            "Epoch {} starts at the first block with height at least {} and \
+             time at least {}",
+            epoch,
+            min_start_height,
+            min_start_time.0.to_rfc3339()
+        ),
+        None => eprintln!("The next epoch is not known"),
+    }
+}
+
+/// Query the governance parameters.
+pub async fn query_governance_parameters(
+    ledger_address: &tendermint::net::Address,
+) -> Option<GovernanceParameters> {
+    let client = HttpClient::new(ledger_address.clone()).unwrap();
+    let key = governance::parameters_key();
+    query_storage_value(client, key).await
+}
+
+/// Query all the tokens in the token registry.
+pub async fn query_tokens(client: HttpClient) -> HashMap<Address, TokenInfo> {
+    let key = token::registry_prefix();
+    query_storage_prefix::<TokenInfo>(client, key)
+        .await
+        .filter_map(|(key, info)| {
+            token::is_registry_key(&key).map(|token| (token.clone(), info))
+        })
+        .collect()
+}
+
+/// Query a token's info from the token registry. Returns `None` if the token
+/// is not registered.
+pub async fn query_token_info(
+    ledger_address: &tendermint::net::Address,
+    token: &Address,
+) -> Option<TokenInfo> {
+    let client = HttpClient::new(ledger_address.clone()).unwrap();
+    let key = token::registry_key(token);
+    query_storage_value(client, key).await
+}
+
+/// Query all the note commitments of the shielded pool in the order in which
+/// they were added to the note commitment tree.
+pub async fn query_note_commitments(
+    ledger_address: &tendermint::net::Address,
+) -> Vec<NoteCommitment> {
+    let client = HttpClient::new(ledger_address.clone()).unwrap();
+    let key = shielded::commitment_prefix();
+    let mut commitments: Vec<(u64, NoteCommitment)> = query_storage_prefix::<
+        NoteCommitment,
+    >(client, key)
+    .await
+    .filter_map(|(key, commitment)| {
+        shielded::is_commitment_key(&key).map(|index| (index, commitment))
+    })
+    .collect();
+    commitments.sort_by_key(|(index, _commitment)| *index);
+    commitments
+        .into_iter()
+        .map(|(_index, commitment)| commitment)
+        .collect()
+}
+
+/// Query a storage value and decode it with [`BorshDeserialize`]. Returns
+/// `None` if there is no value for the key.
+async fn query_storage_value<T>(
+    client: HttpClient,
+    key: storage::Key,
+) -> Option<T>
+where
+    T: BorshDeserialize,
+{
+    query_path(client, Path::Value(key)).await
+}
+
+/// Query a path whose response value is encoded with [`BorshDeserialize`].
+/// Returns `None` if the response code is `1`, which the ledger uses only when
+/// no value is found. Any other error code (e.g. `2` for an invalid path or a
+/// storage error) is reported and terminates the client.
+async fn query_path<T>(client: HttpClient, path: Path) -> Option<T>
 where
     T: BorshDeserialize,
 {
-    let path = Path::Value(key);
     let data = vec![];
     let response = client
         .abci_query(Some(path.into()), data, None, false)
@@ -94,10 +439,12 @@ where
     match response.code {
         tendermint::abci::Code::Ok => {
             match T::try_from_slice(&response.value[..]) {
-                Ok(value) => return value,
+                Ok(value) => return Some(value),
                 Err(err) => eprintln!("Error decoding the value: {}", err),
             }
         }
+        // The code `1` is returned when no value is found
+        tendermint::abci::Code::Err(1) => return None,
         tendermint::abci::Code::Err(err) => eprintln!(
             "Error in the query {} (error code {})",
             response.info, err
@@ -1,12 +1,36 @@
 use std::convert::TryFrom;
+use std::fs::File;
+use std::path::Path;
+#[cfg(feature = "shielded-pool")]
+use std::path::PathBuf;
 
+#[cfg(feature = "shielded-pool")]
+use anoma::ledger::shielded_pool::proof::{self, Spend};
 use anoma::proto::Tx;
-use anoma::types::address::Address;
+use anoma::types::address::{self, Address};
+use anoma::types::governance::ProposalContent;
+use anoma::types::key::common::{PublicKey, Signature};
 use anoma::types::key::ed25519::Keypair;
+use anoma::types::key::multisig;
+use anoma::types::recovery::RecoveryConfig;
+#[cfg(feature = "shielded-pool")]
+use anoma::types::shielded::{
+    Note, NoteCommitment, PaymentAddress, ShieldedTransfer, SpendingKey,
+    TransparentTransfer,
+};
 use anoma::types::token;
-use anoma::types::transaction::{InitAccount, UpdateVp};
-use borsh::BorshSerialize;
+use anoma::types::token::TokenInfo;
+use anoma::types::transaction::{
+    BecomeValidator, Bond, ChangeCommission, ClaimRewards, InitAccount,
+    InitMultisigAccount, InitProposal, InitToken, InitVestingAccount,
+    ProposeRecovery, Recovery, TreasurySpend, Unbond, Unjail, UpdatePk,
+    UpdateRecovery, UpdateVp, VoteProposal, Withdraw,
+};
+use anoma::types::vesting::VestingSchedule;
+use borsh::{BorshDeserialize, BorshSerialize};
 use jsonpath_lib as jsonpath;
+#[cfg(feature = "shielded-pool")]
+use rand::Rng;
 use serde::Serialize;
 use tendermint_rpc::query::{EventType, Query};
 use tendermint_rpc::Client;
@@ -19,9 +43,38 @@ use crate::client::tendermint_websocket_client::{
 use crate::wallet;
 
 const TX_INIT_ACCOUNT_WASM: &str = "wasm/tx_init_account.wasm";
+const TX_INIT_MULTISIG_ACCOUNT_WASM: &str =
+    "wasm/tx_init_multisig_account.wasm";
+const TX_INIT_PROPOSAL_WASM: &str = "wasm/tx_init_proposal.wasm";
+const TX_INIT_TOKEN_WASM: &str = "wasm/tx_init_token.wasm";
+const TX_INIT_VESTING_ACCOUNT_WASM: &str = "wasm/tx_init_vesting_account.wasm";
+const TX_MINT_WASM: &str = "wasm/tx_mint.wasm";
+const TX_BURN_WASM: &str = "wasm/tx_burn.wasm";
+const TX_CLAIM_REWARDS_WASM: &str = "wasm/tx_claim_rewards.wasm";
+const TX_BECOME_VALIDATOR_WASM: &str = "wasm/tx_become_validator.wasm";
+const TX_BOND_WASM: &str = "wasm/tx_bond.wasm";
+const TX_UNBOND_WASM: &str = "wasm/tx_unbond.wasm";
+const TX_WITHDRAW_WASM: &str = "wasm/tx_withdraw.wasm";
+const TX_CHANGE_COMMISSION_WASM: &str = "wasm/tx_change_commission.wasm";
+const TX_UNJAIL_WASM: &str = "wasm/tx_unjail.wasm";
+const TX_APPROVE_WASM: &str = "wasm/tx_approve.wasm";
+const TX_REVOKE_WASM: &str = "wasm/tx_revoke.wasm";
+const TX_TRANSFER_FROM_WASM: &str = "wasm/tx_transfer_from.wasm";
+const TX_TREASURY_SPEND_WASM: &str = "wasm/tx_treasury_spend.wasm";
+const TX_UPDATE_RECOVERY_WASM: &str = "wasm/tx_update_recovery.wasm";
+const TX_PROPOSE_RECOVERY_WASM: &str = "wasm/tx_propose_recovery.wasm";
+const TX_VETO_RECOVERY_WASM: &str = "wasm/tx_veto_recovery.wasm";
+const TX_RECOVER_WASM: &str = "wasm/tx_recover.wasm";
+#[cfg(feature = "shielded-pool")]
+const TX_SHIELDED_TRANSFER_WASM: &str = "wasm/tx_shielded_transfer.wasm";
 const TX_UPDATE_VP_WASM: &str = "wasm/tx_update_vp.wasm";
+const TX_UPDATE_PK_WASM: &str = "wasm/tx_update_pk.wasm";
+const TX_VOTE_PROPOSAL_WASM: &str = "wasm/tx_vote_proposal.wasm";
 const TX_TRANSFER_WASM: &str = "wasm/tx_transfer.wasm";
 const VP_USER_WASM: &str = "wasm/vp_user.wasm";
+const VP_MULTISIG_WASM: &str = "wasm/vp_multisig.wasm";
+const VP_VESTING_WASM: &str = "wasm/vp_vesting.wasm";
+const VP_TOKEN_WASM: &str = "wasm/vp_token.wasm";
 
 pub async fn submit_custom(args: args::TxCustom) {
     let tx_code = std::fs::read(args.code_path)
@@ -51,6 +104,109 @@ pub async fn submit_update_vp(args: args::TxUpdateVp) {
     submit_tx(args.tx, tx).await
 }
 
+pub async fn submit_update_key(args: args::TxUpdateKey) {
+    let addr = args.addr;
+    let source_key: Keypair = wallet::key_of(addr.encode());
+    let tx_code = std::fs::read(TX_UPDATE_PK_WASM)
+        .expect("Expected a file at given code path");
+
+    let update_pk = UpdatePk {
+        addr,
+        public_key: args.public_key,
+    };
+    tracing::debug!("Update public key data {:?}", update_pk);
+    let data = update_pk
+        .try_to_vec()
+        .expect("Encoding unsigned public key update shouldn't fail");
+    let tx = Tx::new(tx_code, Some(data)).sign(&source_key);
+
+    submit_tx(args.tx, tx).await
+}
+
+pub async fn submit_update_recovery(args: args::TxUpdateRecovery) {
+    let addr = args.addr;
+    let config = if args.guardians.is_empty() {
+        None
+    } else {
+        let threshold = match args.threshold {
+            Some(threshold) => threshold,
+            None => {
+                eprintln!("The threshold is required with the guardians");
+                return;
+            }
+        };
+        let config = RecoveryConfig {
+            guardians: args.guardians.into_iter().collect(),
+            threshold,
+            delay: args.delay,
+        };
+        if !config.is_valid(&addr) {
+            eprintln!(
+                "The threshold must be between 1 and the number of unique \
+                 guardians ({}), got {}, and the account cannot be its own \
+                 guardian",
+                config.guardians.len(),
+                config.threshold
+            );
+            return;
+        }
+        Some(config)
+    };
+    let source_key: Keypair = wallet::key_of(addr.encode());
+    let tx_code = std::fs::read(TX_UPDATE_RECOVERY_WASM)
+        .expect("Expected a file at given code path");
+
+    let update_recovery = UpdateRecovery { addr, config };
+    tracing::debug!("Update recovery data {:?}", update_recovery);
+    let data = update_recovery
+        .try_to_vec()
+        .expect("Encoding unsigned recovery update shouldn't fail");
+    let tx = Tx::new(tx_code, Some(data)).sign(&source_key);
+
+    submit_tx(args.tx, tx).await
+}
+
+pub async fn submit_propose_recovery(args: args::TxProposeRecovery) {
+    let guardian_key: Keypair = wallet::key_of(args.guardian.encode());
+    let tx_code = std::fs::read(TX_PROPOSE_RECOVERY_WASM)
+        .expect("Expected a file at given code path");
+
+    let propose = ProposeRecovery {
+        owner: args.owner,
+        guardian: args.guardian,
+        public_key: args.public_key,
+    };
+    tracing::debug!("Propose recovery data {:?}", propose);
+    let data = propose
+        .try_to_vec()
+        .expect("Encoding unsigned recovery proposal shouldn't fail");
+    let tx = Tx::new(tx_code, Some(data)).sign(&guardian_key);
+
+    submit_tx(args.tx, tx).await
+}
+
+pub async fn submit_veto_recovery(args: args::TxRecovery) {
+    submit_recovery(args, TX_VETO_RECOVERY_WASM).await
+}
+
+pub async fn submit_recover(args: args::TxRecovery) {
+    submit_recovery(args, TX_RECOVER_WASM).await
+}
+
+async fn submit_recovery(args: args::TxRecovery, tx_code_path: &str) {
+    let signing_key: Keypair = wallet::key_of(args.signer.encode());
+    let tx_code = std::fs::read(tx_code_path)
+        .expect("Expected a file at given code path");
+
+    let recovery = Recovery { owner: args.owner };
+    let data = recovery
+        .try_to_vec()
+        .expect("Encoding unsigned recovery data shouldn't fail");
+    let tx = Tx::new(tx_code, Some(data)).sign(&signing_key);
+
+    submit_tx(args.tx, tx).await
+}
+
 pub async fn submit_init_account(args: args::TxInitAccount) {
     let source_key: Keypair = wallet::key_of(args.source.encode());
     let public_key = args.public_key;
@@ -78,15 +234,453 @@ pub async fn submit_init_account(args: args::TxInitAccount) {
     submit_tx(args.tx, tx).await
 }
 
+pub async fn submit_init_vesting_account(args: args::TxInitVestingAccount) {
+    let amount = match token_amount(&args.tx, &args.token, &args.amount).await {
+        Some(amount) => amount,
+        None => return,
+    };
+    let schedule = VestingSchedule {
+        token: args.token,
+        amount,
+        start: args.start,
+        cliff: args.cliff,
+        end: args.end,
+        cliff_time: args.cliff_time,
+    };
+    if !schedule.is_valid() {
+        eprintln!(
+            "Invalid vesting schedule: the epochs must be ordered as start <= \
+             cliff <= end"
+        );
+        return;
+    }
+    let source_key: Keypair = wallet::key_of(args.source.encode());
+    let vp_code = args
+        .vp_code_path
+        .map(|path| {
+            std::fs::read(path).expect("Expected a file at given code path")
+        })
+        .unwrap_or_else(|| {
+            std::fs::read(VP_VESTING_WASM)
+                .expect("Expected a file at given code path")
+        });
+    let tx_code = std::fs::read(TX_INIT_VESTING_ACCOUNT_WASM)
+        .expect("Expected a file at given code path");
+
+    let data = InitVestingAccount {
+        public_key: args.public_key,
+        schedule,
+        vp_code,
+    };
+    let data = data.try_to_vec().expect(
+        "Encoding transfer data to initialize a new account shouldn't fail",
+    );
+    let tx = Tx::new(tx_code, Some(data)).sign(&source_key);
+
+    submit_tx(args.tx, tx).await
+}
+
+pub async fn submit_init_multisig_account(args: args::TxInitMultisigAccount) {
+    let source_key: Keypair = wallet::key_of(args.source.encode());
+    if !multisig::is_valid_config(&args.public_keys, args.threshold) {
+        eprintln!(
+            "The public keys must be unique and the threshold must be between \
+             1 and the number of public keys ({}), got {}",
+            args.public_keys.len(),
+            args.threshold
+        );
+        return;
+    }
+    let vp_code = args
+        .vp_code_path
+        .map(|path| {
+            std::fs::read(path).expect("Expected a file at given code path")
+        })
+        .unwrap_or_else(|| {
+            std::fs::read(VP_MULTISIG_WASM)
+                .expect("Expected a file at given code path")
+        });
+    let tx_code = std::fs::read(TX_INIT_MULTISIG_ACCOUNT_WASM)
+        .expect("Expected a file at given code path");
+
+    let data = InitMultisigAccount {
+        public_keys: args.public_keys,
+        threshold: args.threshold,
+        vp_code,
+    };
+    let data = data.try_to_vec().expect(
+        "Encoding transfer data to initialize a new multisig account \
+         shouldn't fail",
+    );
+    let tx = Tx::new(tx_code, Some(data)).sign(&source_key);
+
+    submit_tx(args.tx, tx).await
+}
+
+/// Build an unsigned transaction and write it to a file, so that it can be
+/// signed offline by the keys of a multisig account.
+pub fn build_tx(args: args::TxBuild) {
+    let tx_code = std::fs::read(args.code_path)
+        .expect("Expected a file at given code path");
+    let data = args.data_path.map(|data_path| {
+        std::fs::read(data_path).expect("Expected a file at given data path")
+    });
+    let tx = Tx::new(tx_code, data);
+
+    std::fs::write(&args.output_path, tx.to_bytes())
+        .expect("Couldn't write the unsigned transaction");
+    println!(
+        "Unsigned transaction written to {}",
+        args.output_path.to_string_lossy()
+    );
+}
+
+/// Build an unsigned transaction that spends the treasury funds, to be signed
+/// offline by the keys of the treasury's admin multisig account.
+pub async fn build_treasury_spend(args: args::TxBuildTreasurySpend) {
+    let tx_code = std::fs::read(TX_TREASURY_SPEND_WASM)
+        .expect("Expected a file at given code path");
+    let amount =
+        match decimal_amount(&args.ledger_address, &args.token, &args.amount)
+            .await
+        {
+            Some(amount) => amount,
+            None => return,
+        };
+    let spend = TreasurySpend {
+        target: args.target,
+        token: args.token,
+        amount,
+    };
+    tracing::debug!("Treasury spend data {:?}", spend);
+    let data = spend
+        .try_to_vec()
+        .expect("Encoding unsigned treasury spend shouldn't fail");
+    let tx = Tx::new(tx_code, Some(data));
+
+    std::fs::write(&args.output_path, tx.to_bytes())
+        .expect("Couldn't write the unsigned transaction");
+    println!(
+        "Unsigned transaction written to {}",
+        args.output_path.to_string_lossy()
+    );
+}
+
+/// Sign an unsigned transaction offline and write the public key with the
+/// signature to a file.
+pub fn sign_tx(args: args::TxSign) {
+    let tx = read_tx(&args.tx_path);
+    let signer_key: Keypair = wallet::key_of(args.signer.encode());
+    let sig = multisig::sign_tx_offline(&signer_key, &tx);
+
+    let sig_bytes = sig
+        .try_to_vec()
+        .expect("Encoding a signature shouldn't fail");
+    std::fs::write(&args.output_path, sig_bytes)
+        .expect("Couldn't write the signature");
+    println!(
+        "Signature written to {}",
+        args.output_path.to_string_lossy()
+    );
+}
+
+/// Combine the signatures produced by [`sign_tx`] with the unsigned
+/// transaction and write the signed transaction to a file.
+pub fn combine_signatures(args: args::TxCombineSignatures) {
+    let tx = read_tx(&args.tx_path);
+    let sigs: Vec<(PublicKey, Signature)> = args
+        .signature_paths
+        .iter()
+        .map(|path| {
+            let sig_bytes = std::fs::read(path)
+                .expect("Expected a file at given signature path");
+            BorshDeserialize::try_from_slice(&sig_bytes[..])
+                .expect("Couldn't decode the signature")
+        })
+        .collect();
+    let tx = multisig::combine_signatures(tx, sigs);
+
+    std::fs::write(&args.output_path, tx.to_bytes())
+        .expect("Couldn't write the signed transaction");
+    println!(
+        "Signed transaction written to {}",
+        args.output_path.to_string_lossy()
+    );
+}
+
+/// Submit a transaction read from a file, e.g. one produced by
+/// [`combine_signatures`].
+pub async fn submit_tx_from_file(args: args::TxBroadcast) {
+    let tx = read_tx(&args.tx_path);
+
+    submit_tx(args.tx, tx).await
+}
+
+fn read_tx(path: &Path) -> Tx {
+    let tx_bytes =
+        std::fs::read(path).expect("Expected a file at given tx path");
+    Tx::try_from(&tx_bytes[..]).expect("Couldn't decode the transaction")
+}
+
+pub async fn submit_init_token(args: args::TxInitToken) {
+    let source_key: Keypair = wallet::key_of(args.source.encode());
+    let info = TokenInfo {
+        symbol: args.symbol,
+        name: args.name,
+        decimals: args.decimals,
+        minter: args.minter,
+    };
+    if !info.is_valid() {
+        eprintln!(
+            "The token symbol must be 1 to {} alphanumeric characters, the \
+             name must be non-empty and at most {} characters long and the \
+             number of decimals must be at most {}, got {:?}",
+            token::MAX_SYMBOL_LEN,
+            token::MAX_NAME_LEN,
+            token::MAX_TOKEN_DECIMALS,
+            info
+        );
+        return;
+    }
+    let vp_code = args
+        .vp_code_path
+        .map(|path| {
+            std::fs::read(path).expect("Expected a file at given code path")
+        })
+        .unwrap_or_else(|| {
+            std::fs::read(VP_TOKEN_WASM)
+                .expect("Expected a file at given code path")
+        });
+    let tx_code = std::fs::read(TX_INIT_TOKEN_WASM)
+        .expect("Expected a file at given code path");
+
+    let data = InitToken { info, vp_code };
+    let data = data.try_to_vec().expect(
+        "Encoding transfer data to initialize a new token shouldn't fail",
+    );
+    let tx = Tx::new(tx_code, Some(data)).sign(&source_key);
+
+    submit_tx(args.tx, tx).await
+}
+
+pub async fn submit_init_proposal(args: args::TxInitProposal) {
+    let author_key: Keypair = wallet::key_of(args.author.encode());
+    let tx_code = std::fs::read(TX_INIT_PROPOSAL_WASM)
+        .expect("Expected a file at given code path");
+
+    let file = File::open(&args.data_path)
+        .expect("Expected a file at given data path");
+    let content: ProposalContent = match serde_json::from_reader(file) {
+        Ok(content) => content,
+        Err(err) => {
+            eprintln!("Invalid proposal content: {}", err);
+            return;
+        }
+    };
+    let deposit = match args.deposit.as_ref() {
+        Some(deposit) => {
+            match token_amount(&args.tx, &address::xan(), deposit).await {
+                Some(deposit) => deposit,
+                None => return,
+            }
+        }
+        None => {
+            match rpc::query_governance_parameters(&args.tx.ledger_address)
+                .await
+            {
+                Some(parameters) => parameters.min_proposal_deposit,
+                None => {
+                    eprintln!("The governance parameters are not set");
+                    return;
+                }
+            }
+        }
+    };
+    let init_proposal = InitProposal {
+        author: args.author,
+        content,
+        deposit,
+    };
+    tracing::debug!("Init proposal data {:?}", init_proposal);
+    let data = init_proposal
+        .try_to_vec()
+        .expect("Encoding unsigned proposal shouldn't fail");
+    let tx = Tx::new(tx_code, Some(data)).sign(&author_key);
+
+    submit_tx(args.tx, tx).await
+}
+
+pub async fn submit_vote_proposal(args: args::TxVoteProposal) {
+    let voter_key: Keypair = wallet::key_of(args.voter.encode());
+    let tx_code = std::fs::read(TX_VOTE_PROPOSAL_WASM)
+        .expect("Expected a file at given code path");
+
+    let vote = VoteProposal {
+        id: args.id,
+        voter: args.voter,
+        vote: args.vote,
+    };
+    tracing::debug!("Vote proposal data {:?}", vote);
+    let data = vote
+        .try_to_vec()
+        .expect("Encoding unsigned vote shouldn't fail");
+    let tx = Tx::new(tx_code, Some(data)).sign(&voter_key);
+
+    submit_tx(args.tx, tx).await
+}
+
+pub async fn submit_claim_rewards(args: args::TxClaimRewards) {
+    let delegator_key: Keypair = wallet::key_of(args.delegator.encode());
+    let tx_code = std::fs::read(TX_CLAIM_REWARDS_WASM)
+        .expect("Expected a file at given code path");
+
+    let claim = ClaimRewards {
+        delegator: args.delegator,
+        validator: args.validator,
+    };
+    tracing::debug!("Claim rewards data {:?}", claim);
+    let data = claim
+        .try_to_vec()
+        .expect("Encoding unsigned claim shouldn't fail");
+    let tx = Tx::new(tx_code, Some(data)).sign(&delegator_key);
+
+    submit_tx(args.tx, tx).await
+}
+
+pub async fn submit_become_validator(args: args::TxBecomeValidator) {
+    let validator_key: Keypair = wallet::key_of(args.address.encode());
+    let tx_code = std::fs::read(TX_BECOME_VALIDATOR_WASM)
+        .expect("Expected a file at given code path");
+
+    let data = BecomeValidator {
+        address: args.address,
+        consensus_key: args.consensus_key,
+        commission_rate: args.commission_rate,
+        max_commission_rate_change: args.max_commission_rate_change,
+    };
+    tracing::debug!("Become validator data {:?}", data);
+    let data = data
+        .try_to_vec()
+        .expect("Encoding unsigned become validator data shouldn't fail");
+    let tx = Tx::new(tx_code, Some(data)).sign(&validator_key);
+
+    submit_tx(args.tx, tx).await
+}
+
+pub async fn submit_bond(args: args::TxBond) {
+    let delegator_key: Keypair = wallet::key_of(args.delegator.encode());
+    let tx_code = std::fs::read(TX_BOND_WASM)
+        .expect("Expected a file at given code path");
+
+    let amount =
+        match token_amount(&args.tx, &address::xan(), &args.amount).await {
+            Some(amount) => amount,
+            None => return,
+        };
+    let bond = Bond {
+        delegator: args.delegator,
+        validator: args.validator,
+        amount,
+    };
+    tracing::debug!("Bond data {:?}", bond);
+    let data = bond
+        .try_to_vec()
+        .expect("Encoding unsigned bond shouldn't fail");
+    let tx = Tx::new(tx_code, Some(data)).sign(&delegator_key);
+
+    submit_tx(args.tx, tx).await
+}
+
+pub async fn submit_unbond(args: args::TxUnbond) {
+    let delegator_key: Keypair = wallet::key_of(args.delegator.encode());
+    let tx_code = std::fs::read(TX_UNBOND_WASM)
+        .expect("Expected a file at given code path");
+
+    let amount =
+        match token_amount(&args.tx, &address::xan(), &args.amount).await {
+            Some(amount) => amount,
+            None => return,
+        };
+    let unbond = Unbond {
+        delegator: args.delegator,
+        validator: args.validator,
+        amount,
+    };
+    tracing::debug!("Unbond data {:?}", unbond);
+    let data = unbond
+        .try_to_vec()
+        .expect("Encoding unsigned unbond shouldn't fail");
+    let tx = Tx::new(tx_code, Some(data)).sign(&delegator_key);
+
+    submit_tx(args.tx, tx).await
+}
+
+pub async fn submit_withdraw(args: args::TxWithdraw) {
+    let delegator_key: Keypair = wallet::key_of(args.delegator.encode());
+    let tx_code = std::fs::read(TX_WITHDRAW_WASM)
+        .expect("Expected a file at given code path");
+
+    let withdraw = Withdraw {
+        delegator: args.delegator,
+        validator: args.validator,
+    };
+    tracing::debug!("Withdraw data {:?}", withdraw);
+    let data = withdraw
+        .try_to_vec()
+        .expect("Encoding unsigned withdraw shouldn't fail");
+    let tx = Tx::new(tx_code, Some(data)).sign(&delegator_key);
+
+    submit_tx(args.tx, tx).await
+}
+
+pub async fn submit_change_commission(args: args::TxChangeCommission) {
+    let validator_key: Keypair = wallet::key_of(args.validator.encode());
+    let tx_code = std::fs::read(TX_CHANGE_COMMISSION_WASM)
+        .expect("Expected a file at given code path");
+
+    let change = ChangeCommission {
+        validator: args.validator,
+        rate: args.commission_rate,
+    };
+    tracing::debug!("Change commission data {:?}", change);
+    let data = change
+        .try_to_vec()
+        .expect("Encoding unsigned commission change shouldn't fail");
+    let tx = Tx::new(tx_code, Some(data)).sign(&validator_key);
+
+    submit_tx(args.tx, tx).await
+}
+
+pub async fn submit_unjail(args: args::TxUnjail) {
+    let validator_key: Keypair = wallet::key_of(args.validator.encode());
+    let tx_code = std::fs::read(TX_UNJAIL_WASM)
+        .expect("Expected a file at given code path");
+
+    let unjail = Unjail {
+        validator: args.validator,
+    };
+    tracing::debug!("Unjail data {:?}", unjail);
+    let data = unjail
+        .try_to_vec()
+        .expect("Encoding unsigned unjail shouldn't fail");
+    let tx = Tx::new(tx_code, Some(data)).sign(&validator_key);
+
+    submit_tx(args.tx, tx).await
+}
+
 pub async fn submit_transfer(args: args::TxTransfer) {
     let source_key: Keypair = wallet::key_of(args.source.encode());
     let tx_code = std::fs::read(TX_TRANSFER_WASM).unwrap();
 
+    let amount = match token_amount(&args.tx, &args.token, &args.amount).await {
+        Some(amount) => amount,
+        None => return,
+    };
     let transfer = token::Transfer {
         source: args.source,
         target: args.target,
         token: args.token,
-        amount: args.amount,
+        amount,
     };
     tracing::debug!("Transfer data {:?}", transfer);
     let data = transfer
@@ -97,6 +691,433 @@ pub async fn submit_transfer(args: args::TxTransfer) {
     submit_tx(args.tx, tx).await
 }
 
+pub async fn submit_mint(args: args::TxMint) {
+    let info = match token_info(&args.tx, &args.token).await {
+        Some(info) => info,
+        None => return,
+    };
+    let minter = match info.minter {
+        Some(minter) => minter,
+        None => {
+            eprintln!(
+                "The token {} has no minter, its supply cannot be changed",
+                args.token
+            );
+            return;
+        }
+    };
+    let amount = match args.amount.to_amount(info.decimals) {
+        Ok(amount) => amount,
+        Err(err) => {
+            eprintln!("Invalid amount {}: {}", args.amount, err);
+            return;
+        }
+    };
+    let minter_key: Keypair = wallet::key_of(minter.encode());
+    let tx_code = std::fs::read(TX_MINT_WASM)
+        .expect("Expected a file at given code path");
+
+    let mint = token::Mint {
+        target: args.target,
+        token: args.token,
+        amount,
+    };
+    tracing::debug!("Mint data {:?}", mint);
+    let data = mint
+        .try_to_vec()
+        .expect("Encoding unsigned mint shouldn't fail");
+    let tx = Tx::new(tx_code, Some(data)).sign(&minter_key);
+
+    submit_tx(args.tx, tx).await
+}
+
+pub async fn submit_burn(args: args::TxBurn) {
+    let info = match token_info(&args.tx, &args.token).await {
+        Some(info) => info,
+        None => return,
+    };
+    if info.minter.is_none() {
+        eprintln!(
+            "The token {} has no minter, its supply cannot be changed",
+            args.token
+        );
+        return;
+    }
+    let amount = match args.amount.to_amount(info.decimals) {
+        Ok(amount) => amount,
+        Err(err) => {
+            eprintln!("Invalid amount {}: {}", args.amount, err);
+            return;
+        }
+    };
+    let source_key: Keypair = wallet::key_of(args.source.encode());
+    let tx_code = std::fs::read(TX_BURN_WASM)
+        .expect("Expected a file at given code path");
+
+    let burn = token::Burn {
+        source: args.source,
+        token: args.token,
+        amount,
+    };
+    tracing::debug!("Burn data {:?}", burn);
+    let data = burn
+        .try_to_vec()
+        .expect("Encoding unsigned burn shouldn't fail");
+    let tx = Tx::new(tx_code, Some(data)).sign(&source_key);
+
+    submit_tx(args.tx, tx).await
+}
+
+pub async fn submit_approve(args: args::TxApprove) {
+    let amount = match token_amount(&args.tx, &args.token, &args.amount).await {
+        Some(amount) => amount,
+        None => return,
+    };
+    let owner_key: Keypair = wallet::key_of(args.owner.encode());
+    let tx_code = std::fs::read(TX_APPROVE_WASM)
+        .expect("Expected a file at given code path");
+
+    let approve = token::Approve {
+        owner: args.owner,
+        spender: args.spender,
+        token: args.token,
+        amount,
+        expiry: args.expiry,
+    };
+    tracing::debug!("Approve data {:?}", approve);
+    let data = approve
+        .try_to_vec()
+        .expect("Encoding unsigned approve shouldn't fail");
+    let tx = Tx::new(tx_code, Some(data)).sign(&owner_key);
+
+    submit_tx(args.tx, tx).await
+}
+
+pub async fn submit_revoke(args: args::TxRevoke) {
+    let owner_key: Keypair = wallet::key_of(args.owner.encode());
+    let tx_code = std::fs::read(TX_REVOKE_WASM)
+        .expect("Expected a file at given code path");
+
+    let revoke = token::Revoke {
+        owner: args.owner,
+        spender: args.spender,
+        token: args.token,
+    };
+    tracing::debug!("Revoke data {:?}", revoke);
+    let data = revoke
+        .try_to_vec()
+        .expect("Encoding unsigned revoke shouldn't fail");
+    let tx = Tx::new(tx_code, Some(data)).sign(&owner_key);
+
+    submit_tx(args.tx, tx).await
+}
+
+pub async fn submit_transfer_from(args: args::TxTransferFrom) {
+    let amount = match token_amount(&args.tx, &args.token, &args.amount).await {
+        Some(amount) => amount,
+        None => return,
+    };
+    let spender_key: Keypair = wallet::key_of(args.spender.encode());
+    let tx_code = std::fs::read(TX_TRANSFER_FROM_WASM)
+        .expect("Expected a file at given code path");
+
+    let transfer = token::TransferFrom {
+        spender: args.spender,
+        owner: args.owner,
+        target: args.target,
+        token: args.token,
+        amount,
+    };
+    tracing::debug!("Transfer from data {:?}", transfer);
+    let data = transfer
+        .try_to_vec()
+        .expect("Encoding unsigned transfer from shouldn't fail");
+    let tx = Tx::new(tx_code, Some(data)).sign(&spender_key);
+
+    submit_tx(args.tx, tx).await
+}
+
+#[cfg(feature = "shielded-pool")]
+/// Generate a new shielded spending key and print it with its payment address.
+pub fn shielded_gen_key() {
+    let spending_key = proof::spending_key(rand::thread_rng().gen());
+    let payment_address = proof::payment_address(&spending_key);
+    println!("Spending key: {}", spending_key.encode());
+    println!("Payment address: {}", payment_address);
+}
+
+#[cfg(feature = "shielded-pool")]
+pub async fn submit_shield(args: args::TxShield) {
+    let amount = match token_amount(&args.tx, &args.token, &args.amount).await {
+        Some(amount) => amount,
+        None => return,
+    };
+    let source_key: Keypair = wallet::key_of(args.source.encode());
+    let tx_code = std::fs::read(TX_SHIELDED_TRANSFER_WASM)
+        .expect("Expected a file at given code path");
+
+    let shield = TransparentTransfer {
+        owner: args.source,
+        token: args.token.clone(),
+        amount,
+    };
+    // Nothing is spent, so the empty tree's root can be used as the anchor
+    let transfer = build_shielded_transfer(
+        &args.token,
+        &[],
+        vec![],
+        vec![(args.payment_address, amount, args.output_path)],
+        Some(shield),
+        None,
+    );
+    let transfer = match transfer {
+        Some(transfer) => transfer,
+        None => return,
+    };
+    let data = transfer
+        .try_to_vec()
+        .expect("Encoding unsigned shielded transfer shouldn't fail");
+    let tx = Tx::new(tx_code, Some(data)).sign(&source_key);
+
+    submit_tx(args.tx, tx).await
+}
+
+#[cfg(feature = "shielded-pool")]
+pub async fn submit_unshield(args: args::TxUnshield) {
+    submit_shielded_spend(
+        args.tx,
+        args.spending_key,
+        &args.note_paths,
+        &args.amount,
+        ShieldedRecipient::Transparent(args.target),
+        args.change_path,
+    )
+    .await
+}
+
+#[cfg(feature = "shielded-pool")]
+pub async fn submit_shielded_transfer(args: args::TxShieldedTransfer) {
+    submit_shielded_spend(
+        args.tx,
+        args.spending_key,
+        &args.note_paths,
+        &args.amount,
+        ShieldedRecipient::Shielded(args.payment_address, args.output_path),
+        args.change_path,
+    )
+    .await
+}
+
+#[cfg(feature = "shielded-pool")]
+/// The recipient of the tokens spent from shielded notes
+enum ShieldedRecipient {
+    /// A transparent account to which the tokens are unshielded
+    Transparent(Address),
+    /// A payment address of a new note that is written to the given path
+    Shielded(PaymentAddress, PathBuf),
+}
+
+#[cfg(feature = "shielded-pool")]
+/// Spend the notes in the given files and send the amount to the recipient.
+/// The rest is sent to a new change note owned by the spending key.
+async fn submit_shielded_spend(
+    args: args::Tx,
+    spending_key: SpendingKey,
+    note_paths: &[PathBuf],
+    amount: &token::DecimalAmount,
+    recipient: ShieldedRecipient,
+    change_path: Option<PathBuf>,
+) {
+    let mut notes: Vec<Note> = Vec::with_capacity(note_paths.len());
+    for path in note_paths {
+        match File::open(path)
+            .map_err(|err| err.to_string())
+            .and_then(|file| {
+                serde_json::from_reader(file).map_err(|err| err.to_string())
+            }) {
+            Ok(note) => notes.push(note),
+            Err(err) => {
+                eprintln!("Error reading a note from {:?}: {}", path, err);
+                return;
+            }
+        }
+    }
+    let token = match notes.first() {
+        Some(note) => note.token.clone(),
+        None => {
+            eprintln!("At least one note to spend is required");
+            return;
+        }
+    };
+    let amount = match token_amount(&args, &token, amount).await {
+        Some(amount) => amount,
+        None => return,
+    };
+    let total = notes
+        .iter()
+        .try_fold(token::Amount::default(), |acc, note| {
+            acc.checked_add(&note.amount)
+        });
+    let change = match total.and_then(|total| total.checked_sub(&amount)) {
+        Some(change) => change,
+        None => {
+            eprintln!("The notes are worth less than the amount {}", amount);
+            return;
+        }
+    };
+
+    let mut outputs = vec![];
+    let mut unshield = None;
+    match recipient {
+        ShieldedRecipient::Transparent(target) => {
+            unshield = Some(TransparentTransfer {
+                owner: target,
+                token: token.clone(),
+                amount,
+            });
+        }
+        ShieldedRecipient::Shielded(payment_address, output_path) => {
+            outputs.push((payment_address, amount, output_path));
+        }
+    }
+    if change != token::Amount::default() {
+        match change_path {
+            Some(change_path) => outputs.push((
+                proof::payment_address(&spending_key),
+                change,
+                change_path,
+            )),
+            None => {
+                eprintln!(
+                    "The notes are worth {} more than the amount, a change \
+                     path is required",
+                    change
+                );
+                return;
+            }
+        }
+    }
+
+    let tree = rpc::query_note_commitments(&args.ledger_address).await;
+    let spends = notes
+        .into_iter()
+        .map(|note| Spend {
+            note,
+            spending_key: spending_key.clone(),
+        })
+        .collect();
+    let transfer =
+        build_shielded_transfer(&token, &tree, spends, outputs, None, unshield);
+    let transfer = match transfer {
+        Some(transfer) => transfer,
+        None => return,
+    };
+    let tx_code = std::fs::read(TX_SHIELDED_TRANSFER_WASM)
+        .expect("Expected a file at given code path");
+    let data = transfer
+        .try_to_vec()
+        .expect("Encoding unsigned shielded transfer shouldn't fail");
+    // The shielded pool doesn't check the signature, so the tx is signed with
+    // a new key that cannot be linked to the notes' owner
+    let keypair = Keypair::generate(&mut rand::thread_rng());
+    let tx = Tx::new(tx_code, Some(data)).sign(&keypair);
+
+    submit_tx(args, tx).await
+}
+
+#[cfg(feature = "shielded-pool")]
+/// Build a shielded transfer with its proof and write the new notes to their
+/// paths. Prints an error if the transfer is invalid.
+fn build_shielded_transfer(
+    token: &Address,
+    tree: &[NoteCommitment],
+    spends: Vec<Spend>,
+    outputs: Vec<(PaymentAddress, token::Amount, PathBuf)>,
+    shield: Option<TransparentTransfer>,
+    unshield: Option<TransparentTransfer>,
+) -> Option<ShieldedTransfer> {
+    let (outputs, paths): (Vec<_>, Vec<_>) = outputs
+        .into_iter()
+        .map(|(payment_address, amount, path)| {
+            ((payment_address, amount), path)
+        })
+        .unzip();
+    println!("Creating the shielded transfer's proof...");
+    let result = proof::build_transfer(
+        token,
+        tree,
+        spends,
+        outputs,
+        shield,
+        unshield,
+        rand::thread_rng().gen(),
+    );
+    let (transfer, notes) = match result {
+        Ok(result) => result,
+        Err(err) => {
+            eprintln!("Invalid shielded transfer: {}", err);
+            return None;
+        }
+    };
+    tracing::debug!("Shielded transfer data {:?}", transfer);
+    for (note, path) in notes.iter().zip(paths) {
+        let file = File::create(&path).expect("Couldn't create a note file");
+        serde_json::to_writer_pretty(file, note)
+            .expect("Couldn't write a note file");
+        println!("The new note was written to {:?}", path);
+    }
+    Some(transfer)
+}
+
+/// Convert a decimal amount of the token using its decimals from the token
+/// registry. Tokens that are not registered are assumed to have the maximum
+/// decimal places. Prints an error if the amount is invalid.
+async fn token_amount(
+    args: &args::Tx,
+    token: &Address,
+    amount: &token::DecimalAmount,
+) -> Option<token::Amount> {
+    decimal_amount(&args.ledger_address, token, amount).await
+}
+
+/// Convert a decimal amount of the token like [`token_amount`], looking-up the
+/// token's decimals on the given ledger node.
+async fn decimal_amount(
+    ledger_address: &tendermint::net::Address,
+    token: &Address,
+    amount: &token::DecimalAmount,
+) -> Option<token::Amount> {
+    let decimals = match rpc::query_token_info(ledger_address, token).await {
+        Some(info) => info.decimals,
+        None => {
+            eprintln!(
+                "The token {} is not in the token registry, assuming {} \
+                 decimal places",
+                token,
+                token::MAX_DECIMAL_PLACES
+            );
+            token::MAX_DECIMAL_PLACES as u8
+        }
+    };
+    match amount.to_amount(decimals) {
+        Ok(amount) => Some(amount),
+        Err(err) => {
+            eprintln!("Invalid amount {}: {}", amount, err);
+            None
+        }
+    }
+}
+
+/// Query the token's info from the token registry. Prints an error if the
+/// token is not registered.
+async fn token_info(args: &args::Tx, token: &Address) -> Option<TokenInfo> {
+    let info = rpc::query_token_info(&args.ledger_address, token).await;
+    if info.is_none() {
+        eprintln!("The token {} is not in the token registry", token);
+    }
+    info
+}
+
 async fn submit_tx(args: args::Tx, tx: Tx) {
     let tx_bytes = tx.to_bytes();
 
@@ -1,53 +1,188 @@
 //! The parameters used for the chain's genesis
 
-use anoma::ledger::parameters::{EpochDuration, Parameters};
-#[cfg(feature = "dev")]
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anoma::ledger::gas::BLOCK_GAS_LIMIT;
+use anoma::ledger::parameters::{
+    ConsensusParams, EpochDuration, InflationParams, Parameters,
+};
+use anoma::ledger::upgrade::UpgradePlan;
+use anoma::types::address::{Address, ImplicitAddress};
+use anoma::types::governance::GovernanceParameters;
+use anoma::types::key::ed25519;
+use anoma::types::{pos, token};
 use ed25519_dalek::Keypair;
-#[cfg(not(feature = "dev"))]
-use ed25519_dalek::PublicKey;
-use rand::prelude::ThreadRng;
-use rand::thread_rng;
-use sha2::{Digest, Sha256};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Failed to read the genesis file {0:?}: {1}")]
+    ReadFile(PathBuf, std::io::Error),
+    #[error("Failed to deserialize the genesis file: {0}")]
+    Deserialize(toml::de::Error),
+    #[error("Failed to serialize the genesis file: {0}")]
+    Serialize(toml::ser::Error),
+    #[error("Failed to write the genesis file {0:?}: {1}")]
+    WriteFile(PathBuf, std::io::Error),
+    #[error("Invalid consensus key {0} in the genesis file: {1}")]
+    ConsensusKey(String, ed25519::ParsePublicKeyError),
+    #[error("A genesis file must be configured for the ledger")]
+    MissingFile,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug)]
 pub struct Genesis {
-    #[cfg(not(feature = "dev"))]
     pub validators: Vec<Validator>,
-    #[cfg(feature = "dev")]
-    pub validator: Validator,
+    /// The genesis balances of XAN, in addition to the balances of the
+    /// accounts that are set up for testing
+    pub balances: Vec<Balance>,
     pub parameters: Parameters,
+    pub governance: GovernanceParameters,
+    /// An upgrade plan to be set from the genesis
+    pub upgrade: Option<UpgradePlan>,
+    /// An account that can spend the treasury funds, in addition to the
+    /// governance proposals
+    pub treasury_admin: Option<Address>,
 }
 
-#[cfg(not(feature = "dev"))]
 #[derive(Debug)]
 pub struct Validator {
+    /// The validator's Tendermint address
     pub address: String,
-    pub pk: PublicKey,
-    pub voting_power: u64,
+    /// The validator's consensus key
+    pub pk: ed25519::PublicKey,
+    /// The validator's account, which gets its rewards
+    pub account: Address,
+    /// The validator's self-bond
+    pub stake: token::Amount,
+    /// The validator's commission rate in basis points
+    pub commission_rate: u64,
+    /// The maximum change of the validator's commission rate in an epoch, in
+    /// basis points
+    pub max_commission_rate_change: u64,
 }
 
-#[cfg(feature = "dev")]
-#[derive(Debug)]
-pub struct Validator {
-    pub address: String,
-    pub keypair: Keypair,
-    pub voting_power: u64,
+/// A genesis balance of XAN
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Balance {
+    pub owner: Address,
+    pub amount: token::Amount,
+}
+
+/// The genesis file that is shared by all the nodes of a network, as
+/// generated by `anoma utils init-network`. The chain's parameters are the
+/// same as in the default genesis.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GenesisFile {
+    pub validators: Vec<ValidatorConfig>,
+    pub balances: Vec<Balance>,
+}
+
+/// A genesis validator in a [`GenesisFile`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidatorConfig {
+    /// The hex-encoded consensus key
+    pub consensus_key: String,
+    pub account: Address,
+    pub stake: token::Amount,
+    pub commission_rate: u64,
+    pub max_commission_rate_change: u64,
 }
 
+impl GenesisFile {
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = fs::read_to_string(path)
+            .map_err(|err| Error::ReadFile(path.to_owned(), err))?;
+        toml::from_str(&file).map_err(Error::Deserialize)
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let file = toml::to_string(self).map_err(Error::Serialize)?;
+        fs::write(path, file)
+            .map_err(|err| Error::WriteFile(path.to_owned(), err))
+    }
+
+    /// Get the genesis with the validators and balances of this file and the
+    /// default parameters.
+    pub fn genesis(self) -> Result<Genesis> {
+        let validators = self
+            .validators
+            .into_iter()
+            .map(|validator| {
+                let pk: ed25519::PublicKey =
+                    validator.consensus_key.parse().map_err(|err| {
+                        Error::ConsensusKey(
+                            validator.consensus_key.clone(),
+                            err,
+                        )
+                    })?;
+                Ok(Validator {
+                    address: pos::consensus_address(&pk),
+                    pk,
+                    account: validator.account,
+                    stake: validator.stake,
+                    commission_rate: validator.commission_rate,
+                    max_commission_rate_change: validator
+                        .max_commission_rate_change,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Genesis {
+            validators,
+            balances: self.balances,
+            parameters: parameters(),
+            governance: governance(),
+            upgrade: None,
+            treasury_admin: None,
+        })
+    }
+}
+
+impl From<&Validator> for ValidatorConfig {
+    fn from(validator: &Validator) -> Self {
+        Self {
+            consensus_key: validator.pk.to_string(),
+            account: validator.account.clone(),
+            stake: validator.stake,
+            commission_rate: validator.commission_rate,
+            max_commission_rate_change: validator.max_commission_rate_change,
+        }
+    }
+}
+
+/// Load the genesis from the given genesis file of a network. Without a
+/// genesis file, the default genesis is used in "dev".
+pub fn load(path: Option<&Path>) -> Result<Genesis> {
+    match path {
+        Some(path) => GenesisFile::read(path)?.genesis(),
+        #[cfg(feature = "dev")]
+        None => Ok(genesis()),
+        #[cfg(not(feature = "dev"))]
+        None => Err(Error::MissingFile),
+    }
+}
+
+/// The consensus keypair of the validator in the default genesis.
 #[cfg(feature = "dev")]
-pub fn genesis() -> Genesis {
+pub fn validator_keypair() -> Keypair {
     // NOTE When the validator's key changes, tendermint must be reset with
     // `anoma reset` command. To get fresh key bytes, generate a new
     // validator and print its keypair with:
     // ```
-    // let validator = Validator::new();
+    // let (validator, keypair) = Validator::generate();
     // println!(
     //     "keypair {:?}, address {}",
-    //     validator.keypair.to_bytes(),
+    //     keypair.to_bytes(),
     //     validator.address
     // );
     // ```
-    let keypair = Keypair::from_bytes(&[
+    Keypair::from_bytes(&[
         // SecretKey bytes
         80, 110, 166, 33, 135, 254, 34, 138, 253, 44, 214, 71, 50, 230, 39, 246,
         124, 201, 68, 138, 194, 251, 192, 36, 55, 160, 211, 68, 65, 189, 121,
@@ -55,39 +190,114 @@ pub fn genesis() -> Genesis {
         94, 112, 76, 78, 70, 38, 94, 28, 204, 135, 80, 81, 73, 247, 155, 157,
         46, 65, 77, 1, 164, 227, 128, 109, 252, 101, 240, 167, 57, 1, 193, 208,
     ])
-    .unwrap();
+    .unwrap()
+}
+
+#[cfg(feature = "dev")]
+pub fn genesis() -> Genesis {
     let address = "E62578B4AA08AB8EB12A46DC2F05EAE4622542A7".to_owned();
+    // the validator's account is the dev user "alberto"
+    let account = Address::decode("a1qq5qqqqqg4znssfsgcurjsfhgfpy2vjyxy6yg3z98pp5zvp5xgersvfjxvcnx3f4xycrzdfkak0xhx")
+        .expect("The genesis address shouldn't fail decoding");
     let validator = Validator {
         address,
-        keypair,
-        voting_power: 10,
+        pk: validator_keypair().public.into(),
+        account,
+        stake: token::Amount::whole(1_000_000),
+        commission_rate: 1000,
+        max_commission_rate_change: 100,
     };
-    let parameters = Parameters {
+    Genesis {
+        validators: vec![validator],
+        balances: vec![],
+        parameters: parameters(),
+        governance: governance(),
+        upgrade: None,
+        treasury_admin: None,
+    }
+}
+
+fn parameters() -> Parameters {
+    Parameters {
         epoch_duration: EpochDuration {
             min_num_of_blocks: 10,
             min_duration: anoma::types::time::Duration::minutes(1).into(),
         },
-    };
-    Genesis {
-        validator,
-        parameters,
+        consensus: ConsensusParams {
+            // Tendermint's default, large enough for the WASM txs
+            max_block_bytes: 22_020_096,
+            max_block_gas: BLOCK_GAS_LIMIT,
+            evidence_max_age_num_blocks: 100_000,
+            evidence_max_age_duration: anoma::types::time::Duration::hours(48)
+                .into(),
+            pub_key_types: vec!["ed25519".to_owned()],
+        },
+        inflation: InflationParams {
+            // 10% a year at the target bonded ratio of 2/3
+            annual_rate: 1000,
+            target_bonded_ratio: 6667,
+            proposer_bonus: 500,
+            treasury_share: 1000,
+        },
+    }
+}
+
+fn governance() -> GovernanceParameters {
+    GovernanceParameters {
+        min_proposal_deposit: token::Amount::whole(500),
+        voting_period: 2,
     }
 }
 
 impl Validator {
-    // Generates a new validator
-    #[allow(dead_code)]
-    fn new() -> Self {
-        let mut rng: ThreadRng = thread_rng();
-        let keypair = Keypair::generate(&mut rng);
-        let mut hasher = Sha256::new();
-        hasher.update(keypair.public.to_bytes());
-        // hex of the first 40 chars of the hash
-        let address = format!("{:.40X}", hasher.finalize());
-        Validator {
-            address,
-            keypair,
-            voting_power: 10,
-        }
+    /// Generates a new validator with a fresh consensus keypair. The
+    /// validator's account is the implicit address of its consensus key.
+    pub fn generate() -> (Self, Keypair) {
+        let keypair = Keypair::generate(&mut rand::thread_rng());
+        let pk = ed25519::PublicKey::from(keypair.public);
+        let account =
+            Address::Implicit(ImplicitAddress::Ed25519(pk.clone().into()));
+        let validator = Validator {
+            address: pos::consensus_address(&pk),
+            pk,
+            account,
+            stake: token::Amount::whole(1_000_000),
+            commission_rate: 1000,
+            max_commission_rate_change: 100,
+        };
+        (validator, keypair)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that the validators and balances written into a genesis file are
+    /// loaded back into the genesis.
+    #[test]
+    fn test_genesis_file_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("genesis.toml");
+        let (validator, _keypair) = Validator::generate();
+        let file = GenesisFile {
+            validators: vec![ValidatorConfig::from(&validator)],
+            balances: vec![Balance {
+                owner: validator.account.clone(),
+                amount: token::Amount::whole(1_000),
+            }],
+        };
+        file.write(&path).unwrap();
+
+        let genesis = load(Some(&path)).unwrap();
+        assert_eq!(genesis.validators.len(), 1);
+        let loaded = &genesis.validators[0];
+        assert_eq!(loaded.address, validator.address);
+        assert_eq!(loaded.pk, validator.pk);
+        assert_eq!(loaded.account, validator.account);
+        assert_eq!(loaded.stake, validator.stake);
+        assert_eq!(genesis.balances.len(), 1);
+        assert_eq!(genesis.balances[0].owner, validator.account);
+        assert_eq!(genesis.balances[0].amount, token::Amount::whole(1_000));
     }
 }
@@ -90,6 +90,9 @@ pub struct Ledger {
     pub db: PathBuf,
     pub address: SocketAddr,
     pub network: String,
+    /// The genesis file of a network generated with `anoma utils
+    /// init-network`. Without it, the default genesis is used.
+    pub genesis: Option<PathBuf>,
 }
 
 impl Default for Ledger {
@@ -104,6 +107,7 @@ impl Default for Ledger {
                 26658,
             ),
             network: String::from("mainnet"),
+            genesis: None,
         }
     }
 }
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::fmt::{self, Display};
 use std::ops::{Index, IndexMut};
 
+use anoma::types::transaction::TxEvent;
 use sha2::{Digest, Sha256};
 use tendermint_proto::abci::EventAttribute;
 
@@ -13,13 +14,15 @@ pub struct Event {
     attributes: HashMap<String, String>,
 }
 
-/// The two types of custom events we currently use
+/// The types of custom events we currently use
 #[derive(Clone)]
 pub enum EventType {
     // The transaction was accepted to be included in a block
     Accepted,
     // The transaction was applied during block finalization
     Applied,
+    // An event emitted by a transaction's code
+    Custom(String),
 }
 
 impl Display for EventType {
@@ -27,6 +30,7 @@ impl Display for EventType {
         match self {
             EventType::Accepted => write!(f, "accepted"),
             EventType::Applied => write!(f, "applied"),
+            EventType::Custom(event_type) => write!(f, "{}", event_type),
         }?;
         Ok(())
     }
@@ -44,6 +48,29 @@ impl Event {
         event["height"] = height.to_string();
         event
     }
+
+    /// Creates a new event from an event emitted by a transaction, with the
+    /// hash and height of the transaction filled in. Returns `None` if the
+    /// event's type clashes with one of the built-in event types.
+    pub fn new_custom_tx_event(
+        event: TxEvent,
+        tx: &[u8],
+        height: i64,
+    ) -> Option<Self> {
+        let is_reserved = [EventType::Accepted, EventType::Applied]
+            .iter()
+            .any(|ty| ty.to_string() == event.event_type);
+        if is_reserved {
+            return None;
+        }
+        let mut custom = Event {
+            event_type: EventType::Custom(event.event_type),
+            attributes: event.attributes.into_iter().collect(),
+        };
+        custom["hash"] = hash_tx(tx);
+        custom["height"] = height.to_string();
+        Some(custom)
+    }
 }
 
 impl Index<&str> for Event {
@@ -0,0 +1,185 @@
+//! The storage migrations of the protocol upgrades supported by this binary.
+//!
+//! When the height of the pending upgrade plan is reached, a binary whose
+//! version differs from the plan's version halts. The binary with the plan's
+//! version runs the migration registered under the plan's name, if any, in the
+//! first block of the upgrade before applying its transactions.
+
+use anoma::ledger::storage::types::{decode, encode};
+use anoma::ledger::storage::write_log::WriteLog;
+use anoma::ledger::storage::Error;
+use anoma::ledger::upgrade::UpgradePlan;
+use anoma::types::key::{common, ed25519};
+use anoma::types::storage::{DbKeySeg, Key};
+
+use crate::node::ledger::storage::PersistentStorage;
+
+/// The version of this binary
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// A storage migration. The changes must be written into the write log, so
+/// that they are committed together with the upgrade's first block.
+pub type Migration = fn(&PersistentStorage, &mut WriteLog) -> Result<(), Error>;
+
+/// The migrations by the names of the upgrades that need them. Add an entry
+/// here together with the bump of the binary's version.
+const MIGRATIONS: &[(&str, Migration)] = &[("public-keys", public_keys)];
+
+/// The storage key segment of the accounts' public keys before the public
+/// keys of other signature schemes than ed25519 were supported
+const ED25519_PK_STORAGE_KEY: &str = "ed25519_pk";
+
+/// Check if this binary supports the upgrade.
+pub fn is_supported(plan: &UpgradePlan) -> bool {
+    plan.version == VERSION
+}
+
+/// Find the migration of the upgrade, if it needs one.
+pub fn find(plan: &UpgradePlan) -> Option<Migration> {
+    MIGRATIONS
+        .iter()
+        .find(|(name, _)| *name == plan.name)
+        .map(|(_, migration)| *migration)
+}
+
+/// Move the accounts' ed25519 public keys to the storage key of the public
+/// keys of any signature scheme. The values that are not valid ed25519 public
+/// keys are left in place.
+fn public_keys(
+    storage: &PersistentStorage,
+    write_log: &mut WriteLog,
+) -> Result<(), Error> {
+    let (iter, _gas) = storage.iter_prefix(&Key::default());
+    for (key, value, _gas) in iter {
+        let key = match Key::parse(key) {
+            Ok(key) => key,
+            Err(_) => continue,
+        };
+        let owner = match &key.segments[..] {
+            [DbKeySeg::AddressSeg(owner), DbKeySeg::StringSeg(segment)]
+                if segment == ED25519_PK_STORAGE_KEY =>
+            {
+                owner
+            }
+            _ => continue,
+        };
+        // The old key isn't protected by the user VP anymore, so its value
+        // may have been overwritten by the account with arbitrary bytes
+        let pk: ed25519::PublicKey = match decode(value) {
+            Ok(pk) => pk,
+            Err(err) => {
+                tracing::warn!(
+                    "Skipping the migration of an invalid public key under \
+                     {}: {}",
+                    key,
+                    err
+                );
+                continue;
+            }
+        };
+        write_log
+            .write(
+                &common::pk_key(owner),
+                encode(&common::PublicKey::Ed25519(pk)),
+            )
+            .expect("Writing a public key shouldn't fail");
+        write_log
+            .delete(&key)
+            .expect("Deleting a public key shouldn't fail");
+    }
+    write_log.commit_tx();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anoma::types::address::{self, Address};
+    use anoma::types::storage::{BlockHash, BlockHeight, KeySeg};
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::config::DEFAULT_CHAIN_ID;
+    use crate::node::ledger::storage;
+
+    /// Test that an account's ed25519 public key is moved to the storage key
+    /// of the public keys of any signature scheme.
+    #[test]
+    fn test_public_keys_migration() {
+        let db_path = TempDir::new().unwrap();
+        let mut storage =
+            storage::open(db_path.path(), DEFAULT_CHAIN_ID.to_owned());
+        let owner = address::testing::established_address_1();
+        let pk: ed25519::PublicKey =
+            ed25519::testing::keypair_1().public.into();
+        let old_key = Key::from(owner.to_db_key())
+            .push(&ED25519_PK_STORAGE_KEY.to_owned())
+            .unwrap();
+        storage
+            .begin_block(BlockHash::default(), BlockHeight(1))
+            .unwrap();
+        storage.write(&old_key, encode(&pk)).unwrap();
+        storage.commit().unwrap();
+
+        let migration = find(&UpgradePlan {
+            name: "public-keys".to_owned(),
+            height: BlockHeight(2),
+            version: VERSION.to_owned(),
+        })
+        .unwrap();
+        let mut write_log = WriteLog::default();
+        migration(&storage, &mut write_log).unwrap();
+        write_log.commit_block(&mut storage).unwrap();
+
+        let (value, _gas) = storage.read(&common::pk_key(&owner)).unwrap();
+        let migrated: common::PublicKey = decode(value.unwrap()).unwrap();
+        assert_eq!(migrated, common::PublicKey::Ed25519(pk));
+        let (value, _gas) = storage.read(&old_key).unwrap();
+        assert!(value.is_none());
+    }
+
+    /// Test that a value under the old public key storage key that is not a
+    /// valid ed25519 public key doesn't abort the migration of the other
+    /// accounts' keys.
+    #[test]
+    fn test_public_keys_migration_with_invalid_value() {
+        let db_path = TempDir::new().unwrap();
+        let mut storage =
+            storage::open(db_path.path(), DEFAULT_CHAIN_ID.to_owned());
+        let owner = address::testing::established_address_1();
+        let corrupted_owner = address::testing::established_address_2();
+        let pk: ed25519::PublicKey =
+            ed25519::testing::keypair_1().public.into();
+        let old_key = |owner: &Address| {
+            Key::from(owner.to_db_key())
+                .push(&ED25519_PK_STORAGE_KEY.to_owned())
+                .unwrap()
+        };
+        storage
+            .begin_block(BlockHash::default(), BlockHeight(1))
+            .unwrap();
+        storage.write(&old_key(&owner), encode(&pk)).unwrap();
+        storage
+            .write(&old_key(&corrupted_owner), vec![1, 2, 3])
+            .unwrap();
+        storage.commit().unwrap();
+
+        let migration = find(&UpgradePlan {
+            name: "public-keys".to_owned(),
+            height: BlockHeight(2),
+            version: VERSION.to_owned(),
+        })
+        .unwrap();
+        let mut write_log = WriteLog::default();
+        migration(&storage, &mut write_log).unwrap();
+        write_log.commit_block(&mut storage).unwrap();
+
+        let (value, _gas) = storage.read(&common::pk_key(&owner)).unwrap();
+        let migrated: common::PublicKey = decode(value.unwrap()).unwrap();
+        assert_eq!(migrated, common::PublicKey::Ed25519(pk));
+        let (value, _gas) =
+            storage.read(&common::pk_key(&corrupted_owner)).unwrap();
+        assert!(value.is_none());
+        let (value, _gas) = storage.read(&old_key(&corrupted_owner)).unwrap();
+        assert_eq!(value, Some(vec![1, 2, 3]));
+    }
+}
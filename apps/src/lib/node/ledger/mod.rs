@@ -1,4 +1,6 @@
 mod events;
+mod migrations;
+mod network;
 pub mod protocol;
 pub mod rpc;
 mod shell;
@@ -7,9 +9,14 @@ pub mod storage;
 mod tendermint_node;
 
 use std::convert::{TryFrom, TryInto};
-use std::sync::mpsc::channel;
+use std::path::Path;
+use std::sync::mpsc::{channel, Sender};
 
+use anoma::ledger::pos::CommitVote;
+use anoma::types::key::ed25519;
+use anoma::types::pos::consensus_address_from_bytes;
 use anoma::types::storage::BlockHash;
+use anoma::types::token;
 use futures::future::{AbortHandle, AbortRegistration, Abortable};
 use tendermint_proto::abci::CheckTxType;
 use tower::ServiceBuilder;
@@ -54,38 +61,33 @@ impl Shell {
     fn call(&mut self, req: Request) -> Result<Response, Error> {
         match req {
             Request::InitChain(init) => {
-                match self.init_chain(init) {
-                    Ok(mut resp) => {
-                        // Set the initial validator set
-                        let genesis = genesis::genesis();
-                        let mut abci_validator =
-                            tendermint_proto::abci::ValidatorUpdate::default();
-                        let pub_key = tendermint_proto::crypto::PublicKey {
-                            sum: Some(tendermint_proto::crypto::public_key::Sum::Ed25519(
-                                genesis.validator.keypair.public.to_bytes().to_vec(),
-                            )),
-                        };
-                        abci_validator.pub_key = Some(pub_key);
-                        abci_validator.power = genesis
-                            .validator
-                            .voting_power
-                            .try_into()
-                            .expect("unexpected validator's voting power");
-                        resp.validators.push(abci_validator);
-                        Ok(Response::InitChain(resp))
-                    }
-                    Err(inner) => Err(inner),
-                }
+                self.init_chain(init).map(Response::InitChain)
             }
             Request::Info(_) => Ok(Response::Info(self.last_state())),
             Request::Query(query) => Ok(Response::Query(self.query(query))),
             Request::PrepareProposal(block) => {
+                let votes = block
+                    .votes
+                    .iter()
+                    .filter_map(|vote| {
+                        vote.validator.as_ref().map(|validator| CommitVote {
+                            consensus_address: consensus_address_from_bytes(
+                                &validator.address,
+                            ),
+                            power: validator
+                                .power
+                                .try_into()
+                                .unwrap_or_default(),
+                            signed: vote.signed_last_block,
+                        })
+                    })
+                    .collect();
                 match (
                     BlockHash::try_from(&*block.hash),
                     block.header.expect("missing block's header").try_into(),
                 ) {
                     (Ok(hash), Ok(header)) => {
-                        let _ = self.prepare_proposal(hash, header);
+                        let _ = self.prepare_proposal(hash, header, votes);
                     }
                     (Ok(_), Err(msg)) => {
                         tracing::error!("Unexpected block header {}", msg);
@@ -94,8 +96,8 @@ impl Shell {
                 };
                 Ok(Response::PrepareProposal(Default::default()))
             }
-            Request::VerifyHeader(_req) => {
-                Ok(Response::VerifyHeader(self.verify_header(_req)))
+            Request::VerifyHeader(req) => {
+                Ok(Response::VerifyHeader(self.verify_header(req)))
             }
             Request::ProcessProposal(block) => {
                 Ok(Response::ProcessProposal(self.process_proposal(block)))
@@ -150,6 +152,25 @@ pub fn reset(config: config::Ledger) -> Result<(), shell::Error> {
     shell::reset(config)
 }
 
+/// Generates a new validator consensus key for the Tendermint node and returns
+/// its public key
+pub fn gen_validator_key(
+    config: config::Ledger,
+) -> Result<ed25519::PublicKey, tendermint_node::Error> {
+    tendermint_node::gen_validator_key(&config.tendermint)
+}
+
+/// Generates the base directories of a network of validator nodes that run on
+/// the same machine
+pub fn init_network(
+    output_dir: impl AsRef<Path>,
+    num_validators: u16,
+    stake: token::Amount,
+    balance: token::Amount,
+) -> Result<Vec<network::Node>, network::Error> {
+    network::init_network(output_dir, num_validators, stake, balance)
+}
+
 /// Runs the an asynchronous ABCI server with four sub-components for consensus,
 /// mempool, snapshot, and info.
 ///
@@ -158,10 +179,18 @@ pub fn reset(config: config::Ledger) -> Result<(), shell::Error> {
 async fn run_shell(
     config: config::Ledger,
     abort_registration: AbortRegistration,
+    halt: Sender<bool>,
 ) {
+    let genesis = genesis::load(config.genesis.as_deref())
+        .expect("Failed to load the genesis");
+
     // Construct our ABCI application.
-    let service =
-        AbcippShim::new(&config.db, config::DEFAULT_CHAIN_ID.to_owned());
+    let service = AbcippShim::new(
+        &config.db,
+        config::DEFAULT_CHAIN_ID.to_owned(),
+        genesis,
+        halt,
+    );
 
     // Split it into components.
     let (consensus, mempool, snapshot, info) = split::service(service, 5);
@@ -209,6 +238,9 @@ pub fn run(config: config::Ledger) {
     // used for shutting down Tendermint node in case the shell panics
     let (sender, receiver) = channel();
     let kill_switch = sender.clone();
+    // used for halting the node when the shell cannot continue, e.g. at an
+    // upgrade height that requires a new binary
+    let halt = sender.clone();
     // used for shutting down the shell and making sure that drop is called
     // on the database
     let (abort_handle, abort_registration) = AbortHandle::new_pair();
@@ -232,7 +264,7 @@ pub fn run(config: config::Ledger) {
 
     // start the shell + ABCI server
     let shell_handle = std::thread::spawn(move || {
-        run_shell(config, abort_registration);
+        run_shell(config, abort_registration, halt);
     });
 
     tracing::info!("Anoma ledger node started.");
@@ -0,0 +1,194 @@
+//! Generator of a network of validator nodes that run on the same machine
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anoma::types::address::Address;
+use anoma::types::{pos, token};
+use libp2p::Multiaddr;
+use thiserror::Error;
+
+use super::tendermint_node;
+use crate::config::genesis::{self, Balance, GenesisFile, ValidatorConfig};
+use crate::config::{self, Config};
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("The network must have at least one validator")]
+    NoValidators,
+    #[error("Too many validators, the nodes' ports would overflow")]
+    PortOverflow,
+    #[error("The node directory {0:?} already exists")]
+    NodeDirExists(PathBuf),
+    #[error("{0}")]
+    Tendermint(tendermint_node::Error),
+    #[error("{0}")]
+    Genesis(genesis::Error),
+    #[error("{0}")]
+    Config(config::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The name of the genesis file in a node's base directory
+pub const GENESIS_FILE: &str = "genesis.toml";
+
+/// The ports of each node are offset from the default ports of the previous
+/// node by this much
+const PORT_OFFSET: u16 = 10;
+const P2P_PORT: u16 = 26656;
+const RPC_PORT: u16 = 26657;
+const ABCI_PORT: u16 = 26658;
+const GOSSIP_PORT: u16 = 20201;
+
+/// A node of a generated network
+#[derive(Debug)]
+pub struct Node {
+    /// The node's base directory, from which it can be run with
+    /// `anoma --base-dir {base_dir} ledger`
+    pub base_dir: PathBuf,
+    /// The validator's account, which owns its self-bond and balance
+    pub account: Address,
+    /// The port of the Tendermint RPC, which is the node's ledger address for
+    /// the client
+    pub rpc_port: u16,
+}
+
+/// Generate the base directories of a network of validator nodes in the
+/// output directory. Each node has its own Tendermint validator and node
+/// keys. All the nodes share the same Tendermint genesis and genesis file,
+/// which gives each validator's account the given stake and balance of XAN.
+/// The nodes are persistent peers of each other and their ports are offset
+/// from the default ports, so that they can run on the same machine.
+pub fn init_network(
+    output_dir: impl AsRef<Path>,
+    num_validators: u16,
+    stake: token::Amount,
+    balance: token::Amount,
+) -> Result<Vec<Node>> {
+    if num_validators == 0 {
+        return Err(Error::NoValidators);
+    }
+    let output_dir = output_dir.as_ref();
+    let mut validators = Vec::with_capacity(num_validators.into());
+    let mut node_ids = Vec::with_capacity(num_validators.into());
+    // the ABCI port is the largest of the default ports, so the other ports
+    // cannot overflow if the last node's ABCI port doesn't
+    port(ABCI_PORT, num_validators - 1)?;
+    for ix in 0..num_validators {
+        let base_dir = node_dir(output_dir, ix);
+        if base_dir.exists() {
+            return Err(Error::NodeDirExists(base_dir));
+        }
+    }
+
+    for ix in 0..num_validators {
+        let tendermint_dir =
+            node_dir(output_dir, ix).join(config::TENDERMINT_DIR);
+        let (mut validator, keypair) = genesis::Validator::generate();
+        validator.stake = stake;
+        tendermint_node::write_validator_key(
+            &tendermint_dir,
+            &validator.address,
+            &keypair,
+        )
+        .map_err(Error::Tendermint)?;
+        let node_id = tendermint_node::write_node_key(&tendermint_dir)
+            .map_err(Error::Tendermint)?;
+        tendermint_node::init(&tendermint_dir).map_err(Error::Tendermint)?;
+        validators.push(validator);
+        node_ids.push(node_id);
+    }
+
+    let genesis_validators: Vec<_> = validators
+        .iter()
+        .map(|validator| {
+            (validator.pk.clone(), pos::voting_power(validator.stake))
+        })
+        .collect();
+    let genesis_file = GenesisFile {
+        validators: validators.iter().map(ValidatorConfig::from).collect(),
+        balances: validators
+            .iter()
+            .map(|validator| Balance {
+                owner: validator.account.clone(),
+                amount: balance,
+            })
+            .collect(),
+    };
+    // the first node's Tendermint genesis is the template for all the nodes
+    let template = node_dir(output_dir, 0).join(config::TENDERMINT_DIR);
+
+    let mut nodes = Vec::with_capacity(num_validators.into());
+    for (ix, validator) in (0..num_validators).zip(validators) {
+        let base_dir = node_dir(output_dir, ix);
+        let tendermint_dir = base_dir.join(config::TENDERMINT_DIR);
+        tendermint_node::write_network_genesis(
+            &tendermint_dir,
+            &template,
+            config::DEFAULT_CHAIN_ID,
+            &genesis_validators,
+        )
+        .map_err(Error::Tendermint)?;
+        let peers: Vec<String> = node_ids
+            .iter()
+            .zip(0..num_validators)
+            .filter(|(_, peer_ix)| *peer_ix != ix)
+            .map(|(node_id, peer_ix)| {
+                Ok(format!(
+                    "{}@127.0.0.1:{}",
+                    node_id,
+                    port(P2P_PORT, peer_ix)?
+                ))
+            })
+            .collect::<Result<_>>()?;
+        let rpc_port = port(RPC_PORT, ix)?;
+        tendermint_node::configure_network(
+            &tendermint_dir,
+            port(P2P_PORT, ix)?,
+            rpc_port,
+            &peers,
+        )
+        .map_err(Error::Tendermint)?;
+
+        let genesis_path = base_dir.join(GENESIS_FILE);
+        genesis_file.write(&genesis_path).map_err(Error::Genesis)?;
+
+        let mut node_config = Config::default();
+        let ledger = node_config
+            .ledger
+            .as_mut()
+            .expect("safe because default has ledger");
+        ledger.tendermint = tendermint_dir;
+        ledger.db =
+            base_dir.join(config::DB_DIR).join(config::DEFAULT_CHAIN_ID);
+        ledger.address.set_port(port(ABCI_PORT, ix)?);
+        ledger.genesis = Some(genesis_path);
+        if let Some(gossiper) = node_config.intent_gossiper.as_mut() {
+            gossiper.address = Multiaddr::from_str(&format!(
+                "/ip4/0.0.0.0/tcp/{}",
+                port(GOSSIP_PORT, ix)?
+            ))
+            .expect("The gossiper address shouldn't fail parsing");
+        }
+        node_config.write(&base_dir, false).map_err(Error::Config)?;
+
+        nodes.push(Node {
+            base_dir,
+            account: validator.account,
+            rpc_port,
+        });
+    }
+    Ok(nodes)
+}
+
+fn node_dir(output_dir: &Path, ix: u16) -> PathBuf {
+    output_dir.join(format!("node-{}", ix))
+}
+
+/// Get the port of the node with the given index
+fn port(default: u16, ix: u16) -> Result<u16> {
+    ix.checked_mul(PORT_OFFSET)
+        .and_then(|offset| default.checked_add(offset))
+        .ok_or(Error::PortOverflow)
+}
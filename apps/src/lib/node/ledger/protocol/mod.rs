@@ -5,11 +5,17 @@ use std::convert::TryFrom;
 use std::fmt;
 
 use anoma::ledger::gas::{self, BlockGasMeter, VpGasMeter, VpsGas};
+use anoma::ledger::governance::{self, GovernanceVp};
 use anoma::ledger::ibc::{self, Ibc};
 use anoma::ledger::native_vp::{self, NativeVp};
 use anoma::ledger::parameters::{self, ParametersVp};
 use anoma::ledger::pos::{self, PoS};
+#[cfg(feature = "shielded-pool")]
+use anoma::ledger::shielded_pool::{self, ShieldedPool};
 use anoma::ledger::storage::write_log::WriteLog;
+use anoma::ledger::token::{self, TokenVp};
+use anoma::ledger::token_registry::{self, TokenRegistry};
+use anoma::ledger::treasury::{self, TreasuryVp};
 use anoma::proto::{self, Tx};
 use anoma::types::address::{Address, InternalAddress};
 use anoma::types::storage::Key;
@@ -29,7 +35,7 @@ pub enum Error {
     TxRunnerError(vm::wasm::run::Error),
     #[error("Gas error: {0}")]
     GasError(gas::Error),
-    #[error("Error executing VP for addresses: {0:?}")]
+    #[error("Error executing VP: {0}")]
     VpRunnerError(vm::wasm::run::Error),
     #[error("The address {0} doesn't exist")]
     MissingAddress(Address),
@@ -39,6 +45,21 @@ pub enum Error {
     PosNativeVpError(pos::Error),
     #[error("Parameters native VP: {0}")]
     ParametersNativeVpError(parameters::Error),
+    #[error("Token registry native VP: {0}")]
+    TokenRegistryNativeVpError(token_registry::Error),
+    #[cfg(feature = "shielded-pool")]
+    #[error("Shielded pool native VP: {0}")]
+    ShieldedPoolNativeVpError(shielded_pool::Error),
+    #[error("Governance native VP: {0}")]
+    GovernanceNativeVpError(governance::Error),
+    #[error("Treasury native VP: {0}")]
+    TreasuryNativeVpError(treasury::Error),
+    #[error("Error reading the transactions allowlist: {0}")]
+    TxAllowlistError(parameters::ReadError),
+    #[error("The transaction code is not in the allowlist")]
+    DisallowedTx,
+    #[error("Token native VP: {0}")]
+    TokenNativeVpError(token::Error),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -91,6 +112,13 @@ pub fn apply_tx(
 
     let tx = Tx::try_from(tx_bytes).map_err(Error::TxDecodingError)?;
 
+    let (is_allowed, gas) = parameters::is_tx_allowed(storage, &tx.code)
+        .map_err(Error::TxAllowlistError)?;
+    block_gas_meter.add(gas).map_err(Error::GasError)?;
+    if !is_allowed {
+        return Err(Error::DisallowedTx);
+    }
+
     let verifiers = execute_tx(&tx, storage, block_gas_meter, write_log)?;
 
     let vps_result =
@@ -130,6 +158,8 @@ fn execute_tx(
 enum Vp<'a> {
     Wasm(Vec<u8>),
     Native(&'a InternalAddress),
+    /// The native VP of a token in the token registry
+    NativeToken,
 }
 
 /// Check the acceptance of a transaction by validity predicates
@@ -148,6 +178,11 @@ fn check_vps(
         .map(|(addr, keys)| {
             let vp = match addr {
                 Address::Internal(addr) => Vp::Native(addr),
+                Address::Established(_)
+                    if is_registered_token(storage, gas_meter, addr)? =>
+                {
+                    Vp::NativeToken
+                }
                 Address::Established(_) | Address::Implicit(_) => {
                     let (vp, gas) = storage
                         .validity_predicate(addr)
@@ -180,6 +215,19 @@ fn check_vps(
     Ok(vps_result)
 }
 
+/// Check if the address is a token in the token registry, which is validated
+/// by the native token VP.
+fn is_registered_token(
+    storage: &PersistentStorage,
+    gas_meter: &mut BlockGasMeter,
+    addr: &Address,
+) -> Result<bool> {
+    let (is_registered, gas) = token_registry::is_registered(storage, addr)
+        .map_err(Error::StorageError)?;
+    gas_meter.add(gas).map_err(Error::GasError)?;
+    Ok(is_registered)
+}
+
 /// Execute verifiers' validity predicates
 fn execute_vps(
     verifiers: Vec<(Address, HashSet<Key>, Vp)>,
@@ -246,10 +294,70 @@ fn execute_vps(
                             gas_meter = parameters.ctx.gas_meter.into_inner();
                             result
                         }
+                        InternalAddress::TokenRegistry => {
+                            let registry = TokenRegistry { ctx };
+                            let result = registry
+                                .validate_tx(tx_data, keys, &verifiers_addr)
+                                .map_err(Error::TokenRegistryNativeVpError);
+                            // Take the gas meter back out of the context
+                            gas_meter = registry.ctx.gas_meter.into_inner();
+                            result
+                        }
+                        #[cfg(feature = "shielded-pool")]
+                        InternalAddress::ShieldedPool => {
+                            let pool = ShieldedPool { ctx };
+                            let result = pool
+                                .validate_tx(tx_data, keys, &verifiers_addr)
+                                .map_err(Error::ShieldedPoolNativeVpError);
+                            // Take the gas meter back out of the context
+                            gas_meter = pool.ctx.gas_meter.into_inner();
+                            result
+                        }
+                        // Without the shielded pool, any change to its
+                        // storage is rejected
+                        #[cfg(not(feature = "shielded-pool"))]
+                        InternalAddress::ShieldedPool => {
+                            // Take the gas meter back out of the context
+                            gas_meter = ctx.gas_meter.into_inner();
+                            Ok(false)
+                        }
+                        InternalAddress::Governance => {
+                            let governance = GovernanceVp { ctx };
+                            let result = governance
+                                .validate_tx(tx_data, keys, &verifiers_addr)
+                                .map_err(Error::GovernanceNativeVpError);
+                            // Take the gas meter back out of the context
+                            gas_meter = governance.ctx.gas_meter.into_inner();
+                            result
+                        }
+                        InternalAddress::Treasury => {
+                            let treasury = TreasuryVp { ctx };
+                            let result = treasury
+                                .validate_tx(tx_data, keys, &verifiers_addr)
+                                .map_err(Error::TreasuryNativeVpError);
+                            // Take the gas meter back out of the context
+                            gas_meter = treasury.ctx.gas_meter.into_inner();
+                            result
+                        }
                     };
 
                     accepted
                 }
+                Vp::NativeToken => {
+                    let ctx =
+                        native_vp::Ctx::new(storage, write_log, tx, gas_meter);
+                    let tx_data = match tx.data.as_ref() {
+                        Some(data) => &data[..],
+                        None => &[],
+                    };
+                    let token_vp = TokenVp { ctx, token: addr };
+                    let result = token_vp
+                        .validate_tx(tx_data, keys, &verifiers_addr)
+                        .map_err(Error::TokenNativeVpError);
+                    // Take the gas meter back out of the context
+                    gas_meter = token_vp.ctx.gas_meter.into_inner();
+                    result
+                }
             };
 
             // Returning error from here will short-circuit the VP parallel
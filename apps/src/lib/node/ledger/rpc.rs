@@ -4,7 +4,8 @@ use std::fmt::Display;
 use std::str::FromStr;
 
 use anoma::types::address::Address;
-use anoma::types::storage;
+use anoma::types::storage::{self, BlockHeight, Epoch};
+use anoma::types::time::DateTimeUtc;
 use borsh::{BorshDeserialize, BorshSerialize};
 use thiserror::Error;
 
@@ -17,6 +18,12 @@ pub enum Path {
     Value(storage::Key),
     /// Read a range of storage values with a matching key prefix
     Prefix(storage::Key),
+    /// Read the current epoch
+    Epoch,
+    /// Look-up the epoch of a block height
+    EpochAtHeight(BlockHeight),
+    /// Read the earliest start of the next epoch
+    NextEpoch,
 }
 
 /// RPC query path
@@ -26,6 +33,16 @@ pub struct PrefixValue {
     pub value: Vec<u8>,
 }
 
+/// The earliest start of the next epoch. The next epoch begins at the first
+/// block whose height and time are both at least the minimum start height and
+/// time.
+#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+pub struct NextEpoch {
+    pub epoch: Epoch,
+    pub min_start_height: BlockHeight,
+    pub min_start_time: DateTimeUtc,
+}
+
 #[derive(Debug, Clone)]
 pub struct BalanceQuery {
     owner: Option<Address>,
@@ -35,6 +52,8 @@ pub struct BalanceQuery {
 const DRY_RUN_TX_PATH: &str = "dry_run_tx";
 const VALUE_PREFIX: &str = "value";
 const PREFIX_PREFIX: &str = "prefix";
+const EPOCH_PATH: &str = "epoch";
+const NEXT_EPOCH_PATH: &str = "next_epoch";
 
 impl Display for Path {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -46,6 +65,11 @@ impl Display for Path {
             Path::Prefix(storage_key) => {
                 write!(f, "{}/{}", PREFIX_PREFIX, storage_key)
             }
+            Path::Epoch => write!(f, "{}", EPOCH_PATH),
+            Path::EpochAtHeight(height) => {
+                write!(f, "{}/{}", EPOCH_PATH, height)
+            }
+            Path::NextEpoch => write!(f, "{}", NEXT_EPOCH_PATH),
         }
     }
 }
@@ -57,6 +81,8 @@ impl FromStr for Path {
         let path = s.to_lowercase();
         match path.as_str() {
             DRY_RUN_TX_PATH => Ok(Self::DryRunTx),
+            EPOCH_PATH => Ok(Self::Epoch),
+            NEXT_EPOCH_PATH => Ok(Self::NextEpoch),
             _ => match path.split_once("/") {
                 Some((VALUE_PREFIX, storage_key)) => {
                     let key = storage::Key::parse(storage_key)
@@ -68,6 +94,12 @@ impl FromStr for Path {
                         .map_err(PathParseError::InvalidStorageKey)?;
                     Ok(Self::Prefix(key))
                 }
+                Some((EPOCH_PATH, height)) => {
+                    let height = height.parse().map_err(|_| {
+                        PathParseError::InvalidHeight(height.to_string())
+                    })?;
+                    Ok(Self::EpochAtHeight(BlockHeight(height)))
+                }
                 _ => Err(PathParseError::InvalidPath(s.to_string())),
             },
         }
@@ -90,4 +122,6 @@ pub enum PathParseError {
     InvalidPath(String),
     #[error("Invalid storage key: {0}")]
     InvalidStorageKey(storage::Error),
+    #[error("Invalid block height: {0}")]
+    InvalidHeight(String),
 }
@@ -1,16 +1,26 @@
+use std::collections::HashSet;
 use std::convert::{TryFrom, TryInto};
 use std::path::Path;
 use std::str::FromStr;
 
-use anoma::ledger::gas::BlockGasMeter;
+use anoma::ledger::gas::{self, BlockGasMeter};
+use anoma::ledger::parameters::ConsensusParams;
+#[cfg(feature = "shielded-pool")]
+use anoma::ledger::shielded_pool;
+use anoma::ledger::storage::types::{decode, encode};
 use anoma::ledger::storage::write_log::WriteLog;
-use anoma::ledger::{ibc, parameters, pos};
+use anoma::ledger::storage::BlockState;
+use anoma::ledger::upgrade::{self, UpgradePlan};
+use anoma::ledger::{
+    governance, ibc, parameters, pos, token_registry, treasury,
+};
 use anoma::proto::{self, Tx};
 use anoma::types::address::Address;
-use anoma::types::key::ed25519::PublicKey;
+use anoma::types::key::common::SigningKey;
+use anoma::types::pos::voting_power;
 use anoma::types::storage::{BlockHash, BlockHeight, Key};
 use anoma::types::time::{DateTime, DateTimeUtc, TimeZone, Utc};
-use anoma::types::token::Amount;
+use anoma::types::token::{Amount, TokenInfo};
 use anoma::types::{address, key, token};
 use borsh::BorshSerialize;
 use itertools::Itertools;
@@ -21,9 +31,9 @@ use tower_abci::{request, response};
 use super::rpc;
 use crate::config::genesis;
 use crate::node::ledger::events::{Event, EventType};
-use crate::node::ledger::rpc::PrefixValue;
+use crate::node::ledger::rpc::{NextEpoch, PrefixValue};
 use crate::node::ledger::shims::abcipp_shim_types::shim;
-use crate::node::ledger::{protocol, storage, tendermint_node};
+use crate::node::ledger::{migrations, protocol, storage, tendermint_node};
 use crate::{config, wallet};
 
 #[derive(Error, Debug)]
@@ -38,6 +48,26 @@ pub enum Error {
     TxApply(protocol::Error),
     #[error("Gas limit exceeding while applying transactions in block")]
     GasOverflow,
+    #[cfg(feature = "shielded-pool")]
+    #[error("Error updating the note commitment tree: {0}")]
+    CommitmentTree(anoma::ledger::storage::Error),
+    #[error("Error tallying the governance proposals: {0}")]
+    Governance(anoma::ledger::storage::Error),
+    #[error("Error distributing the validators' rewards: {0}")]
+    Rewards(anoma::ledger::storage::Error),
+    #[error("Error reading the upgrade plan: {0}")]
+    UpgradePlan(upgrade::Error),
+    #[error(
+        "The upgrade {} at height {} requires the binary version {}, but \
+         this binary's version is {}",
+        .0.name,
+        .0.height,
+        .0.version,
+        migrations::VERSION
+    )]
+    UpgradeRequired(UpgradePlan),
+    #[error("Error migrating the storage for an upgrade: {0}")]
+    Migration(anoma::ledger::storage::Error),
     #[error("{0}")]
     Tendermint(tendermint_node::Error),
 }
@@ -70,12 +100,29 @@ pub struct Shell {
     pub(super) storage: storage::PersistentStorage,
     gas_meter: BlockGasMeter,
     write_log: WriteLog,
+    /// The consensus parameters that became active in the current block, if
+    /// they have changed
+    consensus_params_update: Option<ConsensusParams>,
+    /// The current block's proposer and the votes in the commit of the
+    /// previous block, which are recorded for the validators' rewards
+    last_commit: Option<(String, Vec<pos::CommitVote>)>,
+    /// The state from before the current block was prepared, if no block has
+    /// been committed yet. There's no committed state to reload it from when
+    /// the block's proposal is reverted.
+    genesis_state: Option<BlockState>,
+    /// The genesis used to initialize the chain
+    genesis: genesis::Genesis,
 }
 
 impl Shell {
-    /// Create a new shell from a path to a database and a chain id. Looks
-    /// up the database with this data and tries to load the last state.
-    pub fn new(db_path: impl AsRef<Path>, chain_id: String) -> Self {
+    /// Create a new shell from a path to a database, a chain id and the
+    /// chain's genesis. Looks up the database with this data and tries to
+    /// load the last state.
+    pub fn new(
+        db_path: impl AsRef<Path>,
+        chain_id: String,
+        genesis: genesis::Genesis,
+    ) -> Self {
         let mut storage = storage::open(db_path, chain_id);
         storage
             .load_last_state()
@@ -88,6 +135,10 @@ impl Shell {
             storage,
             gas_meter: BlockGasMeter::default(),
             write_log: WriteLog::default(),
+            consensus_params_update: None,
+            last_commit: None,
+            genesis_state: None,
+            genesis,
         }
     }
 
@@ -99,7 +150,7 @@ impl Shell {
         &mut self,
         init: request::InitChain,
     ) -> Result<response::InitChain> {
-        let response = response::InitChain::default();
+        let mut response = response::InitChain::default();
         let (current_chain_id, _) = self.storage.get_chain_id();
         if current_chain_id != init.chain_id {
             return Err(Error::ChainId(format!(
@@ -107,9 +158,11 @@ impl Shell {
                 current_chain_id, init.chain_id
             )));
         }
-        let genesis = genesis::genesis();
+        let genesis = &self.genesis;
 
-        // Initialize because there is no block
+        // Initialize because there is no block. The genesis tokens are added
+        // to the token registry and so they are validated by the native token
+        // VP, but their accounts must still have a VP.
         let token_vp =
             std::fs::read("wasm/vp_token.wasm").expect("cannot load token VP");
         let user_vp =
@@ -136,6 +189,41 @@ impl Shell {
                 .expect("Unable to write token VP");
         }
 
+        // default tokens in the token registry for testing, with the total
+        // supply given by the users' balances below
+        let total_supply = Amount::whole(1_000_000)
+            .checked_mul(users.len() as u64)
+            .expect("The genesis total supply shouldn't overflow");
+        // the genesis balances of XAN are added to its total supply
+        let xan = address::xan();
+        let xan_supply = genesis
+            .balances
+            .iter()
+            .try_fold(total_supply, |supply, balance| {
+                supply.checked_add(&balance.amount)
+            })
+            .expect("The genesis total supply shouldn't overflow");
+        let registry: Vec<(Address, TokenInfo, Amount)> = tokens
+            .iter()
+            .map(|(token, symbol)| {
+                let info = TokenInfo {
+                    symbol: symbol.to_string(),
+                    name: symbol.to_string(),
+                    decimals: token::MAX_DECIMAL_PLACES as u8,
+                    minter: None,
+                };
+                let supply = if token == &xan {
+                    xan_supply
+                } else {
+                    total_supply
+                };
+                (token.clone(), info, supply)
+            })
+            .collect();
+        token_registry::init_genesis_storage(&mut self.storage, &registry);
+        #[cfg(feature = "shielded-pool")]
+        shielded_pool::init_genesis_storage(&mut self.storage);
+
         for (user, token) in users.iter().cartesian_product(tokens.keys()) {
             // default user VPs for testing
             self.storage
@@ -153,24 +241,63 @@ impl Shell {
                 .expect("Unable to set genesis balance");
 
             // default user's public keys for testing
-            let pk_key = key::ed25519::pk_key(user);
-            let pk = PublicKey::from(wallet::key_of(user.encode()).public);
+            let pk_key = key::common::pk_key(user);
+            let pk = wallet::key_of(user.encode()).public_key();
             self.storage
                 .write(&pk_key, pk.try_to_vec().expect("encode public key"))
                 .expect("Unable to set genesis user public key");
         }
 
+        for balance in &genesis.balances {
+            let key = token::balance_key(&xan, &balance.owner);
+            let (current, _gas) = self
+                .storage
+                .read(&key)
+                .expect("Unable to read a genesis balance");
+            let current: Amount = current
+                .and_then(|bytes| decode(bytes).ok())
+                .unwrap_or_default();
+            let amount = current
+                .checked_add(&balance.amount)
+                .expect("The genesis balance shouldn't overflow");
+            self.storage
+                .write(&key, encode(&amount))
+                .expect("Unable to set genesis balance");
+        }
+
+        // The accounts of the validators generated for a network are the
+        // implicit addresses of their consensus keys, which also sign their
+        // txs
+        for validator in &genesis.validators {
+            let pk = key::common::PublicKey::from(validator.pk.clone());
+            if Address::from(&pk) == validator.account {
+                self.storage
+                    .write(
+                        &Key::validity_predicate(&validator.account),
+                        user_vp.to_vec(),
+                    )
+                    .expect("Unable to write user VP");
+                self.storage
+                    .write(
+                        &key::common::pk_key(&validator.account),
+                        encode(&pk),
+                    )
+                    .expect("Unable to set genesis validator public key");
+            }
+        }
+
         // Temporary for testing, we have a fixed matchmaker account.  This
         // account has a public key for signing matchmaker txs and verifying
         // their signatures in its VP. The VP is the same as the user's VP,
         // which simply checks the signature. We could consider using the
         // same key as the intent gossip's p2p key.
         let matchmaker = address::matchmaker();
-        let matchmaker_pk = key::ed25519::pk_key(&matchmaker);
+        let matchmaker_pk = key::common::pk_key(&matchmaker);
         self.storage
             .write(
                 &matchmaker_pk,
-                wallet::matchmaker_pk()
+                wallet::matchmaker_keypair()
+                    .public_key()
                     .try_to_vec()
                     .expect("encode public key"),
             )
@@ -179,27 +306,61 @@ impl Shell {
             .write(&Key::validity_predicate(&matchmaker), user_vp.to_vec())
             .expect("Unable to write matchmaker VP");
 
-        pos::init_genesis_storage(&mut self.storage);
+        let ts: tendermint_proto::google::protobuf::Timestamp =
+            init.time.expect("Missing genesis time");
+        // TODO hacky conversion, depends on https://github.com/informalsystems/tendermint-rs/issues/870
+        let genesis_time: DateTimeUtc =
+            (Utc.timestamp(ts.seconds, ts.nanos as u32)).into();
+
+        let validators: Vec<pos::GenesisValidator> = genesis
+            .validators
+            .iter()
+            .map(|validator| pos::GenesisValidator {
+                address: validator.account.clone(),
+                consensus_key: validator.pk.clone(),
+                stake: validator.stake,
+                commission_rate: validator.commission_rate,
+                max_commission_rate_change: validator
+                    .max_commission_rate_change,
+            })
+            .collect();
+        pos::init_genesis_storage(&mut self.storage, &validators, genesis_time);
         ibc::init_genesis_storage(&mut self.storage);
         parameters::init_genesis_storage(
             &mut self.storage,
             &genesis.parameters,
         );
+        governance::init_genesis_storage(
+            &mut self.storage,
+            &genesis.governance,
+        );
+        if let Some(plan) = genesis.upgrade.as_ref() {
+            upgrade::init_genesis_storage(&mut self.storage, plan);
+        }
+        treasury::init_genesis_storage(
+            &mut self.storage,
+            genesis.treasury_admin.as_ref(),
+        );
 
-        let ts: tendermint_proto::google::protobuf::Timestamp =
-            init.time.expect("Missing genesis time");
         let initial_height = init
             .initial_height
             .try_into()
             .expect("Unexpected block height");
-        // TODO hacky conversion, depends on https://github.com/informalsystems/tendermint-rs/issues/870
-        let genesis_time: DateTimeUtc =
-            (Utc.timestamp(ts.seconds, ts.nanos as u32)).into();
 
         self.storage
             .init_genesis_epoch(initial_height, genesis_time)
             .expect("Initializing genesis epoch must not fail");
 
+        response.consensus_params =
+            Some(consensus_params(&genesis.parameters.consensus));
+        // Set the initial validator set
+        response.validators = genesis
+            .validators
+            .iter()
+            .map(|validator| {
+                validator_update(&validator.pk, voting_power(validator.stake))
+            })
+            .collect();
         Ok(response)
     }
 
@@ -232,6 +393,9 @@ impl Shell {
     /// Uses `path` in the query to forward the request to the
     /// right query method and returns the result (which may be
     /// the default if `path` is not a supported string.
+    ///
+    /// The response code `1` is only used when no value is found, so that
+    /// clients can tell it apart from an error, for which the code is `2`.
     pub fn query(&self, query: request::Query) -> response::Query {
         use rpc::Path;
         match Path::from_str(&query.path) {
@@ -243,9 +407,12 @@ impl Shell {
                 Path::Prefix(storage_key) => {
                     self.read_storage_prefix(&storage_key)
                 }
+                Path::Epoch => self.read_epoch(),
+                Path::EpochAtHeight(height) => self.read_epoch_at(height),
+                Path::NextEpoch => self.read_next_epoch(),
             },
             Err(err) => response::Query {
-                code: 1,
+                code: 2,
                 info: format!("RPC error: {}", err),
                 ..Default::default()
             },
@@ -253,11 +420,22 @@ impl Shell {
     }
 
     /// Begin a new block.
-    pub fn prepare_proposal(&mut self, hash: BlockHash, header: Header) {
+    pub fn prepare_proposal(
+        &mut self,
+        hash: BlockHash,
+        header: Header,
+        votes: Vec<pos::CommitVote>,
+    ) {
         let height = BlockHeight(header.height.into());
+        self.last_commit = Some((header.proposer_address.to_string(), votes));
         let time: DateTime<Utc> = header.time.into();
         let time: DateTimeUtc = time.into();
 
+        self.genesis_state = if self.storage.last_height == BlockHeight(0) {
+            Some(self.storage.block_state())
+        } else {
+            None
+        };
         self.gas_meter.reset();
         self.storage
             .begin_block(hash, height)
@@ -265,33 +443,161 @@ impl Shell {
         self.storage
             .set_header(header)
             .expect("Setting a header shouldn't fail");
+        let (epoch, _gas) = self.storage.get_block_epoch();
+        let (parameters, _gas) = parameters::read(&self.storage)
+            .expect("Couldn't read protocol parameters");
         self.storage
             .update_epoch(height, time)
             .expect("Must be able to update epoch");
+        // The parameters can only change when a new epoch begins
+        self.consensus_params_update = None;
+        if self.storage.get_block_epoch().0 != epoch {
+            let (new_parameters, _gas) = parameters::read(&self.storage)
+                .expect("Couldn't read protocol parameters");
+            if new_parameters.consensus != parameters.consensus {
+                self.consensus_params_update = Some(new_parameters.consensus);
+            }
+        }
     }
 
+    /// Check that a block's header is for this chain and that the block
+    /// extends the last committed block.
     pub fn verify_header(
         &self,
-        _req: shim::request::VerifyHeader,
+        req: shim::request::VerifyHeader,
     ) -> shim::response::VerifyHeader {
+        let invalid =
+            |info: String| shim::response::VerifyHeader { code: 1, info };
+        let header = match req.header.map(Header::try_from) {
+            Some(Ok(header)) => header,
+            Some(Err(err)) => {
+                return invalid(format!("Unexpected block header {}", err));
+            }
+            None => return invalid("Missing block's header".into()),
+        };
+        if header.chain_id.as_str() != self.storage.chain_id {
+            return invalid(
+                Error::ChainId(format!(
+                    "expected {}, got {}",
+                    self.storage.chain_id, header.chain_id
+                ))
+                .to_string(),
+            );
+        }
+        let height = BlockHeight(header.height.into());
+        if height <= self.storage.last_height {
+            return invalid(format!(
+                "The block height {} is not above the last committed height {}",
+                height, self.storage.last_height
+            ));
+        }
         Default::default()
     }
 
-    /// Check the fees and signatures of the fee payer for a transaction
+    /// Validate the transactions of a proposed block before any of them are
+    /// applied. Every transaction must be decodable, all the signatures of a
+    /// transaction signed by multiple keys must be valid and the gas that is
+    /// charged before its code is run must fit within the transaction gas
+    /// limit. The sum of this gas for the valid transactions must fit within
+    /// the block gas limit. The invalid transactions are dropped from the
+    /// block. The whole proposal is only rejected for a fault of its proposer,
+    /// which is including the same transaction more than once.
+    ///
+    /// A single signature can only be verified with the signer's public key,
+    /// which is known to the signer's VP. There are no wrapper transactions
+    /// or fees yet, so the fees are not checked.
     pub fn process_proposal(
-        &mut self,
-        _req: shim::request::ProcessProposal,
+        &self,
+        req: shim::request::ProcessProposal,
     ) -> shim::response::ProcessProposal {
-        Default::default()
+        let mut seen_txs = HashSet::new();
+        if req.txs.iter().any(|tx_bytes| !seen_txs.insert(tx_bytes)) {
+            return shim::response::ProcessProposal {
+                code: 1,
+                info: "The proposal contains a duplicate transaction".into(),
+                tx_results: vec![],
+            };
+        }
+
+        let (parameters, _gas) = parameters::read(&self.storage)
+            .expect("Couldn't read protocol parameters");
+        let max_block_gas =
+            parameters.consensus.max_block_gas.min(gas::BLOCK_GAS_LIMIT);
+        let mut block_gas: u64 = 0;
+        let tx_results = req
+            .txs
+            .iter()
+            .map(|tx_bytes| {
+                let tx = match Tx::try_from(&tx_bytes[..]) {
+                    Ok(tx) => tx,
+                    Err(err) => {
+                        return shim::response::TxResult {
+                            code: 1,
+                            info: Error::TxDecoding(err).to_string(),
+                        };
+                    }
+                };
+                if let Err(err) = key::common::verify_tx_multi_sigs(&tx) {
+                    return shim::response::TxResult {
+                        code: 1,
+                        info: format!("Invalid transaction signature: {}", err),
+                    };
+                }
+                let within_limit = min_tx_gas(tx_bytes, &tx)
+                    .map_err(|err| err.to_string())
+                    .and_then(|tx_gas| {
+                        let new_block_gas = block_gas.saturating_add(tx_gas);
+                        if new_block_gas > max_block_gas {
+                            Err(format!(
+                                "The transaction's gas exceeds the remaining \
+                                 block gas limit {}",
+                                max_block_gas
+                            ))
+                        } else {
+                            block_gas = new_block_gas;
+                            Ok(())
+                        }
+                    });
+                match within_limit {
+                    Ok(()) => shim::response::TxResult {
+                        code: 0,
+                        info: "Transaction is valid".into(),
+                    },
+                    Err(info) => shim::response::TxResult { code: 2, info },
+                }
+            })
+            .collect();
+        shim::response::ProcessProposal {
+            code: 0,
+            info: "Proposal is valid".into(),
+            tx_results,
+        }
     }
 
+    /// Discard the block started by `prepare_proposal`, so that the block
+    /// can be prepared again. The storage is reloaded from the last committed
+    /// block or, before the first block is committed, restored to the state
+    /// from before the block was prepared.
     pub fn revert_proposal(
         &mut self,
         _req: shim::request::RevertProposal,
     ) -> shim::response::RevertProposal {
+        self.write_log = WriteLog::default();
+        self.gas_meter.reset();
+        self.consensus_params_update = None;
+        self.last_commit = None;
+        match self.genesis_state.take() {
+            Some(state) => self.storage.restore_block_state(state),
+            None => self
+                .storage
+                .load_last_state()
+                .expect("Reloading the last committed state shouldn't fail"),
+        }
+        self.storage.header = None;
         Default::default()
     }
 
+    /// The votes are not extended with any data yet.
     pub fn extend_vote(
         &mut self,
         _req: shim::request::ExtendVote,
@@ -305,9 +611,56 @@ impl Shell {
         req: shim::request::FinalizeBlock,
     ) -> Result<shim::response::FinalizeBlock> {
         let mut response = shim::response::FinalizeBlock::default();
+
+        self.apply_upgrade()?;
+
+        // the proposals whose voting period has ended are tallied before any
+        // of the block's transactions are applied
+        let results =
+            governance::tally_proposals(&self.storage, &mut self.write_log)
+                .map_err(Error::Governance)?;
+        for (id, result) in results {
+            tracing::info!("Tallied governance proposal {}: {:?}", id, result);
+        }
+
+        // the inflation is minted in the first block of an epoch and the
+        // validators' participation is recorded for the next one
+        let (parameters, _gas) = parameters::read(&self.storage)
+            .expect("Couldn't read protocol parameters");
+        if let Some(minted) = pos::distribute_rewards(
+            &self.storage,
+            &mut self.write_log,
+            &parameters,
+        )
+        .map_err(Error::Rewards)?
+        {
+            tracing::info!("Minted {} XAN of inflation", minted);
+            // the validators' voting power is updated from their stake at
+            // the beginning of the epoch
+            response.validator_updates =
+                pos::update_validator_set(&self.storage, &mut self.write_log)
+                    .map_err(Error::Rewards)?
+                    .into_iter()
+                    .map(|(consensus_key, power)| {
+                        validator_update(&consensus_key, power)
+                    })
+                    .collect();
+        }
+        if let Some((proposer, votes)) = self.last_commit.take() {
+            pos::record_participation(
+                &self.storage,
+                &mut self.write_log,
+                &parameters,
+                &proposer,
+                &votes,
+            )
+            .map_err(Error::Rewards)?;
+        }
+
         for tx in &req.txs {
             let mut tx_result =
                 Event::new_tx_event(EventType::Applied, tx, req.height);
+            let mut custom_events = vec![];
             match protocol::apply_tx(
                 tx,
                 &mut self.gas_meter,
@@ -323,6 +676,7 @@ impl Shell {
                              {:#?}",
                             result
                         );
+                        let tx_events = self.write_log.take_tx_events();
                         self.write_log.commit_tx();
                         tx_result["code"] = "0".into();
                         match serde_json::to_string(
@@ -340,6 +694,19 @@ impl Shell {
                                 );
                             }
                         }
+                        for event in tx_events {
+                            let event_type = event.event_type.clone();
+                            match Event::new_custom_tx_event(
+                                event, tx, req.height,
+                            ) {
+                                Some(event) => custom_events.push(event.into()),
+                                None => tracing::info!(
+                                    "Ignoring an event with a reserved type \
+                                     {} emitted by a transaction",
+                                    event_type
+                                ),
+                            }
+                        }
                     } else {
                         tracing::info!(
                             "some VPs rejected apply_tx storage modification \
@@ -353,6 +720,7 @@ impl Shell {
                     tx_result["info"] = result.to_string();
                 }
                 Err(msg) => {
+                    self.write_log.drop_tx();
                     tx_result["gas_used"] = self
                         .gas_meter
                         .get_current_transaction_gas()
@@ -362,15 +730,66 @@ impl Shell {
                 }
             }
             response.events.push(tx_result.into());
+            response.events.append(&mut custom_events);
         }
 
+        // the commitments of the new shielded notes are added to the tree
+        // only after all the transactions in the block have been applied
+        #[cfg(feature = "shielded-pool")]
+        shielded_pool::update_commitment_tree(
+            &self.storage,
+            &mut self.write_log,
+        )
+        .map_err(Error::CommitmentTree)?;
+
         response.gas_used = self
             .gas_meter
             .finalize_transaction()
             .map_err(|_| Error::GasOverflow)?;
+        response.consensus_param_updates = self
+            .consensus_params_update
+            .take()
+            .map(|params| consensus_params(&params));
         Ok(response)
     }
 
+    /// Apply the pending upgrade plan if its height has been reached. The
+    /// node must halt if this binary doesn't support the upgrade. Otherwise,
+    /// the upgrade's storage migration is written into the block's write log
+    /// before any of the block's transactions are applied.
+    fn apply_upgrade(&mut self) -> Result<()> {
+        let plan = match upgrade::read_plan(&self.storage)
+            .map_err(Error::UpgradePlan)?
+        {
+            Some(plan) => plan,
+            None => return Ok(()),
+        };
+        let (height, _gas) = self.storage.get_block_height();
+        if plan.height > height {
+            return Ok(());
+        }
+        if !migrations::is_supported(&plan) {
+            return Err(Error::UpgradeRequired(plan));
+        }
+        tracing::info!(
+            "Applying the upgrade {} at height {}",
+            plan.name,
+            height
+        );
+        if let Some(migration) = migrations::find(&plan) {
+            migration(&self.storage, &mut self.write_log)
+                .map_err(Error::Migration)?;
+        }
+        self.write_log
+            .delete(&upgrade::plan_key())
+            .expect("Deleting the upgrade plan shouldn't fail");
+        self.write_log
+            .write(&upgrade::applied_key(), encode(&plan))
+            .expect("Writing the applied upgrade shouldn't fail");
+        self.write_log.commit_tx();
+        Ok(())
+    }
+
     /// Commit a block. Persist the application state and return the Merkle root
     /// hash.
     pub fn commit(&mut self) -> response::Commit {
@@ -457,6 +876,51 @@ impl Shell {
         }
     }
 
+    /// Query to read the current epoch
+    fn read_epoch(&self) -> response::Query {
+        let (epoch, _gas) = self.storage.get_block_epoch();
+        response::Query {
+            value: epoch.try_to_vec().unwrap(),
+            ..Default::default()
+        }
+    }
+
+    /// Query to look-up the epoch of a committed block height
+    fn read_epoch_at(&self, height: BlockHeight) -> response::Query {
+        let epoch = if height <= self.storage.last_height {
+            self.storage.block.pred_epochs.get_epoch(height)
+        } else {
+            None
+        };
+        match epoch {
+            Some(epoch) => response::Query {
+                value: epoch.try_to_vec().unwrap(),
+                ..Default::default()
+            },
+            None => response::Query {
+                code: 1,
+                info: format!("No epoch found for block height {}", height),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Query to read the earliest start of the next epoch. The value in
+    /// successful response is a [`NextEpoch`] encoded with
+    /// [`BorshSerialize`].
+    fn read_next_epoch(&self) -> response::Query {
+        let (epoch, _gas) = self.storage.get_block_epoch();
+        let next_epoch = NextEpoch {
+            epoch: epoch.next(),
+            min_start_height: self.storage.next_epoch_min_start_height,
+            min_start_time: self.storage.next_epoch_min_start_time,
+        };
+        response::Query {
+            value: next_epoch.try_to_vec().unwrap(),
+            ..Default::default()
+        }
+    }
+
     /// Query to read a range of values from storage with a matching prefix. The
     /// value in successful response is a [`Vec<PrefixValue>`] encoded with
     /// [`BorshSerialize`].
@@ -488,7 +952,7 @@ impl Shell {
                     }
                 }
                 Err(err) => response::Query {
-                    code: 1,
+                    code: 2,
                     info: format!(
                         "Error parsing a storage key {}: {}",
                         key, err
@@ -499,3 +963,57 @@ impl Shell {
         }
     }
 }
+
+/// The gas that applying a transaction is charged before its code is run.
+fn min_tx_gas(
+    tx_bytes: &[u8],
+    tx: &Tx,
+) -> std::result::Result<u64, gas::Error> {
+    let mut gas_meter = BlockGasMeter::default();
+    gas_meter.add_base_transaction_fee(tx_bytes.len())?;
+    gas_meter.add_compiling_fee(tx.code.len())?;
+    Ok(gas_meter.get_current_transaction_gas())
+}
+
+/// Convert a validator's voting power into a Tendermint validator update.
+pub fn validator_update(
+    consensus_key: &key::ed25519::PublicKey,
+    power: u64,
+) -> tendermint_proto::abci::ValidatorUpdate {
+    let pk: ed25519_dalek::PublicKey = consensus_key.clone().into();
+    tendermint_proto::abci::ValidatorUpdate {
+        pub_key: Some(tendermint_proto::crypto::PublicKey {
+            sum: Some(tendermint_proto::crypto::public_key::Sum::Ed25519(
+                pk.to_bytes().to_vec(),
+            )),
+        }),
+        power: i64::try_from(power).unwrap_or(i64::MAX),
+    }
+}
+
+/// Convert the consensus parameters for Tendermint.
+fn consensus_params(
+    params: &ConsensusParams,
+) -> tendermint_proto::abci::ConsensusParams {
+    let to_i64 = |value: u64| i64::try_from(value).unwrap_or(i64::MAX);
+    tendermint_proto::abci::ConsensusParams {
+        block: Some(tendermint_proto::abci::BlockParams {
+            max_bytes: to_i64(params.max_block_bytes),
+            max_gas: to_i64(params.max_block_gas),
+        }),
+        evidence: Some(tendermint_proto::types::EvidenceParams {
+            max_age_num_blocks: to_i64(params.evidence_max_age_num_blocks),
+            max_age_duration: Some(
+                tendermint_proto::google::protobuf::Duration {
+                    seconds: to_i64(params.evidence_max_age_duration.0),
+                    nanos: 0,
+                },
+            ),
+            ..Default::default()
+        }),
+        validator: Some(tendermint_proto::types::ValidatorParams {
+            pub_key_types: params.pub_key_types.clone(),
+        }),
+        ..Default::default()
+    }
+}
@@ -2,17 +2,22 @@ use std::convert::TryFrom;
 use std::future::Future;
 use std::path::Path;
 use std::pin::Pin;
+use std::sync::mpsc::Sender;
 use std::task::{Context, Poll};
 
 use anoma::types::storage::BlockHeight;
 use futures::future::FutureExt;
+use tendermint_proto::abci::RequestBeginBlock;
 use tower::Service;
 use tower_abci::{BoxError, Request as Req, Response as Resp};
 
+use super::super::events::{Event, EventType};
+use super::super::shell::Error as ShellError;
 use super::super::Shell;
 use super::abcipp_shim_types::shim::{
     request, Error, Request, Response, TxBytes,
 };
+use crate::config::genesis::Genesis;
 
 /// The shim wraps the shell, which implements ABCI++
 /// The shim makes a crude translation between the ABCI
@@ -21,13 +26,131 @@ use super::abcipp_shim_types::shim::{
 pub struct AbcippShim {
     service: Shell,
     block_txs: Vec<TxBytes>,
+    /// The current block, which is prepared again if its proposal is rejected
+    begin_block: Option<RequestBeginBlock>,
+    /// Used to halt the node, which stops the Tendermint node and then the
+    /// shell
+    halt: Sender<bool>,
 }
 
 impl AbcippShim {
-    pub fn new(db_path: impl AsRef<Path>, chain_id: String) -> Self {
+    pub fn new(
+        db_path: impl AsRef<Path>,
+        chain_id: String,
+        genesis: Genesis,
+        halt: Sender<bool>,
+    ) -> Self {
         Self {
-            service: Shell::new(db_path, chain_id),
+            service: Shell::new(db_path, chain_id, genesis),
             block_txs: vec![],
+            begin_block: None,
+            halt,
+        }
+    }
+
+    /// Verify the header of a new block and then forward the block to the
+    /// PrepareProposal request
+    fn begin_block(&mut self, block: RequestBeginBlock) -> Result<Resp, Error> {
+        match self.service.call(Request::VerifyHeader(
+            request::VerifyHeader {
+                header: block.header.clone(),
+            },
+        ))? {
+            Response::VerifyHeader(resp) if resp.is_valid() => {}
+            Response::VerifyHeader(resp) => {
+                return Err(Error::InvalidHeader(resp.info));
+            }
+            res => return Err(Error::ConvertResp(res)),
+        }
+        self.begin_block = Some(block.clone());
+        self.prepare_proposal(block)
+    }
+
+    fn prepare_proposal(
+        &mut self,
+        block: RequestBeginBlock,
+    ) -> Result<Resp, Error> {
+        match self.service.call(Request::PrepareProposal(block.into()))? {
+            Response::PrepareProposal(resp) => {
+                Ok(Resp::BeginBlock(resp.into()))
+            }
+            res => Err(Error::ConvertResp(res)),
+        }
+    }
+
+    /// Process the proposal of the block's transactions and then finalize
+    /// the block. The invalid transactions are dropped from the block.
+    /// Tendermint has already committed to the block, so if the whole
+    /// proposal is rejected, the block is reverted and prepared again and
+    /// none of its transactions are applied.
+    fn end_block(&mut self, height: i64) -> Result<Resp, Error> {
+        let mut txs = vec![];
+        std::mem::swap(&mut txs, &mut self.block_txs);
+        let begin_block = self.begin_block.take();
+
+        let proposal = match self.service.call(Request::ProcessProposal(
+            request::ProcessProposal { txs: txs.clone() },
+        ))? {
+            Response::ProcessProposal(resp) => resp,
+            res => return Err(Error::ConvertResp(res)),
+        };
+        let mut rejected_txs = vec![];
+        let mut rejected_tx_event = |tx: &TxBytes, info: String| {
+            let mut event = Event::new_tx_event(EventType::Applied, tx, height);
+            event["code"] = "2".into();
+            event["gas_used"] = "0".into();
+            event["info"] = info;
+            rejected_txs.push(event.into());
+        };
+        if proposal.is_accepted() {
+            txs = txs
+                .into_iter()
+                .zip(proposal.tx_results)
+                .filter_map(|(tx, result)| {
+                    if result.is_valid() {
+                        Some(tx)
+                    } else {
+                        rejected_tx_event(&tx, result.info);
+                        None
+                    }
+                })
+                .collect();
+        } else {
+            tracing::info!(
+                "Rejected the proposal of the block at height {}: {}",
+                height,
+                proposal.info
+            );
+            for tx in &txs {
+                rejected_tx_event(tx, proposal.info.clone());
+            }
+            txs.clear();
+            self.service
+                .call(Request::RevertProposal(request::RevertProposal))?;
+            self.prepare_proposal(
+                begin_block.expect("The block must have begun"),
+            )?;
+        }
+
+        let result =
+            self.service
+                .call(Request::FinalizeBlock(request::FinalizeBlock {
+                    height,
+                    txs,
+                }));
+        if let Err(err @ ShellError::UpgradeRequired(_)) = &result {
+            // Nothing from this block has been committed, so the
+            // node can be restarted from the same state with a
+            // binary that supports the upgrade
+            tracing::error!("{}. Halting the node.", err);
+            let _ = self.halt.send(true);
+        }
+        match result? {
+            Response::FinalizeBlock(mut resp) => {
+                resp.events.append(&mut rejected_txs);
+                Ok(Resp::EndBlock(resp.into()))
+            }
+            res => Err(Error::ConvertResp(res)),
         }
     }
 }
@@ -51,19 +174,7 @@ impl Service<Req> for AbcippShim {
     fn call(&mut self, req: Req) -> Self::Future {
         tracing::debug!(?req);
         let rsp = match req {
-            Req::BeginBlock(block) => {
-                // we simply forward BeginBlock request to the PrepareProposal
-                // request
-                self.service
-                    .call(Request::PrepareProposal(block.into()))
-                    .map_err(Error::from)
-                    .and_then(|res| match res {
-                        Response::PrepareProposal(resp) => {
-                            Ok(Resp::BeginBlock(resp.into()))
-                        }
-                        _ => Err(Error::ConvertResp(res)),
-                    })
-            }
+            Req::BeginBlock(block) => self.begin_block(block),
             Req::DeliverTx(deliver_tx) => {
                 // We store all the transactions to be applied in
                 // bulk at a later step
@@ -74,22 +185,7 @@ impl Service<Req> for AbcippShim {
                 BlockHeight::try_from(end.height).unwrap_or_else(|_| {
                     panic!("Unexpected block height {}", end.height)
                 });
-                let mut txs = vec![];
-                std::mem::swap(&mut txs, &mut self.block_txs);
-
-                self.service
-                    .call(Request::FinalizeBlock(request::FinalizeBlock {
-                        height: end.height,
-                        txs,
-                    }))
-                    .map_err(Error::from)
-                    .and_then(|res| match res {
-                        Response::FinalizeBlock(resp) => {
-                            let x = Resp::EndBlock(resp.into());
-                            Ok(x)
-                        }
-                        _ => Err(Error::ConvertResp(res)),
-                    })
+                self.end_block(end.height)
             }
             _ => match Request::try_from(req.clone()) {
                 Ok(request) => self
@@ -104,3 +200,284 @@ impl Service<Req> for AbcippShim {
         Box::pin(async move { rsp.map_err(|e| e.into()) }.boxed())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use anoma::ledger::parameters::{self, EpochDuration};
+    use anoma::ledger::storage::types::encode;
+    use anoma::proto::Tx;
+    use anoma::types::time::DurationSecs;
+    use tempfile::TempDir;
+    use tendermint_proto::abci::{
+        RequestCommit, RequestDeliverTx, RequestEndBlock, RequestInfo,
+        RequestInitChain,
+    };
+    use tendermint_proto::google::protobuf::Timestamp;
+    use tendermint_proto::types::Header;
+    use tendermint_proto::version::Consensus;
+    use tower_abci::response;
+
+    use super::*;
+    use crate::config::genesis::{GenesisFile, Validator, ValidatorConfig};
+    use crate::config::DEFAULT_CHAIN_ID;
+
+    /// Initialize a chain with a single genesis validator
+    fn init_chain(genesis: Genesis) -> (AbcippShim, TempDir) {
+        // The genesis VPs are loaded relative to the workspace's root
+        std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/.."))
+            .unwrap();
+        let db = tempfile::tempdir().unwrap();
+        let (halt, _) = channel();
+        let mut shim = AbcippShim::new(
+            db.path(),
+            DEFAULT_CHAIN_ID.to_owned(),
+            genesis,
+            halt,
+        );
+        call(
+            &mut shim,
+            Req::InitChain(RequestInitChain {
+                time: Some(time()),
+                chain_id: DEFAULT_CHAIN_ID.to_owned(),
+                initial_height: 1,
+                ..Default::default()
+            }),
+        )
+        .unwrap();
+        (shim, db)
+    }
+
+    fn genesis() -> Genesis {
+        let (validator, _keypair) = Validator::generate();
+        GenesisFile {
+            validators: vec![ValidatorConfig::from(&validator)],
+            balances: vec![],
+        }
+        .genesis()
+        .unwrap()
+    }
+
+    fn time() -> Timestamp {
+        Timestamp {
+            seconds: 1_600_000_000,
+            nanos: 0,
+        }
+    }
+
+    fn begin_block(chain_id: &str, height: i64) -> Req {
+        Req::BeginBlock(RequestBeginBlock {
+            hash: vec![height as u8; 32],
+            header: Some(Header {
+                version: Some(Consensus { block: 11, app: 0 }),
+                chain_id: chain_id.to_owned(),
+                height,
+                time: Some(time()),
+                proposer_address: vec![0; 20],
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+    }
+
+    fn call(shim: &mut AbcippShim, req: Req) -> Result<Resp, BoxError> {
+        tokio_test::block_on(shim.call(req))
+    }
+
+    /// Run a block with the given transactions and commit it. Returns the
+    /// attributes of the block's "applied" events.
+    fn run_block(
+        shim: &mut AbcippShim,
+        height: i64,
+        txs: Vec<Vec<u8>>,
+    ) -> Vec<Vec<(String, String)>> {
+        call(shim, begin_block(DEFAULT_CHAIN_ID, height)).unwrap();
+        for tx in txs {
+            call(shim, Req::DeliverTx(RequestDeliverTx { tx })).unwrap();
+        }
+        let events = match call(shim, Req::EndBlock(RequestEndBlock { height }))
+            .unwrap()
+        {
+            Resp::EndBlock(response::EndBlock { events, .. }) => events,
+            resp => panic!("Unexpected response {:?}", resp),
+        };
+        call(shim, Req::Commit(RequestCommit {})).unwrap();
+        events
+            .into_iter()
+            .filter(|event| event.r#type == "applied")
+            .map(|event| {
+                event
+                    .attributes
+                    .into_iter()
+                    .map(|attr| {
+                        (
+                            String::from_utf8(attr.key).unwrap(),
+                            String::from_utf8(attr.value).unwrap(),
+                        )
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn attribute<'a>(event: &'a [(String, String)], key: &str) -> &'a str {
+        event
+            .iter()
+            .find(|(attr_key, _)| attr_key == key)
+            .map(|(_, value)| value.as_str())
+            .unwrap()
+    }
+
+    fn last_block_height(shim: &mut AbcippShim) -> i64 {
+        match call(shim, Req::Info(RequestInfo::default())).unwrap() {
+            Resp::Info(info) => info.last_block_height,
+            resp => panic!("Unexpected response {:?}", resp),
+        }
+    }
+
+    /// Test that a block's header for another chain is rejected.
+    #[test]
+    fn test_reject_header_of_another_chain() {
+        let (mut shim, _db) = init_chain(genesis());
+        assert!(call(&mut shim, begin_block("other-chain", 1)).is_err());
+        assert!(call(&mut shim, begin_block(DEFAULT_CHAIN_ID, 1)).is_ok());
+    }
+
+    /// Test that an undecodable transaction is dropped from the block and
+    /// that the other transactions are applied.
+    #[test]
+    fn test_drop_undecodable_tx() {
+        let (mut shim, _db) = init_chain(genesis());
+        let tx_code = std::fs::read("wasm_for_tests/tx_no_op.wasm").unwrap();
+        let valid_tx = Tx::new(tx_code, None).to_bytes();
+
+        let events = run_block(
+            &mut shim,
+            1,
+            vec![valid_tx, b"not a transaction".to_vec()],
+        );
+        assert_eq!(events.len(), 2);
+        assert_eq!(attribute(&events[0], "code"), "0");
+        assert_eq!(attribute(&events[1], "code"), "2");
+        assert_eq!(attribute(&events[1], "gas_used"), "0");
+        assert!(
+            attribute(&events[1], "info").contains("Error decoding"),
+            "Unexpected info {}",
+            attribute(&events[1], "info")
+        );
+        assert_eq!(last_block_height(&mut shim), 1);
+    }
+
+    /// Test that the transactions that don't fit in the block gas limit are
+    /// dropped from the block.
+    #[test]
+    fn test_drop_txs_over_block_gas_limit() {
+        let mut genesis = genesis();
+        let tx_code = std::fs::read("wasm_for_tests/tx_no_op.wasm").unwrap();
+        // the compiling fee of a single transaction fits in the limit
+        genesis.parameters.consensus.max_block_gas = tx_code.len() as u64 + 10;
+        let (mut shim, _db) = init_chain(genesis);
+        let tx = |data: u8| Tx::new(tx_code.clone(), Some(vec![data]));
+
+        let events = run_block(&mut shim, 1, vec![tx(1).to_bytes()]);
+        assert_eq!(attribute(&events[0], "code"), "0");
+
+        let events =
+            run_block(&mut shim, 2, vec![tx(2).to_bytes(), tx(3).to_bytes()]);
+        assert_eq!(events.len(), 2);
+        assert_eq!(attribute(&events[0], "code"), "0");
+        assert_eq!(attribute(&events[1], "code"), "2");
+        assert!(
+            attribute(&events[1], "info").contains("block gas limit"),
+            "Unexpected info {}",
+            attribute(&events[1], "info")
+        );
+        assert_eq!(last_block_height(&mut shim), 2);
+    }
+
+    /// Test that none of the transactions of a proposal with a duplicate
+    /// transaction are applied and that the block is still committed.
+    #[test]
+    fn test_reject_proposal_with_duplicate_tx() {
+        let (mut shim, _db) = init_chain(genesis());
+        let tx_code = std::fs::read("wasm_for_tests/tx_no_op.wasm").unwrap();
+        let tx = |data: u8| Tx::new(tx_code.clone(), Some(vec![data]));
+
+        let events = run_block(
+            &mut shim,
+            1,
+            vec![tx(1).to_bytes(), tx(2).to_bytes(), tx(1).to_bytes()],
+        );
+        assert_eq!(events.len(), 3);
+        for event in &events {
+            assert_eq!(attribute(event, "code"), "2");
+            assert_eq!(attribute(event, "gas_used"), "0");
+            assert_eq!(
+                attribute(event, "info"),
+                "The proposal contains a duplicate transaction"
+            );
+        }
+        assert_eq!(last_block_height(&mut shim), 1);
+
+        // the same transactions are applied in a valid proposal
+        let events =
+            run_block(&mut shim, 2, vec![tx(1).to_bytes(), tx(2).to_bytes()]);
+        assert_eq!(events.len(), 2);
+        assert_eq!(attribute(&events[0], "code"), "0");
+        assert_eq!(attribute(&events[1], "code"), "0");
+        assert_eq!(last_block_height(&mut shim), 2);
+    }
+
+    /// Test that reverting a proposal before any block has been committed
+    /// restores the genesis state, including the epoch and the parameters
+    /// that became active when the block was prepared.
+    #[test]
+    fn test_revert_proposal_before_first_commit() {
+        let mut genesis = genesis();
+        // the first block begins a new epoch
+        genesis.parameters.epoch_duration = EpochDuration {
+            min_num_of_blocks: 0,
+            min_duration: DurationSecs(0),
+        };
+        let genesis_parameters = genesis.parameters.clone();
+        let (mut shim, _db) = init_chain(genesis);
+        let storage = &mut shim.service.storage;
+        let genesis_epoch = storage.get_block_epoch().0;
+        let mut new_parameters = genesis_parameters.clone();
+        new_parameters.epoch_duration.min_num_of_blocks = 10;
+        let pending_key = parameters::pending_key(genesis_epoch.next());
+        storage
+            .write(&pending_key, encode(&new_parameters))
+            .unwrap();
+        let genesis_root = storage.merkle_root().0;
+        let genesis_next_epoch_height = storage.next_epoch_min_start_height;
+
+        call(&mut shim, begin_block(DEFAULT_CHAIN_ID, 1)).unwrap();
+        let storage = &shim.service.storage;
+        assert_eq!(storage.get_block_epoch().0, genesis_epoch.next());
+        assert_eq!(parameters::read(storage).unwrap().0, new_parameters);
+
+        shim.service
+            .call(Request::RevertProposal(request::RevertProposal))
+            .unwrap();
+        let storage = &shim.service.storage;
+        assert_eq!(storage.get_block_epoch().0, genesis_epoch);
+        assert_eq!(parameters::read(storage).unwrap().0, genesis_parameters);
+        assert!(storage.has_key(&pending_key).unwrap().0);
+        assert_eq!(storage.merkle_root().0, genesis_root);
+        assert_eq!(
+            storage.next_epoch_min_start_height,
+            genesis_next_epoch_height
+        );
+        assert_eq!(last_block_height(&mut shim), 0);
+
+        // the block is prepared again from the genesis state
+        run_block(&mut shim, 1, vec![]);
+        let storage = &shim.service.storage;
+        assert_eq!(storage.get_block_epoch().0, genesis_epoch.next());
+        assert_eq!(parameters::read(storage).unwrap().0, new_parameters);
+        assert!(!storage.has_key(&pending_key).unwrap().0);
+        assert_eq!(last_block_height(&mut shim), 1);
+    }
+}
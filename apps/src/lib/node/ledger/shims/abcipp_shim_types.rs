@@ -24,6 +24,8 @@ pub mod shim {
         ConvertReq(Req),
         #[error("Error converting Response from ABCI++ to ABCI: {0:?}")]
         ConvertResp(Response),
+        #[error("Invalid block header: {0}")]
+        InvalidHeader(String),
         #[error("{0:?}")]
         Shell(shell::Error),
     }
@@ -46,11 +48,8 @@ pub mod shim {
         Info(RequestInfo),
         Query(RequestQuery),
         PrepareProposal(request::PrepareProposal),
-        #[allow(dead_code)]
         VerifyHeader(request::VerifyHeader),
-        #[allow(dead_code)]
         ProcessProposal(request::ProcessProposal),
-        #[allow(dead_code)]
         RevertProposal(request::RevertProposal),
         #[allow(dead_code)]
         ExtendVote(request::ExtendVote),
@@ -154,12 +153,14 @@ pub mod shim {
 
     /// Custom types for request payloads
     pub mod request {
-        use tendermint_proto::abci::RequestBeginBlock;
+        use tendermint_proto::abci::{RequestBeginBlock, VoteInfo};
         use tendermint_proto::types::Header;
 
         pub struct PrepareProposal {
             pub hash: Vec<u8>,
             pub header: Option<Header>,
+            /// The validators' votes in the commit of the previous block
+            pub votes: Vec<VoteInfo>,
         }
 
         impl From<RequestBeginBlock> for PrepareProposal {
@@ -167,12 +168,22 @@ pub mod shim {
                 PrepareProposal {
                     hash: block.hash,
                     header: block.header,
+                    votes: block
+                        .last_commit_info
+                        .map(|info| info.votes)
+                        .unwrap_or_default(),
                 }
             }
         }
 
-        pub struct VerifyHeader;
-        pub struct ProcessProposal;
+        pub struct VerifyHeader {
+            pub header: Option<Header>,
+        }
+
+        pub struct ProcessProposal {
+            pub txs: Vec<super::TxBytes>,
+        }
+
         pub struct RevertProposal;
         pub struct ExtendVote;
         pub struct VerifyVoteExtension;
@@ -185,7 +196,7 @@ pub mod shim {
 
     /// Custom types for response payloads
     pub mod response {
-        use tendermint_proto::abci::Event;
+        use tendermint_proto::abci::{ConsensusParams, Event, ValidatorUpdate};
         use tower_abci::response;
 
         #[derive(Debug, Default)]
@@ -198,10 +209,34 @@ pub mod shim {
         }
 
         #[derive(Debug, Default)]
-        pub struct VerifyHeader;
+        pub struct VerifyHeader {
+            /// `0` if the header is valid
+            pub code: u32,
+            pub info: String,
+        }
+
+        impl VerifyHeader {
+            pub fn is_valid(&self) -> bool {
+                self.code == 0
+            }
+        }
 
         #[derive(Debug, Default)]
-        pub struct ProcessProposal;
+        pub struct ProcessProposal {
+            /// `0` if the proposal is accepted, otherwise its proposer is at
+            /// fault and none of its transactions are applied
+            pub code: u32,
+            pub info: String,
+            /// The results of the proposed transactions, in the order of
+            /// the proposal. Empty if the proposal is rejected.
+            pub tx_results: Vec<TxResult>,
+        }
+
+        impl ProcessProposal {
+            pub fn is_accepted(&self) -> bool {
+                self.code == 0
+            }
+        }
 
         #[derive(Debug, Default)]
         pub struct RevertProposal;
@@ -214,20 +249,33 @@ pub mod shim {
 
         #[derive(Debug, Default)]
         pub struct TxResult {
+            /// `0` if the transaction is valid, `1` if it cannot be decoded
+            /// and `2` if it exceeds a gas limit. The invalid transactions
+            /// are dropped from the block.
             pub code: u32,
             pub info: String,
         }
 
+        impl TxResult {
+            pub fn is_valid(&self) -> bool {
+                self.code == 0
+            }
+        }
+
         #[derive(Debug, Default)]
         pub struct FinalizeBlock {
             pub events: Vec<Event>,
             pub gas_used: u64,
+            pub consensus_param_updates: Option<ConsensusParams>,
+            pub validator_updates: Vec<ValidatorUpdate>,
         }
 
         impl From<FinalizeBlock> for response::EndBlock {
             fn from(resp: FinalizeBlock) -> Self {
                 Self {
                     events: resp.events,
+                    consensus_param_updates: resp.consensus_param_updates,
+                    validator_updates: resp.validator_updates,
                     ..Default::default()
                 }
             }
@@ -6,14 +6,19 @@ use std::str::FromStr;
 use std::sync::mpsc::Receiver;
 use std::time::Duration;
 
+use anoma::types::key::ed25519;
+use anoma::types::pos;
+use ed25519_dalek::Keypair;
 use serde_json::json;
+use sha2::{Digest, Sha256};
 use signal_hook::consts::TERM_SIGNALS;
 use signal_hook::iterator::Signals;
 use tendermint::config::TendermintConfig;
 use thiserror::Error;
 
 use crate::config;
-use crate::config::genesis::{self, Validator};
+#[cfg(feature = "dev")]
+use crate::config::genesis;
 use crate::std::sync::mpsc::Sender;
 
 #[derive(Error, Debug)]
@@ -30,6 +35,18 @@ pub enum Error {
     WriteConfig(std::io::Error),
     #[error("Failed to start up Tendermint node: {0}")]
     StartUp(std::io::Error),
+    #[error("The validator key file {0:?} already exists")]
+    ValidatorKeyExists(PathBuf),
+    #[error("Failed to write the validator key file: {0}")]
+    WriteValidatorKey(std::io::Error),
+    #[error("Failed to write the node key file: {0}")]
+    WriteNodeKey(std::io::Error),
+    #[error("Failed to read the genesis file: {0}")]
+    ReadGenesis(std::io::Error),
+    #[error("Failed to write the genesis file: {0}")]
+    WriteGenesis(std::io::Error),
+    #[error("Invalid network address: {0}")]
+    NetAddress(tendermint::error::Error),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -43,18 +60,18 @@ pub fn run(
 ) -> Result<()> {
     let home_dir_string = home_dir.to_string_lossy().to_string();
 
-    // init and run a tendermint node child process
-    let output = Command::new("tendermint")
-        .args(&["init", "--home", &home_dir_string])
-        .output()
-        .map_err(Error::Init)?;
-    if !output.status.success() {
-        panic!("Tendermint failed to initialize with {:#?}", output);
+    #[cfg(feature = "dev")]
+    if !validator_key_path(&home_dir).exists() {
+        // use the genesis validator's key, unless a key has been generated
+        let keypair = genesis::validator_keypair();
+        let pk = ed25519::PublicKey::from(keypair.public);
+        write_validator_key(&home_dir, &pos::consensus_address(&pk), &keypair)?;
     }
 
+    // init and run a tendermint node child process
+    init(&home_dir)?;
+
     if cfg!(feature = "dev") {
-        // override the validator key file
-        write_validator_key(&home_dir, &genesis::genesis().validator);
         write_chain_id(&home_dir, config::DEFAULT_CHAIN_ID);
     }
 
@@ -83,6 +100,19 @@ pub fn run(
     Ok(())
 }
 
+/// Initialize the Tendermint node's home directory. The existing keys,
+/// genesis and config files are kept.
+pub fn init(home_dir: impl AsRef<Path>) -> Result<()> {
+    let output = Command::new("tendermint")
+        .args(&["init", "--home", &home_dir.as_ref().to_string_lossy()])
+        .output()
+        .map_err(Error::Init)?;
+    if !output.status.success() {
+        panic!("Tendermint failed to initialize with {:#?}", output);
+    }
+    Ok(())
+}
+
 /// Listens for termination signals and forwards a kill command to the
 /// tendermint node when it encounters one.
 fn kill_on_term_signal(kill_switch: Sender<bool>) {
@@ -169,16 +199,46 @@ fn update_tendermint_config(home_dir: impl AsRef<Path>) -> Result<()> {
         .map_err(Error::WriteConfig)
 }
 
-#[cfg(feature = "dev")]
-fn write_validator_key(home_dir: impl AsRef<Path>, account: &Validator) {
+/// Generate a new validator consensus key and write it into the Tendermint's
+/// private validator key file. An existing key file is never overwritten.
+/// Returns the public key.
+pub fn gen_validator_key(
+    home_dir: impl AsRef<Path>,
+) -> Result<ed25519::PublicKey> {
     let home_dir = home_dir.as_ref();
-    let path = home_dir.join("config").join("priv_validator_key.json");
-    let file =
-        File::create(path).expect("Couldn't create private validator key file");
-    let pk = base64::encode(account.keypair.public.as_bytes());
-    let sk = base64::encode(account.keypair.to_bytes());
+    let path = validator_key_path(home_dir);
+    if path.exists() {
+        return Err(Error::ValidatorKeyExists(path));
+    }
+    let keypair = Keypair::generate(&mut rand::thread_rng());
+    let pk = ed25519::PublicKey::from(keypair.public);
+    write_validator_key(home_dir, &pos::consensus_address(&pk), &keypair)?;
+    Ok(pk)
+}
+
+pub fn validator_key_path(home_dir: impl AsRef<Path>) -> PathBuf {
+    home_dir
+        .as_ref()
+        .join("config")
+        .join("priv_validator_key.json")
+}
+
+/// Write the Tendermint's private validator key file for the given
+/// validator's Tendermint address and consensus key.
+pub fn write_validator_key(
+    home_dir: impl AsRef<Path>,
+    address: &str,
+    keypair: &Keypair,
+) -> Result<()> {
+    let path = validator_key_path(home_dir);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(Error::WriteValidatorKey)?;
+    }
+    let file = File::create(path).map_err(Error::WriteValidatorKey)?;
+    let pk = base64::encode(keypair.public.as_bytes());
+    let sk = base64::encode(keypair.to_bytes());
     let key = json!({
-       "address": account.address,
+       "address": address,
        "pub_key": {
          "type": "tendermint/PubKeyEd25519",
          "value": pk,
@@ -189,7 +249,7 @@ fn write_validator_key(home_dir: impl AsRef<Path>, account: &Validator) {
       }
     });
     serde_json::to_writer_pretty(file, &key)
-        .expect("Couldn't write private validator key file");
+        .map_err(|err| Error::WriteValidatorKey(err.into()))
 }
 
 #[cfg(feature = "dev")]
@@ -221,3 +281,100 @@ fn write_chain_id(home_dir: impl AsRef<Path>, chain_id: impl AsRef<str>) {
     serde_json::to_writer_pretty(file, &genesis)
         .expect("Couldn't write the genesis file");
 }
+
+/// Generate a new node key and write it into the Tendermint's node key file.
+/// Returns the node ID, which is used to connect to the node as a peer.
+pub fn write_node_key(home_dir: impl AsRef<Path>) -> Result<String> {
+    let path = home_dir.as_ref().join("config").join("node_key.json");
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(Error::WriteNodeKey)?;
+    }
+    let file = File::create(path).map_err(Error::WriteNodeKey)?;
+    let keypair = Keypair::generate(&mut rand::thread_rng());
+    let key = json!({
+       "priv_key": {
+         "type": "tendermint/PrivKeyEd25519",
+         "value": base64::encode(keypair.to_bytes()),
+      }
+    });
+    serde_json::to_writer_pretty(file, &key)
+        .map_err(|err| Error::WriteNodeKey(err.into()))?;
+    // the node ID is the hex of the first 20 bytes of the public key's hash
+    let mut hasher = Sha256::new();
+    hasher.update(keypair.public.as_bytes());
+    Ok(hasher.finalize()[..20]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// Write the genesis file of a network into the Tendermint's home directory.
+/// The genesis file that has been created by `tendermint init` is used as a
+/// template for the consensus parameters, the chain ID and the validators are
+/// replaced by the given ones.
+pub fn write_network_genesis(
+    home_dir: impl AsRef<Path>,
+    template: impl AsRef<Path>,
+    chain_id: impl AsRef<str>,
+    validators: &[(ed25519::PublicKey, u64)],
+) -> Result<()> {
+    let template = template.as_ref().join("config").join("genesis.json");
+    let file = File::open(&template).map_err(Error::ReadGenesis)?;
+    let mut genesis: serde_json::Value =
+        serde_json::from_reader(BufReader::new(file))
+            .map_err(|err| Error::ReadGenesis(err.into()))?;
+    genesis["chain_id"] = json!(chain_id.as_ref());
+    genesis["validators"] = validators
+        .iter()
+        .enumerate()
+        .map(|(ix, (pk, power))| {
+            let pk_bytes: ed25519_dalek::PublicKey = pk.clone().into();
+            json!({
+                "address": pos::consensus_address(pk),
+                "pub_key": {
+                    "type": "tendermint/PubKeyEd25519",
+                    "value": base64::encode(pk_bytes.as_bytes()),
+                },
+                "power": power.to_string(),
+                "name": format!("validator-{}", ix),
+            })
+        })
+        .collect();
+
+    let path = home_dir.as_ref().join("config").join("genesis.json");
+    let file = File::create(path).map_err(Error::WriteGenesis)?;
+    serde_json::to_writer_pretty(file, &genesis)
+        .map_err(|err| Error::WriteGenesis(err.into()))
+}
+
+/// Set the Tendermint node's P2P and RPC listening ports and its persistent
+/// peers, given as `{node_id}@{host}:{port}`. Because the peers of a local
+/// network share the same IP address, Tendermint must allow it.
+pub fn configure_network(
+    home_dir: impl AsRef<Path>,
+    p2p_port: u16,
+    rpc_port: u16,
+    peers: &[String],
+) -> Result<()> {
+    let path = home_dir.as_ref().join("config").join("config.toml");
+    let mut config =
+        TendermintConfig::load_toml_file(&path).map_err(Error::LoadConfig)?;
+
+    config.p2p.laddr = net_address(&format!("0.0.0.0:{}", p2p_port))?;
+    config.rpc.laddr = net_address(&format!("127.0.0.1:{}", rpc_port))?;
+    config.p2p.persistent_peers = peers
+        .iter()
+        .map(|peer| net_address(peer))
+        .collect::<Result<_>>()?;
+    config.p2p.allow_duplicate_ip = true;
+    config.p2p.addr_book_strict = false;
+
+    let config_str =
+        toml::to_string(&config).map_err(Error::ConfigSerializeToml)?;
+    fs::write(path, config_str).map_err(Error::WriteConfig)
+}
+
+fn net_address(address: &str) -> Result<tendermint::net::Address> {
+    tendermint::net::Address::from_str(&format!("tcp://{}", address))
+        .map_err(Error::NetAddress)
+}
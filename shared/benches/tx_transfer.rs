@@ -0,0 +1,152 @@
+//! Benchmarks of a `tx_transfer` of a registered token validated by the wasm
+//! `vp_token` and by the native token VP.
+//!
+//! The benchmarks use the wasm files from the `wasm` directory, which have to
+//! be built first with `make build-wasm-scripts`. Run them with `make bench`.
+
+use std::collections::HashSet;
+
+use anoma::ledger::gas::{BlockGasMeter, VpGasMeter};
+use anoma::ledger::native_vp::{Ctx, NativeVp};
+use anoma::ledger::storage::testing::TestStorage;
+use anoma::ledger::storage::types::encode;
+use anoma::ledger::storage::write_log::WriteLog;
+use anoma::ledger::token::TokenVp;
+use anoma::ledger::token_registry;
+use anoma::proto::Tx;
+use anoma::types::address::{self, Address};
+use anoma::types::key::ed25519;
+use anoma::types::storage::Key;
+use anoma::types::token::{self, Amount, TokenInfo};
+use anoma::vm::wasm;
+use borsh::BorshSerialize;
+use criterion::{criterion_group, criterion_main, Criterion};
+
+const TX_TRANSFER_WASM: &str = "../wasm/tx_transfer.wasm";
+const VP_TOKEN_WASM: &str = "../wasm/vp_token.wasm";
+
+struct Setup {
+    storage: TestStorage,
+    tx: Tx,
+    token: Address,
+    vp_code: Vec<u8>,
+}
+
+fn setup() -> Setup {
+    let mut storage = TestStorage::default();
+    let token = address::xan();
+    let source = address::testing::established_address_1();
+    let target = address::testing::established_address_2();
+    let vp_code =
+        std::fs::read(VP_TOKEN_WASM).expect("Expected the token VP wasm");
+    for addr in [&token, &source, &target].iter() {
+        storage
+            .write(&Key::validity_predicate(addr), vp_code.clone())
+            .unwrap();
+    }
+    let info = TokenInfo {
+        symbol: "XAN".to_owned(),
+        name: "Anoma".to_owned(),
+        decimals: 6,
+        minter: None,
+    };
+    let total_supply = Amount::whole(1_000_000);
+    token_registry::init_genesis_storage(
+        &mut storage,
+        &[(token.clone(), info, total_supply)],
+    );
+    storage
+        .write(&token::balance_key(&token, &source), encode(&total_supply))
+        .unwrap();
+
+    let transfer = token::Transfer {
+        source,
+        target,
+        token: token.clone(),
+        amount: Amount::whole(1),
+    };
+    let tx_code =
+        std::fs::read(TX_TRANSFER_WASM).expect("Expected the transfer wasm");
+    let tx = Tx::new(tx_code, Some(transfer.try_to_vec().unwrap()))
+        .sign(&ed25519::testing::keypair_1());
+    Setup {
+        storage,
+        tx,
+        token,
+        vp_code,
+    }
+}
+
+/// Apply the transfer and get the keys changed for the token's VP and all the
+/// verifiers
+fn apply_transfer(
+    Setup {
+        storage, tx, token, ..
+    }: &Setup,
+) -> (WriteLog, HashSet<Key>, HashSet<Address>) {
+    let mut write_log = WriteLog::default();
+    let mut gas_meter = BlockGasMeter::default();
+    let tx_data = tx.data.clone().unwrap_or_default();
+    let verifiers_from_tx = wasm::run::tx(
+        storage,
+        &mut write_log,
+        &mut gas_meter,
+        &tx.code,
+        tx_data,
+    )
+    .unwrap();
+    let verifiers = write_log.verifiers_changed_keys(&verifiers_from_tx);
+    let keys_changed = verifiers.get(token).cloned().unwrap_or_default();
+    let verifiers = verifiers.keys().cloned().collect();
+    (write_log, keys_changed, verifiers)
+}
+
+fn tx_transfer(c: &mut Criterion) {
+    let setup = setup();
+    let mut group = c.benchmark_group("tx_transfer");
+
+    group.bench_function("wasm token VP", |b| {
+        b.iter(|| {
+            let (write_log, keys_changed, verifiers) = apply_transfer(&setup);
+            let mut gas_meter = VpGasMeter::new(0);
+            let accepted = wasm::run::vp(
+                &setup.vp_code,
+                &setup.tx,
+                &setup.token,
+                &setup.storage,
+                &write_log,
+                &mut gas_meter,
+                &keys_changed,
+                &verifiers,
+            )
+            .unwrap();
+            assert!(accepted);
+        })
+    });
+
+    group.bench_function("native token VP", |b| {
+        b.iter(|| {
+            let (write_log, keys_changed, verifiers) = apply_transfer(&setup);
+            let ctx = Ctx::new(
+                &setup.storage,
+                &write_log,
+                &setup.tx,
+                VpGasMeter::new(0),
+            );
+            let token_vp = TokenVp {
+                ctx,
+                token: &setup.token,
+            };
+            let tx_data = setup.tx.data.as_ref().unwrap();
+            let accepted = token_vp
+                .validate_tx(tx_data, &keys_changed, &verifiers)
+                .unwrap();
+            assert!(accepted);
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, tx_transfer);
+criterion_main!(benches);
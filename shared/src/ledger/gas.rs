@@ -22,7 +22,7 @@ const PARALLEL_GAS_DIVIDER: u64 = 10;
 
 /// The maximum value should be less or equal to i64::MAX
 /// to avoid the gas overflow when sending this to ABCI
-const BLOCK_GAS_LIMIT: u64 = 10_000_000_000_000;
+pub const BLOCK_GAS_LIMIT: u64 = 10_000_000_000_000;
 const TRANSACTION_GAS_LIMIT: u64 = 10_000_000_000;
 
 /// The minimum gas cost for accessing the storage
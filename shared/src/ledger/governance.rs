@@ -0,0 +1,860 @@
+//! On-chain governance as a native validity predicate. Proposals are
+//! submitted with a deposit of XAN and voted on by the token holders, whose
+//! voting power is their XAN balance plus their XAN bonded in proof-of-stake.
+//! The votes are tallied by the protocol in the first block of the epoch that
+//! follows a proposal's voting period and passed proposals are applied.
+
+use std::collections::{BTreeSet, HashSet};
+
+use thiserror::Error;
+
+use super::storage::types::{decode, encode};
+use crate::ledger::native_vp::{self, Ctx, NativeVp};
+use crate::ledger::storage::write_log::WriteLog;
+use crate::ledger::storage::{self, read_block_value, Storage, StorageHasher};
+use crate::ledger::{parameters, upgrade};
+use crate::types::address::{self, Address, InternalAddress};
+use crate::types::governance::{
+    self, GovernanceParameters, Proposal, ProposalContent, ProposalResult,
+    Tally, Vote,
+};
+use crate::types::pos::{self, Bond, Unbonds};
+use crate::types::storage::{Epoch, Key};
+use crate::types::token::{self, Amount};
+use crate::types::treasury;
+
+const ADDR: InternalAddress = InternalAddress::Governance;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Native VP error: {0}")]
+    NativeVpError(native_vp::Error),
+}
+
+/// Governance functions result
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Governance VP
+pub struct GovernanceVp<'a, DB, H>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    /// Context to interact with the host structures.
+    pub ctx: Ctx<'a, DB, H>,
+}
+
+/// Initialize the governance in the genesis block with the given parameters
+/// and no proposals.
+pub fn init_genesis_storage<DB, H>(
+    storage: &mut Storage<DB, H>,
+    parameters: &GovernanceParameters,
+) where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    storage
+        .write(&governance::parameters_key(), encode(parameters))
+        .expect("Unable to write the genesis governance parameters");
+    storage
+        .write(&governance::counter_key(), encode(&0_u64))
+        .expect("Unable to write the genesis proposal counter");
+    storage
+        .write(
+            &governance::active_proposals_key(),
+            encode(&BTreeSet::<u64>::new()),
+        )
+        .expect("Unable to write the genesis active proposals");
+}
+
+/// Tally the votes of the proposals whose voting period has ended before the
+/// current epoch and apply the proposals that passed. The author of a passed
+/// proposal gets the deposit back, otherwise the deposit goes to the
+/// treasury. This must be called before any of the block's transactions
+/// are applied. The changes are committed into the block write log. Returns
+/// the tallied proposals' identifiers and results.
+pub fn tally_proposals<DB, H>(
+    storage: &Storage<DB, H>,
+    write_log: &mut WriteLog,
+) -> std::result::Result<Vec<(u64, ProposalResult)>, storage::Error>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    let (epoch, _gas) = storage.get_block_epoch();
+    let active_key = governance::active_proposals_key();
+    let mut active: BTreeSet<u64> =
+        read_block_value(storage, write_log, &active_key)?.unwrap_or_default();
+    let mut proposals = vec![];
+    for id in &active {
+        match read_block_value::<DB, H, Proposal>(
+            storage,
+            write_log,
+            &governance::proposal_key(*id),
+        )? {
+            Some(proposal) if proposal.voting_end_epoch <= epoch => {
+                proposals.push(proposal)
+            }
+            Some(_) => {}
+            None => tracing::error!("Missing an active proposal {}", id),
+        }
+    }
+    if proposals.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let xan = address::xan();
+    let total_voting_power: Amount =
+        read_block_value(storage, write_log, &token::total_supply_key(&xan))?
+            .unwrap_or_default();
+    let mut results = vec![];
+    for proposal in proposals {
+        active.remove(&proposal.id);
+        write_log
+            .write(&active_key, encode(&active))
+            .expect("Writing the active proposals shouldn't fail");
+
+        let tally = tally_votes(storage, write_log, proposal.id)?;
+        let passed = tally.is_passed(total_voting_power);
+        let deposit_target = if passed {
+            proposal.author.clone()
+        } else {
+            treasury::treasury_address()
+        };
+        if !transfer(
+            storage,
+            write_log,
+            &xan,
+            &governance::governance_address(),
+            &deposit_target,
+            proposal.deposit,
+        )? {
+            tracing::error!(
+                "Couldn't transfer the deposit of proposal {}",
+                proposal.id
+            );
+        }
+        let applied = passed && apply_proposal(storage, write_log, &proposal)?;
+        let result = ProposalResult {
+            tally,
+            epoch,
+            passed,
+            applied,
+        };
+        write_log
+            .write(&governance::result_key(proposal.id), encode(&result))
+            .expect("Writing a proposal result shouldn't fail");
+        results.push((proposal.id, result));
+    }
+    write_log.commit_tx();
+    Ok(results)
+}
+
+/// Sum up the voting power of the votes on the proposal at the time of the
+/// tally.
+fn tally_votes<DB, H>(
+    storage: &Storage<DB, H>,
+    write_log: &WriteLog,
+    id: u64,
+) -> std::result::Result<Tally, storage::Error>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    let (iter, _gas) = storage.iter_prefix(&governance::vote_prefix(id));
+    let mut tally = Tally::default();
+    for (key, value, _gas) in iter {
+        let key = match Key::parse(key) {
+            Ok(key) => key,
+            Err(_) => continue,
+        };
+        let voter = match governance::is_vote_key(&key) {
+            Some((vote_id, voter)) if vote_id == id => voter,
+            _ => continue,
+        };
+        let vote: Vote = match decode(value) {
+            Ok(vote) => vote,
+            Err(_) => continue,
+        };
+        let sum = match vote {
+            Vote::Yay => &mut tally.yay,
+            Vote::Nay => &mut tally.nay,
+        };
+        match voting_power(storage, write_log, voter)?
+            .and_then(|voting_power| sum.checked_add(&voting_power))
+        {
+            Some(new_sum) => *sum = new_sum,
+            None => tracing::error!("The voting power overflowed"),
+        }
+    }
+    Ok(tally)
+}
+
+/// Get the voting power of a voter, which is their XAN balance plus their XAN
+/// bonded to validators, including the XAN that is still unbonding. Returns
+/// `None` on overflow.
+fn voting_power<DB, H>(
+    storage: &Storage<DB, H>,
+    write_log: &WriteLog,
+    voter: &Address,
+) -> std::result::Result<Option<Amount>, storage::Error>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    let mut voting_power: Amount = read_block_value(
+        storage,
+        write_log,
+        &token::balance_key(&address::xan(), voter),
+    )?
+    .unwrap_or_default();
+    let mut add = |amount: Amount| {
+        voting_power
+            .checked_add(&amount)
+            .map(|sum| voting_power = sum)
+    };
+
+    let (iter, _gas) = storage.iter_prefix(&pos::delegator_bonds_prefix(voter));
+    for (key, _value, _gas) in iter {
+        let key = match Key::parse(key) {
+            Ok(key) => key,
+            Err(_) => continue,
+        };
+        match pos::is_bond_key(&key) {
+            Some((delegator, _validator)) if delegator == voter => {}
+            _ => continue,
+        }
+        if let Some(bond) =
+            read_block_value::<DB, H, Bond>(storage, write_log, &key)?
+        {
+            if add(bond.amount).is_none() {
+                return Ok(None);
+            }
+        }
+    }
+
+    let (iter, _gas) =
+        storage.iter_prefix(&pos::delegator_unbonds_prefix(voter));
+    for (key, _value, _gas) in iter {
+        let key = match Key::parse(key) {
+            Ok(key) => key,
+            Err(_) => continue,
+        };
+        match pos::is_unbond_key(&key) {
+            Some((delegator, _validator)) if delegator == voter => {}
+            _ => continue,
+        }
+        let unbonds: Unbonds =
+            read_block_value(storage, write_log, &key)?.unwrap_or_default();
+        for amount in unbonds.values() {
+            if add(*amount).is_none() {
+                return Ok(None);
+            }
+        }
+    }
+    Ok(Some(voting_power))
+}
+
+/// Apply the content of a passed proposal. Returns `false` if the proposal
+/// couldn't be applied.
+fn apply_proposal<DB, H>(
+    storage: &Storage<DB, H>,
+    write_log: &mut WriteLog,
+    proposal: &Proposal,
+) -> std::result::Result<bool, storage::Error>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    match &proposal.content {
+        ProposalContent::ParameterChange(parameters) => {
+            let (epoch, _gas) = storage.get_block_epoch();
+            let key =
+                parameters::pending_key(parameters::activation_epoch(epoch));
+            write_log
+                .write(&key, encode(parameters))
+                .expect("Writing the protocol parameters shouldn't fail");
+            Ok(true)
+        }
+        ProposalContent::TxAllowlist(allowlist) => {
+            let key = parameters::tx_allowlist_key();
+            let result = match allowlist {
+                Some(allowlist) => write_log.write(&key, encode(allowlist)),
+                None => write_log.delete(&key),
+            };
+            result.expect("Writing the transactions allowlist shouldn't fail");
+            Ok(true)
+        }
+        ProposalContent::Upgrade(plan) => {
+            let (height, _gas) = storage.get_block_height();
+            if plan.height <= height {
+                tracing::info!(
+                    "The upgrade height of proposal {} has already passed",
+                    proposal.id
+                );
+                return Ok(false);
+            }
+            write_log
+                .write(&upgrade::plan_key(), encode(plan))
+                .expect("Writing the upgrade plan shouldn't fail");
+            Ok(true)
+        }
+        ProposalContent::Spend {
+            target,
+            token,
+            amount,
+        } => {
+            let treasury = treasury::treasury_address();
+            let spent = transfer(
+                storage, write_log, token, &treasury, target, *amount,
+            )?;
+            if !spent {
+                tracing::info!(
+                    "Insufficient treasury funds for proposal {}",
+                    proposal.id
+                );
+            }
+            Ok(spent)
+        }
+    }
+}
+
+/// Transfer the amount of the token in the write log. Returns `false` if the
+/// source's balance is insufficient or the target's balance would overflow.
+pub(crate) fn transfer<DB, H>(
+    storage: &Storage<DB, H>,
+    write_log: &mut WriteLog,
+    token: &Address,
+    source: &Address,
+    target: &Address,
+    amount: Amount,
+) -> std::result::Result<bool, storage::Error>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    if source == target {
+        return Ok(true);
+    }
+    let source_key = token::balance_key(token, source);
+    let target_key = token::balance_key(token, target);
+    let source_balance: Amount =
+        read_block_value(storage, write_log, &source_key)?.unwrap_or_default();
+    let target_balance: Amount =
+        read_block_value(storage, write_log, &target_key)?.unwrap_or_default();
+    match (
+        source_balance.checked_sub(&amount),
+        target_balance.checked_add(&amount),
+    ) {
+        (Some(source_balance), Some(target_balance)) => {
+            write_log
+                .write(&source_key, encode(&source_balance))
+                .expect("Writing a balance shouldn't fail");
+            write_log
+                .write(&target_key, encode(&target_balance))
+                .expect("Writing a balance shouldn't fail");
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+impl<'a, DB, H> NativeVp for GovernanceVp<'a, DB, H>
+where
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+{
+    type Error = Error;
+
+    const ADDR: InternalAddress = ADDR;
+
+    /// A transaction can only submit a new proposal, vote on a proposal or
+    /// send tokens to the governance. The proposals are tallied and applied
+    /// by the protocol.
+    fn validate_tx(
+        &self,
+        _tx_data: &[u8],
+        keys_changed: &HashSet<Key>,
+        verifiers: &HashSet<Address>,
+    ) -> Result<bool> {
+        let gov = governance::governance_address();
+        let counter_key = governance::counter_key();
+        let active_key = governance::active_proposals_key();
+        let counter: u64 =
+            self.ctx.read_pre_value(&counter_key)?.unwrap_or_default();
+        let new_proposal_key = governance::proposal_key(counter);
+        for key in keys_changed {
+            let is_valid = if *key == counter_key || *key == active_key {
+                // checked with the new proposal
+                keys_changed.contains(&new_proposal_key)
+            } else if let Some(id) = governance::is_proposal_key(key) {
+                id == counter && self.is_valid_new_proposal(id, verifiers)?
+            } else if let Some((id, voter)) = governance::is_vote_key(key) {
+                self.is_valid_vote(key, id, voter, verifiers)?
+            } else if let Some((_, owner)) = token::is_any_balance_key(key) {
+                // the deposits can only be moved by the tally
+                owner == &gov && {
+                    let pre: Amount =
+                        self.ctx.read_pre_value(key)?.unwrap_or_default();
+                    let post: Amount =
+                        self.ctx.read_post_value(key)?.unwrap_or_default();
+                    post >= pre
+                }
+            } else {
+                false
+            };
+            if !is_valid {
+                tracing::info!("Governance rejected a change of key {}", key);
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl<'a, DB, H> GovernanceVp<'a, DB, H>
+where
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+{
+    /// A new proposal must take the next identifier, be authorized by its
+    /// author and its deposit must be at least the minimum and transferred to
+    /// the governance. The voting period starts in the current epoch.
+    fn is_valid_new_proposal(
+        &self,
+        id: u64,
+        verifiers: &HashSet<Address>,
+    ) -> Result<bool> {
+        let key = governance::proposal_key(id);
+        if self.ctx.has_key_pre(&key)? {
+            return Ok(false);
+        }
+        let proposal: Proposal = match self.ctx.read_post_value(&key)? {
+            Some(proposal) => proposal,
+            None => return Ok(false),
+        };
+        let parameters: GovernanceParameters =
+            match self.ctx.read_pre_value(&governance::parameters_key())? {
+                Some(parameters) => parameters,
+                None => return Ok(false),
+            };
+        let epoch = self.ctx.get_block_epoch()?;
+        let voting_end_epoch =
+            match epoch.0.checked_add(parameters.voting_period) {
+                Some(epoch) => Epoch(epoch),
+                None => return Ok(false),
+            };
+        let counter: Option<u64> =
+            self.ctx.read_post_value(&governance::counter_key())?;
+        let active_key = governance::active_proposals_key();
+        let mut active: BTreeSet<u64> =
+            self.ctx.read_pre_value(&active_key)?.unwrap_or_default();
+        active.insert(id);
+        let post_active: Option<BTreeSet<u64>> =
+            self.ctx.read_post_value(&active_key)?;
+
+        let balance_key = token::balance_key(
+            &address::xan(),
+            &governance::governance_address(),
+        );
+        let pre_balance: Amount =
+            self.ctx.read_pre_value(&balance_key)?.unwrap_or_default();
+        let post_balance: Amount =
+            self.ctx.read_post_value(&balance_key)?.unwrap_or_default();
+        let is_deposited = post_balance
+            .checked_sub(&pre_balance)
+            .map_or(false, |deposited| deposited >= proposal.deposit);
+
+        Ok(proposal.id == id
+            && verifiers.contains(&proposal.author)
+            && proposal.deposit >= parameters.min_proposal_deposit
+            && is_deposited
+            && proposal.voting_start_epoch == epoch
+            && proposal.voting_end_epoch == voting_end_epoch
+            && counter == Some(id + 1)
+            && post_active == Some(active))
+    }
+
+    /// A vote must be authorized by the voter and the proposal must be in its
+    /// voting period. A vote can be changed until the end of the period.
+    fn is_valid_vote(
+        &self,
+        key: &Key,
+        id: u64,
+        voter: &Address,
+        verifiers: &HashSet<Address>,
+    ) -> Result<bool> {
+        let proposal: Proposal =
+            match self.ctx.read_pre_value(&governance::proposal_key(id))? {
+                Some(proposal) => proposal,
+                None => return Ok(false),
+            };
+        let epoch = self.ctx.get_block_epoch()?;
+        let vote: Option<Vote> = self.ctx.read_post_value(key)?;
+        Ok(vote.is_some()
+            && proposal.is_voting_open(epoch)
+            && verifiers.contains(voter))
+    }
+}
+
+impl From<native_vp::Error> for Error {
+    fn from(err: native_vp::Error) -> Self {
+        Self::NativeVpError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use borsh::BorshSerialize;
+
+    use super::*;
+    use crate::ledger::gas::VpGasMeter;
+    use crate::ledger::storage::testing::TestStorage;
+    use crate::ledger::upgrade::UpgradePlan;
+    use crate::proto::Tx;
+    use crate::types::storage::BlockHeight;
+
+    fn validate(
+        storage: &TestStorage,
+        write_log: &WriteLog,
+        verifiers: &[&Address],
+    ) -> bool {
+        let keys_changed = write_log
+            .verifiers_changed_keys(&HashSet::new())
+            .remove(&governance::governance_address())
+            .unwrap_or_default();
+        let verifiers: HashSet<Address> =
+            verifiers.iter().map(|&addr| addr.clone()).collect();
+        let tx = Tx::new(vec![], None);
+        let ctx = Ctx::new(storage, write_log, &tx, VpGasMeter::new(0));
+        let vp = GovernanceVp { ctx };
+        vp.validate_tx(&[], &keys_changed, &verifiers)
+            .expect("validation shouldn't fail")
+    }
+
+    fn write<T: BorshSerialize>(write_log: &mut WriteLog, key: &Key, value: T) {
+        write_log.write(key, encode(&value)).unwrap();
+    }
+
+    /// Initialize the governance with a minimum deposit of 100 XAN and a
+    /// voting period of 2 epochs. Each of the accounts gets 1000 XAN, which
+    /// make up the total supply.
+    fn init_storage(accounts: &[&Address]) -> TestStorage {
+        let mut storage = TestStorage::default();
+        init_genesis_storage(
+            &mut storage,
+            &GovernanceParameters {
+                min_proposal_deposit: Amount::from(100),
+                voting_period: 2,
+            },
+        );
+        let xan = address::xan();
+        for account in accounts {
+            storage
+                .write(
+                    &token::balance_key(&xan, account),
+                    encode(&Amount::from(1000)),
+                )
+                .unwrap();
+        }
+        storage
+            .write(
+                &token::total_supply_key(&xan),
+                encode(&Amount::from(1000 * accounts.len() as u64)),
+            )
+            .unwrap();
+        storage
+    }
+
+    /// Submit a proposal in the same way as the `tx_init_proposal` wasm.
+    fn submit(
+        storage: &TestStorage,
+        write_log: &mut WriteLog,
+        author: &Address,
+        content: ProposalContent,
+        deposit: u64,
+    ) -> Proposal {
+        let counter_key = governance::counter_key();
+        let id: u64 = read_block_value(storage, write_log, &counter_key)
+            .unwrap()
+            .unwrap();
+        let epoch = storage.current_epoch;
+        let proposal = Proposal {
+            id,
+            author: author.clone(),
+            content,
+            deposit: Amount::from(deposit),
+            voting_start_epoch: epoch,
+            voting_end_epoch: Epoch(epoch.0 + 2),
+        };
+        assert!(transfer(
+            storage,
+            write_log,
+            &address::xan(),
+            author,
+            &governance::governance_address(),
+            proposal.deposit,
+        )
+        .unwrap());
+        let active_key = governance::active_proposals_key();
+        let mut active: BTreeSet<u64> =
+            read_block_value(storage, write_log, &active_key)
+                .unwrap()
+                .unwrap();
+        active.insert(id);
+        write(write_log, &active_key, active);
+        write(write_log, &counter_key, id + 1);
+        write(write_log, &governance::proposal_key(id), proposal.clone());
+        proposal
+    }
+
+    fn spend(amount: u64) -> ProposalContent {
+        ProposalContent::Spend {
+            target: address::btc(),
+            token: address::xan(),
+            amount: Amount::from(amount),
+        }
+    }
+
+    /// Test that a proposal must be authorized by its author and come with
+    /// the minimum deposit.
+    #[test]
+    fn test_submit_proposal() {
+        let author = address::testing::established_address_1();
+        let storage = init_storage(&[&author]);
+        let mut write_log = WriteLog::default();
+
+        submit(&storage, &mut write_log, &author, spend(10), 100);
+        assert!(validate(&storage, &write_log, &[&author]));
+        assert!(!validate(&storage, &write_log, &[]));
+        write_log.drop_tx();
+
+        submit(&storage, &mut write_log, &author, spend(10), 99);
+        assert!(!validate(&storage, &write_log, &[&author]));
+        write_log.drop_tx();
+
+        // the voting period must start in the current epoch
+        let mut proposal =
+            submit(&storage, &mut write_log, &author, spend(10), 100);
+        proposal.voting_end_epoch = Epoch(10);
+        write(&mut write_log, &governance::proposal_key(0), proposal);
+        assert!(!validate(&storage, &write_log, &[&author]));
+        write_log.drop_tx();
+
+        // the governance funds cannot be taken by a transaction
+        let gov_key = token::balance_key(
+            &address::xan(),
+            &governance::governance_address(),
+        );
+        write(&mut write_log, &gov_key, Amount::from(0));
+        assert!(validate(&storage, &write_log, &[&author]));
+        write_log.commit_tx();
+        write(&mut write_log, &gov_key, Amount::from(100));
+        assert!(validate(&storage, &write_log, &[&author]));
+        write_log.commit_tx();
+        write(&mut write_log, &gov_key, Amount::from(50));
+        assert!(!validate(&storage, &write_log, &[&author]));
+    }
+
+    /// Test that a vote must be authorized by the voter and cast in the
+    /// voting period.
+    #[test]
+    fn test_vote() {
+        let author = address::testing::established_address_1();
+        let voter = address::testing::established_address_2();
+        let mut storage = init_storage(&[&author, &voter]);
+        let mut write_log = WriteLog::default();
+        submit(&storage, &mut write_log, &author, spend(10), 100);
+        write_log.commit_tx();
+        write_log.commit_block(&mut storage).unwrap();
+
+        let vote_key = governance::vote_key(0, &voter);
+        write(&mut write_log, &vote_key, Vote::Yay);
+        assert!(validate(&storage, &write_log, &[&voter]));
+        assert!(!validate(&storage, &write_log, &[&author]));
+        write_log.drop_tx();
+
+        // a vote on a proposal that doesn't exist
+        write(&mut write_log, &governance::vote_key(1, &voter), Vote::Yay);
+        assert!(!validate(&storage, &write_log, &[&voter]));
+        write_log.drop_tx();
+
+        // the votes are closed in the epoch of the tally
+        storage.current_epoch = Epoch(1);
+        write(&mut write_log, &vote_key, Vote::Nay);
+        assert!(validate(&storage, &write_log, &[&voter]));
+        write_log.drop_tx();
+        storage.current_epoch = Epoch(2);
+        write(&mut write_log, &vote_key, Vote::Nay);
+        assert!(!validate(&storage, &write_log, &[&voter]));
+    }
+
+    /// Test that the proposals are tallied after their voting period and
+    /// that the passed proposals are applied.
+    #[test]
+    fn test_tally_proposals() {
+        let xan = address::xan();
+        let gov = governance::governance_address();
+        let accounts = [
+            address::testing::established_address_1(),
+            address::testing::established_address_2(),
+            address::matchmaker(),
+        ];
+        let mut storage = init_storage(&accounts.iter().collect::<Vec<_>>());
+        let mut write_log = WriteLog::default();
+        let author = &accounts[0];
+        submit(&storage, &mut write_log, author, spend(150), 100);
+        submit(&storage, &mut write_log, author, spend(10), 100);
+        submit(&storage, &mut write_log, author, spend(200), 100);
+        // fund the treasury
+        let treasury = treasury::treasury_address();
+        transfer(
+            &storage,
+            &mut write_log,
+            &xan,
+            author,
+            &treasury,
+            Amount::from(200),
+        )
+        .unwrap();
+        // the first proposal passes, the second one doesn't have more than
+        // 2/3 of the votes in favor and its deposit goes to the treasury and
+        // the last one passes with the quorum of 1/3 of the total voting
+        // power, but the remaining treasury funds are insufficient for it
+        let votes = [
+            (0, &accounts[0], Vote::Yay),
+            (0, &accounts[1], Vote::Yay),
+            (1, &accounts[0], Vote::Yay),
+            (1, &accounts[1], Vote::Yay),
+            (1, &accounts[2], Vote::Nay),
+            (2, &accounts[2], Vote::Yay),
+        ];
+        for (id, voter, vote) in votes.iter() {
+            write(&mut write_log, &governance::vote_key(*id, voter), *vote);
+        }
+        write_log.commit_tx();
+        write_log.commit_block(&mut storage).unwrap();
+        storage.commit().unwrap();
+
+        // nothing is tallied before the end of the voting period
+        storage.current_epoch = Epoch(1);
+        assert!(tally_proposals(&storage, &mut write_log)
+            .unwrap()
+            .is_empty());
+
+        storage.current_epoch = Epoch(2);
+        let results = tally_proposals(&storage, &mut write_log).unwrap();
+        let outcomes: Vec<(u64, bool, bool)> = results
+            .iter()
+            .map(|(id, result)| (*id, result.passed, result.applied))
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![(0, true, true), (1, false, false), (2, true, false)]
+        );
+        assert_eq!(results[0].1.tally.yay, Amount::from(1500));
+        assert_eq!(results[1].1.tally.nay, Amount::from(1000));
+
+        let balance = |owner: &Address| -> Amount {
+            read_block_value(
+                &storage,
+                &write_log,
+                &token::balance_key(&xan, owner),
+            )
+            .unwrap()
+            .unwrap_or_default()
+        };
+        assert_eq!(balance(&address::btc()), Amount::from(150));
+        // the deposits of the passed proposals are returned
+        assert_eq!(balance(author), Amount::from(700));
+        assert_eq!(balance(&gov), Amount::from(0));
+        assert_eq!(balance(&treasury), Amount::from(150));
+        let active: BTreeSet<u64> = read_block_value(
+            &storage,
+            &write_log,
+            &governance::active_proposals_key(),
+        )
+        .unwrap()
+        .unwrap();
+        assert!(active.is_empty());
+    }
+
+    /// Test that the XAN that a voter has bonded or is unbonding counts
+    /// towards their voting power.
+    #[test]
+    fn test_tally_bonded_voting_power() {
+        let author = address::testing::established_address_1();
+        let voter = address::testing::established_address_2();
+        let validator = address::matchmaker();
+        let mut storage = init_storage(&[&author]);
+        let mut write_log = WriteLog::default();
+        submit(&storage, &mut write_log, &author, spend(0), 100);
+        // the voter has no balance, but 500 XAN bonded to a validator and
+        // 300 XAN unbonding from it
+        write(
+            &mut write_log,
+            &pos::bond_key(&voter, &validator),
+            Bond::new(Amount::from(500), 0),
+        );
+        let mut unbonds = Unbonds::new();
+        unbonds.insert(Epoch(2), Amount::from(100));
+        unbonds.insert(Epoch(3), Amount::from(200));
+        write(
+            &mut write_log,
+            &pos::unbond_key(&voter, &validator),
+            unbonds,
+        );
+        // another delegator's bond doesn't count
+        write(
+            &mut write_log,
+            &pos::bond_key(&author, &validator),
+            Bond::new(Amount::from(1000), 0),
+        );
+        write(&mut write_log, &governance::vote_key(0, &voter), Vote::Yay);
+        write(&mut write_log, &governance::vote_key(0, &author), Vote::Nay);
+        write_log.commit_tx();
+        write_log.commit_block(&mut storage).unwrap();
+        storage.commit().unwrap();
+
+        storage.current_epoch = Epoch(2);
+        let results = tally_proposals(&storage, &mut write_log).unwrap();
+        assert_eq!(results[0].1.tally.yay, Amount::from(800));
+        assert_eq!(results[0].1.tally.nay, Amount::from(1900));
+    }
+
+    /// Test that a passed upgrade proposal sets the upgrade plan only if its
+    /// height hasn't been reached yet.
+    #[test]
+    fn test_upgrade_proposal() {
+        let author = address::testing::established_address_1();
+        let mut storage = init_storage(&[&author]);
+        let mut write_log = WriteLog::default();
+        let plan = |height: u64| UpgradePlan {
+            name: "v2".to_owned(),
+            height: BlockHeight(height),
+            version: "0.2.0".to_owned(),
+        };
+        let content = |height: u64| ProposalContent::Upgrade(plan(height));
+        submit(&storage, &mut write_log, &author, content(10), 100);
+        submit(&storage, &mut write_log, &author, content(11), 100);
+        write(&mut write_log, &governance::vote_key(0, &author), Vote::Yay);
+        write(&mut write_log, &governance::vote_key(1, &author), Vote::Yay);
+        write_log.commit_tx();
+        write_log.commit_block(&mut storage).unwrap();
+        storage.commit().unwrap();
+
+        storage.current_epoch = Epoch(2);
+        storage.block.height = BlockHeight(10);
+        let results = tally_proposals(&storage, &mut write_log).unwrap();
+        let outcomes: Vec<(u64, bool, bool)> = results
+            .iter()
+            .map(|(id, result)| (*id, result.passed, result.applied))
+            .collect();
+        assert_eq!(outcomes, vec![(0, true, false), (1, true, true)]);
+        let pending: Option<UpgradePlan> =
+            read_block_value(&storage, &write_log, &upgrade::plan_key())
+                .unwrap();
+        assert_eq!(pending, Some(plan(11)));
+    }
+}
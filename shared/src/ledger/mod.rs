@@ -1,10 +1,17 @@
 //! The ledger modules
 
 pub mod gas;
+pub mod governance;
 #[cfg(feature = "ibc-vp")]
 pub mod ibc;
 pub mod native_vp;
 pub mod parameters;
 pub mod pos;
+#[cfg(feature = "shielded-pool")]
+pub mod shielded_pool;
 pub mod storage;
+pub mod token;
+pub mod token_registry;
+pub mod treasury;
+pub mod upgrade;
 pub mod vp_env;
@@ -3,15 +3,18 @@
 use std::cell::RefCell;
 use std::collections::HashSet;
 
+use borsh::BorshDeserialize;
 use thiserror::Error;
 
 use crate::ledger::gas::VpGasMeter;
+use crate::ledger::storage::types::decode;
 use crate::ledger::storage::write_log::WriteLog;
 use crate::ledger::storage::{Storage, StorageHasher};
 use crate::ledger::{storage, vp_env};
 use crate::proto::Tx;
 use crate::types::address::{Address, InternalAddress};
-use crate::types::storage::{BlockHash, BlockHeight, Key};
+use crate::types::storage::{BlockHash, BlockHeight, Epoch, Key};
+use crate::types::time::DateTimeUtc;
 use crate::vm::prefix_iter::PrefixIterators;
 
 #[allow(missing_docs)]
@@ -111,6 +114,24 @@ where
         .map_err(Error::ContextError)
     }
 
+    /// Read and decode a prior value. Values that cannot be decoded are
+    /// treated as missing.
+    pub fn read_pre_value<T: BorshDeserialize>(
+        &self,
+        key: &Key,
+    ) -> Result<Option<T>> {
+        Ok(self.read_pre(key)?.and_then(|bytes| decode(bytes).ok()))
+    }
+
+    /// Read and decode a posterior value. Values that cannot be decoded are
+    /// treated as missing.
+    pub fn read_post_value<T: BorshDeserialize>(
+        &self,
+        key: &Key,
+    ) -> Result<Option<T>> {
+        Ok(self.read_post(key)?.and_then(|bytes| decode(bytes).ok()))
+    }
+
     /// Storage `has_key` in prior state (before tx execution). It will try to
     /// read from the storage.
     pub fn has_key_pre(&self, key: &Key) -> Result<bool> {
@@ -157,6 +178,28 @@ where
             .map_err(Error::ContextError)
     }
 
+    /// Getting the block epoch. The epoch is that of the block to which the
+    /// current transaction is being applied.
+    pub fn get_block_epoch(&self) -> Result<Epoch> {
+        vp_env::get_block_epoch(&mut *self.gas_meter.borrow_mut(), self.storage)
+            .map_err(Error::ContextError)
+    }
+
+    /// Getting the block time. The time is that of the block to which the
+    /// current transaction is being applied. It's only available when the
+    /// block header has been set.
+    pub fn get_block_time(&self) -> Result<Option<DateTimeUtc>> {
+        vp_env::get_block_time(&mut *self.gas_meter.borrow_mut(), self.storage)
+            .map_err(Error::ContextError)
+    }
+
+    /// Getting the timestamp of the transaction that triggered the validity
+    /// predicate.
+    pub fn get_tx_timestamp(&self) -> Result<DateTimeUtc> {
+        vp_env::get_tx_timestamp(&mut *self.gas_meter.borrow_mut(), self.tx)
+            .map_err(Error::ContextError)
+    }
+
     /// Storage prefix iterator. It will try to get an iterator from the
     /// storage.
     pub fn iter_prefix(
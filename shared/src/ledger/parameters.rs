@@ -1,8 +1,9 @@
 //! Protocol parameters
 
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 
 use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use super::storage::types::decode;
@@ -10,10 +11,17 @@ use crate::ledger::native_vp::{self, Ctx, NativeVp};
 use crate::ledger::storage::types::{self, encode};
 use crate::ledger::storage::{self, Storage, StorageHasher};
 use crate::types::address::{Address, InternalAddress};
-use crate::types::storage::{DbKeySeg, Key};
+use crate::types::hash;
+use crate::types::storage::{DbKeySeg, Epoch, Key};
 use crate::types::time::DurationSecs;
 
 const ADDR: InternalAddress = InternalAddress::Parameters;
+const TX_ALLOWLIST_STORAGE_KEY: &str = "tx_allowlist";
+const PENDING_STORAGE_KEY: &str = "pending";
+
+/// The number of epochs after the epoch in which a parameters change is
+/// scheduled until it becomes active
+pub const ACTIVATION_DELAY: u64 = 2;
 
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
@@ -46,10 +54,16 @@ where
     Hash,
     BorshSerialize,
     BorshDeserialize,
+    Serialize,
+    Deserialize,
 )]
 pub struct Parameters {
     /// Epoch duration
     pub epoch_duration: EpochDuration,
+    /// Consensus parameters passed to Tendermint
+    pub consensus: ConsensusParams,
+    /// Inflation of XAN that rewards the validators
+    pub inflation: InflationParams,
 }
 
 /// Epoch duration. A new epoch begins as soon as both the `min_num_of_blocks`
@@ -64,6 +78,8 @@ pub struct Parameters {
     Hash,
     BorshSerialize,
     BorshDeserialize,
+    Serialize,
+    Deserialize,
 )]
 pub struct EpochDuration {
     /// Minimum number of blocks in an epoch
@@ -72,6 +88,81 @@ pub struct EpochDuration {
     pub min_duration: DurationSecs,
 }
 
+/// Consensus parameters, which are given to Tendermint in the genesis and
+/// whenever they change
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct ConsensusParams {
+    /// Maximum size of a block in bytes
+    pub max_block_bytes: u64,
+    /// Maximum gas of a block
+    pub max_block_gas: u64,
+    /// Maximum age of evidence in blocks
+    pub evidence_max_age_num_blocks: u64,
+    /// Maximum age of evidence in time. Evidence is only rejected when both
+    /// its age in blocks and in time are exceeded.
+    pub evidence_max_age_duration: DurationSecs,
+    /// The types of public keys that validators can use, e.g. "ed25519"
+    pub pub_key_types: Vec<String>,
+}
+
+/// Inflation parameters. All the rates are in basis points, i.e. 1/10000. The
+/// annual inflation rate is adjusted by the ratio of the bonded XAN to its
+/// total supply, so that it rises when less than the target is bonded and
+/// falls when more is bonded.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct InflationParams {
+    /// Annual inflation rate when the bonded ratio is at the target
+    pub annual_rate: u64,
+    /// Target ratio of the bonded XAN to its total supply
+    pub target_bonded_ratio: u64,
+    /// Bonus of a block's proposer as a rate of the voting power that signed
+    /// the previous block
+    pub proposer_bonus: u64,
+    /// Share of the inflation that goes to the treasury
+    pub treasury_share: u64,
+}
+
+impl InflationParams {
+    /// Get the annual inflation rate for the given bonded ratio. The rate
+    /// decreases linearly from twice the `annual_rate` when nothing is bonded
+    /// to zero when twice the target ratio is bonded.
+    pub fn rate(&self, bonded_ratio: u64) -> u64 {
+        if self.target_bonded_ratio == 0 {
+            return self.annual_rate;
+        }
+        let double_target = 2 * u128::from(self.target_bonded_ratio);
+        let bonded_ratio = u128::from(bonded_ratio).min(double_target);
+        let rate = u128::from(self.annual_rate)
+            * (double_target - bonded_ratio)
+            / u128::from(self.target_bonded_ratio);
+        rate as u64
+    }
+}
+
 /// Initialize parameters in storage in the genesis block.
 pub fn init_genesis_storage<DB, H>(
     storage: &mut Storage<DB, H>,
@@ -114,6 +205,29 @@ where
     Ok((parameters, gas))
 }
 
+/// Check if the transaction code is in the transactions allowlist, if there is
+/// one. Returns the gas cost together with the result.
+pub fn is_tx_allowed<DB, H>(
+    storage: &Storage<DB, H>,
+    tx_code: &[u8],
+) -> std::result::Result<(bool, u64), ReadError>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: storage::StorageHasher,
+{
+    let key = tx_allowlist_key();
+    let (value, gas) = storage.read(&key).map_err(ReadError::StorageError)?;
+    match value {
+        Some(value) => {
+            let allowlist: BTreeSet<String> =
+                decode(value).map_err(ReadError::StorageTypeError)?;
+            let code_hash = hex::encode(hash::sha256(tx_code));
+            Ok((allowlist.contains(&code_hash), gas))
+        }
+        None => Ok((true, gas)),
+    }
+}
+
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
 pub enum WriteError {
@@ -121,6 +235,42 @@ pub enum WriteError {
     StorageError(storage::Error),
 }
 
+/// Get the epoch in which the parameters scheduled in the given epoch become
+/// active.
+pub fn activation_epoch(epoch: Epoch) -> Epoch {
+    epoch + ACTIVATION_DELAY
+}
+
+/// Make the parameters scheduled for the given epoch, if any, the current
+/// parameters. This must be called when the epoch begins, so that the
+/// parameters never change within an epoch. Returns `true` if the parameters
+/// have changed.
+pub fn activate_pending<DB, H>(
+    storage: &mut Storage<DB, H>,
+    epoch: Epoch,
+) -> std::result::Result<bool, WriteError>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: storage::StorageHasher,
+{
+    let pending_key = pending_key(epoch);
+    let (value, _gas) = storage
+        .read(&pending_key)
+        .map_err(WriteError::StorageError)?;
+    match value {
+        Some(value) => {
+            storage
+                .write(&storage_key(), value)
+                .map_err(WriteError::StorageError)?;
+            storage
+                .delete(&pending_key)
+                .map_err(WriteError::StorageError)?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
 /// Update the current parameters in storage. Returns the parameters and gas
 /// cost.
 pub fn update<DB, H>(
@@ -155,19 +305,56 @@ where
         _keys_changed: &HashSet<Key>,
         _verifiers: &HashSet<Address>,
     ) -> Result<bool> {
-        // TODO allow parameters change by over 2/3 validator voting power
-        // No changes are currently permitted
+        // The parameters can only be changed by governance proposals, which
+        // are applied by the protocol
         Ok(false)
     }
 }
 
 /// Storage key used for parameters.
-fn storage_key() -> Key {
+pub fn storage_key() -> Key {
     Key {
         segments: vec![DbKeySeg::AddressSeg(Address::Internal(ADDR))],
     }
 }
 
+/// Storage key used for the allowlist of the hex-encoded SHA-256 hashes of
+/// the transactions' code. When there is no allowlist, any code is allowed.
+pub fn tx_allowlist_key() -> Key {
+    storage_key()
+        .push(&TX_ALLOWLIST_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key prefix of the parameters that are scheduled to become active.
+pub fn pending_prefix() -> Key {
+    storage_key()
+        .push(&PENDING_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key of the parameters that are scheduled to become active in the
+/// given epoch.
+pub fn pending_key(epoch: Epoch) -> Key {
+    pending_prefix()
+        .push(&epoch.0.to_string())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is a key of scheduled parameters. If it is,
+/// returns the epoch in which they become active.
+pub fn is_pending_key(key: &Key) -> Option<Epoch> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(prefix), DbKeySeg::StringSeg(epoch)]
+            if addr == &Address::Internal(ADDR)
+                && prefix == PENDING_STORAGE_KEY =>
+        {
+            epoch.parse().ok().map(Epoch)
+        }
+        _ => None,
+    }
+}
+
 impl From<native_vp::Error> for Error {
     fn from(err: native_vp::Error) -> Self {
         Self::NativeVpError(err)
@@ -1,13 +1,33 @@
-//! Proof-of-Stake integration as a native validity predicate
+//! Proof-of-Stake integration as a native validity predicate. The validators
+//! are rewarded from the inflation of XAN, which is minted at the beginning of
+//! every epoch and split by the validators' participation in the consensus in
+//! the previous epoch. The delegators' rewards are accumulated per a bonded
+//! token in the validators' reward accumulators, so they can be claimed
+//! without touching every bond when the rewards are distributed.
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::convert::TryFrom;
 
 use thiserror::Error;
 
+use super::storage::types::{decode, encode};
 use crate::ledger::native_vp::{self, Ctx, NativeVp};
-use crate::ledger::storage::{self, Storage, StorageHasher};
-use crate::types::address::{Address, InternalAddress};
-use crate::types::storage::Key;
+use crate::ledger::parameters::Parameters;
+use crate::ledger::storage::write_log::WriteLog;
+use crate::ledger::storage::{self, read_block_value, Storage, StorageHasher};
+use crate::ledger::treasury;
+use crate::types::address::{self, Address, InternalAddress};
+use crate::types::key::ed25519;
+use crate::types::pos::{self, Bond, Unbonds, BASIS_POINTS, REWARD_PRECISION};
+use crate::types::storage::{Epoch, Key};
+use crate::types::time::DateTimeUtc;
+use crate::types::token::{self, Amount};
+
+const ADDR: InternalAddress = InternalAddress::PoS;
+
+/// The number of seconds in a year used to scale the annual inflation rate
+/// to an epoch
+pub const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
 
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
@@ -29,30 +49,814 @@ where
     pub ctx: Ctx<'a, DB, H>,
 }
 
-/// Initialize storage in the genesis block.
-pub fn init_genesis_storage<DB, H>(_storage: &mut Storage<DB, H>)
+/// A validator in the genesis block
+#[derive(Debug, Clone)]
+pub struct GenesisValidator {
+    /// The validator's account address
+    pub address: Address,
+    /// The key that the validator uses to sign the blocks
+    pub consensus_key: ed25519::PublicKey,
+    /// The validator's self-bond, which is minted in the genesis
+    pub stake: Amount,
+    /// The validator's commission rate in basis points
+    pub commission_rate: u64,
+    /// The maximum change of the validator's commission rate in an epoch, in
+    /// basis points
+    pub max_commission_rate_change: u64,
+}
+
+/// A validator's vote in the commit of the previous block
+#[derive(Debug, Clone)]
+pub struct CommitVote {
+    /// The validator's Tendermint address
+    pub consensus_address: String,
+    /// The validator's voting power
+    pub power: u64,
+    /// Whether the validator signed the previous block
+    pub signed: bool,
+}
+
+/// Initialize storage in the genesis block with the given validators. Their
+/// stake is minted into the PoS account and added to the total supply of XAN.
+/// The genesis time is the start of the first epoch's rewards.
+pub fn init_genesis_storage<DB, H>(
+    storage: &mut Storage<DB, H>,
+    validators: &[GenesisValidator],
+    genesis_time: DateTimeUtc,
+) where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    let xan = address::xan();
+    let (epoch, _gas) = storage.get_block_epoch();
+    let mut total_stake = Amount::default();
+    for validator in validators {
+        let address = &validator.address;
+        let consensus_address =
+            pos::consensus_address(&validator.consensus_key);
+        storage
+            .write(
+                &pos::consensus_key_key(address),
+                encode(&validator.consensus_key),
+            )
+            .expect("Unable to write a genesis validator's consensus key");
+        storage
+            .write(
+                &pos::consensus_address_key(&consensus_address),
+                encode(address),
+            )
+            .expect("Unable to write a genesis validator's consensus address");
+        storage
+            .write(
+                &pos::commission_rate_key(address),
+                encode(&validator.commission_rate),
+            )
+            .expect("Unable to write a genesis validator's commission rate");
+        storage
+            .write(
+                &pos::max_commission_rate_change_key(address),
+                encode(&validator.max_commission_rate_change),
+            )
+            .expect(
+                "Unable to write a genesis validator's maximum commission \
+                 rate change",
+            );
+        storage
+            .write(&pos::commission_epoch_key(address), encode(&epoch))
+            .expect("Unable to write a genesis validator's commission epoch");
+        storage
+            .write(&pos::stake_key(address), encode(&validator.stake))
+            .expect("Unable to write a genesis validator's stake");
+        storage
+            .write(
+                &pos::voting_power_key(address),
+                encode(&pos::voting_power(validator.stake)),
+            )
+            .expect("Unable to write a genesis validator's voting power");
+        storage
+            .write(&pos::reward_acc_key(address), encode(&0_u128))
+            .expect("Unable to write a genesis validator's reward accumulator");
+        storage
+            .write(
+                &pos::bond_key(address, address),
+                encode(&Bond::new(validator.stake, 0)),
+            )
+            .expect("Unable to write a genesis validator's self-bond");
+        total_stake = total_stake
+            .checked_add(&validator.stake)
+            .expect("The genesis stake shouldn't overflow");
+    }
+    for key in &[
+        token::balance_key(&xan, &pos::pos_address()),
+        token::total_supply_key(&xan),
+    ] {
+        let amount: Amount = storage
+            .read(key)
+            .expect("Unable to read a genesis amount")
+            .0
+            .and_then(|bytes| decode(bytes).ok())
+            .unwrap_or_default();
+        let amount = amount
+            .checked_add(&total_stake)
+            .expect("The genesis stake shouldn't overflow");
+        storage
+            .write(key, encode(&amount))
+            .expect("Unable to write a genesis amount");
+    }
+    storage
+        .write(&pos::reward_epoch_key(), encode(&epoch))
+        .expect("Unable to write the genesis reward epoch");
+    storage
+        .write(&pos::reward_epoch_start_key(), encode(&genesis_time))
+        .expect("Unable to write the genesis reward epoch start");
+}
+
+/// Record the validators' participation in the commit of the previous block
+/// as their reward points. A validator that signed the block gets the points
+/// of its voting power and the block's proposer gets a bonus of the
+/// [`crate::ledger::parameters::InflationParams::proposer_bonus`] of the
+/// voting power that signed the block. The changes are committed into the
+/// block write log.
+pub fn record_participation<DB, H>(
+    storage: &Storage<DB, H>,
+    write_log: &mut WriteLog,
+    parameters: &Parameters,
+    proposer: &str,
+    votes: &[CommitVote],
+) -> std::result::Result<(), storage::Error>
 where
     DB: storage::DB + for<'iter> storage::DBIter<'iter>,
     H: StorageHasher,
 {
+    let mut signed_power: u64 = 0;
+    for vote in votes.iter().filter(|vote| vote.signed) {
+        signed_power = signed_power.saturating_add(vote.power);
+        add_reward_points(
+            storage,
+            write_log,
+            &vote.consensus_address,
+            vote.power,
+        )?;
+    }
+    let bonus = u128::from(signed_power)
+        * u128::from(parameters.inflation.proposer_bonus)
+        / u128::from(BASIS_POINTS);
+    add_reward_points(storage, write_log, proposer, bonus as u64)?;
+    write_log.commit_tx();
+    Ok(())
 }
 
-impl<'a, DB, H> NativeVp for PoS<'a, DB, H>
+fn add_reward_points<DB, H>(
+    storage: &Storage<DB, H>,
+    write_log: &mut WriteLog,
+    consensus_address: &str,
+    points: u64,
+) -> std::result::Result<(), storage::Error>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    if points == 0 {
+        return Ok(());
+    }
+    let validator: Address = match read_block_value(
+        storage,
+        write_log,
+        &pos::consensus_address_key(consensus_address),
+    )? {
+        Some(validator) => validator,
+        None => {
+            tracing::error!("Unknown validator {}", consensus_address);
+            return Ok(());
+        }
+    };
+    let key = pos::reward_points_key(&validator);
+    let current: u64 =
+        read_block_value(storage, write_log, &key)?.unwrap_or_default();
+    write_log
+        .write(&key, encode(&current.saturating_add(points)))
+        .expect("Writing reward points shouldn't fail");
+    Ok(())
+}
+
+/// Mint the inflation of the previous epoch, if it hasn't been minted yet.
+/// This must be called in the first block of an epoch, before the block's
+/// participation is recorded. The treasury gets its share of the inflation
+/// and the rest is split by the validators' reward points, which are reset.
+/// The validators' commission is set aside for them and the rest is added to
+/// their reward accumulators for their delegators. The validators that haven't
+/// signed any block in the previous epoch are jailed. The annual inflation
+/// rate is scaled by the previous epoch's actual duration. The changes are
+/// committed into the block write log. Returns the minted amount, if any.
+pub fn distribute_rewards<DB, H>(
+    storage: &Storage<DB, H>,
+    write_log: &mut WriteLog,
+    parameters: &Parameters,
+) -> std::result::Result<Option<Amount>, storage::Error>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    let (epoch, _gas) = storage.get_block_epoch();
+    let reward_epoch: Option<Epoch> =
+        read_block_value(storage, write_log, &pos::reward_epoch_key())?;
+    if reward_epoch.map_or(true, |reward_epoch| reward_epoch >= epoch) {
+        return Ok(None);
+    }
+    write_log
+        .write(&pos::reward_epoch_key(), encode(&epoch))
+        .expect("Writing the reward epoch shouldn't fail");
+
+    // the inflation is scaled by the time from the first block of the
+    // previous epoch to this block, which starts the next reward epoch. If
+    // either time isn't known, the epoch's minimum duration is used instead.
+    let (block_time, _gas) = storage.get_block_time();
+    let epoch_start: Option<DateTimeUtc> =
+        read_block_value(storage, write_log, &pos::reward_epoch_start_key())?;
+    let epoch_duration = match (epoch_start, block_time) {
+        (Some(start), Some(end)) => {
+            u128::try_from((end.0 - start.0).num_seconds()).unwrap_or_default()
+        }
+        _ => u128::from(parameters.epoch_duration.min_duration.0),
+    };
+    if let Some(block_time) = block_time {
+        write_log
+            .write(&pos::reward_epoch_start_key(), encode(&block_time))
+            .expect("Writing the reward epoch start shouldn't fail");
+    }
+
+    let mut stakes: BTreeMap<Address, Amount> = BTreeMap::new();
+    let mut points: BTreeMap<Address, u64> = BTreeMap::new();
+    let mut active: Vec<Address> = vec![];
+    let (iter, _gas) = storage.iter_prefix(&pos::validator_prefix());
+    for (key, _value, _gas) in iter {
+        let key = match Key::parse(key) {
+            Ok(key) => key,
+            Err(_) => continue,
+        };
+        if let Some(validator) = pos::is_stake_key(&key) {
+            let stake: Amount =
+                read_block_value(storage, write_log, &key)?.unwrap_or_default();
+            stakes.insert(validator.clone(), stake);
+        } else if let Some(validator) = pos::is_reward_points_key(&key) {
+            let validator_points: u64 =
+                read_block_value(storage, write_log, &key)?.unwrap_or_default();
+            points.insert(validator.clone(), validator_points);
+            write_log
+                .delete(&key)
+                .expect("Deleting reward points shouldn't fail");
+        } else if let Some(validator) = pos::is_voting_power_key(&key) {
+            let power: u64 =
+                read_block_value(storage, write_log, &key)?.unwrap_or_default();
+            if power > 0 {
+                active.push(validator.clone());
+            }
+        }
+    }
+
+    // the validators that had voting power in the previous epoch, but haven't
+    // signed any of its blocks are jailed
+    for validator in active {
+        if points.get(&validator).copied().unwrap_or_default() > 0 {
+            continue;
+        }
+        let jailed_key = pos::jailed_key(&validator);
+        let jailed: Option<Epoch> =
+            read_block_value(storage, write_log, &jailed_key)?;
+        if jailed.is_none() {
+            tracing::info!("Jailing validator {}", validator);
+            write_log
+                .write(&jailed_key, encode(&epoch))
+                .expect("Writing a jailed epoch shouldn't fail");
+        }
+    }
+
+    let xan = address::xan();
+    let supply: Amount =
+        read_block_value(storage, write_log, &token::total_supply_key(&xan))?
+            .unwrap_or_default();
+    let supply = u128::from(u64::from(supply));
+    let bonded: u128 = stakes
+        .values()
+        .map(|stake| u128::from(u64::from(*stake)))
+        .sum();
+    let bonded_ratio = if supply == 0 {
+        0
+    } else {
+        bonded * u128::from(BASIS_POINTS) / supply
+    };
+    let rate = parameters.inflation.rate(bonded_ratio as u64);
+    let inflation = supply * u128::from(rate) / u128::from(BASIS_POINTS)
+        * epoch_duration
+        / u128::from(SECONDS_PER_YEAR);
+    let treasury_share = inflation
+        * u128::from(parameters.inflation.treasury_share)
+        / u128::from(BASIS_POINTS);
+    let rewards = inflation - treasury_share;
+
+    let total_points: u128 = points.values().map(|p| u128::from(*p)).sum();
+    let mut minted_rewards: u128 = 0;
+    if total_points > 0 {
+        for (validator, validator_points) in &points {
+            let stake = stakes
+                .get(validator)
+                .map_or(0, |stake| u128::from(u64::from(*stake)));
+            if stake == 0 {
+                continue;
+            }
+            let reward = rewards * u128::from(*validator_points) / total_points;
+            let commission_rate: u64 = read_block_value(
+                storage,
+                write_log,
+                &pos::commission_rate_key(validator),
+            )?
+            .unwrap_or_default();
+            let commission = reward
+                * u128::from(commission_rate.min(BASIS_POINTS))
+                / u128::from(BASIS_POINTS);
+
+            let commission_key = pos::commission_key(validator);
+            let unclaimed: Amount =
+                read_block_value(storage, write_log, &commission_key)?
+                    .unwrap_or_default();
+            let unclaimed = u128::from(u64::from(unclaimed)) + commission;
+            let acc_key = pos::reward_acc_key(validator);
+            let acc: u128 = read_block_value(storage, write_log, &acc_key)?
+                .unwrap_or_default();
+            let acc = acc + (reward - commission) * REWARD_PRECISION / stake;
+            match u64::try_from(unclaimed) {
+                Ok(unclaimed) => {
+                    write_log
+                        .write(
+                            &commission_key,
+                            encode(&Amount::from(unclaimed)),
+                        )
+                        .expect("Writing a commission shouldn't fail");
+                    write_log
+                        .write(&acc_key, encode(&acc))
+                        .expect("Writing a reward accumulator shouldn't fail");
+                    minted_rewards += reward;
+                }
+                Err(_) => {
+                    tracing::error!(
+                        "The commission of validator {} overflowed",
+                        validator
+                    );
+                }
+            }
+        }
+    }
+
+    let treasury_share = u64::try_from(treasury_share).unwrap_or_default();
+    let minted_rewards = u64::try_from(minted_rewards).unwrap_or_default();
+    if !treasury::mint(storage, write_log, &xan, Amount::from(treasury_share))?
+        || !mint(storage, write_log, &xan, Amount::from(minted_rewards))?
+    {
+        tracing::error!("The total supply of XAN overflowed");
+        write_log.drop_tx();
+        return Ok(None);
+    }
+    write_log.commit_tx();
+    Ok(Some(Amount::from(treasury_share + minted_rewards)))
+}
+
+/// Update the validators' voting power from their stake. This must be called
+/// in the first block of an epoch, after the rewards have been distributed. A
+/// jailed validator has no voting power. The voting power is left unchanged
+/// if no validator would have any. The changes are committed into the block
+/// write log. Returns the changed voting power of the validators' consensus
+/// keys, to be given to Tendermint.
+pub fn update_validator_set<DB, H>(
+    storage: &Storage<DB, H>,
+    write_log: &mut WriteLog,
+) -> std::result::Result<Vec<(ed25519::PublicKey, u64)>, storage::Error>
 where
     DB: storage::DB + for<'iter> storage::DBIter<'iter>,
     H: StorageHasher,
+{
+    let mut total_power: u64 = 0;
+    let mut changes = vec![];
+    let (iter, _gas) = storage.iter_prefix(&pos::validator_prefix());
+    for (key, _value, _gas) in iter {
+        let key = match Key::parse(key) {
+            Ok(key) => key,
+            Err(_) => continue,
+        };
+        let validator = match pos::is_consensus_key_key(&key) {
+            Some(validator) => validator,
+            None => continue,
+        };
+        let consensus_key: ed25519::PublicKey =
+            match read_block_value(storage, write_log, &key)? {
+                Some(consensus_key) => consensus_key,
+                None => continue,
+            };
+        let jailed: Option<Epoch> =
+            read_block_value(storage, write_log, &pos::jailed_key(validator))?;
+        let power = if jailed.is_some() {
+            0
+        } else {
+            let stake: Amount = read_block_value(
+                storage,
+                write_log,
+                &pos::stake_key(validator),
+            )?
+            .unwrap_or_default();
+            pos::voting_power(stake)
+        };
+        total_power = total_power.saturating_add(power);
+        let power_key = pos::voting_power_key(validator);
+        let current: u64 = read_block_value(storage, write_log, &power_key)?
+            .unwrap_or_default();
+        if power != current {
+            changes.push((power_key, consensus_key, power));
+        }
+    }
+    if total_power == 0 {
+        tracing::error!("No validator would have any voting power");
+        return Ok(vec![]);
+    }
+    let updates = changes
+        .into_iter()
+        .map(|(power_key, consensus_key, power)| {
+            write_log
+                .write(&power_key, encode(&power))
+                .expect("Writing a voting power shouldn't fail");
+            (consensus_key, power)
+        })
+        .collect();
+    write_log.commit_tx();
+    Ok(updates)
+}
+
+/// Mint the amount of the token into the PoS account in the write log.
+/// Returns `false` if the total supply would overflow.
+fn mint<DB, H>(
+    storage: &Storage<DB, H>,
+    write_log: &mut WriteLog,
+    token: &Address,
+    amount: Amount,
+) -> std::result::Result<bool, storage::Error>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    let supply_key = token::total_supply_key(token);
+    let balance_key = token::balance_key(token, &pos::pos_address());
+    let supply: Amount =
+        read_block_value(storage, write_log, &supply_key)?.unwrap_or_default();
+    let balance: Amount =
+        read_block_value(storage, write_log, &balance_key)?.unwrap_or_default();
+    match (supply.checked_add(&amount), balance.checked_add(&amount)) {
+        (Some(supply), Some(balance)) => {
+            write_log
+                .write(&supply_key, encode(&supply))
+                .expect("Writing a total supply shouldn't fail");
+            write_log
+                .write(&balance_key, encode(&balance))
+                .expect("Writing a balance shouldn't fail");
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+impl<'a, DB, H> NativeVp for PoS<'a, DB, H>
+where
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
 {
     type Error = Error;
 
-    const ADDR: InternalAddress = InternalAddress::PoS;
+    const ADDR: InternalAddress = ADDR;
 
+    /// A transaction can register a new validator, change a validator's
+    /// commission rate within its limit, unjail a validator, bond, unbond and
+    /// withdraw tokens and claim the rewards. The changes of the validators'
+    /// stake and of the PoS balance of XAN must match the bonded, withdrawn
+    /// and claimed amounts. The rewards, the voting power and the jailing are
+    /// managed by the protocol.
     fn validate_tx(
         &self,
         _tx_data: &[u8],
-        _keys_changed: &HashSet<Key>,
-        _verifiers: &HashSet<Address>,
+        keys_changed: &HashSet<Key>,
+        verifiers: &HashSet<Address>,
+    ) -> Result<bool> {
+        let epoch = self.ctx.get_block_epoch()?;
+        let balance_key =
+            token::balance_key(&address::xan(), &pos::pos_address());
+        let mut changes = Changes::default();
+        for key in keys_changed {
+            let is_valid = if let Some(validator) =
+                pos::is_consensus_key_key(key)
+            {
+                verifiers.contains(validator)
+                    && self.is_valid_registration(validator)?
+            } else if let Some(consensus_address) =
+                pos::is_consensus_address_key(key)
+            {
+                self.is_valid_consensus_address(key, consensus_address)?
+            } else if let Some(validator) = pos::is_reward_acc_key(key) {
+                self.is_registering(validator)?
+                    && self.ctx.read_post_value(key)? == Some(0_u128)
+            } else if let Some(validator) =
+                pos::is_max_commission_rate_change_key(key)
+            {
+                self.is_registering(validator)?
+                    && self
+                        .read_post::<u64>(key)?
+                        .map_or(false, |change| change <= BASIS_POINTS)
+            } else if let Some(validator) = pos::is_commission_rate_key(key) {
+                verifiers.contains(validator)
+                    && self.is_valid_commission_rate(key, validator, epoch)?
+            } else if let Some(validator) = pos::is_commission_epoch_key(key) {
+                let pre: Option<Epoch> = self.ctx.read_pre_value(key)?;
+                verifiers.contains(validator)
+                    && pre.map_or(true, |pre| pre < epoch)
+                    && self.ctx.read_post_value(key)? == Some(epoch)
+            } else if let Some(validator) = pos::is_stake_key(key) {
+                let pre: Amount =
+                    self.ctx.read_pre_value(key)?.unwrap_or_default();
+                let post: Amount =
+                    self.ctx.read_post_value(key)?.unwrap_or_default();
+                changes
+                    .stake
+                    .insert(validator.clone(), amount_change(pre, post));
+                true
+            } else if let Some((delegator, validator)) = pos::is_bond_key(key) {
+                verifiers.contains(delegator)
+                    && self.add_bond_change(
+                        key,
+                        delegator,
+                        validator,
+                        &mut changes,
+                    )?
+            } else if let Some((delegator, validator)) = pos::is_unbond_key(key)
+            {
+                verifiers.contains(delegator)
+                    && self.add_unbond_change(
+                        key,
+                        delegator,
+                        validator,
+                        epoch,
+                        &mut changes,
+                    )?
+            } else if let Some(validator) = pos::is_commission_key(key) {
+                // the commission must be claimed in full
+                let pre: Option<Amount> = self.ctx.read_pre_value(key)?;
+                let post: Amount =
+                    self.ctx.read_post_value(key)?.unwrap_or_default();
+                match pre {
+                    Some(pre)
+                        if verifiers.contains(validator)
+                            && post == Amount::default() =>
+                    {
+                        changes.claimed += i128::from(u64::from(pre));
+                        true
+                    }
+                    _ => false,
+                }
+            } else if let Some(validator) = pos::is_jailed_key(key) {
+                let pre: Option<Epoch> = self.ctx.read_pre_value(key)?;
+                verifiers.contains(validator)
+                    && pre.map_or(false, |pre| pre < epoch)
+                    && !self.ctx.has_key_post(key)?
+            } else {
+                key == &balance_key
+            };
+            if !is_valid {
+                tracing::info!("PoS rejected a change of key {}", key);
+                return Ok(false);
+            }
+        }
+
+        // the bonded tokens can be unbonded only in the same amount
+        let mut deposited: i128 = 0;
+        let mut stake_changes: BTreeMap<Address, i128> = BTreeMap::new();
+        for (bond, bonded) in &changes.bonded {
+            let unbonded = changes.unbonded.remove(bond).unwrap_or_default();
+            let is_valid = if *bonded >= 0 {
+                unbonded == 0
+            } else {
+                bonded + unbonded == 0
+            };
+            if !is_valid {
+                tracing::info!("PoS rejected an unbond of a different amount");
+                return Ok(false);
+            }
+            deposited += (*bonded).max(0);
+            let (_delegator, validator) = bond;
+            *stake_changes.entry(validator.clone()).or_default() += bonded;
+        }
+        if changes.unbonded.values().any(|unbonded| *unbonded != 0) {
+            tracing::info!("PoS rejected an unbond without a bond change");
+            return Ok(false);
+        }
+        stake_changes.retain(|_, change| *change != 0);
+        changes.stake.retain(|_, change| *change != 0);
+        if stake_changes != changes.stake {
+            tracing::info!("PoS rejected a stake change");
+            return Ok(false);
+        }
+
+        let pre: Amount =
+            self.ctx.read_pre_value(&balance_key)?.unwrap_or_default();
+        let post: Amount =
+            self.ctx.read_post_value(&balance_key)?.unwrap_or_default();
+        Ok(amount_change(pre, post)
+            == deposited - changes.withdrawn - changes.claimed)
+    }
+}
+
+/// The changes made by a transaction that must match its changes of the
+/// validators' stake and of the PoS balance
+#[derive(Debug, Default)]
+struct Changes {
+    /// The changes of the bonds' amounts by the delegator and the validator
+    bonded: BTreeMap<(Address, Address), i128>,
+    /// The newly unbonded amounts by the delegator and the validator
+    unbonded: BTreeMap<(Address, Address), i128>,
+    /// The changes of the validators' stake
+    stake: BTreeMap<Address, i128>,
+    /// The withdrawn amount
+    withdrawn: i128,
+    /// The claimed rewards and commission
+    claimed: i128,
+}
+
+fn amount_change(pre: Amount, post: Amount) -> i128 {
+    i128::from(u64::from(post)) - i128::from(u64::from(pre))
+}
+
+impl<'a, DB, H> PoS<'a, DB, H>
+where
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+{
+    /// Check if the validator is being registered by the transaction.
+    fn is_registering(&self, validator: &Address) -> Result<bool> {
+        let key = pos::consensus_key_key(validator);
+        Ok(!self.ctx.has_key_pre(&key)? && self.ctx.has_key_post(&key)?)
+    }
+
+    /// A validator's consensus key can only be set when the validator is
+    /// registered, together with the validator's Tendermint address, and it
+    /// cannot be changed afterwards.
+    fn is_valid_registration(&self, validator: &Address) -> Result<bool> {
+        if !self.is_registering(validator)? {
+            return Ok(false);
+        }
+        let consensus_key: ed25519::PublicKey = match self
+            .ctx
+            .read_post_value(&pos::consensus_key_key(validator))?
+        {
+            Some(consensus_key) => consensus_key,
+            None => return Ok(false),
+        };
+        let consensus_address = pos::consensus_address(&consensus_key);
+        let owner: Option<Address> = self
+            .ctx
+            .read_post_value(&pos::consensus_address_key(&consensus_address))?;
+        Ok(owner.as_ref() == Some(validator))
+    }
+
+    /// A Tendermint address can only be taken by a validator that is being
+    /// registered with the matching consensus key.
+    fn is_valid_consensus_address(
+        &self,
+        key: &Key,
+        consensus_address: &str,
     ) -> Result<bool> {
-        Ok(false)
+        if self.ctx.has_key_pre(key)? {
+            return Ok(false);
+        }
+        let validator: Address = match self.ctx.read_post_value(key)? {
+            Some(validator) => validator,
+            None => return Ok(false),
+        };
+        if !self.is_registering(&validator)? {
+            return Ok(false);
+        }
+        let consensus_key: Option<ed25519::PublicKey> = self
+            .ctx
+            .read_post_value(&pos::consensus_key_key(&validator))?;
+        Ok(consensus_key.map_or(false, |consensus_key| {
+            pos::consensus_address(&consensus_key) == consensus_address
+        }))
+    }
+
+    /// A validator's commission rate can be set when the validator is
+    /// registered and then changed at most once per epoch by at most its
+    /// maximum commission rate change.
+    fn is_valid_commission_rate(
+        &self,
+        key: &Key,
+        validator: &Address,
+        epoch: Epoch,
+    ) -> Result<bool> {
+        let post: u64 = match self.ctx.read_post_value(key)? {
+            Some(post) if post <= BASIS_POINTS => post,
+            _ => return Ok(false),
+        };
+        let epoch_key = pos::commission_epoch_key(validator);
+        let pre_epoch: Option<Epoch> = self.ctx.read_pre_value(&epoch_key)?;
+        let post_epoch: Option<Epoch> = self.ctx.read_post_value(&epoch_key)?;
+        if pre_epoch.map_or(false, |pre| pre >= epoch)
+            || post_epoch != Some(epoch)
+        {
+            return Ok(false);
+        }
+        if self.is_registering(validator)? {
+            return Ok(true);
+        }
+        let pre: u64 = match self.ctx.read_pre_value(key)? {
+            Some(pre) => pre,
+            None => return Ok(false),
+        };
+        let max_change: u64 = self
+            .read_pre(&pos::max_commission_rate_change_key(validator))?
+            .unwrap_or_default();
+        let change = if post > pre { post - pre } else { pre - post };
+        Ok(change <= max_change)
+    }
+
+    /// A bond can be created to a registered validator with its current
+    /// reward accumulator. When a bond is changed, its rewards must be
+    /// settled and they can be claimed. The bonded amount can be increased by
+    /// depositing the tokens or decreased by unbonding them.
+    fn add_bond_change(
+        &self,
+        key: &Key,
+        delegator: &Address,
+        validator: &Address,
+        changes: &mut Changes,
+    ) -> Result<bool> {
+        if !self.ctx.has_key_post(&pos::consensus_key_key(validator))? {
+            return Ok(false);
+        }
+        let reward_acc: u128 = self
+            .read_post(&pos::reward_acc_key(validator))?
+            .unwrap_or_default();
+        let post: Bond = match self.ctx.read_post_value(key)? {
+            Some(post) => post,
+            None => return Ok(false),
+        };
+        let (pre_amount, claimed) = match self.read_pre::<Bond>(key)? {
+            None if post == Bond::new(post.amount, reward_acc) => {
+                (Amount::default(), Amount::default())
+            }
+            None => return Ok(false),
+            Some(mut pre) => {
+                let claimed = pre
+                    .settle(reward_acc)
+                    .and_then(|()| pre.unclaimed.checked_sub(&post.unclaimed));
+                match claimed {
+                    Some(claimed) if post.reward_acc == reward_acc => {
+                        (pre.amount, claimed)
+                    }
+                    _ => return Ok(false),
+                }
+            }
+        };
+        changes.claimed += i128::from(u64::from(claimed));
+        changes.bonded.insert(
+            (delegator.clone(), validator.clone()),
+            amount_change(pre_amount, post.amount),
+        );
+        Ok(true)
+    }
+
+    /// The unbonded tokens can only be added to the epoch from which they can
+    /// be withdrawn and they can be withdrawn from that epoch onwards.
+    fn add_unbond_change(
+        &self,
+        key: &Key,
+        delegator: &Address,
+        validator: &Address,
+        epoch: Epoch,
+        changes: &mut Changes,
+    ) -> Result<bool> {
+        let pre: Unbonds = self.ctx.read_pre_value(key)?.unwrap_or_default();
+        let post: Unbonds = self.ctx.read_post_value(key)?.unwrap_or_default();
+        let epochs: BTreeSet<&Epoch> = pre.keys().chain(post.keys()).collect();
+        let mut unbonded: i128 = 0;
+        for unbond_epoch in epochs {
+            let change = amount_change(
+                pre.get(unbond_epoch).copied().unwrap_or_default(),
+                post.get(unbond_epoch).copied().unwrap_or_default(),
+            );
+            if change > 0 && *unbond_epoch == pos::withdrawable_epoch(epoch) {
+                unbonded += change;
+            } else if change < 0 && *unbond_epoch <= epoch {
+                changes.withdrawn -= change;
+            } else if change != 0 {
+                return Ok(false);
+            }
+        }
+        changes
+            .unbonded
+            .insert((delegator.clone(), validator.clone()), unbonded);
+        Ok(true)
     }
 }
 
@@ -61,3 +865,555 @@ impl From<native_vp::Error> for Error {
         Self::NativeVpError(err)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use borsh::BorshSerialize;
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::ledger::gas::VpGasMeter;
+    use crate::ledger::parameters::{
+        ConsensusParams, EpochDuration, InflationParams,
+    };
+    use crate::ledger::storage::testing::{block_header, TestStorage};
+    use crate::proto::Tx;
+    use crate::types::key::ed25519::testing::{keypair_1, keypair_2};
+    use crate::types::time::DurationSecs;
+    use crate::types::treasury::treasury_address;
+
+    fn parameters() -> Parameters {
+        Parameters {
+            epoch_duration: EpochDuration {
+                min_num_of_blocks: 1,
+                // a tenth of a year
+                min_duration: DurationSecs(SECONDS_PER_YEAR / 10),
+            },
+            consensus: ConsensusParams {
+                max_block_bytes: 22_020_096,
+                max_block_gas: 10_000_000,
+                evidence_max_age_num_blocks: 100_000,
+                evidence_max_age_duration: DurationSecs(48 * 60 * 60),
+                pub_key_types: vec!["ed25519".to_owned()],
+            },
+            inflation: InflationParams {
+                annual_rate: 1000,
+                target_bonded_ratio: 5000,
+                proposer_bonus: 1000,
+                treasury_share: 1000,
+            },
+        }
+    }
+
+    fn consensus_key() -> ed25519::PublicKey {
+        keypair_1().public.into()
+    }
+
+    fn genesis_time() -> DateTimeUtc {
+        Utc.timestamp(1_600_000_000, 0).into()
+    }
+
+    /// Initialize a validator with a stake of a half of the total supply, so
+    /// that the bonded ratio is at the target.
+    fn init_storage(validator: &Address) -> TestStorage {
+        let mut storage = TestStorage::default();
+        storage
+            .write(
+                &token::total_supply_key(&address::xan()),
+                encode(&Amount::from(100_000)),
+            )
+            .unwrap();
+        init_genesis_storage(
+            &mut storage,
+            &[GenesisValidator {
+                address: validator.clone(),
+                consensus_key: consensus_key(),
+                stake: Amount::from(100_000),
+                commission_rate: 2000,
+                max_commission_rate_change: 100,
+            }],
+            genesis_time(),
+        );
+        storage
+    }
+
+    fn read<T: borsh::BorshDeserialize>(
+        storage: &TestStorage,
+        write_log: &WriteLog,
+        key: &Key,
+    ) -> T {
+        read_block_value(storage, write_log, key).unwrap().unwrap()
+    }
+
+    fn validate(
+        storage: &TestStorage,
+        write_log: &WriteLog,
+        verifiers: &[&Address],
+    ) -> bool {
+        let keys_changed = write_log
+            .verifiers_changed_keys(&HashSet::new())
+            .remove(&pos::pos_address())
+            .unwrap_or_default();
+        let verifiers: HashSet<Address> =
+            verifiers.iter().map(|&addr| addr.clone()).collect();
+        let tx = Tx::new(vec![], None);
+        let ctx = Ctx::new(storage, write_log, &tx, VpGasMeter::new(0));
+        let vp = PoS { ctx };
+        vp.validate_tx(&[], &keys_changed, &verifiers)
+            .expect("validation shouldn't fail")
+    }
+
+    fn write<T: BorshSerialize>(write_log: &mut WriteLog, key: &Key, value: T) {
+        write_log.write(key, encode(&value)).unwrap();
+    }
+
+    #[test]
+    fn test_inflation_rate() {
+        let inflation = parameters().inflation;
+        assert_eq!(inflation.rate(5000), 1000);
+        assert_eq!(inflation.rate(0), 2000);
+        assert_eq!(inflation.rate(2500), 1500);
+        assert_eq!(inflation.rate(10_000), 0);
+    }
+
+    /// Test that the inflation is minted once per epoch and split by the
+    /// reward points.
+    #[test]
+    fn test_distribute_rewards() {
+        let validator = address::testing::established_address_1();
+        let mut storage = init_storage(&validator);
+        let mut write_log = WriteLog::default();
+        let parameters = parameters();
+        let consensus_address = pos::consensus_address(&consensus_key());
+        let votes = [CommitVote {
+            consensus_address: consensus_address.clone(),
+            power: 10,
+            signed: true,
+        }];
+        record_participation(
+            &storage,
+            &mut write_log,
+            &parameters,
+            &consensus_address,
+            &votes,
+        )
+        .unwrap();
+        assert_eq!(
+            read::<u64>(
+                &storage,
+                &write_log,
+                &pos::reward_points_key(&validator)
+            ),
+            11
+        );
+        assert_eq!(
+            distribute_rewards(&storage, &mut write_log, &parameters).unwrap(),
+            None
+        );
+        write_log.commit_block(&mut storage).unwrap();
+        storage.commit().unwrap();
+
+        storage.current_epoch = Epoch(1);
+        // a tenth of the annual rate of 10% of the supply of 200000
+        assert_eq!(
+            distribute_rewards(&storage, &mut write_log, &parameters).unwrap(),
+            Some(Amount::from(2000))
+        );
+        let xan = address::xan();
+        let treasury: Amount = read(
+            &storage,
+            &write_log,
+            &token::balance_key(&xan, &treasury_address()),
+        );
+        assert_eq!(treasury, Amount::from(200));
+        let commission: Amount =
+            read(&storage, &write_log, &pos::commission_key(&validator));
+        assert_eq!(commission, Amount::from(360));
+        let acc: u128 =
+            read(&storage, &write_log, &pos::reward_acc_key(&validator));
+        let bond: Bond =
+            read(&storage, &write_log, &pos::bond_key(&validator, &validator));
+        assert_eq!(bond.accrued(acc), Some(Amount::from(1440)));
+        let supply: Amount =
+            read(&storage, &write_log, &token::total_supply_key(&xan));
+        assert_eq!(supply, Amount::from(202_000));
+        assert!(read_block_value::<_, _, u64>(
+            &storage,
+            &write_log,
+            &pos::reward_points_key(&validator)
+        )
+        .unwrap()
+        .is_none());
+
+        // only once per epoch
+        assert_eq!(
+            distribute_rewards(&storage, &mut write_log, &parameters).unwrap(),
+            None
+        );
+    }
+
+    /// Test that the inflation is scaled by the actual duration of the
+    /// previous epoch, rather than by its minimum duration.
+    #[test]
+    fn test_distribute_rewards_by_epoch_duration() {
+        let validator = address::testing::established_address_1();
+        let mut storage = init_storage(&validator);
+        let mut write_log = WriteLog::default();
+        let parameters = parameters();
+        let consensus_address = pos::consensus_address(&consensus_key());
+        let votes = [CommitVote {
+            consensus_address: consensus_address.clone(),
+            power: 10,
+            signed: true,
+        }];
+        record_participation(
+            &storage,
+            &mut write_log,
+            &parameters,
+            &consensus_address,
+            &votes,
+        )
+        .unwrap();
+        write_log.commit_block(&mut storage).unwrap();
+        storage.commit().unwrap();
+
+        // the epoch took twice its minimum duration of a tenth of a year
+        storage.current_epoch = Epoch(1);
+        let block_time = genesis_time() + DurationSecs(SECONDS_PER_YEAR / 5);
+        storage.set_header(block_header(block_time)).unwrap();
+        // a fifth of the annual rate of 10% of the supply of 200000
+        assert_eq!(
+            distribute_rewards(&storage, &mut write_log, &parameters).unwrap(),
+            Some(Amount::from(4000))
+        );
+        let epoch_start: DateTimeUtc =
+            read(&storage, &write_log, &pos::reward_epoch_start_key());
+        assert_eq!(epoch_start, block_time);
+    }
+
+    /// Test that the rewards can only be claimed in full by their owner.
+    #[test]
+    fn test_claim_rewards() {
+        let validator = address::testing::established_address_1();
+        let other = address::testing::established_address_2();
+        let mut storage = init_storage(&validator);
+        let xan = address::xan();
+        let balance_key = token::balance_key(&xan, &pos::pos_address());
+        let bond_key = pos::bond_key(&validator, &validator);
+        let commission_key = pos::commission_key(&validator);
+        storage
+            .write(&pos::reward_acc_key(&validator), encode(&REWARD_PRECISION))
+            .unwrap();
+        storage
+            .write(&commission_key, encode(&Amount::from(50)))
+            .unwrap();
+        storage
+            .write(&balance_key, encode(&Amount::from(200_050)))
+            .unwrap();
+        let mut write_log = WriteLog::default();
+
+        let settled = Bond::new(Amount::from(100_000), REWARD_PRECISION);
+        write(&mut write_log, &bond_key, settled.clone());
+        write(&mut write_log, &balance_key, Amount::from(100_050));
+        assert!(validate(&storage, &write_log, &[&validator]));
+        assert!(!validate(&storage, &write_log, &[&other]));
+        // the claimed amount must match the balance
+        write(&mut write_log, &balance_key, Amount::from(100_000));
+        assert!(!validate(&storage, &write_log, &[&validator]));
+        write(&mut write_log, &commission_key, Amount::from(0));
+        assert!(validate(&storage, &write_log, &[&validator]));
+        write_log.drop_tx();
+
+        // the bond amount cannot be changed without the stake
+        let mut changed = settled;
+        changed.amount = Amount::from(1);
+        write(&mut write_log, &bond_key, changed);
+        write(&mut write_log, &balance_key, Amount::from(100_050));
+        assert!(!validate(&storage, &write_log, &[&validator]));
+        write_log.drop_tx();
+
+        // the commission must be claimed in full
+        write(&mut write_log, &commission_key, Amount::from(10));
+        write(&mut write_log, &balance_key, Amount::from(200_010));
+        assert!(!validate(&storage, &write_log, &[&validator]));
+        write_log.drop_tx();
+
+        // the reward points cannot be changed by a transaction
+        write(&mut write_log, &pos::reward_points_key(&validator), 100_u64);
+        assert!(!validate(&storage, &write_log, &[&validator]));
+    }
+
+    /// Test that a new validator can be registered with a self-bond and that
+    /// the consensus key cannot be changed.
+    #[test]
+    fn test_become_validator() {
+        let genesis_validator = address::testing::established_address_1();
+        let validator = address::testing::established_address_2();
+        let storage = init_storage(&genesis_validator);
+        let consensus_key: ed25519::PublicKey = keypair_2().public.into();
+        let consensus_address = pos::consensus_address(&consensus_key);
+        let balance_key =
+            token::balance_key(&address::xan(), &pos::pos_address());
+        let mut write_log = WriteLog::default();
+
+        write(
+            &mut write_log,
+            &pos::consensus_key_key(&validator),
+            consensus_key.clone(),
+        );
+        write(
+            &mut write_log,
+            &pos::consensus_address_key(&consensus_address),
+            validator.clone(),
+        );
+        write(
+            &mut write_log,
+            &pos::commission_rate_key(&validator),
+            500_u64,
+        );
+        write(
+            &mut write_log,
+            &pos::max_commission_rate_change_key(&validator),
+            100_u64,
+        );
+        write(
+            &mut write_log,
+            &pos::commission_epoch_key(&validator),
+            Epoch(0),
+        );
+        write(&mut write_log, &pos::reward_acc_key(&validator), 0_u128);
+        assert!(validate(&storage, &write_log, &[&validator]));
+        assert!(!validate(&storage, &write_log, &[&genesis_validator]));
+
+        // with a self-bond
+        write(
+            &mut write_log,
+            &pos::bond_key(&validator, &validator),
+            Bond::new(Amount::from(1000), 0),
+        );
+        write(
+            &mut write_log,
+            &pos::stake_key(&validator),
+            Amount::from(1000),
+        );
+        write(&mut write_log, &balance_key, Amount::from(101_000));
+        assert!(validate(&storage, &write_log, &[&validator]));
+        // the stake must match the bond
+        write(
+            &mut write_log,
+            &pos::stake_key(&validator),
+            Amount::from(999),
+        );
+        assert!(!validate(&storage, &write_log, &[&validator]));
+        write_log.drop_tx();
+
+        // the reward accumulator cannot be set to a different value
+        write(
+            &mut write_log,
+            &pos::consensus_key_key(&validator),
+            consensus_key.clone(),
+        );
+        write(
+            &mut write_log,
+            &pos::consensus_address_key(&consensus_address),
+            validator.clone(),
+        );
+        write(&mut write_log, &pos::reward_acc_key(&validator), 1_u128);
+        assert!(!validate(&storage, &write_log, &[&validator]));
+        write_log.drop_tx();
+
+        // the Tendermint address must match the consensus key
+        write(
+            &mut write_log,
+            &pos::consensus_key_key(&validator),
+            consensus_key.clone(),
+        );
+        assert!(!validate(&storage, &write_log, &[&validator]));
+        write_log.drop_tx();
+
+        // the consensus key of a registered validator cannot be changed
+        write(
+            &mut write_log,
+            &pos::consensus_key_key(&genesis_validator),
+            consensus_key,
+        );
+        write(
+            &mut write_log,
+            &pos::consensus_address_key(&consensus_address),
+            genesis_validator.clone(),
+        );
+        assert!(!validate(&storage, &write_log, &[&genesis_validator]));
+    }
+
+    /// Test that the bonded tokens must be deposited and that the unbonded
+    /// tokens can only be withdrawn after the unbonding epochs.
+    #[test]
+    fn test_bond_unbond_withdraw() {
+        let validator = address::testing::established_address_1();
+        let delegator = address::testing::established_address_2();
+        let mut storage = init_storage(&validator);
+        let bond_key = pos::bond_key(&delegator, &validator);
+        let unbond_key = pos::unbond_key(&delegator, &validator);
+        let stake_key = pos::stake_key(&validator);
+        let balance_key =
+            token::balance_key(&address::xan(), &pos::pos_address());
+        let mut write_log = WriteLog::default();
+
+        write(&mut write_log, &bond_key, Bond::new(Amount::from(1000), 0));
+        write(&mut write_log, &stake_key, Amount::from(101_000));
+        write(&mut write_log, &balance_key, Amount::from(101_000));
+        assert!(validate(&storage, &write_log, &[&delegator]));
+        assert!(!validate(&storage, &write_log, &[&validator]));
+        // the bonded tokens must be deposited
+        write(&mut write_log, &balance_key, Amount::from(100_500));
+        assert!(!validate(&storage, &write_log, &[&delegator]));
+        write(&mut write_log, &balance_key, Amount::from(101_000));
+        write_log.commit_tx();
+        write_log.commit_block(&mut storage).unwrap();
+
+        let withdrawable_epoch = pos::withdrawable_epoch(Epoch(0));
+        let mut unbonds = Unbonds::new();
+        unbonds.insert(withdrawable_epoch, Amount::from(600));
+        write(&mut write_log, &bond_key, Bond::new(Amount::from(400), 0));
+        write(&mut write_log, &stake_key, Amount::from(100_400));
+        write(&mut write_log, &unbond_key, unbonds.clone());
+        assert!(validate(&storage, &write_log, &[&delegator]));
+        // the unbonded amount must match the bond
+        let mut less = Unbonds::new();
+        less.insert(withdrawable_epoch, Amount::from(500));
+        write(&mut write_log, &unbond_key, less);
+        assert!(!validate(&storage, &write_log, &[&delegator]));
+        // the unbonded tokens must wait for the unbonding epochs
+        let mut early = Unbonds::new();
+        early.insert(Epoch(1), Amount::from(600));
+        write(&mut write_log, &unbond_key, early);
+        assert!(!validate(&storage, &write_log, &[&delegator]));
+        write(&mut write_log, &unbond_key, unbonds);
+        write_log.commit_tx();
+        write_log.commit_block(&mut storage).unwrap();
+
+        write(&mut write_log, &unbond_key, Unbonds::new());
+        write(&mut write_log, &balance_key, Amount::from(100_400));
+        assert!(!validate(&storage, &write_log, &[&delegator]));
+        storage.current_epoch = withdrawable_epoch;
+        assert!(validate(&storage, &write_log, &[&delegator]));
+        // the withdrawn amount must match the balance
+        write(&mut write_log, &balance_key, Amount::from(100_300));
+        assert!(!validate(&storage, &write_log, &[&delegator]));
+    }
+
+    /// Test that the commission rate can only be changed once per epoch by
+    /// at most its maximum change.
+    #[test]
+    fn test_change_commission() {
+        let validator = address::testing::established_address_1();
+        let other = address::testing::established_address_2();
+        let mut storage = init_storage(&validator);
+        let rate_key = pos::commission_rate_key(&validator);
+        let epoch_key = pos::commission_epoch_key(&validator);
+        let mut write_log = WriteLog::default();
+
+        write(&mut write_log, &rate_key, 2100_u64);
+        write(&mut write_log, &epoch_key, Epoch(0));
+        assert!(!validate(&storage, &write_log, &[&validator]));
+        storage.current_epoch = Epoch(1);
+        write(&mut write_log, &epoch_key, Epoch(1));
+        assert!(validate(&storage, &write_log, &[&validator]));
+        assert!(!validate(&storage, &write_log, &[&other]));
+        write(&mut write_log, &rate_key, 1900_u64);
+        assert!(validate(&storage, &write_log, &[&validator]));
+        write(&mut write_log, &rate_key, 2101_u64);
+        assert!(!validate(&storage, &write_log, &[&validator]));
+        write_log.drop_tx();
+
+        // the commission epoch must be updated
+        write(&mut write_log, &rate_key, 1900_u64);
+        assert!(!validate(&storage, &write_log, &[&validator]));
+    }
+
+    /// Test that a validator that hasn't signed any block in an epoch is
+    /// jailed without any voting power and that it can unjail itself in the
+    /// next epoch.
+    #[test]
+    fn test_jail_and_unjail() {
+        let validator = address::testing::established_address_1();
+        let other = address::testing::established_address_2();
+        let other_key: ed25519::PublicKey = keypair_2().public.into();
+        let other_address = pos::consensus_address(&other_key);
+        let mut storage = TestStorage::default();
+        init_genesis_storage(
+            &mut storage,
+            &[
+                GenesisValidator {
+                    address: validator.clone(),
+                    consensus_key: consensus_key(),
+                    stake: Amount::whole(100),
+                    commission_rate: 2000,
+                    max_commission_rate_change: 100,
+                },
+                GenesisValidator {
+                    address: other.clone(),
+                    consensus_key: other_key,
+                    stake: Amount::whole(200),
+                    commission_rate: 2000,
+                    max_commission_rate_change: 100,
+                },
+            ],
+            genesis_time(),
+        );
+        let parameters = parameters();
+        let mut write_log = WriteLog::default();
+        let votes = [
+            CommitVote {
+                consensus_address: pos::consensus_address(&consensus_key()),
+                power: 100,
+                signed: false,
+            },
+            CommitVote {
+                consensus_address: other_address.clone(),
+                power: 200,
+                signed: true,
+            },
+        ];
+        record_participation(
+            &storage,
+            &mut write_log,
+            &parameters,
+            &other_address,
+            &votes,
+        )
+        .unwrap();
+        assert!(update_validator_set(&storage, &mut write_log)
+            .unwrap()
+            .is_empty());
+
+        storage.current_epoch = Epoch(1);
+        distribute_rewards(&storage, &mut write_log, &parameters).unwrap();
+        let jailed_key = pos::jailed_key(&validator);
+        let jailed: Epoch = read(&storage, &write_log, &jailed_key);
+        assert_eq!(jailed, Epoch(1));
+        assert!(read_block_value::<_, _, Epoch>(
+            &storage,
+            &write_log,
+            &pos::jailed_key(&other)
+        )
+        .unwrap()
+        .is_none());
+        assert_eq!(
+            update_validator_set(&storage, &mut write_log).unwrap(),
+            vec![(consensus_key(), 0)]
+        );
+        write_log.commit_block(&mut storage).unwrap();
+
+        write_log.delete(&jailed_key).unwrap();
+        assert!(!validate(&storage, &write_log, &[&validator]));
+        storage.current_epoch = Epoch(2);
+        assert!(validate(&storage, &write_log, &[&validator]));
+        assert!(!validate(&storage, &write_log, &[&other]));
+        write_log.commit_tx();
+        assert_eq!(
+            update_validator_set(&storage, &mut write_log).unwrap(),
+            vec![(consensus_key(), 100)]
+        );
+    }
+}
@@ -0,0 +1,94 @@
+//! The MiMC hash function over the scalar field of the proof system. It's used
+//! for the note commitments, nullifiers, payment addresses and the note
+//! commitment tree, because it can be efficiently proven in a circuit.
+//!
+//! The construction follows Albrecht et al., "MiMC: Efficient Encryption and
+//! Cryptographic Hashing with Minimal Multiplicative Complexity" (ASIACRYPT
+//! 2016, <https://eprint.iacr.org/2016/492>). The cipher is MiMC-p/p over the
+//! prime field of order `l = 2^252 + 27742317777372353535851937790883648493`
+//! with the round function `x -> (x + k + c_i)^5`. The paper's cubing isn't a
+//! permutation of this field, because `3` divides `l - 1`, so the smallest
+//! exponent coprime to `l - 1`, which is `5`, is used instead. The number of
+//! rounds is the paper's bound `ceil(log_5(l)) = 109` against interpolation
+//! attacks, rounded up to `110`.
+
+use curve25519_dalek::scalar::Scalar;
+use once_cell::sync::Lazy;
+use sha2::{Digest, Sha512};
+
+/// The number of rounds of the MiMC permutation with the exponent 5, which is
+/// the smallest exponent that is a permutation of the scalar field. It must be
+/// at least `ceil(log_5(l)) = 109`.
+pub const MIMC_ROUNDS: usize = 110;
+
+/// The round constants of the MiMC permutation. The `i`-th constant is the
+/// SHA-512 hash of `"anoma_shielded_pool_mimc"` followed by `i` as a 64-bit
+/// little-endian integer, reduced modulo `l`.
+pub static ROUND_CONSTANTS: Lazy<Vec<Scalar>> = Lazy::new(|| {
+    (0..MIMC_ROUNDS as u64)
+        .map(|round| {
+            let mut hasher = Sha512::new();
+            hasher.update(b"anoma_shielded_pool_mimc");
+            hasher.update(round.to_le_bytes());
+            let mut bytes = [0u8; 64];
+            bytes.copy_from_slice(&hasher.finalize());
+            Scalar::from_bytes_mod_order_wide(&bytes)
+        })
+        .collect()
+});
+
+/// Encrypt `x` with the MiMC block cipher with the given `key`.
+pub fn encrypt(key: Scalar, x: Scalar) -> Scalar {
+    let mut x = x;
+    for constant in ROUND_CONSTANTS.iter() {
+        let t = x + key + constant;
+        let t2 = t * t;
+        x = t2 * t2 * t;
+    }
+    x + key
+}
+
+/// The two-to-one compression function built from the MiMC block cipher in
+/// the Miyaguchi-Preneel mode.
+pub fn compress(left: Scalar, right: Scalar) -> Scalar {
+    encrypt(left, right) + left + right
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compress() {
+        let a = Scalar::from(1u64);
+        let b = Scalar::from(2u64);
+        assert_eq!(compress(a, b), compress(a, b));
+        assert_ne!(compress(a, b), compress(b, a));
+        assert_ne!(compress(a, b), compress(a, a));
+    }
+
+    /// Known-answer tests. The expected values were computed with an
+    /// independent Python implementation of the same construction and are
+    /// the little-endian encodings of the field elements.
+    #[test]
+    fn test_known_answers() {
+        let hex = |scalar: Scalar| hex::encode(scalar.to_bytes());
+
+        assert_eq!(
+            hex(ROUND_CONSTANTS[0]),
+            "aae9235598b7faeeb73df69a92058ea9df675e1fe4420461cdc024fed6881308"
+        );
+        assert_eq!(
+            hex(ROUND_CONSTANTS[MIMC_ROUNDS - 1]),
+            "b507b2a710589ff828f617439048d155016ef41dc04689537824b7d2e62f2f0b"
+        );
+        assert_eq!(
+            hex(encrypt(Scalar::zero(), Scalar::zero())),
+            "6d63b5d2ce5d6cc850a4f16d8af83551b7f202e7c7c353d5ca8c4d47bcb3a00d"
+        );
+        assert_eq!(
+            hex(compress(Scalar::from(1u64), Scalar::from(2u64))),
+            "0f4d8e92e91005d4460834fb78a787af1c0c5b8e598f6adec8bf486af08dc00a"
+        );
+    }
+}
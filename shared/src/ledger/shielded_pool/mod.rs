@@ -0,0 +1,489 @@
+//! The shielded pool for private token transfers as a native validity
+//! predicate. The pool holds the transparent balances of all the shielded
+//! tokens. The shielded notes are recorded only by their commitments in the
+//! note commitment tree and the spent notes by their nullifiers.
+
+pub mod hash;
+pub mod proof;
+pub mod tree;
+
+use std::collections::{HashMap, HashSet};
+
+use borsh::BorshDeserialize;
+use thiserror::Error;
+
+use self::tree::CommitmentTree;
+use super::storage::types::{decode, encode};
+use crate::ledger::native_vp::{self, Ctx, NativeVp};
+use crate::ledger::storage::write_log::{StorageModification, WriteLog};
+use crate::ledger::storage::{self, read_block_value, Storage, StorageHasher};
+use crate::types::address::{Address, InternalAddress};
+use crate::types::key::common::SignedTxData;
+use crate::types::shielded::{
+    self, NoteCommitment, ShieldedTransfer, TransparentTransfer,
+};
+use crate::types::storage::Key;
+use crate::types::token::{self, Change};
+
+const ADDR: InternalAddress = InternalAddress::ShieldedPool;
+
+/// The gas cost of the verification of a shielded transfer's proof
+const VERIFY_PROOF_GAS: u64 = 10_000_000;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Native VP error: {0}")]
+    NativeVpError(native_vp::Error),
+}
+
+/// Shielded pool functions result
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Shielded pool VP
+pub struct ShieldedPool<'a, DB, H>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    /// Context to interact with the host structures.
+    pub ctx: Ctx<'a, DB, H>,
+}
+
+/// Initialize the shielded pool in the genesis block with an empty note
+/// commitment tree, whose root is a valid anchor.
+pub fn init_genesis_storage<DB, H>(storage: &mut Storage<DB, H>)
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    let tree = CommitmentTree::default();
+    storage
+        .write(&shielded::anchor_key(&tree.root), encode(&()))
+        .expect("Unable to write the genesis anchor");
+    storage
+        .write(&shielded::tree_key(), encode(&tree))
+        .expect("Unable to write the genesis note commitment tree");
+}
+
+/// Append the note commitments added by the transactions in the current block
+/// to the note commitment tree. The new root of the tree is added to the valid
+/// anchors. This must be called after all the block's transactions have been
+/// applied. The changes are committed into the block write log.
+pub fn update_commitment_tree<DB, H>(
+    storage: &Storage<DB, H>,
+    write_log: &mut WriteLog,
+) -> std::result::Result<(), storage::Error>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    let count: u64 = read_block_value(
+        storage,
+        write_log,
+        &shielded::commitment_count_key(),
+    )?
+    .unwrap_or_default();
+    let mut tree: CommitmentTree =
+        read_block_value(storage, write_log, &shielded::tree_key())?
+            .unwrap_or_default();
+    if tree.size == count {
+        return Ok(());
+    }
+    for index in tree.size..count {
+        let key = shielded::commitment_key(index);
+        let commitment: NoteCommitment =
+            match read_block_value(storage, write_log, &key)? {
+                Some(commitment) => commitment,
+                None => {
+                    tracing::error!("Missing a note commitment {}", index);
+                    break;
+                }
+            };
+        if !tree.append(&commitment) {
+            tracing::error!("The note commitment tree is full");
+            break;
+        }
+    }
+    write_log
+        .write(&shielded::anchor_key(&tree.root), encode(&()))
+        .expect("Writing a new anchor shouldn't fail");
+    write_log
+        .write(&shielded::tree_key(), encode(&tree))
+        .expect("Writing the note commitment tree shouldn't fail");
+    write_log.commit_tx();
+    Ok(())
+}
+
+impl<'a, DB, H> NativeVp for ShieldedPool<'a, DB, H>
+where
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+{
+    type Error = Error;
+
+    const ADDR: InternalAddress = ADDR;
+
+    /// The pool can only be changed by a valid shielded transfer, which
+    /// appends two note commitments, adds two new nullifiers, moves the
+    /// transparent amounts in or out of the pool and has a valid proof.
+    fn validate_tx(
+        &self,
+        tx_data: &[u8],
+        keys_changed: &HashSet<Key>,
+        _verifiers: &HashSet<Address>,
+    ) -> Result<bool> {
+        let transfer = match decode_transfer(tx_data) {
+            Some(transfer) => transfer,
+            None => {
+                tracing::info!("Shielded pool rejected invalid tx data");
+                return Ok(false);
+            }
+        };
+
+        let count_key = shielded::commitment_count_key();
+        let count: u64 = self.read_pre(&count_key)?.unwrap_or_default();
+        let commitment_keys = [
+            shielded::commitment_key(count),
+            shielded::commitment_key(count + 1),
+        ];
+        let nullifier_keys = [
+            shielded::nullifier_key(&transfer.nullifiers[0]),
+            shielded::nullifier_key(&transfer.nullifiers[1]),
+        ];
+        for key in keys_changed {
+            let is_valid =
+                if let Some((_, owner)) = token::is_any_balance_key(key) {
+                    // the balances are checked below
+                    owner == &Address::Internal(ADDR)
+                } else {
+                    key == &count_key
+                        || commitment_keys.contains(key)
+                        || nullifier_keys.contains(key)
+                };
+            if !is_valid {
+                tracing::info!(
+                    "Shielded pool rejected a change of key {}",
+                    key
+                );
+                return Ok(false);
+            }
+        }
+
+        let is_valid = self.is_valid_commitments(
+            &count_key,
+            count,
+            &commitment_keys,
+            &transfer.commitments,
+        )? && self.is_valid_nullifiers(&nullifier_keys)?
+            && self.has_key_pre(&shielded::anchor_key(&transfer.anchor))?
+            && self.is_valid_balances(&transfer, keys_changed)?;
+        if !is_valid {
+            tracing::info!("Shielded pool rejected an invalid transfer");
+            return Ok(false);
+        }
+
+        self.ctx.add_gas(VERIFY_PROOF_GAS)?;
+        match proof::verify(&transfer) {
+            Ok(()) => Ok(true),
+            Err(err) => {
+                tracing::info!("Shielded pool rejected a proof: {}", err);
+                Ok(false)
+            }
+        }
+    }
+}
+
+impl<'a, DB, H> ShieldedPool<'a, DB, H>
+where
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+{
+    /// The commitments must be appended at the end of the note commitment
+    /// tree.
+    fn is_valid_commitments(
+        &self,
+        count_key: &Key,
+        count: u64,
+        keys: &[Key; 2],
+        commitments: &[NoteCommitment; 2],
+    ) -> Result<bool> {
+        let post_count: Option<u64> = self.ctx.read_post_value(count_key)?;
+        if post_count != Some(count + 2) {
+            return Ok(false);
+        }
+        for (key, commitment) in keys.iter().zip(commitments) {
+            if self.has_key_pre(key)? {
+                return Ok(false);
+            }
+            let post: Option<NoteCommitment> = self.ctx.read_post_value(key)?;
+            if post.as_ref() != Some(commitment) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// The nullifiers must be distinct and not spent before.
+    fn is_valid_nullifiers(&self, keys: &[Key; 2]) -> Result<bool> {
+        if keys[0] == keys[1] {
+            return Ok(false);
+        }
+        for key in keys {
+            if self.has_key_pre(key)? || !self.ctx.has_key_post(key)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// The shielded amount must be moved from its owner into the pool and the
+    /// unshielded amount from the pool to its owner. No other balances of the
+    /// pool may change.
+    fn is_valid_balances(
+        &self,
+        transfer: &ShieldedTransfer,
+        keys_changed: &HashSet<Key>,
+    ) -> Result<bool> {
+        let pool = Address::Internal(ADDR);
+        let mut expected: HashMap<Key, Change> = HashMap::new();
+        let mut add = |owner: &Address, token: &Address, change: Change| {
+            *expected
+                .entry(token::balance_key(token, owner))
+                .or_default() += change;
+        };
+        let transparent = |transfer: &Option<TransparentTransfer>| {
+            transfer.as_ref().map(
+                |TransparentTransfer {
+                     owner,
+                     token,
+                     amount,
+                 }| {
+                    (owner.clone(), token.clone(), amount.change())
+                },
+            )
+        };
+        if let Some((owner, token, amount)) = transparent(&transfer.shield) {
+            if owner == pool {
+                return Ok(false);
+            }
+            add(&owner, &token, -amount);
+            add(&pool, &token, amount);
+        }
+        if let Some((owner, token, amount)) = transparent(&transfer.unshield) {
+            if owner == pool {
+                return Ok(false);
+            }
+            add(&pool, &token, -amount);
+            add(&owner, &token, amount);
+        }
+        for key in keys_changed {
+            if token::is_any_balance_key(key).is_some() {
+                expected.entry(key.clone()).or_default();
+            }
+        }
+        for (key, change) in expected {
+            let pre: token::Amount = self.read_pre(&key)?.unwrap_or_default();
+            let post: token::Amount =
+                self.ctx.read_post_value(&key)?.unwrap_or_default();
+            if post.change() - pre.change() != change {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Read a prior value. Unlike the VPs' prior state, which is read from
+    /// the storage, the pool's prior state includes the changes of the
+    /// transactions applied earlier in the same block, so that the
+    /// commitments and nullifiers of an earlier shielded transfer are
+    /// visible.
+    fn read_pre_bytes(&self, key: &Key) -> Result<Option<Vec<u8>>> {
+        let (log_val, gas) = self.ctx.write_log.read_pre(key);
+        self.ctx.add_gas(gas)?;
+        match log_val {
+            Some(StorageModification::Write { value }) => {
+                Ok(Some(value.clone()))
+            }
+            Some(StorageModification::Delete) => Ok(None),
+            Some(StorageModification::InitAccount { .. }) => Ok(None),
+            None => Ok(self.ctx.read_pre(key)?),
+        }
+    }
+
+    /// Check if a key is present in the prior state.
+    fn has_key_pre(&self, key: &Key) -> Result<bool> {
+        Ok(self.read_pre_bytes(key)?.is_some())
+    }
+
+    /// Read and decode a prior value. Values that cannot be decoded are
+    /// treated as missing.
+    fn read_pre<T: BorshDeserialize>(&self, key: &Key) -> Result<Option<T>> {
+        Ok(self
+            .read_pre_bytes(key)?
+            .and_then(|bytes| decode(bytes).ok()))
+    }
+}
+
+/// Decode a shielded transfer from the signed tx data
+fn decode_transfer(tx_data: &[u8]) -> Option<ShieldedTransfer> {
+    let signed = SignedTxData::try_from_slice(tx_data).ok()?;
+    ShieldedTransfer::try_from_slice(&signed.into_data()?[..]).ok()
+}
+
+impl From<native_vp::Error> for Error {
+    fn from(err: native_vp::Error) -> Self {
+        Self::NativeVpError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use borsh::BorshSerialize;
+
+    use super::proof::{build_transfer, payment_address, spending_key};
+    use super::*;
+    use crate::ledger::gas::VpGasMeter;
+    use crate::ledger::storage::testing::TestStorage;
+    use crate::proto::Tx;
+    use crate::types::address;
+    use crate::types::key::common::sign_tx;
+    use crate::types::key::ed25519::testing::keypair_1;
+    use crate::types::token::Amount;
+
+    fn validate(storage: &TestStorage, write_log: &WriteLog, tx: &Tx) -> bool {
+        let keys_changed = write_log
+            .verifiers_changed_keys(&HashSet::new())
+            .remove(&shielded::pool_address())
+            .unwrap_or_default();
+        let ctx = Ctx::new(storage, write_log, tx, VpGasMeter::new(0));
+        let pool = ShieldedPool { ctx };
+        let tx_data = tx.data.as_ref().expect("the tx must have data");
+        pool.validate_tx(tx_data, &keys_changed, &HashSet::new())
+            .expect("validation shouldn't fail")
+    }
+
+    fn write<T: BorshSerialize>(write_log: &mut WriteLog, key: &Key, value: T) {
+        write_log.write(key, encode(&value)).unwrap();
+    }
+
+    /// Apply the changes of a shielded transfer in the same way as the
+    /// `tx_shielded_transfer` wasm, with the given change of the pool's
+    /// balance.
+    fn apply(
+        storage: &TestStorage,
+        write_log: &mut WriteLog,
+        transfer: &ShieldedTransfer,
+        pool_balance: u64,
+    ) {
+        let shield = transfer.shield.as_ref().unwrap();
+        let balance_key = token::balance_key(&shield.token, &shield.owner);
+        let balance: token::Amount =
+            read_block_value(storage, write_log, &balance_key)
+                .unwrap()
+                .unwrap_or_default();
+        write(
+            write_log,
+            &balance_key,
+            token::Amount::from(u64::from(balance) - u64::from(shield.amount)),
+        );
+        let pool_key =
+            token::balance_key(&shield.token, &shielded::pool_address());
+        write(write_log, &pool_key, token::Amount::from(pool_balance));
+
+        let count_key = shielded::commitment_count_key();
+        let count: u64 = read_block_value(storage, write_log, &count_key)
+            .unwrap()
+            .unwrap_or_default();
+        for (index, commitment) in transfer.commitments.iter().enumerate() {
+            let key = shielded::commitment_key(count + index as u64);
+            write(write_log, &key, *commitment);
+        }
+        write(write_log, &count_key, count + 2);
+        for nullifier in &transfer.nullifiers {
+            write(write_log, &shielded::nullifier_key(nullifier), ());
+        }
+    }
+
+    /// Test a shield transfer and that its changes must match the transfer.
+    /// Proving is slow, so this test reuses a single proof.
+    #[test]
+    fn test_shield() {
+        let mut storage = TestStorage::default();
+        init_genesis_storage(&mut storage);
+        let token = address::xan();
+        let source = address::testing::established_address_1();
+        storage
+            .write(
+                &token::balance_key(&token, &source),
+                encode(&Amount::from(1000)),
+            )
+            .unwrap();
+
+        let owner = payment_address(&spending_key([1; 32]));
+        let (transfer, _notes) = build_transfer(
+            &token,
+            &[],
+            vec![],
+            vec![(owner, Amount::from(100))],
+            Some(TransparentTransfer {
+                owner: source,
+                token,
+                amount: Amount::from(100),
+            }),
+            None,
+            [2; 32],
+        )
+        .unwrap();
+        let tx = sign_tx(
+            &keypair_1(),
+            Tx::new(vec![], Some(transfer.try_to_vec().unwrap())),
+        );
+
+        // the pool must receive the shielded amount
+        let mut write_log = WriteLog::default();
+        apply(&storage, &mut write_log, &transfer, 99);
+        assert!(!validate(&storage, &write_log, &tx));
+        write_log.drop_tx();
+
+        // the note commitment tree can only be changed by the protocol
+        apply(&storage, &mut write_log, &transfer, 100);
+        write(&mut write_log, &shielded::tree_key(), ());
+        assert!(!validate(&storage, &write_log, &tx));
+        write_log.drop_tx();
+
+        apply(&storage, &mut write_log, &transfer, 100);
+        assert!(validate(&storage, &write_log, &tx));
+        write_log.commit_tx();
+
+        // the nullifiers cannot be reused in the same block
+        apply(&storage, &mut write_log, &transfer, 200);
+        assert!(!validate(&storage, &write_log, &tx));
+        write_log.drop_tx();
+
+        // the new commitments are added to the tree at the end of the block
+        update_commitment_tree(&storage, &mut write_log).unwrap();
+        let tree: CommitmentTree =
+            read_block_value(&storage, &write_log, &shielded::tree_key())
+                .unwrap()
+                .unwrap();
+        assert_eq!(tree.size, 2);
+        assert_eq!(tree.root, proof::tree_root(&transfer.commitments));
+        let (anchor, _gas) =
+            write_log.read_pre(&shielded::anchor_key(&tree.root));
+        assert!(anchor.is_some());
+    }
+
+    /// Test that the pool rejects changes that are not made by a shielded
+    /// transfer.
+    #[test]
+    fn test_transfer_into_pool() {
+        let storage = TestStorage::default();
+        let mut write_log = WriteLog::default();
+        let pool_key =
+            token::balance_key(&address::xan(), &shielded::pool_address());
+        write(&mut write_log, &pool_key, Amount::from(100));
+        let tx = sign_tx(&keypair_1(), Tx::new(vec![], Some(vec![])));
+        assert!(!validate(&storage, &write_log, &tx));
+    }
+}
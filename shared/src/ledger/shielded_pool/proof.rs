@@ -0,0 +1,859 @@
+//! The zero-knowledge proofs of the shielded transfers, using R1CS
+//! Bulletproofs that need no trusted setup.
+//!
+//! A note is committed to as `C(C(a * 2^64 + v, owner), C(rho, r))`, where `C`
+//! is the MiMC compression function, `a` is the token's asset identifier, `v`
+//! the amount, `owner` the payment address `C(sk, 0)` of the spending key `sk`,
+//! `rho` the note's unique value and `r` its randomness. The nullifier of a
+//! note is `C(sk, rho)`.
+//!
+//! Every transfer spends two notes and creates two notes. The proof shows that
+//! for each spent note, the prover knows its spending key, the note is in the
+//! note commitment tree with the anchor root (unless its amount is zero) and
+//! the public nullifier is derived from it. For each created note, it shows
+//! that the public commitment is derived from the note, whose `rho` is derived
+//! from the transfer's nullifiers to make it unique. All the amounts are
+//! 64-bit and the asset identifier is 128-bit, so that they are uniquely packed
+//! into a field element. All the notes are of the same token and the spent
+//! amounts plus the shielded amount are equal to the created amounts plus the
+//! unshielded amount. When any amount is shielded or unshielded, the notes'
+//! token has to be the transparent token.
+//!
+//! The R1CS proofs use the `yoloproofs` feature of the `bulletproofs` crate,
+//! which its authors haven't audited and whose API may change. The pool is
+//! only built with the `shielded-pool` feature of this crate.
+
+use borsh::BorshSerialize;
+use bulletproofs::r1cs::{
+    ConstraintSystem, LinearCombination, Prover, R1CSError, R1CSProof,
+    Variable, Verifier,
+};
+use bulletproofs::{BulletproofGens, PedersenGens};
+use curve25519_dalek::scalar::Scalar;
+use merlin::Transcript;
+use once_cell::sync::Lazy;
+use sha2::{Digest, Sha256, Sha512};
+use thiserror::Error;
+
+use super::hash::{compress, ROUND_CONSTANTS};
+use super::tree::{merkle_path, merkle_root, MerklePath, TREE_DEPTH};
+use crate::types::address::Address;
+use crate::types::shielded::{
+    MerkleRoot, Note, NoteCommitment, Nullifier, PaymentAddress,
+    ShieldedTransfer, SpendingKey, TransparentTransfer,
+};
+use crate::types::token::Amount;
+
+/// The number of generators, which must be a power of two larger than the
+/// number of multipliers in the circuit
+const GENERATORS_CAPACITY: usize = 1 << 15;
+
+/// The number of bits of the amounts
+const VALUE_BITS: usize = 64;
+
+/// The number of bits of the asset identifiers
+const ASSET_BITS: usize = 128;
+
+static PEDERSEN_GENS: Lazy<PedersenGens> = Lazy::new(PedersenGens::default);
+
+static BULLETPROOF_GENS: Lazy<BulletproofGens> =
+    Lazy::new(|| BulletproofGens::new(GENERATORS_CAPACITY, 1));
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("A shielded transfer can spend at most 2 notes")]
+    TooManySpends,
+    #[error("A shielded transfer can create at most 2 notes")]
+    TooManyOutputs,
+    #[error("All the notes and transparent amounts must be of token {0}")]
+    MixedTokens(Address),
+    #[error("The shielded and unshielded tokens must be the same")]
+    TransparentTokensMismatch,
+    #[error(
+        "The spent and shielded amount {0} is not equal to the created and \
+         unshielded amount {1}"
+    )]
+    Unbalanced(u128, u128),
+    #[error("The spending key doesn't own the spent note")]
+    NotOwner,
+    #[error("The spent note is not in the note commitment tree")]
+    NoteNotFound,
+    #[error("Invalid encoding of a field element")]
+    InvalidFieldElement,
+    #[error("Proof error: {0}")]
+    Proof(R1CSError),
+}
+
+/// Proof functions result
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// A note to be spent in a shielded transfer
+pub struct Spend {
+    /// The spent note
+    pub note: Note,
+    /// The spending key of the note's owner
+    pub spending_key: SpendingKey,
+}
+
+/// The public inputs of the circuit
+struct Statement {
+    anchor: Scalar,
+    nullifiers: [Scalar; 2],
+    commitments: [Scalar; 2],
+    asset: Scalar,
+    public_input: u64,
+    public_output: u64,
+    /// The encoded transparent parts of the transfer, which are bound to the
+    /// proof
+    transparent: Vec<u8>,
+}
+
+/// The private inputs of the circuit
+struct Witness {
+    asset: Scalar,
+    inputs: [InputWitness; 2],
+    outputs: [OutputWitness; 2],
+}
+
+struct InputWitness {
+    spending_key: Scalar,
+    value: u64,
+    rho: Scalar,
+    randomness: Scalar,
+    path: MerklePath,
+}
+
+struct OutputWitness {
+    owner: Scalar,
+    value: u64,
+    randomness: Scalar,
+}
+
+/// Derive a new spending key from a random seed.
+pub fn spending_key(seed: [u8; 32]) -> SpendingKey {
+    SpendingKey(derive_scalar(&seed, b"spending_key", 0).to_bytes())
+}
+
+/// Derive the payment address of a spending key.
+pub fn payment_address(key: &SpendingKey) -> PaymentAddress {
+    let key = Scalar::from_bytes_mod_order(key.0);
+    PaymentAddress(compress(key, Scalar::zero()).to_bytes())
+}
+
+/// Derive the asset identifier of a token. It's a 128-bit hash of the token's
+/// address, so it can be packed with a 64-bit amount into a field element.
+pub fn asset_id(token: &Address) -> Scalar {
+    let bytes = token
+        .try_to_vec()
+        .expect("Encoding an address shouldn't fail");
+    let hash = Sha256::digest(&bytes);
+    let mut id = [0u8; 16];
+    id.copy_from_slice(&hash[..16]);
+    Scalar::from(u128::from_le_bytes(id))
+}
+
+/// Compute the commitment of a note.
+pub fn note_commitment(note: &Note) -> NoteCommitment {
+    let commitment = commit(
+        asset_id(&note.token),
+        u64::from(note.amount),
+        Scalar::from_bytes_mod_order(note.owner.0),
+        Scalar::from_bytes_mod_order(note.rho),
+        Scalar::from_bytes_mod_order(note.randomness),
+    );
+    NoteCommitment(commitment.to_bytes())
+}
+
+/// Compute the nullifier of a note with its owner's spending key.
+pub fn nullifier(note: &Note, key: &SpendingKey) -> Nullifier {
+    let nullifier = compress(
+        Scalar::from_bytes_mod_order(key.0),
+        Scalar::from_bytes_mod_order(note.rho),
+    );
+    Nullifier(nullifier.to_bytes())
+}
+
+/// Compute the root of the note commitment tree with the given commitments.
+pub fn tree_root(commitments: &[NoteCommitment]) -> MerkleRoot {
+    let commitments: Vec<Scalar> = commitments
+        .iter()
+        .map(|commitment| Scalar::from_bytes_mod_order(commitment.0))
+        .collect();
+    MerkleRoot(merkle_root(&commitments).to_bytes())
+}
+
+/// Build a shielded transfer of the given token with its proof. The spent
+/// notes are looked up in the given commitments of the note commitment tree,
+/// whose root is used as the anchor. Missing spends and outputs are replaced
+/// with dummy notes with a zero amount. The randomness of the notes is derived
+/// from the given seed, which must be random. Returns the transfer and the
+/// created notes for the given outputs, which have to be sent to their owners.
+pub fn build_transfer(
+    token: &Address,
+    tree: &[NoteCommitment],
+    spends: Vec<Spend>,
+    outputs: Vec<(PaymentAddress, Amount)>,
+    shield: Option<TransparentTransfer>,
+    unshield: Option<TransparentTransfer>,
+    seed: [u8; 32],
+) -> Result<(ShieldedTransfer, Vec<Note>)> {
+    if spends.len() > 2 {
+        return Err(Error::TooManySpends);
+    }
+    if outputs.len() > 2 {
+        return Err(Error::TooManyOutputs);
+    }
+    let is_other_token = |other: &Address| other != token;
+    if spends.iter().any(|spend| is_other_token(&spend.note.token))
+        || shield.iter().any(|shield| is_other_token(&shield.token))
+        || unshield
+            .iter()
+            .any(|unshield| is_other_token(&unshield.token))
+    {
+        return Err(Error::MixedTokens(token.clone()));
+    }
+    let spent: u128 = spends
+        .iter()
+        .map(|spend| spend.note.amount)
+        .chain(shield.iter().map(|shield| shield.amount))
+        .map(|amount| u64::from(amount) as u128)
+        .sum();
+    let created: u128 = outputs
+        .iter()
+        .map(|(_, amount)| *amount)
+        .chain(unshield.iter().map(|unshield| unshield.amount))
+        .map(|amount| u64::from(amount) as u128)
+        .sum();
+    if spent != created {
+        return Err(Error::Unbalanced(spent, created));
+    }
+
+    let commitments: Vec<Scalar> = tree
+        .iter()
+        .map(|commitment| Scalar::from_bytes_mod_order(commitment.0))
+        .collect();
+    let mut inputs = Vec::with_capacity(2);
+    let mut nullifiers = Vec::with_capacity(2);
+    for spend in &spends {
+        if payment_address(&spend.spending_key) != spend.note.owner {
+            return Err(Error::NotOwner);
+        }
+        let commitment = note_commitment(&spend.note);
+        let position = tree
+            .iter()
+            .position(|other| other == &commitment)
+            .ok_or(Error::NoteNotFound)?;
+        let path = merkle_path(&commitments, position as u64)
+            .ok_or(Error::NoteNotFound)?;
+        let spending_key = Scalar::from_bytes_mod_order(spend.spending_key.0);
+        let rho = Scalar::from_bytes_mod_order(spend.note.rho);
+        nullifiers.push(compress(spending_key, rho));
+        inputs.push(InputWitness {
+            spending_key,
+            value: u64::from(spend.note.amount),
+            rho,
+            randomness: Scalar::from_bytes_mod_order(spend.note.randomness),
+            path,
+        });
+    }
+    for index in inputs.len()..2 {
+        let spending_key = derive_scalar(&seed, b"dummy_spending_key", index);
+        let rho = derive_scalar(&seed, b"dummy_rho", index);
+        nullifiers.push(compress(spending_key, rho));
+        inputs.push(InputWitness {
+            spending_key,
+            value: 0,
+            rho,
+            randomness: Scalar::zero(),
+            path: vec![(Scalar::zero(), false); TREE_DEPTH],
+        });
+    }
+
+    let asset = asset_id(token);
+    let rhos = output_rhos(&[nullifiers[0], nullifiers[1]]);
+    let mut notes = Vec::with_capacity(2);
+    for (index, rho) in rhos.iter().enumerate() {
+        let (owner, amount) = match outputs.get(index) {
+            Some((owner, amount)) => (*owner, *amount),
+            None => {
+                let key = derive_scalar(&seed, b"dummy_owner", index);
+                let owner = compress(key, Scalar::zero());
+                (PaymentAddress(owner.to_bytes()), Amount::default())
+            }
+        };
+        notes.push(Note {
+            token: token.clone(),
+            amount,
+            owner,
+            rho: rho.to_bytes(),
+            randomness: derive_scalar(&seed, b"randomness", index).to_bytes(),
+        });
+    }
+
+    let mut transfer = ShieldedTransfer {
+        shield,
+        unshield,
+        anchor: MerkleRoot(merkle_root(&commitments).to_bytes()),
+        nullifiers: [
+            Nullifier(nullifiers[0].to_bytes()),
+            Nullifier(nullifiers[1].to_bytes()),
+        ],
+        commitments: [note_commitment(&notes[0]), note_commitment(&notes[1])],
+        proof: vec![],
+    };
+    let output_witness = |note: &Note| OutputWitness {
+        owner: Scalar::from_bytes_mod_order(note.owner.0),
+        value: u64::from(note.amount),
+        randomness: Scalar::from_bytes_mod_order(note.randomness),
+    };
+    let mut inputs = inputs.into_iter();
+    let witness = Witness {
+        asset,
+        inputs: [
+            inputs.next().expect("There are two inputs"),
+            inputs.next().expect("There are two inputs"),
+        ],
+        outputs: [output_witness(&notes[0]), output_witness(&notes[1])],
+    };
+    let statement = Statement::new(&transfer)?;
+    transfer.proof = prove(&statement, &witness)?;
+
+    notes.truncate(outputs.len());
+    Ok((transfer, notes))
+}
+
+/// Verify the proof of a shielded transfer.
+pub fn verify(transfer: &ShieldedTransfer) -> Result<()> {
+    let statement = Statement::new(transfer)?;
+    let proof = R1CSProof::from_bytes(&transfer.proof).map_err(Error::Proof)?;
+    let mut verifier = Verifier::new(statement.transcript());
+    circuit(&mut verifier, &statement, None).map_err(Error::Proof)?;
+    verifier
+        .verify(&proof, &PEDERSEN_GENS, &BULLETPROOF_GENS)
+        .map_err(Error::Proof)
+}
+
+fn prove(statement: &Statement, witness: &Witness) -> Result<Vec<u8>> {
+    let mut prover = Prover::new(&PEDERSEN_GENS, statement.transcript());
+    circuit(&mut prover, statement, Some(witness)).map_err(Error::Proof)?;
+    let proof = prover.prove(&BULLETPROOF_GENS).map_err(Error::Proof)?;
+    Ok(proof.to_bytes())
+}
+
+impl Statement {
+    /// The public inputs of a shielded transfer. The field elements must be
+    /// canonically encoded, so that there is only one encoding of each
+    /// nullifier.
+    fn new(transfer: &ShieldedTransfer) -> Result<Self> {
+        let element = |bytes: [u8; 32]| {
+            Scalar::from_canonical_bytes(bytes)
+                .ok_or(Error::InvalidFieldElement)
+        };
+        let asset = match (&transfer.shield, &transfer.unshield) {
+            (None, None) => Scalar::zero(),
+            _ => match transfer.transparent_token() {
+                Some(token) => asset_id(token),
+                None => return Err(Error::TransparentTokensMismatch),
+            },
+        };
+        let amount = |transparent: &Option<TransparentTransfer>| {
+            transparent
+                .as_ref()
+                .map(|transparent| u64::from(transparent.amount))
+                .unwrap_or_default()
+        };
+        let mut transparent = transfer
+            .shield
+            .try_to_vec()
+            .expect("Encoding a transparent transfer shouldn't fail");
+        transparent.extend(
+            transfer
+                .unshield
+                .try_to_vec()
+                .expect("Encoding a transparent transfer shouldn't fail"),
+        );
+        Ok(Self {
+            anchor: element(transfer.anchor.0)?,
+            nullifiers: [
+                element(transfer.nullifiers[0].0)?,
+                element(transfer.nullifiers[1].0)?,
+            ],
+            commitments: [
+                element(transfer.commitments[0].0)?,
+                element(transfer.commitments[1].0)?,
+            ],
+            asset,
+            public_input: amount(&transfer.shield),
+            public_output: amount(&transfer.unshield),
+            transparent,
+        })
+    }
+
+    /// A transcript that binds all the public inputs to the proof
+    fn transcript(&self) -> Transcript {
+        let mut transcript = Transcript::new(b"anoma_shielded_transfer");
+        transcript.append_message(b"anchor", self.anchor.as_bytes());
+        for nullifier in &self.nullifiers {
+            transcript.append_message(b"nullifier", nullifier.as_bytes());
+        }
+        for commitment in &self.commitments {
+            transcript.append_message(b"commitment", commitment.as_bytes());
+        }
+        transcript.append_message(b"asset", self.asset.as_bytes());
+        transcript.append_u64(b"public_input", self.public_input);
+        transcript.append_u64(b"public_output", self.public_output);
+        transcript.append_message(b"transparent", &self.transparent);
+        transcript
+    }
+}
+
+/// The circuit of a shielded transfer. The prover has to provide the witness.
+fn circuit<CS: ConstraintSystem>(
+    cs: &mut CS,
+    statement: &Statement,
+    witness: Option<&Witness>,
+) -> std::result::Result<(), R1CSError> {
+    let asset = cs.allocate(witness.map(|witness| witness.asset))?;
+    range_gadget(
+        cs,
+        asset.into(),
+        witness.map(|witness| witness.asset),
+        ASSET_BITS,
+    )?;
+
+    let mut input_values = Vec::with_capacity(2);
+    for (index, nullifier) in statement.nullifiers.iter().enumerate() {
+        let input = witness.map(|witness| &witness.inputs[index]);
+        let spending_key =
+            cs.allocate(input.map(|input| input.spending_key))?;
+        let value =
+            cs.allocate(input.map(|input| Scalar::from(input.value)))?;
+        let rho = cs.allocate(input.map(|input| input.rho))?;
+        let randomness = cs.allocate(input.map(|input| input.randomness))?;
+        range_gadget(
+            cs,
+            value.into(),
+            input.map(|input| Scalar::from(input.value)),
+            VALUE_BITS,
+        )?;
+
+        let owner =
+            compress_gadget(cs, spending_key.into(), Scalar::zero().into());
+        let commitment = commitment_gadget(
+            cs,
+            asset,
+            value,
+            owner,
+            rho.into(),
+            randomness.into(),
+        );
+        let computed_nullifier =
+            compress_gadget(cs, spending_key.into(), rho.into());
+        cs.constrain(computed_nullifier - *nullifier);
+
+        let mut node = commitment;
+        for height in 0..TREE_DEPTH {
+            let step = input.map(|input| input.path[height]);
+            let sibling = cs.allocate(step.map(|(sibling, _)| sibling))?;
+            let is_right =
+                allocate_bit(cs, step.map(|(_, is_right)| is_right))?;
+            // swap the node with its sibling if it's the right child
+            let (_, _, delta) =
+                cs.multiply(is_right.into(), sibling - node.clone());
+            let left = node + delta;
+            let right = sibling - delta;
+            node = compress_gadget(cs, left, right);
+        }
+        // the anchor is only checked for the notes with a non-zero amount
+        let (_, _, anchor_check) =
+            cs.multiply(node - statement.anchor, value.into());
+        cs.constrain(anchor_check.into());
+        input_values.push(value);
+    }
+
+    let rhos = output_rhos(&statement.nullifiers);
+    let mut output_values = Vec::with_capacity(2);
+    for (index, commitment) in statement.commitments.iter().enumerate() {
+        let output = witness.map(|witness| &witness.outputs[index]);
+        let owner = cs.allocate(output.map(|output| output.owner))?;
+        let value =
+            cs.allocate(output.map(|output| Scalar::from(output.value)))?;
+        let randomness = cs.allocate(output.map(|output| output.randomness))?;
+        range_gadget(
+            cs,
+            value.into(),
+            output.map(|output| Scalar::from(output.value)),
+            VALUE_BITS,
+        )?;
+
+        let computed_commitment = commitment_gadget(
+            cs,
+            asset,
+            value,
+            owner.into(),
+            rhos[index].into(),
+            randomness.into(),
+        );
+        cs.constrain(computed_commitment - *commitment);
+        output_values.push(value);
+    }
+
+    cs.constrain(
+        input_values[0]
+            + input_values[1]
+            + Scalar::from(statement.public_input)
+            - output_values[0]
+            - output_values[1]
+            - Scalar::from(statement.public_output),
+    );
+    if statement.public_input != 0 || statement.public_output != 0 {
+        cs.constrain(asset - statement.asset);
+    }
+    Ok(())
+}
+
+/// The MiMC compression function in the circuit
+fn compress_gadget<CS: ConstraintSystem>(
+    cs: &mut CS,
+    left: LinearCombination,
+    right: LinearCombination,
+) -> LinearCombination {
+    // reduce the inputs to single variables to keep the constraints small
+    let (left, _, _) = cs.multiply(left, Scalar::one().into());
+    let (right, _, _) = cs.multiply(right, Scalar::one().into());
+    let mut x: LinearCombination = right.into();
+    for constant in ROUND_CONSTANTS.iter() {
+        let t = x + left + *constant;
+        let (_, _, t2) = cs.multiply(t.clone(), t.clone());
+        let (_, _, t4) = cs.multiply(t2.into(), t2.into());
+        let (_, _, t5) = cs.multiply(t4.into(), t);
+        x = t5.into();
+    }
+    x + left + left + right
+}
+
+/// The note commitment in the circuit
+fn commitment_gadget<CS: ConstraintSystem>(
+    cs: &mut CS,
+    asset: Variable,
+    value: Variable,
+    owner: LinearCombination,
+    rho: LinearCombination,
+    randomness: LinearCombination,
+) -> LinearCombination {
+    let packed = asset * two_pow_64() + value;
+    let left = compress_gadget(cs, packed, owner);
+    let right = compress_gadget(cs, rho, randomness);
+    compress_gadget(cs, left, right)
+}
+
+/// Allocate a variable constrained to be either 0 or 1
+fn allocate_bit<CS: ConstraintSystem>(
+    cs: &mut CS,
+    bit: Option<bool>,
+) -> std::result::Result<Variable, R1CSError> {
+    let (bit, not_bit, product) = cs.allocate_multiplier(bit.map(|bit| {
+        let bit = if bit { Scalar::one() } else { Scalar::zero() };
+        (bit, Scalar::one() - bit)
+    }))?;
+    cs.constrain(product.into());
+    cs.constrain(bit + not_bit - Scalar::one());
+    Ok(bit)
+}
+
+/// Constrain the value to the given number of bits
+fn range_gadget<CS: ConstraintSystem>(
+    cs: &mut CS,
+    value: LinearCombination,
+    assignment: Option<Scalar>,
+    bits: usize,
+) -> std::result::Result<(), R1CSError> {
+    let mut sum = LinearCombination::default();
+    let mut exponent = Scalar::one();
+    for index in 0..bits {
+        let bit = allocate_bit(
+            cs,
+            assignment.map(|value| {
+                (value.as_bytes()[index / 8] >> (index % 8)) & 1 == 1
+            }),
+        )?;
+        sum = sum + bit * exponent;
+        exponent = exponent + exponent;
+    }
+    cs.constrain(value - sum);
+    Ok(())
+}
+
+/// The note commitment, which is computed in the same way as in the
+/// [`commitment_gadget`]
+fn commit(
+    asset: Scalar,
+    value: u64,
+    owner: Scalar,
+    rho: Scalar,
+    randomness: Scalar,
+) -> Scalar {
+    let packed = asset * two_pow_64() + Scalar::from(value);
+    compress(compress(packed, owner), compress(rho, randomness))
+}
+
+/// The unique values of the created notes, derived from the nullifiers
+fn output_rhos(nullifiers: &[Scalar; 2]) -> [Scalar; 2] {
+    [
+        compress(nullifiers[0], nullifiers[1]),
+        compress(nullifiers[1], nullifiers[0]),
+    ]
+}
+
+fn two_pow_64() -> Scalar {
+    Scalar::from(u64::MAX) + Scalar::one()
+}
+
+/// Derive a field element from a seed with a label and an index
+fn derive_scalar(seed: &[u8; 32], label: &[u8], index: usize) -> Scalar {
+    let mut hasher = Sha512::new();
+    hasher.update(b"anoma_shielded_pool_seed");
+    hasher.update(seed);
+    hasher.update(label);
+    hasher.update((index as u64).to_le_bytes());
+    let mut bytes = [0u8; 64];
+    bytes.copy_from_slice(&hasher.finalize());
+    Scalar::from_bytes_mod_order_wide(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::address;
+
+    /// Test that a valid proof of a transfer verifies and that any change of
+    /// the transfer invalidates it. Proving is slow, so this test reuses a
+    /// single proof.
+    #[test]
+    fn test_transfer_proof() {
+        let token = address::xan();
+        let key = spending_key([1; 32]);
+        let owner = payment_address(&key);
+        let recipient = payment_address(&spending_key([2; 32]));
+        let note = Note {
+            token: token.clone(),
+            amount: Amount::from(100),
+            owner,
+            rho: [3; 32],
+            randomness: [4; 32],
+        };
+        let other_note = Note {
+            owner: recipient,
+            ..note.clone()
+        };
+
+        // transfer 60 to the recipient, 30 back to the owner and unshield 10
+        let tree = vec![note_commitment(&other_note), note_commitment(&note)];
+        let target = TransparentTransfer {
+            owner: address::testing::established_address_2(),
+            token: token.clone(),
+            amount: Amount::from(10),
+        };
+        let (transfer, notes) = build_transfer(
+            &token,
+            &tree,
+            vec![Spend {
+                note: note.clone(),
+                spending_key: key.clone(),
+            }],
+            vec![(recipient, Amount::from(60)), (owner, Amount::from(30))],
+            None,
+            Some(target.clone()),
+            [4; 32],
+        )
+        .unwrap();
+        assert_eq!(notes.len(), 2);
+        assert_eq!(transfer.anchor, tree_root(&tree));
+        assert_eq!(transfer.nullifiers[0], nullifier(&note, &key));
+        verify(&transfer).unwrap();
+
+        // the unshielded amount cannot be changed
+        let mut invalid = transfer.clone();
+        invalid.unshield = Some(TransparentTransfer {
+            amount: Amount::from(11),
+            ..target.clone()
+        });
+        assert!(verify(&invalid).is_err());
+
+        // the unshielded tokens cannot be redirected
+        let mut invalid = transfer.clone();
+        invalid.unshield = Some(TransparentTransfer {
+            owner: address::testing::established_address_1(),
+            ..target
+        });
+        assert!(verify(&invalid).is_err());
+
+        // the anchor, nullifiers and commitments cannot be changed
+        let mut invalid = transfer.clone();
+        invalid.anchor = tree_root(&tree[..1]);
+        assert!(verify(&invalid).is_err());
+        let mut invalid = transfer.clone();
+        invalid.nullifiers.swap(0, 1);
+        assert!(verify(&invalid).is_err());
+        let mut invalid = transfer;
+        invalid.commitments[1] = note_commitment(&note);
+        assert!(verify(&invalid).is_err());
+    }
+
+    /// Test that the asset identifier cannot be forged to create notes out of
+    /// nothing. Without its range check, a zero-value output with the asset
+    /// `asset_id(T) + k * 2^-64` would have the same commitment as a note of
+    /// `k` tokens `T`.
+    #[test]
+    fn test_forged_asset() {
+        let token = address::xan();
+        let owner = payment_address(&spending_key([1; 32]));
+        let amount = 1_000_000;
+        let seed = [5; 32];
+
+        // two dummy inputs with a zero amount, which are not checked against
+        // the anchor
+        let inputs: Vec<InputWitness> = (0..2)
+            .map(|index| InputWitness {
+                spending_key: derive_scalar(
+                    &seed,
+                    b"dummy_spending_key",
+                    index,
+                ),
+                value: 0,
+                rho: derive_scalar(&seed, b"dummy_rho", index),
+                randomness: Scalar::zero(),
+                path: vec![(Scalar::zero(), false); TREE_DEPTH],
+            })
+            .collect();
+        let nullifiers = [
+            compress(inputs[0].spending_key, inputs[0].rho),
+            compress(inputs[1].spending_key, inputs[1].rho),
+        ];
+        let rhos = output_rhos(&nullifiers);
+        let forged_notes: Vec<Note> = rhos
+            .iter()
+            .map(|rho| Note {
+                token: token.clone(),
+                amount: Amount::from(amount),
+                owner,
+                rho: rho.to_bytes(),
+                randomness: [6; 32],
+            })
+            .collect();
+
+        // the zero-value outputs with the forged asset open to the notes
+        let forged_asset =
+            asset_id(&token) + Scalar::from(amount) * two_pow_64().invert();
+        let forged_commitment = |note: &Note| {
+            let commitment = commit(
+                forged_asset,
+                0,
+                Scalar::from_bytes_mod_order(note.owner.0),
+                Scalar::from_bytes_mod_order(note.rho),
+                Scalar::from_bytes_mod_order(note.randomness),
+            );
+            NoteCommitment(commitment.to_bytes())
+        };
+        for note in &forged_notes {
+            assert_eq!(forged_commitment(note), note_commitment(note));
+        }
+
+        let mut transfer = ShieldedTransfer {
+            shield: None,
+            unshield: None,
+            anchor: tree_root(&[]),
+            nullifiers: [
+                Nullifier(nullifiers[0].to_bytes()),
+                Nullifier(nullifiers[1].to_bytes()),
+            ],
+            commitments: [
+                note_commitment(&forged_notes[0]),
+                note_commitment(&forged_notes[1]),
+            ],
+            proof: vec![],
+        };
+        let output_witness = || OutputWitness {
+            owner: Scalar::from_bytes_mod_order(owner.0),
+            value: 0,
+            randomness: Scalar::from_bytes_mod_order([6; 32]),
+        };
+        let mut inputs = inputs.into_iter();
+        let witness = Witness {
+            asset: forged_asset,
+            inputs: [inputs.next().unwrap(), inputs.next().unwrap()],
+            outputs: [output_witness(), output_witness()],
+        };
+        let statement = Statement::new(&transfer).unwrap();
+        transfer.proof = prove(&statement, &witness).unwrap();
+        assert!(verify(&transfer).is_err());
+    }
+
+    #[test]
+    fn test_invalid_transfer() {
+        let token = address::xan();
+        let key = spending_key([1; 32]);
+        let owner = payment_address(&key);
+        let note = Note {
+            token: token.clone(),
+            amount: Amount::from(100),
+            owner,
+            rho: [0; 32],
+            randomness: [0; 32],
+        };
+        let tree = vec![note_commitment(&note)];
+        let spend = |spending_key: SpendingKey| Spend {
+            note: note.clone(),
+            spending_key,
+        };
+
+        // unbalanced
+        let result = build_transfer(
+            &token,
+            &tree,
+            vec![spend(key.clone())],
+            vec![(owner, Amount::from(101))],
+            None,
+            None,
+            [0; 32],
+        );
+        assert!(matches!(result, Err(Error::Unbalanced(100, 101))));
+
+        // spent by another key
+        let result = build_transfer(
+            &token,
+            &tree,
+            vec![spend(spending_key([2; 32]))],
+            vec![(owner, Amount::from(100))],
+            None,
+            None,
+            [0; 32],
+        );
+        assert!(matches!(result, Err(Error::NotOwner)));
+
+        // not in the tree
+        let result = build_transfer(
+            &token,
+            &[],
+            vec![spend(key.clone())],
+            vec![(owner, Amount::from(100))],
+            None,
+            None,
+            [0; 32],
+        );
+        assert!(matches!(result, Err(Error::NoteNotFound)));
+
+        // another token
+        let result = build_transfer(
+            &address::btc(),
+            &tree,
+            vec![spend(key)],
+            vec![(owner, Amount::from(100))],
+            None,
+            None,
+            [0; 32],
+        );
+        assert!(matches!(result, Err(Error::MixedTokens(_))));
+    }
+}
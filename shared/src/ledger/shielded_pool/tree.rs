@@ -0,0 +1,180 @@
+//! The note commitment tree is an append-only Merkle tree of a fixed depth,
+//! whose leaves are the note commitments in the order in which they were
+//! added.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use curve25519_dalek::scalar::Scalar;
+use once_cell::sync::Lazy;
+
+use super::hash::compress;
+use crate::types::shielded::{FieldElement, MerkleRoot, NoteCommitment};
+
+/// The depth of the note commitment tree
+pub const TREE_DEPTH: usize = 20;
+
+/// The maximum number of note commitments in the tree
+pub const TREE_CAPACITY: u64 = 1 << TREE_DEPTH;
+
+/// The roots of the empty subtrees of each height, from the leaves up to the
+/// root of the empty tree
+static EMPTY_ROOTS: Lazy<Vec<Scalar>> = Lazy::new(|| {
+    let mut roots = vec![Scalar::zero()];
+    for height in 0..TREE_DEPTH {
+        let root = roots[height];
+        roots.push(compress(root, root));
+    }
+    roots
+});
+
+/// A path from a leaf to the root of the tree. It contains the sibling of the
+/// node at each height, paired with a flag that is `true` if the node is the
+/// right child of its parent.
+pub type MerklePath = Vec<(Scalar, bool)>;
+
+/// The state of the note commitment tree that's needed to append new
+/// commitments and to compute the tree's root
+#[derive(Clone, Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct CommitmentTree {
+    /// The number of commitments in the tree
+    pub size: u64,
+    /// The last left child at each height of the tree, from the leaves up
+    pub frontier: Vec<FieldElement>,
+    /// The root of the tree
+    pub root: MerkleRoot,
+}
+
+impl Default for CommitmentTree {
+    fn default() -> Self {
+        Self {
+            size: 0,
+            frontier: EMPTY_ROOTS[..TREE_DEPTH]
+                .iter()
+                .map(|root| root.to_bytes())
+                .collect(),
+            root: MerkleRoot(EMPTY_ROOTS[TREE_DEPTH].to_bytes()),
+        }
+    }
+}
+
+impl CommitmentTree {
+    /// Append a commitment to the tree and update its root. Returns `false`
+    /// if the tree is full, in which case it is not modified.
+    pub fn append(&mut self, commitment: &NoteCommitment) -> bool {
+        if self.size >= TREE_CAPACITY {
+            return false;
+        }
+        let mut index = self.size;
+        let mut node = Scalar::from_bytes_mod_order(commitment.0);
+        for (height, left) in self.frontier.iter_mut().enumerate() {
+            node = if index % 2 == 0 {
+                *left = node.to_bytes();
+                compress(node, EMPTY_ROOTS[height])
+            } else {
+                compress(Scalar::from_bytes_mod_order(*left), node)
+            };
+            index /= 2;
+        }
+        self.size += 1;
+        self.root = MerkleRoot(node.to_bytes());
+        true
+    }
+}
+
+/// Compute the root of the tree with the given commitments.
+pub fn merkle_root(commitments: &[Scalar]) -> Scalar {
+    let (root, _path) = merkle_root_and_path(commitments, None);
+    root
+}
+
+/// Compute the path of the commitment at the given position in the tree with
+/// the given commitments. Returns `None` if there's no such commitment.
+pub fn merkle_path(
+    commitments: &[Scalar],
+    position: u64,
+) -> Option<MerklePath> {
+    if position >= commitments.len() as u64 {
+        return None;
+    }
+    let (_root, path) = merkle_root_and_path(commitments, Some(position));
+    Some(path)
+}
+
+fn merkle_root_and_path(
+    commitments: &[Scalar],
+    position: Option<u64>,
+) -> (Scalar, MerklePath) {
+    let mut nodes = commitments.to_vec();
+    let mut path = Vec::with_capacity(TREE_DEPTH);
+    let mut index = position.unwrap_or_default() as usize;
+    for height in 0..TREE_DEPTH {
+        let empty = EMPTY_ROOTS[height];
+        if position.is_some() {
+            let sibling = nodes.get(index ^ 1).copied().unwrap_or(empty);
+            path.push((sibling, index % 2 == 1));
+        }
+        nodes = nodes
+            .chunks(2)
+            .map(|pair| {
+                compress(pair[0], pair.get(1).copied().unwrap_or(empty))
+            })
+            .collect();
+        index /= 2;
+    }
+    let root = nodes.first().copied().unwrap_or(EMPTY_ROOTS[TREE_DEPTH]);
+    (root, path)
+}
+
+/// Compute the root from a commitment and its path.
+pub fn root_from_path(commitment: Scalar, path: &[(Scalar, bool)]) -> Scalar {
+    path.iter().fold(commitment, |node, (sibling, is_right)| {
+        if *is_right {
+            compress(*sibling, node)
+        } else {
+            compress(node, *sibling)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commitments(count: u64) -> Vec<Scalar> {
+        (0..count).map(|i| Scalar::from(i + 1)).collect()
+    }
+
+    #[test]
+    fn test_append_matches_full_tree() {
+        let mut tree = CommitmentTree::default();
+        assert_eq!(tree.root.0, merkle_root(&[]).to_bytes());
+        for count in 1..10 {
+            let commitments = commitments(count);
+            let last = commitments.last().unwrap();
+            assert!(tree.append(&NoteCommitment(last.to_bytes())));
+            assert_eq!(tree.size, count);
+            assert_eq!(tree.root.0, merkle_root(&commitments).to_bytes());
+        }
+    }
+
+    /// Known-answer test of the root of the empty tree, computed with an
+    /// independent Python implementation of the MiMC compression function.
+    #[test]
+    fn test_empty_root() {
+        assert_eq!(
+            hex::encode(CommitmentTree::default().root.0),
+            "48c33f1e7ecaabe2ea06eb31302f3837a394f1c132bb3176cdfef7efda64cf0e"
+        );
+    }
+
+    #[test]
+    fn test_merkle_path() {
+        let commitments = commitments(5);
+        let root = merkle_root(&commitments);
+        for (position, commitment) in commitments.iter().enumerate() {
+            let path = merkle_path(&commitments, position as u64).unwrap();
+            assert_eq!(path.len(), TREE_DEPTH);
+            assert_eq!(root_from_path(*commitment, &path), root);
+        }
+        assert!(merkle_path(&commitments, 5).is_none());
+    }
+}
@@ -9,11 +9,13 @@ use core::fmt::Debug;
 use std::collections::HashMap;
 use std::fmt::Display;
 
+use borsh::BorshDeserialize;
 use sparse_merkle_tree::default_store::DefaultStore;
 use sparse_merkle_tree::{SparseMerkleTree, H256};
 use tendermint::block::Header;
 use thiserror::Error;
 use types::MerkleTree;
+use write_log::{StorageModification, WriteLog};
 
 use crate::bytes::ByteBuf;
 use crate::ledger::gas::MIN_STORAGE_GAS;
@@ -23,7 +25,7 @@ use crate::types::storage::{
     BlockHash, BlockHeight, DbKeySeg, Epoch, Epochs, Key, BLOCK_HASH_LENGTH,
     CHAIN_ID_LENGTH,
 };
-use crate::types::time::DateTimeUtc;
+use crate::types::time::{DateTime, DateTimeUtc, Utc};
 
 /// A result of a function that may fail
 pub type Result<T> = std::result::Result<T, Error>;
@@ -113,6 +115,17 @@ pub struct BlockState {
     pub address_gen: EstablishedAddressGen,
 }
 
+impl core::fmt::Debug for BlockState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let root_hash = format!("{}", ByteBuf(self.root.as_slice()));
+        f.debug_struct("BlockState")
+            .field("root_hash", &root_hash)
+            .field("height", &self.height)
+            .field("epoch", &self.epoch)
+            .finish()
+    }
+}
+
 /// A database backend.
 pub trait DB: std::fmt::Debug {
     /// Flush data on the memory to persistent them
@@ -158,30 +171,8 @@ where
     /// Load the full state at the last committed height, if any. Returns the
     /// Merkle root hash and the height of the committed block.
     pub fn load_last_state(&mut self) -> Result<()> {
-        if let Some(BlockState {
-            root,
-            store,
-            hash,
-            height,
-            epoch,
-            pred_epochs,
-            next_epoch_min_start_height,
-            next_epoch_min_start_time,
-            subspaces,
-            address_gen,
-        }) = self.db.read_last_block()?
-        {
-            self.block.tree = MerkleTree(SparseMerkleTree::new(root, store));
-            self.block.hash = hash;
-            self.block.height = height;
-            self.block.epoch = epoch;
-            self.block.pred_epochs = pred_epochs;
-            self.block.subspaces = subspaces;
-            self.last_height = height;
-            self.current_epoch = epoch;
-            self.next_epoch_min_start_height = next_epoch_min_start_height;
-            self.next_epoch_min_start_time = next_epoch_min_start_time;
-            self.address_gen = address_gen;
+        if let Some(state) = self.db.read_last_block()? {
+            self.restore_block_state(state);
             tracing::debug!("Loaded storage from DB");
         } else {
             tracing::info!("No state could be found");
@@ -202,9 +193,9 @@ where
         }
     }
 
-    /// Persist the current block's state to the database
-    pub fn commit(&mut self) -> Result<()> {
-        let state = BlockState {
+    /// Get a copy of the current block's state.
+    pub fn block_state(&self) -> BlockState {
+        BlockState {
             root: *self.block.tree.0.root(),
             store: self.block.tree.0.store().clone(),
             hash: self.block.hash.clone(),
@@ -215,7 +206,39 @@ where
             next_epoch_min_start_time: self.next_epoch_min_start_time,
             subspaces: self.block.subspaces.clone(),
             address_gen: self.address_gen.clone(),
-        };
+        }
+    }
+
+    /// Restore a block's state as the state of the last committed block.
+    pub fn restore_block_state(&mut self, state: BlockState) {
+        let BlockState {
+            root,
+            store,
+            hash,
+            height,
+            epoch,
+            pred_epochs,
+            next_epoch_min_start_height,
+            next_epoch_min_start_time,
+            subspaces,
+            address_gen,
+        } = state;
+        self.block.tree = MerkleTree(SparseMerkleTree::new(root, store));
+        self.block.hash = hash;
+        self.block.height = height;
+        self.block.epoch = epoch;
+        self.block.pred_epochs = pred_epochs;
+        self.block.subspaces = subspaces;
+        self.last_height = height;
+        self.current_epoch = epoch;
+        self.next_epoch_min_start_height = next_epoch_min_start_height;
+        self.next_epoch_min_start_time = next_epoch_min_start_time;
+        self.address_gen = address_gen;
+    }
+
+    /// Persist the current block's state to the database
+    pub fn commit(&mut self) -> Result<()> {
+        let state = self.block_state();
         self.db.write_block(state)?;
         self.last_height = self.block.height;
         self.header = None;
@@ -409,15 +432,22 @@ where
         (self.header.clone(), MIN_STORAGE_GAS)
     }
 
+    /// Get the current (yet to be committed) block time. This is only
+    /// available when the block header has been set.
+    pub fn get_block_time(&self) -> (Option<DateTimeUtc>, u64) {
+        let time = self.header.as_ref().map(|header| {
+            let time: DateTime<Utc> = header.time.into();
+            time.into()
+        });
+        (time, MIN_STORAGE_GAS)
+    }
+
     /// Initialize a new epoch when the current epoch is finished.
     pub fn update_epoch(
         &mut self,
         height: BlockHeight,
         time: DateTimeUtc,
     ) -> Result<()> {
-        let (parameters, _gas) =
-            parameters::read(self).expect("Couldn't read protocol parameters");
-
         // Check if the current epoch is over
         if height >= self.next_epoch_min_start_height
             && time >= self.next_epoch_min_start_time
@@ -426,18 +456,28 @@ where
             self.block.epoch = self.block.epoch.next();
             self.current_epoch = self.current_epoch.next();
             debug_assert_eq!(self.block.epoch, self.current_epoch);
+            // The parameters scheduled for the new epoch become active before
+            // they are used for its duration
+            if parameters::activate_pending(self, self.current_epoch)
+                .expect("Couldn't activate the scheduled protocol parameters")
+            {
+                tracing::info!(
+                    "Activated new protocol parameters in epoch {}",
+                    self.current_epoch
+                );
+            }
+            let (parameters, _gas) = parameters::read(self)
+                .expect("Couldn't read protocol parameters");
             let EpochDuration {
                 min_num_of_blocks,
                 min_duration,
             } = parameters.epoch_duration;
             self.next_epoch_min_start_height = height + min_num_of_blocks;
             self.next_epoch_min_start_time = time + min_duration;
-            // TODO put this into PoS parameters and pass it to tendermint
-            // `consensus_params` on `InitChain` and `EndBlock`
-            let evidence_max_age_num_blocks: u64 = 100000;
-            self.block
-                .pred_epochs
-                .new_epoch(height, evidence_max_age_num_blocks);
+            self.block.pred_epochs.new_epoch(
+                height,
+                parameters.consensus.evidence_max_age_num_blocks,
+            );
             tracing::info!("Began a new epoch {}", self.block.epoch);
         }
         self.update_epoch_in_merkle_tree()
@@ -470,6 +510,27 @@ where
     }
 }
 
+/// Read a value from the block write log or the storage. Values that cannot be
+/// decoded are treated as missing.
+pub fn read_block_value<D, H, T>(
+    storage: &Storage<D, H>,
+    write_log: &WriteLog,
+    key: &Key,
+) -> Result<Option<T>>
+where
+    D: DB + for<'iter> DBIter<'iter>,
+    H: StorageHasher,
+    T: BorshDeserialize,
+{
+    let value = match write_log.read(key).0 {
+        Some(StorageModification::Write { value }) => Some(value.clone()),
+        Some(StorageModification::Delete) => None,
+        Some(StorageModification::InitAccount { .. }) => None,
+        None => storage.read(key)?.0,
+    };
+    Ok(value.and_then(|bytes| types::decode(bytes).ok()))
+}
+
 /// The storage hasher used for the merkle tree.
 pub trait StorageHasher: sparse_merkle_tree::traits::Hasher + Default {
     /// Hash a storage key
@@ -481,10 +542,13 @@ pub trait StorageHasher: sparse_merkle_tree::traits::Hasher + Default {
 /// Helpers for testing components that depend on storage
 #[cfg(any(test, feature = "testing"))]
 pub mod testing {
-    use std::convert::TryInto;
+    use std::convert::{TryFrom, TryInto};
 
     use sha2::{Digest, Sha256};
     use sparse_merkle_tree::H256;
+    use tendermint::block::header::Version;
+    use tendermint::hash::{AppHash, Hash};
+    use tendermint::{account, block, chain};
 
     use super::mockdb::MockDB;
     use super::*;
@@ -566,6 +630,31 @@ pub mod testing {
             }
         }
     }
+
+    /// A block header with the given time, for the tests that depend on the
+    /// block time, which isn't set in the [`TestStorage`].
+    pub fn block_header(time: DateTimeUtc) -> Header {
+        Header {
+            version: Version { block: 10, app: 0 },
+            chain_id: chain::Id::try_from("test_chain".to_owned())
+                .expect("Creating a chain ID shouldn't fail"),
+            height: block::Height::try_from(10_u64)
+                .expect("Creating a height shouldn't fail"),
+            time: time.0.into(),
+            last_block_id: None,
+            last_commit_hash: None,
+            data_hash: None,
+            validators_hash: Hash::None,
+            next_validators_hash: Hash::None,
+            consensus_hash: Hash::None,
+            app_hash: AppHash::try_from(vec![0])
+                .expect("Creating an AppHash shouldn't fail"),
+            last_results_hash: None,
+            evidence_hash: None,
+            proposer_address: account::Id::try_from(vec![0u8; 20])
+                .expect("Creating an account ID shouldn't fail"),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -575,9 +664,30 @@ mod tests {
 
     use super::testing::*;
     use super::*;
-    use crate::ledger::parameters::Parameters;
+    use crate::ledger::parameters::{
+        ConsensusParams, InflationParams, Parameters,
+    };
     use crate::types::time::{self, Duration};
 
+    fn consensus_params() -> ConsensusParams {
+        ConsensusParams {
+            max_block_bytes: 22_020_096,
+            max_block_gas: 10_000_000,
+            evidence_max_age_num_blocks: 100_000,
+            evidence_max_age_duration: Duration::hours(48).into(),
+            pub_key_types: vec!["ed25519".to_owned()],
+        }
+    }
+
+    fn inflation_params() -> InflationParams {
+        InflationParams {
+            annual_rate: 1000,
+            target_bonded_ratio: 5000,
+            proposer_bonus: 500,
+            treasury_share: 1000,
+        }
+    }
+
     prop_compose! {
         /// Setup test input data with arbitrary epoch duration, epoch start
         /// height and time, and a block height and time that are greater than
@@ -636,6 +746,8 @@ mod tests {
             };
             let mut parameters = Parameters {
                 epoch_duration: epoch_duration.clone(),
+                consensus: consensus_params(),
+                inflation: inflation_params(),
             };
             parameters::init_genesis_storage(&mut storage, &parameters);
 
@@ -706,4 +818,56 @@ mod tests {
                 time_of_update + parameters.epoch_duration.min_duration);
         }
     }
+
+    /// Test that the scheduled parameters only become active when their
+    /// epoch begins and that they are used for the duration of that epoch.
+    #[test]
+    fn update_epoch_activates_scheduled_parameters() {
+        let start_time: DateTimeUtc = Utc.timestamp(0, 0).into();
+        let mut storage = TestStorage {
+            next_epoch_min_start_height: BlockHeight(1),
+            next_epoch_min_start_time: start_time,
+            ..Default::default()
+        };
+        let epoch_duration = |min_num_of_blocks: u64| EpochDuration {
+            min_num_of_blocks,
+            min_duration: Duration::seconds(0).into(),
+        };
+        let parameters = Parameters {
+            epoch_duration: epoch_duration(1),
+            consensus: consensus_params(),
+            inflation: inflation_params(),
+        };
+        parameters::init_genesis_storage(&mut storage, &parameters);
+        let scheduled = Parameters {
+            epoch_duration: epoch_duration(5),
+            consensus: consensus_params(),
+            inflation: inflation_params(),
+        };
+        let activation_epoch = parameters::activation_epoch(Epoch(0));
+        storage
+            .write(
+                &parameters::pending_key(activation_epoch),
+                types::encode(&scheduled),
+            )
+            .unwrap();
+
+        for height in 1..activation_epoch.0 {
+            storage
+                .update_epoch(BlockHeight(height), start_time)
+                .unwrap();
+            assert_eq!(parameters::read(&storage).unwrap().0, parameters);
+        }
+        let height = BlockHeight(activation_epoch.0);
+        storage.update_epoch(height, start_time).unwrap();
+        assert_eq!(storage.current_epoch, activation_epoch);
+        assert_eq!(parameters::read(&storage).unwrap().0, scheduled);
+        assert_eq!(storage.next_epoch_min_start_height, height + 5);
+        assert!(
+            !storage
+                .has_key(&parameters::pending_key(activation_epoch))
+                .unwrap()
+                .0
+        );
+    }
 }
@@ -8,6 +8,7 @@ use thiserror::Error;
 use crate::ledger::storage::{self, Storage, StorageHasher};
 use crate::types::address::{Address, EstablishedAddressGen};
 use crate::types::storage::Key;
+use crate::types::transaction::TxEvent;
 
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
@@ -55,6 +56,8 @@ pub struct WriteLog {
     block_write_log: HashMap<Key, StorageModification>,
     /// The storage modifications for the current transaction
     tx_write_log: HashMap<Key, StorageModification>,
+    /// The events emitted by the current transaction
+    tx_events: Vec<TxEvent>,
 }
 
 impl Default for WriteLog {
@@ -63,6 +66,7 @@ impl Default for WriteLog {
             address_gen: None,
             block_write_log: HashMap::with_capacity(100_000),
             tx_write_log: HashMap::with_capacity(100),
+            tx_events: Vec::new(),
         }
     }
 }
@@ -92,6 +96,27 @@ impl WriteLog {
         }
     }
 
+    /// Read a value before the current transaction's execution at the given
+    /// key and return the value and the gas cost, returns [`None`] if the key
+    /// is not present in the block write log
+    pub fn read_pre(&self, key: &Key) -> (Option<&StorageModification>, u64) {
+        match self.block_write_log.get(key) {
+            Some(v) => {
+                let gas = match v {
+                    StorageModification::Write { ref value } => {
+                        key.len() + value.len()
+                    }
+                    StorageModification::Delete => key.len(),
+                    StorageModification::InitAccount { ref vp } => {
+                        key.len() + vp.len()
+                    }
+                };
+                (Some(v), gas as _)
+            }
+            None => (None, key.len() as _),
+        }
+    }
+
     /// Write a key and a value and return the gas cost and the size difference
     /// Fails with [`Error::UpdateVpOfNewAccount`] when attempting to update a
     /// validity predicate of a new account that's not yet committed to storage.
@@ -204,6 +229,20 @@ impl WriteLog {
             .collect()
     }
 
+    /// Emit an event from the current transaction and return the gas cost
+    pub fn emit_event(&mut self, event: TxEvent) -> u64 {
+        let gas = event.size();
+        self.tx_events.push(event);
+        gas as _
+    }
+
+    /// Take the events emitted by the current transaction. This should be
+    /// called before the transaction is committed, because the events are
+    /// cleared on both [`WriteLog::commit_tx`] and [`WriteLog::drop_tx`].
+    pub fn take_tx_events(&mut self) -> Vec<TxEvent> {
+        std::mem::take(&mut self.tx_events)
+    }
+
     /// Commit the current transaction's write log to the block when it's
     /// accepted by all the triggered validity predicates. Starts a new
     /// transaction write log.
@@ -213,12 +252,14 @@ impl WriteLog {
             HashMap::with_capacity(100),
         );
         self.block_write_log.extend(tx_write_log);
+        self.tx_events.clear();
     }
 
     /// Drop the current transaction's write log when it's declined by any of
     /// the triggered validity predicates. Starts a new transaction write log.
     pub fn drop_tx(&mut self) {
         self.tx_write_log.clear();
+        self.tx_events.clear();
     }
 
     /// Commit the current block's write log to the storage. Starts a new block
@@ -452,6 +493,72 @@ mod tests {
         assert_matches!(result, Error::DeleteVp);
     }
 
+    #[test]
+    fn test_tx_events() {
+        let mut write_log = WriteLog::default();
+        let event = TxEvent {
+            event_type: "order_filled".to_owned(),
+            attributes: [("id".to_owned(), "1".to_owned())]
+                .iter()
+                .cloned()
+                .collect(),
+        };
+
+        // the gas cost is the size of the event
+        let gas = write_log.emit_event(event.clone());
+        assert_eq!(gas, event.size() as u64);
+
+        // events of a dropped tx should be discarded
+        write_log.drop_tx();
+        assert!(write_log.take_tx_events().is_empty());
+
+        // events taken before the tx is committed are returned in order
+        write_log.emit_event(event.clone());
+        write_log.emit_event(event.clone());
+        assert_eq!(write_log.take_tx_events(), vec![event.clone(), event]);
+        write_log.commit_tx();
+        assert!(write_log.take_tx_events().is_empty());
+    }
+
+    #[test]
+    fn test_read_pre() {
+        let mut write_log = WriteLog::default();
+        let key =
+            Key::parse("key".to_owned()).expect("cannot parse the key string");
+
+        // a value written by a committed tx is in the prior state
+        let committed = "committed".as_bytes().to_vec();
+        write_log.write(&key, committed.clone()).unwrap();
+        write_log.commit_tx();
+
+        // a value written by the current tx is not in the prior state
+        let current = "current".as_bytes().to_vec();
+        write_log.write(&key, current.clone()).unwrap();
+
+        let (value, gas) = write_log.read_pre(&key);
+        match value.expect("no read value") {
+            StorageModification::Write { value } => {
+                assert_eq!(*value, committed)
+            }
+            _ => panic!("unexpected read result"),
+        }
+        assert_eq!(gas, (key.len() + committed.len()) as u64);
+        let (value, _gas) = write_log.read(&key);
+        match value.expect("no read value") {
+            StorageModification::Write { value } => {
+                assert_eq!(*value, current)
+            }
+            _ => panic!("unexpected read result"),
+        }
+
+        // a non-existing key
+        let other = Key::parse("other".to_owned())
+            .expect("cannot parse the key string");
+        let (value, gas) = write_log.read_pre(&other);
+        assert!(value.is_none());
+        assert_eq!(gas, other.len() as u64);
+    }
+
     #[test]
     fn test_commit() {
         let mut storage =
@@ -0,0 +1,248 @@
+//! Native validity predicate for the tokens in the token registry
+
+use std::collections::HashSet;
+
+use thiserror::Error;
+
+use crate::ledger::native_vp::{self, Ctx, NativeVp};
+use crate::ledger::storage::{self, StorageHasher};
+use crate::types::address::{Address, InternalAddress};
+use crate::types::storage::Key;
+use crate::types::token::{self, Change, TokenInfo};
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Native VP error: {0}")]
+    NativeVpError(native_vp::Error),
+}
+
+/// Token VP functions result
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The token VP of a token in the token registry. It's used in place of the
+/// token account's validity predicate, which is not being run.
+pub struct TokenVp<'a, DB, H>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    /// Context to interact with the host structures.
+    pub ctx: Ctx<'a, DB, H>,
+    /// The address of the validated token
+    pub token: &'a Address,
+}
+
+impl<'a, DB, H> NativeVp for TokenVp<'a, DB, H>
+where
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+{
+    type Error = Error;
+
+    /// The registered tokens are validated on behalf of the token registry
+    const ADDR: InternalAddress = InternalAddress::TokenRegistry;
+
+    /// See [`token::validate_changes`].
+    fn validate_tx(
+        &self,
+        _tx_data: &[u8],
+        keys_changed: &HashSet<Key>,
+        verifiers: &HashSet<Address>,
+    ) -> Result<bool> {
+        token::validate_changes(
+            self.token,
+            keys_changed,
+            verifiers,
+            |key| self.amount_change(key),
+            || self.read_minter(),
+        )
+    }
+}
+
+impl<'a, DB, H> TokenVp<'a, DB, H>
+where
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+{
+    /// Get the change of an amount. Returns `None` if either of its values
+    /// cannot be decoded.
+    fn amount_change(&self, key: &Key) -> Result<Option<Change>> {
+        let pre = self.ctx.read_pre(key)?;
+        let post = self.ctx.read_post(key)?;
+        Ok(token::amount_change(pre.as_deref(), post.as_deref()))
+    }
+
+    /// Read the token's minter from the token registry.
+    fn read_minter(&self) -> Result<Option<Address>> {
+        let key = token::registry_key(self.token);
+        let info: Option<TokenInfo> = self.ctx.read_pre_value(&key)?;
+        Ok(info.and_then(|info| info.minter))
+    }
+}
+
+impl From<native_vp::Error> for Error {
+    fn from(err: native_vp::Error) -> Self {
+        Self::NativeVpError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::gas::VpGasMeter;
+    use crate::ledger::storage::testing::TestStorage;
+    use crate::ledger::storage::types::encode;
+    use crate::ledger::storage::write_log::WriteLog;
+    use crate::ledger::token_registry;
+    use crate::proto::Tx;
+    use crate::types::address;
+    use crate::types::storage::KeySeg;
+    use crate::types::token::Amount;
+
+    /// Set up a registered token with the given minter and balances
+    fn init_storage(
+        minter: Option<Address>,
+        balances: &[(&Address, u64)],
+    ) -> (Address, TestStorage) {
+        let mut storage = TestStorage::default();
+        let token = address::xan();
+        let info = TokenInfo {
+            symbol: "XAN".to_owned(),
+            name: "Anoma".to_owned(),
+            decimals: 6,
+            minter,
+        };
+        let total_supply: u64 = balances.iter().map(|(_, amount)| amount).sum();
+        token_registry::init_genesis_storage(
+            &mut storage,
+            &[(token.clone(), info, Amount::from(total_supply))],
+        );
+        for (owner, amount) in balances {
+            storage
+                .write(
+                    &token::balance_key(&token, owner),
+                    encode(&Amount::from(*amount)),
+                )
+                .unwrap();
+        }
+        (token, storage)
+    }
+
+    fn validate(
+        token: &Address,
+        storage: &TestStorage,
+        write_log: &WriteLog,
+        verifiers: &[&Address],
+    ) -> bool {
+        let keys_changed = write_log.get_keys();
+        let verifiers = verifiers.iter().map(|&addr| addr.clone()).collect();
+        let tx = Tx::new(vec![], None);
+        let ctx = Ctx::new(storage, write_log, &tx, VpGasMeter::new(0));
+        let token_vp = TokenVp { ctx, token };
+        token_vp
+            .validate_tx(&[], &keys_changed, &verifiers)
+            .expect("validation shouldn't fail")
+    }
+
+    fn write_amount(write_log: &mut WriteLog, key: &Key, amount: u64) {
+        write_log.write(key, encode(&Amount::from(amount))).unwrap();
+    }
+
+    #[test]
+    fn test_transfer() {
+        let source = address::testing::established_address_1();
+        let target = address::testing::established_address_2();
+        let (token, storage) = init_storage(None, &[(&source, 100)]);
+        let mut write_log = WriteLog::default();
+        write_amount(&mut write_log, &token::balance_key(&token, &source), 40);
+        write_amount(&mut write_log, &token::balance_key(&token, &target), 60);
+
+        // The source must approve the debit
+        assert!(validate(&token, &storage, &write_log, &[&source]));
+        assert!(!validate(&token, &storage, &write_log, &[&target]));
+    }
+
+    #[test]
+    fn test_unbalanced_transfer() {
+        let source = address::testing::established_address_1();
+        let target = address::testing::established_address_2();
+        let (token, storage) = init_storage(None, &[(&source, 100)]);
+        let mut write_log = WriteLog::default();
+        write_amount(&mut write_log, &token::balance_key(&token, &source), 40);
+        write_amount(&mut write_log, &token::balance_key(&token, &target), 61);
+        assert!(!validate(&token, &storage, &write_log, &[&source]));
+    }
+
+    #[test]
+    fn test_mint() {
+        let minter = address::testing::established_address_1();
+        let target = address::testing::established_address_2();
+        let (token, storage) = init_storage(Some(minter.clone()), &[]);
+        let mut write_log = WriteLog::default();
+        write_amount(&mut write_log, &token::balance_key(&token, &target), 10);
+        write_amount(&mut write_log, &token::total_supply_key(&token), 10);
+
+        // The minter must approve the change of the total supply
+        assert!(validate(&token, &storage, &write_log, &[&minter]));
+        assert!(!validate(&token, &storage, &write_log, &[&target]));
+
+        // The total supply must match the balances
+        write_amount(&mut write_log, &token::total_supply_key(&token), 11);
+        assert!(!validate(&token, &storage, &write_log, &[&minter]));
+    }
+
+    #[test]
+    fn test_burn() {
+        let minter = address::testing::established_address_1();
+        let source = address::testing::established_address_2();
+        let (token, storage) =
+            init_storage(Some(minter.clone()), &[(&source, 100)]);
+        let mut write_log = WriteLog::default();
+        write_amount(&mut write_log, &token::balance_key(&token, &source), 60);
+        write_amount(&mut write_log, &token::total_supply_key(&token), 60);
+
+        // The source must approve the debit, the minter isn't needed
+        assert!(validate(&token, &storage, &write_log, &[&source]));
+        assert!(!validate(&token, &storage, &write_log, &[&minter]));
+
+        // Tokens without a minter cannot be burned
+        let (token, storage) = init_storage(None, &[(&source, 100)]);
+        assert!(!validate(&token, &storage, &write_log, &[&source]));
+    }
+
+    #[test]
+    fn test_mint_without_minter() {
+        let target = address::testing::established_address_2();
+        let (token, storage) = init_storage(None, &[]);
+        let mut write_log = WriteLog::default();
+        write_amount(&mut write_log, &token::balance_key(&token, &target), 10);
+        write_amount(&mut write_log, &token::total_supply_key(&token), 10);
+        assert!(!validate(&token, &storage, &write_log, &[&target]));
+    }
+
+    #[test]
+    fn test_undecodable_amount_denied() {
+        let source = address::testing::established_address_1();
+        let target = address::testing::established_address_2();
+        let (token, storage) = init_storage(None, &[(&source, 100)]);
+        let mut write_log = WriteLog::default();
+        // A balance that cannot be decoded isn't treated as a zero amount
+        write_log
+            .write(&token::balance_key(&token, &target), vec![0xff])
+            .unwrap();
+        assert!(!validate(&token, &storage, &write_log, &[&source]));
+    }
+
+    #[test]
+    fn test_other_keys_denied() {
+        let owner = address::testing::established_address_1();
+        let (token, storage) = init_storage(None, &[]);
+        let mut write_log = WriteLog::default();
+        let key = Key::from(token.to_db_key())
+            .push(&"other".to_owned())
+            .unwrap();
+        write_log.write(&key, vec![]).unwrap();
+        assert!(!validate(&token, &storage, &write_log, &[&owner]));
+    }
+}
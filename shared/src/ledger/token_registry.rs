@@ -0,0 +1,306 @@
+//! Token registry integration as a native validity predicate
+
+use std::collections::HashSet;
+
+use thiserror::Error;
+
+use super::storage::types::{self, decode, encode};
+use crate::ledger::native_vp::{self, Ctx, NativeVp};
+use crate::ledger::storage::{self, Storage, StorageHasher};
+use crate::types::address::{Address, InternalAddress};
+use crate::types::storage::Key;
+use crate::types::token::{self, Amount, TokenInfo};
+
+const ADDR: InternalAddress = InternalAddress::TokenRegistry;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Native VP error: {0}")]
+    NativeVpError(native_vp::Error),
+}
+
+/// Token registry functions result
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Token registry VP
+pub struct TokenRegistry<'a, DB, H>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    /// Context to interact with the host structures.
+    pub ctx: Ctx<'a, DB, H>,
+}
+
+/// Initialize the token registry in the genesis block with the given tokens
+/// and their total supply.
+pub fn init_genesis_storage<DB, H>(
+    storage: &mut Storage<DB, H>,
+    tokens: &[(Address, TokenInfo, Amount)],
+) where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    for (token, info, total_supply) in tokens {
+        storage
+            .write(&token::registry_key(token), encode(info))
+            .expect("Unable to write a genesis token info");
+        storage
+            .write(&token::registry_symbol_key(&info.symbol), encode(token))
+            .expect("Unable to write a genesis token symbol");
+        storage
+            .write(&token::total_supply_key(token), encode(total_supply))
+            .expect("Unable to write a genesis token total supply");
+    }
+}
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum ReadError {
+    #[error("Storage error: {0}")]
+    StorageError(storage::Error),
+    #[error("Storage type error: {0}")]
+    StorageTypeError(types::Error),
+}
+
+/// Read a token's info from the registry. Returns the token info, if the token
+/// is registered, and gas cost.
+pub fn read_token_info<DB, H>(
+    storage: &Storage<DB, H>,
+    token: &Address,
+) -> std::result::Result<(Option<TokenInfo>, u64), ReadError>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    let key = token::registry_key(token);
+    let (value, gas) = storage.read(&key).map_err(ReadError::StorageError)?;
+    let info = value
+        .map(decode)
+        .transpose()
+        .map_err(ReadError::StorageTypeError)?;
+    Ok((info, gas))
+}
+
+/// Check if the token is in the registry. The registered tokens are
+/// validated by the native [`super::token::TokenVp`] instead of their
+/// accounts' validity predicates. Returns the gas cost together with the
+/// result.
+pub fn is_registered<DB, H>(
+    storage: &Storage<DB, H>,
+    token: &Address,
+) -> std::result::Result<(bool, u64), storage::Error>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    storage.has_key(&token::registry_key(token))
+}
+
+impl<'a, DB, H> NativeVp for TokenRegistry<'a, DB, H>
+where
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+{
+    type Error = Error;
+
+    const ADDR: InternalAddress = ADDR;
+
+    fn validate_tx(
+        &self,
+        _tx_data: &[u8],
+        keys_changed: &HashSet<Key>,
+        _verifiers: &HashSet<Address>,
+    ) -> Result<bool> {
+        for key in keys_changed {
+            let is_valid = if let Some(token) = token::is_registry_key(key) {
+                self.is_valid_new_token(key, token)?
+            } else if let Some(symbol) = token::is_registry_symbol_key(key) {
+                self.is_valid_new_symbol(key, symbol)?
+            } else {
+                // deny any other keys
+                false
+            };
+            if !is_valid {
+                tracing::info!(
+                    "Token registry rejected a change of key {}",
+                    key
+                );
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl<'a, DB, H> TokenRegistry<'a, DB, H>
+where
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+{
+    /// A token can only be added into the registry, it cannot be modified or
+    /// removed. The token's info must be valid and its symbol must be
+    /// registered with it.
+    fn is_valid_new_token(&self, key: &Key, token: &Address) -> Result<bool> {
+        if self.ctx.has_key_pre(key)? {
+            return Ok(false);
+        }
+        let info: TokenInfo = match self.ctx.read_post_value(key)? {
+            Some(info) => info,
+            None => return Ok(false),
+        };
+        if !info.is_valid() {
+            return Ok(false);
+        }
+        let symbol_key = token::registry_symbol_key(&info.symbol);
+        let symbol_token: Option<Address> =
+            self.ctx.read_post_value(&symbol_key)?;
+        Ok(symbol_token.as_ref() == Some(token))
+    }
+
+    /// A symbol can only be added into the registry, it cannot be modified or
+    /// removed. It must point to a token added in the same transaction with
+    /// the same symbol.
+    fn is_valid_new_symbol(&self, key: &Key, symbol: &str) -> Result<bool> {
+        if self.ctx.has_key_pre(key)? {
+            return Ok(false);
+        }
+        let token: Address = match self.ctx.read_post_value(key)? {
+            Some(token) => token,
+            None => return Ok(false),
+        };
+        let token_key = token::registry_key(&token);
+        if self.ctx.has_key_pre(&token_key)? {
+            return Ok(false);
+        }
+        let info: Option<TokenInfo> = self.ctx.read_post_value(&token_key)?;
+        Ok(matches!(info, Some(info) if info.symbol.to_lowercase() == symbol))
+    }
+}
+
+impl From<native_vp::Error> for Error {
+    fn from(err: native_vp::Error) -> Self {
+        Self::NativeVpError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::gas::VpGasMeter;
+    use crate::ledger::storage::testing::TestStorage;
+    use crate::ledger::storage::write_log::WriteLog;
+    use crate::proto::Tx;
+    use crate::types::address;
+
+    fn token_info(symbol: &str) -> TokenInfo {
+        TokenInfo {
+            symbol: symbol.to_owned(),
+            name: "Some token".to_owned(),
+            decimals: 6,
+            minter: None,
+        }
+    }
+
+    fn validate(
+        storage: &TestStorage,
+        write_log: &WriteLog,
+        keys_changed: &HashSet<Key>,
+    ) -> bool {
+        let tx = Tx::new(vec![], None);
+        let ctx = Ctx::new(storage, write_log, &tx, VpGasMeter::new(0));
+        let registry = TokenRegistry { ctx };
+        registry
+            .validate_tx(&[], keys_changed, &HashSet::new())
+            .expect("validation shouldn't fail")
+    }
+
+    fn register(
+        write_log: &mut WriteLog,
+        token: &Address,
+        info: &TokenInfo,
+    ) -> HashSet<Key> {
+        let token_key = token::registry_key(token);
+        let symbol_key = token::registry_symbol_key(&info.symbol);
+        write_log.write(&token_key, encode(info)).unwrap();
+        write_log.write(&symbol_key, encode(token)).unwrap();
+        [token_key, symbol_key].iter().cloned().collect()
+    }
+
+    #[test]
+    fn test_register_new_token() {
+        let storage = TestStorage::default();
+        let mut write_log = WriteLog::default();
+        let token = address::testing::established_address_1();
+        let keys_changed = register(&mut write_log, &token, &token_info("ABC"));
+        assert!(validate(&storage, &write_log, &keys_changed));
+    }
+
+    #[test]
+    fn test_register_invalid_token() {
+        let storage = TestStorage::default();
+        let token = address::testing::established_address_1();
+        let invalid = vec![
+            token_info(""),
+            token_info("NOT A SYMBOL"),
+            TokenInfo {
+                decimals: token::MAX_TOKEN_DECIMALS + 1,
+                ..token_info("ABC")
+            },
+        ];
+        for info in invalid {
+            let mut write_log = WriteLog::default();
+            let keys_changed = register(&mut write_log, &token, &info);
+            assert!(!validate(&storage, &write_log, &keys_changed));
+        }
+    }
+
+    #[test]
+    fn test_register_taken_symbol() {
+        let mut storage = TestStorage::default();
+        let token = address::testing::established_address_1();
+        init_genesis_storage(
+            &mut storage,
+            &[(token, token_info("ABC"), Amount::default())],
+        );
+
+        // The symbol is case-insensitive
+        let mut write_log = WriteLog::default();
+        let other_token = address::testing::established_address_2();
+        let keys_changed =
+            register(&mut write_log, &other_token, &token_info("abc"));
+        assert!(!validate(&storage, &write_log, &keys_changed));
+    }
+
+    #[test]
+    fn test_modify_registered_token() {
+        let mut storage = TestStorage::default();
+        let token = address::testing::established_address_1();
+        init_genesis_storage(
+            &mut storage,
+            &[(token.clone(), token_info("ABC"), Amount::default())],
+        );
+
+        let mut write_log = WriteLog::default();
+        let token_key = token::registry_key(&token);
+        let info = TokenInfo {
+            decimals: 0,
+            ..token_info("ABC")
+        };
+        write_log.write(&token_key, encode(&info)).unwrap();
+        let keys_changed = [token_key].iter().cloned().collect();
+        assert!(!validate(&storage, &write_log, &keys_changed));
+    }
+
+    #[test]
+    fn test_symbol_without_token() {
+        let storage = TestStorage::default();
+        let mut write_log = WriteLog::default();
+        let token = address::testing::established_address_1();
+        let symbol_key = token::registry_symbol_key("ABC");
+        write_log.write(&symbol_key, encode(&token)).unwrap();
+        let keys_changed = [symbol_key].iter().cloned().collect();
+        assert!(!validate(&storage, &write_log, &keys_changed));
+    }
+}
@@ -0,0 +1,271 @@
+//! The community treasury as a native validity predicate. Anyone can send
+//! tokens to the treasury. The funds can only be spent by the passed
+//! governance proposals, which are applied by the protocol, or by the
+//! transactions authorized by the treasury's admin account from the genesis,
+//! which is meant to be a multisig account.
+
+use std::collections::HashSet;
+
+use thiserror::Error;
+
+use super::storage::types::{decode, encode};
+use crate::ledger::governance::transfer;
+use crate::ledger::native_vp::{self, Ctx, NativeVp};
+use crate::ledger::storage::write_log::WriteLog;
+use crate::ledger::storage::{self, read_block_value, Storage, StorageHasher};
+use crate::types::address::{Address, InternalAddress};
+use crate::types::storage::Key;
+use crate::types::token::{self, Amount};
+use crate::types::treasury;
+
+const ADDR: InternalAddress = InternalAddress::Treasury;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Native VP error: {0}")]
+    NativeVpError(native_vp::Error),
+}
+
+/// Treasury functions result
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Treasury VP
+pub struct TreasuryVp<'a, DB, H>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    /// Context to interact with the host structures.
+    pub ctx: Ctx<'a, DB, H>,
+}
+
+/// Initialize the treasury in the genesis block with the given admin, if any.
+pub fn init_genesis_storage<DB, H>(
+    storage: &mut Storage<DB, H>,
+    admin: Option<&Address>,
+) where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    if let Some(admin) = admin {
+        storage
+            .write(&treasury::admin_key(), encode(admin))
+            .expect("Unable to write the genesis treasury admin");
+    }
+}
+
+/// Transfer the amount of the token from the source to the treasury in the
+/// write log, e.g. to collect a fee. Returns `false` if the source's balance
+/// is insufficient. The caller has to commit the changes with
+/// [`WriteLog::commit_tx`].
+pub fn deposit<DB, H>(
+    storage: &Storage<DB, H>,
+    write_log: &mut WriteLog,
+    token: &Address,
+    source: &Address,
+    amount: Amount,
+) -> std::result::Result<bool, storage::Error>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    transfer(
+        storage,
+        write_log,
+        token,
+        source,
+        &treasury::treasury_address(),
+        amount,
+    )
+}
+
+/// Mint the amount of the token into the treasury in the write log, e.g. its
+/// share of the inflation. Returns `false` if the total supply would overflow.
+/// The caller has to commit the changes with [`WriteLog::commit_tx`].
+pub fn mint<DB, H>(
+    storage: &Storage<DB, H>,
+    write_log: &mut WriteLog,
+    token: &Address,
+    amount: Amount,
+) -> std::result::Result<bool, storage::Error>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    let supply_key = token::total_supply_key(token);
+    let balance_key = token::balance_key(token, &treasury::treasury_address());
+    let supply: Amount =
+        read_block_value(storage, write_log, &supply_key)?.unwrap_or_default();
+    let balance: Amount =
+        read_block_value(storage, write_log, &balance_key)?.unwrap_or_default();
+    match (supply.checked_add(&amount), balance.checked_add(&amount)) {
+        (Some(supply), Some(balance)) => {
+            write_log
+                .write(&supply_key, encode(&supply))
+                .expect("Writing a total supply shouldn't fail");
+            write_log
+                .write(&balance_key, encode(&balance))
+                .expect("Writing a balance shouldn't fail");
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+impl<'a, DB, H> NativeVp for TreasuryVp<'a, DB, H>
+where
+    DB: 'static + storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+{
+    type Error = Error;
+
+    const ADDR: InternalAddress = ADDR;
+
+    /// A transaction can send tokens to the treasury, but spending them
+    /// requires the authorization of the treasury's admin. The admin cannot be
+    /// changed by a transaction.
+    fn validate_tx(
+        &self,
+        _tx_data: &[u8],
+        keys_changed: &HashSet<Key>,
+        verifiers: &HashSet<Address>,
+    ) -> Result<bool> {
+        let treasury = treasury::treasury_address();
+        let admin: Option<Address> = self
+            .ctx
+            .read_pre(&treasury::admin_key())?
+            .and_then(|bytes| decode(bytes).ok());
+        let is_admin_verifier =
+            admin.map_or(false, |admin| verifiers.contains(&admin));
+        for key in keys_changed {
+            let is_valid = match token::is_any_balance_key(key) {
+                Some((_, owner)) if owner == &treasury => {
+                    is_admin_verifier || {
+                        let pre: Amount = self
+                            .ctx
+                            .read_pre(key)?
+                            .and_then(|bytes| decode(bytes).ok())
+                            .unwrap_or_default();
+                        let post: Amount = self
+                            .ctx
+                            .read_post(key)?
+                            .and_then(|bytes| decode(bytes).ok())
+                            .unwrap_or_default();
+                        post >= pre
+                    }
+                }
+                _ => false,
+            };
+            if !is_valid {
+                tracing::info!("Treasury rejected a change of key {}", key);
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl From<native_vp::Error> for Error {
+    fn from(err: native_vp::Error) -> Self {
+        Self::NativeVpError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use borsh::BorshSerialize;
+
+    use super::*;
+    use crate::ledger::gas::VpGasMeter;
+    use crate::ledger::storage::testing::TestStorage;
+    use crate::proto::Tx;
+    use crate::types::address;
+
+    fn validate(
+        storage: &TestStorage,
+        write_log: &WriteLog,
+        verifiers: &[&Address],
+    ) -> bool {
+        let keys_changed = write_log
+            .verifiers_changed_keys(&HashSet::new())
+            .remove(&treasury::treasury_address())
+            .unwrap_or_default();
+        let verifiers: HashSet<Address> =
+            verifiers.iter().map(|&addr| addr.clone()).collect();
+        let tx = Tx::new(vec![], None);
+        let ctx = Ctx::new(storage, write_log, &tx, VpGasMeter::new(0));
+        let vp = TreasuryVp { ctx };
+        vp.validate_tx(&[], &keys_changed, &verifiers)
+            .expect("validation shouldn't fail")
+    }
+
+    fn write<T: BorshSerialize>(write_log: &mut WriteLog, key: &Key, value: T) {
+        write_log.write(key, encode(&value)).unwrap();
+    }
+
+    /// Test that anyone can send tokens to the treasury, but only the admin
+    /// can spend them.
+    #[test]
+    fn test_spend_treasury_funds() {
+        let admin = address::testing::established_address_1();
+        let other = address::testing::established_address_2();
+        let mut storage = TestStorage::default();
+        init_genesis_storage(&mut storage, Some(&admin));
+        let key =
+            token::balance_key(&address::xan(), &treasury::treasury_address());
+        storage.write(&key, encode(&Amount::from(100))).unwrap();
+        let mut write_log = WriteLog::default();
+
+        write(&mut write_log, &key, Amount::from(150));
+        assert!(validate(&storage, &write_log, &[]));
+        write_log.drop_tx();
+
+        write(&mut write_log, &key, Amount::from(50));
+        assert!(!validate(&storage, &write_log, &[&other]));
+        assert!(validate(&storage, &write_log, &[&admin]));
+        write_log.drop_tx();
+
+        // the admin cannot be changed
+        write(&mut write_log, &treasury::admin_key(), other.clone());
+        assert!(!validate(&storage, &write_log, &[&admin]));
+        write_log.drop_tx();
+
+        // without an admin, the funds can only be spent by governance
+        let mut storage = TestStorage::default();
+        storage.write(&key, encode(&Amount::from(100))).unwrap();
+        write(&mut write_log, &key, Amount::from(50));
+        assert!(!validate(&storage, &write_log, &[&admin]));
+    }
+
+    /// Test that the minted tokens are added to the total supply.
+    #[test]
+    fn test_mint() {
+        let xan = address::xan();
+        let mut storage = TestStorage::default();
+        storage
+            .write(&token::total_supply_key(&xan), encode(&Amount::from(1000)))
+            .unwrap();
+        let mut write_log = WriteLog::default();
+        assert!(mint(&storage, &mut write_log, &xan, Amount::from(10)).unwrap());
+        assert!(mint(&storage, &mut write_log, &xan, Amount::from(5)).unwrap());
+        write_log.commit_tx();
+        write_log.commit_block(&mut storage).unwrap();
+        let balance: Amount = read_block_value(
+            &storage,
+            &write_log,
+            &token::balance_key(&xan, &treasury::treasury_address()),
+        )
+        .unwrap()
+        .unwrap();
+        let supply: Amount = read_block_value(
+            &storage,
+            &write_log,
+            &token::total_supply_key(&xan),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(balance, Amount::from(15));
+        assert_eq!(supply, Amount::from(1015));
+    }
+}
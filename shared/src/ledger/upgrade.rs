@@ -0,0 +1,110 @@
+//! Plans of coordinated protocol upgrades. An upgrade plan is set in the
+//! genesis or by a governance proposal. When the plan's height is reached, the
+//! nodes whose binary doesn't support the upgrade halt and the nodes running
+//! the new binary migrate the storage and continue from the same state.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::parameters;
+use crate::ledger::storage::types::{self, decode, encode};
+use crate::ledger::storage::{self, Storage, StorageHasher};
+use crate::types::storage::{BlockHeight, Key};
+
+const PLAN_STORAGE_KEY: &str = "upgrade_plan";
+const APPLIED_STORAGE_KEY: &str = "applied_upgrade";
+
+/// A plan of a protocol upgrade
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct UpgradePlan {
+    /// The upgrade's name, which selects the storage migration to run
+    pub name: String,
+    /// The height of the first block that is processed by the new binary
+    pub height: BlockHeight,
+    /// The version of the binary that supports the upgrade
+    pub version: String,
+}
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Storage error: {0}")]
+    StorageError(storage::Error),
+    #[error("Storage type error: {0}")]
+    StorageTypeError(types::Error),
+}
+
+/// Storage key used for the pending upgrade plan.
+pub fn plan_key() -> Key {
+    parameters::storage_key()
+        .push(&PLAN_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Storage key used for the last applied upgrade plan.
+pub fn applied_key() -> Key {
+    parameters::storage_key()
+        .push(&APPLIED_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Initialize the upgrade plan in storage in the genesis block.
+pub fn init_genesis_storage<DB, H>(
+    storage: &mut Storage<DB, H>,
+    plan: &UpgradePlan,
+) where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    storage
+        .write(&plan_key(), encode(plan))
+        .expect("Unable to write the genesis upgrade plan");
+}
+
+/// Read the pending upgrade plan from storage, if any.
+pub fn read_plan<DB, H>(
+    storage: &Storage<DB, H>,
+) -> std::result::Result<Option<UpgradePlan>, Error>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    let (value, _gas) =
+        storage.read(&plan_key()).map_err(Error::StorageError)?;
+    value
+        .map(|value| decode(value).map_err(Error::StorageTypeError))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::storage::testing::TestStorage;
+
+    #[test]
+    fn test_read_plan() {
+        let mut storage = TestStorage::default();
+        assert_eq!(read_plan(&storage).unwrap(), None);
+
+        let plan = UpgradePlan {
+            name: "v2".to_owned(),
+            height: BlockHeight(100),
+            version: "0.2.0".to_owned(),
+        };
+        init_genesis_storage(&mut storage, &plan);
+        assert_eq!(read_plan(&storage).unwrap(), Some(plan));
+
+        storage.write(&plan_key(), vec![1, 2, 3]).unwrap();
+        assert!(read_plan(&storage).is_err());
+    }
+}
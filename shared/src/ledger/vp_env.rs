@@ -6,10 +6,12 @@ use std::num::TryFromIntError;
 use thiserror::Error;
 
 use crate::ledger::gas;
-use crate::ledger::gas::VpGasMeter;
+use crate::ledger::gas::{VpGasMeter, MIN_STORAGE_GAS};
 use crate::ledger::storage::write_log::WriteLog;
 use crate::ledger::storage::{self, write_log, Storage, StorageHasher};
+use crate::proto::Tx;
 use crate::types::storage::{BlockHash, BlockHeight, Epoch, Key};
+use crate::types::time::DateTimeUtc;
 
 /// These runtime errors will abort VP execution immediately
 #[allow(missing_docs)]
@@ -27,6 +29,10 @@ pub enum RuntimeError {
     NumConversionError(TryFromIntError),
     #[error("Memory error: {0}")]
     MemoryError(Box<dyn std::error::Error + Sync + Send + 'static>),
+    #[error("Validity predicate panicked: {0}")]
+    Panic(String),
+    #[error("Validity predicate ran out of memory allocating {0} bytes")]
+    OutOfMemory(u64),
 }
 
 /// VP environment function result
@@ -204,6 +210,32 @@ where
     Ok(epoch)
 }
 
+/// Getting the block time. The time is that of the block to which the current
+/// transaction is being applied. It's only available when the block header has
+/// been set.
+pub fn get_block_time<DB, H>(
+    gas_meter: &mut VpGasMeter,
+    storage: &Storage<DB, H>,
+) -> Result<Option<DateTimeUtc>>
+where
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    let (time, gas) = storage.get_block_time();
+    add_gas(gas_meter, gas)?;
+    Ok(time)
+}
+
+/// Getting the timestamp of the transaction that triggered the validity
+/// predicate.
+pub fn get_tx_timestamp(
+    gas_meter: &mut VpGasMeter,
+    tx: &Tx,
+) -> Result<DateTimeUtc> {
+    add_gas(gas_meter, MIN_STORAGE_GAS)?;
+    Ok(tx.timestamp)
+}
+
 /// Storage prefix iterator. It will try to get an iterator from the storage.
 pub fn iter_prefix<'a, DB, H>(
     gas_meter: &mut VpGasMeter,
@@ -6,7 +6,7 @@ use prost::Message;
 use thiserror::Error;
 
 use super::generated::types;
-use crate::types::key::ed25519::{self, Keypair};
+use crate::types::key::common::{self, SigningKey};
 use crate::types::time::DateTimeUtc;
 
 #[derive(Error, Debug)]
@@ -81,8 +81,8 @@ impl Tx {
         bytes
     }
 
-    pub fn sign(self, keypair: &Keypair) -> Tx {
-        ed25519::sign_tx(keypair, self)
+    pub fn sign(self, keypair: &impl SigningKey) -> Tx {
+        common::sign_tx(keypair, self)
     }
 }
 
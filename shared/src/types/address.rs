@@ -114,6 +114,11 @@ impl Address {
                     return Err(Error::UnexpectedHashLength(pkh.0.len()));
                 }
             }
+            Address::Implicit(ImplicitAddress::Secp256k1(pkh)) => {
+                if pkh.0.len() != HASH_LEN {
+                    return Err(Error::UnexpectedHashLength(pkh.0.len()));
+                }
+            }
             Address::Internal(_) => {}
         }
         Ok(address)
@@ -247,6 +252,8 @@ impl EstablishedAddressGen {
 pub enum ImplicitAddress {
     /// Address derived from [`key::ed25519::PublicKeyHash`]
     Ed25519(key::ed25519::PublicKeyHash),
+    /// Address derived from [`key::secp256k1::PublicKeyHash`]
+    Secp256k1(key::secp256k1::PublicKeyHash),
 }
 
 /// An internal address represents a module with a native VP
@@ -270,6 +277,14 @@ pub enum InternalAddress {
     Ibc,
     /// Protocol parameters
     Parameters,
+    /// Token registry
+    TokenRegistry,
+    /// Shielded pool
+    ShieldedPool,
+    /// Governance
+    Governance,
+    /// Community treasury
+    Treasury,
 }
 
 impl Display for InternalAddress {
@@ -281,6 +296,10 @@ impl Display for InternalAddress {
                 Self::PoS => "PoS",
                 Self::Ibc => "IBC",
                 Self::Parameters => "Parameters",
+                Self::TokenRegistry => "TokenRegistry",
+                Self::ShieldedPool => "ShieldedPool",
+                Self::Governance => "Governance",
+                Self::Treasury => "Treasury",
             }
         )
     }
@@ -396,7 +415,7 @@ pub mod testing {
     use proptest::prelude::*;
 
     use super::*;
-    use crate::types::key::ed25519;
+    use crate::types::key::{ed25519, secp256k1};
 
     /// A sampled established address for tests
     pub fn established_address_1() -> Address {
@@ -435,10 +454,16 @@ pub mod testing {
 
     /// Generate an arbitrary [`ImplicitAddress`].
     pub fn arb_implicit_address() -> impl Strategy<Value = ImplicitAddress> {
-        ed25519::testing::arb_keypair().prop_map(|keypair| {
-            let pk = ed25519::PublicKey::from(keypair.public);
-            let pkh = ed25519::PublicKeyHash::from(pk);
-            ImplicitAddress::Ed25519(pkh)
-        })
+        prop_oneof![
+            ed25519::testing::arb_keypair().prop_map(|keypair| {
+                let pk = ed25519::PublicKey::from(keypair.public);
+                let pkh = ed25519::PublicKeyHash::from(pk);
+                ImplicitAddress::Ed25519(pkh)
+            }),
+            secp256k1::testing::arb_keypair().prop_map(|keypair| {
+                let pkh = secp256k1::PublicKeyHash::from(keypair.public);
+                ImplicitAddress::Secp256k1(pkh)
+            }),
+        ]
     }
 }
@@ -0,0 +1,327 @@
+//! On-chain governance proposals and votes
+
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::ledger::parameters::Parameters;
+use crate::ledger::upgrade::UpgradePlan;
+use crate::types::address::{Address, InternalAddress};
+use crate::types::storage::{DbKeySeg, Epoch, Key, KeySeg};
+use crate::types::token::Amount;
+
+const ADDR: InternalAddress = InternalAddress::Governance;
+const PARAMETERS_STORAGE_KEY: &str = "parameters";
+const COUNTER_STORAGE_KEY: &str = "counter";
+const ACTIVE_STORAGE_KEY: &str = "active";
+const PROPOSAL_STORAGE_KEY: &str = "proposal";
+const VOTE_STORAGE_KEY: &str = "vote";
+const RESULT_STORAGE_KEY: &str = "result";
+
+/// The parameters of the governance
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct GovernanceParameters {
+    /// The minimum amount of XAN deposited with a new proposal
+    pub min_proposal_deposit: Amount,
+    /// The number of epochs in which a proposal can be voted on
+    pub voting_period: u64,
+}
+
+/// The change that a proposal applies when it passes
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub enum ProposalContent {
+    /// Replace the protocol parameters from the epoch given by
+    /// [`crate::ledger::parameters::activation_epoch`] of the epoch in which
+    /// the proposal is tallied
+    ParameterChange(Parameters),
+    /// Replace the allowlist of the hex-encoded SHA-256 hashes of the
+    /// transactions' code or remove it with `None` to allow any code
+    TxAllowlist(Option<BTreeSet<String>>),
+    /// Transfer the treasury funds to the target
+    Spend {
+        /// The recipient of the funds
+        target: Address,
+        /// The transferred token
+        token: Address,
+        /// The transferred amount
+        amount: Amount,
+    },
+    /// Schedule a protocol upgrade, replacing any pending upgrade plan. The
+    /// plan's height must be after the block in which the proposal is
+    /// tallied.
+    Upgrade(UpgradePlan),
+}
+
+/// A governance proposal
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct Proposal {
+    /// The proposal's identifier
+    pub id: u64,
+    /// The address that submitted the proposal and that gets the deposit
+    /// back when the proposal passes
+    pub author: Address,
+    /// The proposed change
+    pub content: ProposalContent,
+    /// The amount of XAN deposited with the proposal
+    pub deposit: Amount,
+    /// The first epoch of the voting period
+    pub voting_start_epoch: Epoch,
+    /// The epoch in which the votes are tallied, which is the first epoch
+    /// after the voting period
+    pub voting_end_epoch: Epoch,
+}
+
+impl Proposal {
+    /// Check if the proposal can be voted on in the given epoch
+    pub fn is_voting_open(&self, epoch: Epoch) -> bool {
+        self.voting_start_epoch <= epoch && epoch < self.voting_end_epoch
+    }
+}
+
+/// A vote on a proposal
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub enum Vote {
+    /// In favor of the proposal
+    Yay,
+    /// Against the proposal
+    Nay,
+}
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+#[error("Invalid vote {0}, expected \"yay\" or \"nay\"")]
+pub struct ParseVoteError(String);
+
+impl FromStr for Vote {
+    type Err = ParseVoteError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "yay" => Ok(Self::Yay),
+            "nay" => Ok(Self::Nay),
+            _ => Err(ParseVoteError(s.to_owned())),
+        }
+    }
+}
+
+impl Display for Vote {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Yay => write!(f, "yay"),
+            Self::Nay => write!(f, "nay"),
+        }
+    }
+}
+
+/// The tally of a proposal's votes, weighted by the voters' voting power
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct Tally {
+    /// The voting power in favor of the proposal
+    pub yay: Amount,
+    /// The voting power against the proposal
+    pub nay: Amount,
+}
+
+impl Tally {
+    /// Check if the proposal passes with the given total voting power. The
+    /// votes must reach a quorum of 1/3 of the total voting power and more
+    /// than 2/3 of the votes must be in favor.
+    pub fn is_passed(&self, total_voting_power: Amount) -> bool {
+        let yay = u128::from(u64::from(self.yay));
+        let nay = u128::from(u64::from(self.nay));
+        let total = u128::from(u64::from(total_voting_power));
+        let votes = yay + nay;
+        votes > 0 && 3 * votes >= total && 3 * yay > 2 * votes
+    }
+}
+
+/// The result of a tallied proposal
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct ProposalResult {
+    /// The final tally of the votes
+    pub tally: Tally,
+    /// The epoch in which the proposal has been tallied
+    pub epoch: Epoch,
+    /// Whether the proposal passed
+    pub passed: bool,
+    /// Whether the proposal's content has been applied. A passed proposal
+    /// may fail to be applied, e.g. when the funds are insufficient.
+    pub applied: bool,
+}
+
+/// Get the address of the governance
+pub fn governance_address() -> Address {
+    Address::Internal(ADDR)
+}
+
+fn governance_key(key: &str) -> Key {
+    Key::from(governance_address().to_db_key())
+        .push(&key.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key for the governance parameters.
+pub fn parameters_key() -> Key {
+    governance_key(PARAMETERS_STORAGE_KEY)
+}
+
+/// Obtain a storage key for the identifier of the next proposal.
+pub fn counter_key() -> Key {
+    governance_key(COUNTER_STORAGE_KEY)
+}
+
+/// Obtain a storage key for the identifiers of the proposals that haven't
+/// been tallied yet.
+pub fn active_proposals_key() -> Key {
+    governance_key(ACTIVE_STORAGE_KEY)
+}
+
+/// Obtain a storage key for a proposal.
+pub fn proposal_key(id: u64) -> Key {
+    governance_key(PROPOSAL_STORAGE_KEY)
+        .push(&id.to_string())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key prefix for all the votes on a proposal.
+pub fn vote_prefix(id: u64) -> Key {
+    proposal_key(id)
+        .push(&VOTE_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key for a vote on a proposal.
+pub fn vote_key(id: u64, voter: &Address) -> Key {
+    vote_prefix(id)
+        .push(&voter.to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key for the result of a proposal.
+pub fn result_key(id: u64) -> Key {
+    proposal_key(id)
+        .push(&RESULT_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is a proposal key. If it is, returns the
+/// proposal's identifier.
+pub fn is_proposal_key(key: &Key) -> Option<u64> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(prefix), DbKeySeg::StringSeg(id)]
+            if addr == &governance_address()
+                && prefix == PROPOSAL_STORAGE_KEY =>
+        {
+            id.parse().ok()
+        }
+        _ => None,
+    }
+}
+
+/// Check if the given storage key is a vote key. If it is, returns the
+/// proposal's identifier and the voter.
+pub fn is_vote_key(key: &Key) -> Option<(u64, &Address)> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(prefix), DbKeySeg::StringSeg(id), DbKeySeg::StringSeg(vote), DbKeySeg::AddressSeg(voter)]
+            if addr == &governance_address()
+                && prefix == PROPOSAL_STORAGE_KEY
+                && vote == VOTE_STORAGE_KEY =>
+        {
+            id.parse().ok().map(|id| (id, voter))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::address;
+
+    #[test]
+    fn test_governance_keys() {
+        let voter = address::testing::established_address_1();
+        assert_eq!(is_proposal_key(&proposal_key(10)), Some(10));
+        assert_eq!(is_vote_key(&vote_key(10, &voter)), Some((10, &voter)));
+        assert_eq!(is_proposal_key(&vote_key(10, &voter)), None);
+        assert_eq!(is_vote_key(&proposal_key(10)), None);
+        assert_eq!(is_proposal_key(&result_key(10)), None);
+        assert_eq!(is_vote_key(&result_key(10)), None);
+    }
+
+    #[test]
+    fn test_tally() {
+        let tally = |yay: u64, nay: u64| Tally {
+            yay: Amount::from(yay),
+            nay: Amount::from(nay),
+        };
+        let total = Amount::from(90);
+        assert!(tally(30, 0).is_passed(total));
+        assert!(tally(21, 10).is_passed(total));
+        // no quorum
+        assert!(!tally(29, 0).is_passed(total));
+        // exactly 2/3 in favor is not enough
+        assert!(!tally(20, 10).is_passed(total));
+        assert!(!tally(0, 0).is_passed(Amount::from(0)));
+    }
+}
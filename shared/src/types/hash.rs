@@ -0,0 +1,57 @@
+//! Hash functions that are also exposed to the wasm guests via host functions.
+
+use blake2::digest::{Update, VariableOutput};
+use blake2::VarBlake2b;
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+
+/// The length of the hashes produced by the functions in this module
+pub const HASH_LENGTH: usize = 32;
+
+/// A 32 bytes hash
+pub type Hash = [u8; HASH_LENGTH];
+
+/// Compute a SHA-256 hash of the given data
+pub fn sha256(data: &[u8]) -> Hash {
+    let mut hash = [0; HASH_LENGTH];
+    hash.copy_from_slice(&Sha256::digest(data));
+    hash
+}
+
+/// Compute a Blake2b hash of the given data with 32 bytes output
+pub fn blake2b(data: &[u8]) -> Hash {
+    let mut hasher = VarBlake2b::new(HASH_LENGTH)
+        .expect("The hash length should be valid for Blake2b");
+    hasher.update(data);
+    let mut hash = [0; HASH_LENGTH];
+    hasher.finalize_variable(|res| hash.copy_from_slice(res));
+    hash
+}
+
+/// Compute a Keccak-256 hash of the given data
+pub fn keccak256(data: &[u8]) -> Hash {
+    let mut hash = [0; HASH_LENGTH];
+    hash.copy_from_slice(&Keccak256::digest(data));
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hashes_of_empty_input() {
+        assert_eq!(
+            hex::encode(sha256(&[])),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            hex::encode(blake2b(&[])),
+            "0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8"
+        );
+        assert_eq!(
+            hex::encode(keccak256(&[])),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+    }
+}
@@ -10,7 +10,8 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::types::address::Address;
-use crate::types::key::ed25519::Signed;
+use crate::types::hash::{self, Hash};
+use crate::types::key::common::Signed;
 use crate::types::storage::{DbKeySeg, Key, KeySeg};
 use crate::types::token;
 
@@ -197,8 +198,18 @@ pub fn invalid_intent_key(owner: &Address) -> Key {
         .expect("Cannot obtain a storage key")
 }
 
-/// Check if the given storage key is a key for a set of intent sig. If it is,
-/// returns the owner.
+/// Compute the hash of an exchange's signed data. Matched intents are
+/// invalidated by this hash rather than by their signature, because a valid
+/// signature may be re-encoded into another valid signature of the same data.
+pub fn exchange_hash(exchange: &Exchange) -> Hash {
+    let bytes = exchange
+        .try_to_vec()
+        .expect("Encoding an exchange shouldn't fail");
+    hash::sha256(&bytes)
+}
+
+/// Check if the given storage key is a key for a set of invalidated intents'
+/// hashes. If it is, returns the owner.
 pub fn is_invalid_intent_key(key: &Key) -> Option<&Address> {
     match &key.segments[..] {
         [DbKeySeg::AddressSeg(owner), DbKeySeg::StringSeg(key)]
@@ -0,0 +1,583 @@
+//! Keys and signatures of any of the supported signature schemes
+
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{ed25519, secp256k1};
+use crate::proto::Tx;
+use crate::types::address::{Address, ImplicitAddress};
+use crate::types::storage::{DbKeySeg, Key, KeySeg};
+
+/// Public key of any of the supported signature schemes
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    PartialOrd,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub enum PublicKey {
+    /// Ed25519 public key
+    Ed25519(ed25519::PublicKey),
+    /// Secp256k1 public key
+    Secp256k1(secp256k1::PublicKey),
+}
+
+/// Signature of any of the supported signature schemes
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    PartialEq,
+    PartialOrd,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub enum Signature {
+    /// Ed25519 signature
+    Ed25519(ed25519::Signature),
+    /// Secp256k1 signature
+    Secp256k1(secp256k1::Signature),
+}
+
+/// A keypair of any of the supported signature schemes that can be used to
+/// sign data.
+pub trait SigningKey {
+    /// The public key of this keypair
+    fn public_key(&self) -> PublicKey;
+
+    /// Sign the given bytes
+    fn sign_bytes(&self, data: &[u8]) -> Signature;
+}
+
+impl SigningKey for ed25519::Keypair {
+    fn public_key(&self) -> PublicKey {
+        PublicKey::Ed25519(self.public.into())
+    }
+
+    fn sign_bytes(&self, data: &[u8]) -> Signature {
+        Signature::Ed25519(ed25519::sign(self, data))
+    }
+}
+
+impl SigningKey for secp256k1::Keypair {
+    fn public_key(&self) -> PublicKey {
+        PublicKey::Secp256k1(self.public.clone())
+    }
+
+    fn sign_bytes(&self, data: &[u8]) -> Signature {
+        Signature::Secp256k1(secp256k1::sign(self, data))
+    }
+}
+
+const PK_STORAGE_KEY: &str = "public_key";
+
+/// Obtain a storage key for user's public key.
+pub fn pk_key(owner: &Address) -> Key {
+    Key::from(owner.to_db_key())
+        .push(&PK_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is a public key. If it is, returns the owner.
+pub fn is_pk_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(owner), DbKeySeg::StringSeg(key)]
+            if key == PK_STORAGE_KEY =>
+        {
+            Some(owner)
+        }
+        _ => None,
+    }
+}
+
+/// Sign the data with a key.
+pub fn sign(keypair: &impl SigningKey, data: impl AsRef<[u8]>) -> Signature {
+    keypair.sign_bytes(data.as_ref())
+}
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum VerifySigError {
+    #[error("Ed25519 {0}")]
+    Ed25519(ed25519::VerifySigError),
+    #[error("Secp256k1 {0}")]
+    Secp256k1(secp256k1::VerifySigError),
+    #[error(
+        "Signature verification failed: the signature scheme doesn't match \
+         the public key"
+    )]
+    MismatchedScheme,
+    #[error("Signature verification failed to encode the data: {0}")]
+    EncodingError(std::io::Error),
+    #[error("Signature verification failed: the transaction has no data")]
+    MissingData,
+    #[error(
+        "Signature verification failed to decode the signed transaction data: \
+         {0}"
+    )]
+    SignedDataDecoding(std::io::Error),
+}
+
+/// Check that the public key matches the signature on the given data.
+pub fn verify_signature<T: BorshSerialize + BorshDeserialize>(
+    pk: &PublicKey,
+    data: &T,
+    sig: &Signature,
+) -> Result<(), VerifySigError> {
+    let bytes = data.try_to_vec().map_err(VerifySigError::EncodingError)?;
+    verify_signature_raw(pk, &bytes, sig)
+}
+
+/// Check that the public key matches the signature on the given raw data.
+pub fn verify_signature_raw(
+    pk: &PublicKey,
+    data: &[u8],
+    sig: &Signature,
+) -> Result<(), VerifySigError> {
+    match (pk, sig) {
+        (PublicKey::Ed25519(pk), Signature::Ed25519(sig)) => {
+            ed25519::verify_signature_raw(pk, data, sig)
+                .map_err(VerifySigError::Ed25519)
+        }
+        (PublicKey::Secp256k1(pk), Signature::Secp256k1(sig)) => {
+            secp256k1::verify_signature_raw(pk, data, sig)
+                .map_err(VerifySigError::Secp256k1)
+        }
+        _ => Err(VerifySigError::MismatchedScheme),
+    }
+}
+
+/// Check that every public key in the list matches its signature on the
+/// given raw data. The Ed25519 signatures are verified in a single batch and
+/// the others one by one.
+pub fn verify_all_signatures_raw(
+    sigs: &[(PublicKey, Vec<u8>, Signature)],
+) -> Result<(), VerifySigError> {
+    let mut ed25519_sigs = Vec::with_capacity(sigs.len());
+    for (pk, data, sig) in sigs {
+        match (pk, sig) {
+            (PublicKey::Ed25519(pk), Signature::Ed25519(sig)) => {
+                ed25519_sigs.push((pk, &data[..], sig))
+            }
+            _ => verify_signature_raw(pk, data, sig)?,
+        }
+    }
+    ed25519::verify_signatures_batch_raw(&ed25519_sigs)
+        .map_err(VerifySigError::Ed25519)
+}
+
+/// This can be used to sign an arbitrary tx. The signature is produced and
+/// verified on the tx data concatenated with the tx code, however the tx code
+/// itself is not part of this structure.
+///
+/// Because the signature is not checked by the ledger, we don't inline it into
+/// the `Tx` type directly. Instead, the signature is attached to the `tx.data`,
+/// which is can then be checked by a validity predicate wasm.
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
+pub enum SignedTxData {
+    /// A transaction signed by a single key
+    Single {
+        /// The original tx data bytes, if any
+        data: Option<Vec<u8>>,
+        /// The signature is produced on the tx data concatenated with the tx
+        /// code and the timestamp.
+        sig: Signature,
+    },
+    /// A transaction signed by multiple keys, e.g. for a multisig account.
+    /// See [`super::multisig`].
+    Multi {
+        /// The original tx data bytes, if any
+        data: Option<Vec<u8>>,
+        /// The signatures with the public keys that produced them. Each
+        /// signature is produced on the same bytes as in the
+        /// [`SignedTxData::Single`] variant.
+        sigs: Vec<(PublicKey, Signature)>,
+    },
+}
+
+impl SignedTxData {
+    /// Get a reference to the original tx data bytes, if any
+    pub fn data(&self) -> Option<&Vec<u8>> {
+        match self {
+            SignedTxData::Single { data, .. }
+            | SignedTxData::Multi { data, .. } => data.as_ref(),
+        }
+    }
+
+    /// Take the original tx data bytes, if any
+    pub fn into_data(self) -> Option<Vec<u8>> {
+        match self {
+            SignedTxData::Single { data, .. }
+            | SignedTxData::Multi { data, .. } => data,
+        }
+    }
+}
+
+/// Sign a transaction using [`SignedTxData`].
+pub fn sign_tx(keypair: &impl SigningKey, tx: Tx) -> Tx {
+    let to_sign = tx.to_bytes();
+    let sig = sign(keypair, &to_sign);
+    let signed = SignedTxData::Single { data: tx.data, sig }
+        .try_to_vec()
+        .expect("Encoding transaction data shouldn't fail");
+    Tx {
+        code: tx.code,
+        data: Some(signed),
+        timestamp: tx.timestamp,
+    }
+}
+
+/// Verify that the transaction has been signed by the secret key
+/// counterpart of the given public key.
+pub fn verify_tx_sig(
+    pk: &PublicKey,
+    tx: &Tx,
+    sig: &Signature,
+) -> Result<(), VerifySigError> {
+    // revert the transaction data
+    let mut tx = tx.clone();
+    let tx_data = tx.data.ok_or(VerifySigError::MissingData)?;
+    let signed_tx_data = SignedTxData::try_from_slice(&tx_data[..])
+        .map_err(VerifySigError::SignedDataDecoding)?;
+    tx.data = signed_tx_data.into_data();
+    let data = tx.to_bytes();
+    verify_signature_raw(pk, &data, sig)
+}
+
+/// Verify every signature of a transaction signed by multiple keys with
+/// [`SignedTxData::Multi`] against its public key, regardless of whether the
+/// key is authorized by the signer's account, which is left to the account's
+/// validity predicate. A transaction with any other data is accepted, because
+/// its signature cannot be verified without the signer's public key.
+pub fn verify_tx_multi_sigs(tx: &Tx) -> Result<(), VerifySigError> {
+    let signed_tx_data = tx
+        .data
+        .as_ref()
+        .and_then(|data| SignedTxData::try_from_slice(&data[..]).ok());
+    let (data, sigs) = match signed_tx_data {
+        Some(SignedTxData::Multi { data, sigs }) => (data, sigs),
+        _ => return Ok(()),
+    };
+    // revert the transaction data
+    let mut tx = tx.clone();
+    tx.data = data;
+    let data = tx.to_bytes();
+    let sigs: Vec<_> = sigs
+        .into_iter()
+        .map(|(pk, sig)| (pk, data.clone(), sig))
+        .collect();
+    verify_all_signatures_raw(&sigs)
+}
+
+/// A generic signed data wrapper for Borsh encode-able data.
+#[derive(
+    Clone, Debug, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
+)]
+pub struct Signed<T: BorshSerialize + BorshDeserialize> {
+    /// Arbitrary data to be signed
+    pub data: T,
+    /// The signature of the data
+    pub sig: Signature,
+}
+
+impl<T> PartialEq for Signed<T>
+where
+    T: BorshSerialize + BorshDeserialize + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data && self.sig == other.sig
+    }
+}
+
+impl<T> Eq for Signed<T> where
+    T: BorshSerialize + BorshDeserialize + Eq + PartialEq
+{
+}
+
+impl<T> Hash for Signed<T>
+where
+    T: BorshSerialize + BorshDeserialize + Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.data.hash(state);
+        self.sig.hash(state);
+    }
+}
+
+impl<T> PartialOrd for Signed<T>
+where
+    T: BorshSerialize + BorshDeserialize + PartialOrd,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.data.partial_cmp(&other.data)
+    }
+}
+
+impl<T> Signed<T>
+where
+    T: BorshSerialize + BorshDeserialize,
+{
+    /// Initialize a new signed data.
+    pub fn new(keypair: &impl SigningKey, data: T) -> Self {
+        let to_sign = data
+            .try_to_vec()
+            .expect("Encoding data for signing shouldn't fail");
+        let sig = sign(keypair, &to_sign);
+        Self { data, sig }
+    }
+
+    /// Verify that the data has been signed by the secret key
+    /// counterpart of the given public key.
+    pub fn verify(&self, pk: &PublicKey) -> Result<(), VerifySigError> {
+        let bytes = self
+            .data
+            .try_to_vec()
+            .expect("Encoding data for verifying signature shouldn't fail");
+        verify_signature_raw(pk, &bytes, &self.sig)
+    }
+}
+
+impl Display for PublicKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let vec = self
+            .try_to_vec()
+            .expect("Encoding public key shouldn't fail");
+        write!(f, "{}", hex::encode(&vec))
+    }
+}
+
+impl FromStr for PublicKey {
+    type Err = ParsePublicKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let vec = hex::decode(s).map_err(ParsePublicKeyError::InvalidHex)?;
+        BorshDeserialize::try_from_slice(&vec)
+            .map_err(ParsePublicKeyError::InvalidEncoding)
+    }
+}
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum ParsePublicKeyError {
+    #[error("Invalid public key hex: {0}")]
+    InvalidHex(hex::FromHexError),
+    #[error("Invalid public key encoding: {0}")]
+    InvalidEncoding(std::io::Error),
+}
+
+impl From<ed25519::PublicKey> for PublicKey {
+    fn from(pk: ed25519::PublicKey) -> Self {
+        Self::Ed25519(pk)
+    }
+}
+
+impl From<secp256k1::PublicKey> for PublicKey {
+    fn from(pk: secp256k1::PublicKey) -> Self {
+        Self::Secp256k1(pk)
+    }
+}
+
+impl From<ed25519::Signature> for Signature {
+    fn from(sig: ed25519::Signature) -> Self {
+        Self::Ed25519(sig)
+    }
+}
+
+impl From<secp256k1::Signature> for Signature {
+    fn from(sig: secp256k1::Signature) -> Self {
+        Self::Secp256k1(sig)
+    }
+}
+
+impl From<&PublicKey> for ImplicitAddress {
+    fn from(pk: &PublicKey) -> Self {
+        match pk {
+            PublicKey::Ed25519(pk) => Self::Ed25519(pk.clone().into()),
+            PublicKey::Secp256k1(pk) => Self::Secp256k1(pk.clone().into()),
+        }
+    }
+}
+
+impl From<&PublicKey> for Address {
+    fn from(pk: &PublicKey) -> Self {
+        Self::Implicit(pk.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify_any_scheme() {
+        let ed25519_keypair = ed25519::testing::keypair_1();
+        let secp256k1_keypair = secp256k1::testing::keypair_1();
+        let data = vec![1, 2, 3, 4].repeat(10);
+
+        let ed25519_pk = ed25519_keypair.public_key();
+        let ed25519_sig = sign(&ed25519_keypair, &data);
+        assert!(verify_signature_raw(&ed25519_pk, &data, &ed25519_sig).is_ok());
+
+        let secp256k1_pk = secp256k1_keypair.public_key();
+        let secp256k1_sig = sign(&secp256k1_keypair, &data);
+        assert!(
+            verify_signature_raw(&secp256k1_pk, &data, &secp256k1_sig).is_ok()
+        );
+
+        assert!(matches!(
+            verify_signature_raw(&ed25519_pk, &data, &secp256k1_sig),
+            Err(VerifySigError::MismatchedScheme)
+        ));
+        assert!(matches!(
+            verify_signature_raw(&secp256k1_pk, &data, &ed25519_sig),
+            Err(VerifySigError::MismatchedScheme)
+        ));
+    }
+
+    /// Test that a batch of signatures of both schemes is verified and that
+    /// it's rejected if any one of them is bad.
+    #[test]
+    fn test_verify_all_signatures() {
+        let data = vec![1, 2, 3, 4].repeat(10);
+        let other_data = vec![4, 3, 2, 1];
+        let ed25519_keypairs =
+            [ed25519::testing::keypair_1(), ed25519::testing::keypair_2()];
+        let secp256k1_keypair = secp256k1::testing::keypair_1();
+        let mut sigs: Vec<(PublicKey, Vec<u8>, Signature)> = ed25519_keypairs
+            .iter()
+            .map(|keypair| {
+                (keypair.public_key(), data.clone(), sign(keypair, &data))
+            })
+            .collect();
+        sigs.push((
+            secp256k1_keypair.public_key(),
+            other_data.clone(),
+            sign(&secp256k1_keypair, &other_data),
+        ));
+        assert!(verify_all_signatures_raw(&sigs).is_ok());
+        assert!(verify_all_signatures_raw(&[]).is_ok());
+
+        // One bad Ed25519 signature in the batch
+        let mut bad_ed25519 = sigs.clone();
+        bad_ed25519[1].2 = sign(&ed25519_keypairs[0], &data);
+        assert!(matches!(
+            verify_all_signatures_raw(&bad_ed25519),
+            Err(VerifySigError::Ed25519(_))
+        ));
+
+        // One Ed25519 signature of different data in the batch
+        let mut bad_data = sigs.clone();
+        bad_data[0].1 = other_data.clone();
+        assert!(matches!(
+            verify_all_signatures_raw(&bad_data),
+            Err(VerifySigError::Ed25519(_))
+        ));
+
+        // One bad Secp256k1 signature
+        let mut bad_secp256k1 = sigs;
+        bad_secp256k1[2].2 = sign(&secp256k1_keypair, &data);
+        assert!(matches!(
+            verify_all_signatures_raw(&bad_secp256k1),
+            Err(VerifySigError::Secp256k1(_))
+        ));
+    }
+
+    #[test]
+    fn test_signed_tx_data_any_scheme() {
+        fn sign_and_verify(keypair: &impl SigningKey) {
+            let tx = Tx::new(vec![4, 3, 2, 1, 0], Some(vec![1, 2, 3, 4]));
+            let signed = sign_tx(keypair, tx.clone());
+            let signed_data =
+                SignedTxData::try_from_slice(&signed.data.clone().unwrap()[..])
+                    .unwrap();
+            let sig = match &signed_data {
+                SignedTxData::Single { sig, .. } => sig.clone(),
+                SignedTxData::Multi { .. } => panic!("expected a single sig"),
+            };
+            assert_eq!(signed_data.into_data(), tx.data);
+            assert!(verify_tx_sig(&keypair.public_key(), &signed, &sig).is_ok());
+        }
+        sign_and_verify(&ed25519::testing::keypair_1());
+        sign_and_verify(&secp256k1::testing::keypair_1());
+    }
+
+    /// Test that the signature of a transaction without data is verified and
+    /// that a transaction without signed data is rejected without panicking.
+    #[test]
+    fn test_verify_tx_sig_data() {
+        let keypair = ed25519::testing::keypair_1();
+        let pk = keypair.public_key();
+        let tx = Tx::new(vec![4, 3, 2, 1, 0], None);
+        let sig = sign(&keypair, tx.to_bytes());
+        let signed = sign_tx(&keypair, tx.clone());
+        assert!(verify_tx_sig(&pk, &signed, &sig).is_ok());
+
+        assert!(matches!(
+            verify_tx_sig(&pk, &tx, &sig),
+            Err(VerifySigError::MissingData)
+        ));
+        let junk = Tx {
+            data: Some(vec![0xff; 3]),
+            ..tx
+        };
+        assert!(matches!(
+            verify_tx_sig(&pk, &junk, &sig),
+            Err(VerifySigError::SignedDataDecoding(_))
+        ));
+    }
+
+    /// Test that all the signatures of a multisig transaction must be valid,
+    /// while other transactions are left to their signers' VPs.
+    #[test]
+    fn test_verify_tx_multi_sigs() {
+        use super::super::multisig;
+
+        let ed25519_keypair = ed25519::testing::keypair_1();
+        let secp256k1_keypair = secp256k1::testing::keypair_1();
+        let tx = Tx::new(vec![4, 3, 2, 1, 0], Some(vec![1, 2, 3, 4]));
+        let sigs = vec![
+            multisig::sign_tx_offline(&ed25519_keypair, &tx),
+            multisig::sign_tx_offline(&secp256k1_keypair, &tx),
+        ];
+        let signed = multisig::combine_signatures(tx.clone(), sigs.clone());
+        assert!(verify_tx_multi_sigs(&signed).is_ok());
+
+        // One signature of other data
+        let other_tx = Tx::new(vec![4, 3, 2, 1, 0], Some(vec![4, 3, 2, 1]));
+        let mut bad_sigs = sigs;
+        bad_sigs[0] = multisig::sign_tx_offline(&ed25519_keypair, &other_tx);
+        let signed = multisig::combine_signatures(tx.clone(), bad_sigs);
+        assert!(verify_tx_multi_sigs(&signed).is_err());
+
+        // A single signature can't be verified without the signer's key
+        let signed = sign_tx(&ed25519::testing::keypair_2(), tx.clone());
+        assert!(verify_tx_multi_sigs(&signed).is_ok());
+        assert!(verify_tx_multi_sigs(&tx).is_ok());
+    }
+
+    #[test]
+    fn test_public_key_string_roundtrip() {
+        let pks = [
+            ed25519::testing::keypair_1().public_key(),
+            secp256k1::testing::keypair_1().public_key(),
+        ];
+        for pk in pks.iter() {
+            let decoded: PublicKey = pk.to_string().parse().unwrap();
+            assert_eq!(&decoded, pk);
+        }
+    }
+}
@@ -13,9 +13,7 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use thiserror::Error;
 
-use crate::proto::Tx;
-use crate::types::address::{self, Address};
-use crate::types::storage::{DbKeySeg, Key, KeySeg};
+use crate::types::address;
 
 const SIGNATURE_LEN: usize = ed25519_dalek::SIGNATURE_LENGTH;
 
@@ -47,27 +45,6 @@ pub struct Signature(ed25519_dalek::Signature);
 )]
 pub struct PublicKeyHash(pub(crate) String);
 
-const PK_STORAGE_KEY: &str = "ed25519_pk";
-
-/// Obtain a storage key for user's public key.
-pub fn pk_key(owner: &Address) -> Key {
-    Key::from(owner.to_db_key())
-        .push(&PK_STORAGE_KEY.to_owned())
-        .expect("Cannot obtain a storage key")
-}
-
-/// Check if the given storage key is a public key. If it is, returns the owner.
-pub fn is_pk_key(key: &Key) -> Option<&Address> {
-    match &key.segments[..] {
-        [DbKeySeg::AddressSeg(owner), DbKeySeg::StringSeg(key)]
-            if key == PK_STORAGE_KEY =>
-        {
-            Some(owner)
-        }
-        _ => None,
-    }
-}
-
 /// Sign the data with a key.
 pub fn sign(keypair: &Keypair, data: impl AsRef<[u8]>) -> Signature {
     Signature(keypair.sign(data.as_ref()))
@@ -103,118 +80,27 @@ pub fn verify_signature_raw(
         .map_err(VerifySigError::SigError)
 }
 
-/// This can be used to sign an arbitrary tx. The signature is produced and
-/// verified on the tx data concatenated with the tx code, however the tx code
-/// itself is not part of this structure.
-///
-/// Because the signature is not checked by the ledger, we don't inline it into
-/// the `Tx` type directly. Instead, the signature is attached to the `tx.data`,
-/// which is can then be checked by a validity predicate wasm.
-#[derive(Clone, Debug, BorshSerialize, BorshDeserialize)]
-pub struct SignedTxData {
-    /// The original tx data bytes, if any
-    pub data: Option<Vec<u8>>,
-    /// The signature is produced on the tx data concatenated with the tx code
-    /// and the timestamp.
-    pub sig: Signature,
-}
-
-/// Sign a transaction using [`SignedTxData`].
-pub fn sign_tx(keypair: &Keypair, tx: Tx) -> Tx {
-    let to_sign = tx.to_bytes();
-    let sig = sign(keypair, &to_sign);
-    let signed = SignedTxData { data: tx.data, sig }
-        .try_to_vec()
-        .expect("Encoding transaction data shouldn't fail");
-    Tx {
-        code: tx.code,
-        data: Some(signed),
-        timestamp: tx.timestamp,
-    }
-}
-
-/// Verify that the transaction has been signed by the secret key
-/// counterpart of the given public key.
-pub fn verify_tx_sig(
-    pk: &PublicKey,
-    tx: &Tx,
-    sig: &Signature,
+/// Check that every public key matches its signature on the given raw data
+/// with a single batch verification. Unlike [`verify_signature_raw`], the
+/// batch verification doesn't reject weak public keys, so it should only be
+/// used for the signatures of the same transaction. A single signature is
+/// verified strictly.
+pub fn verify_signatures_batch_raw(
+    sigs: &[(&PublicKey, &[u8], &Signature)],
 ) -> Result<(), VerifySigError> {
-    // revert the transaction data
-    let mut tx = tx.clone();
-    let tx_data = tx.data.expect("signed data should exist");
-    let signed_tx_data = SignedTxData::try_from_slice(&tx_data[..])
-        .expect("Decoding transaction data shouldn't fail");
-    tx.data = Some(signed_tx_data.data.expect("data should exist"));
-    let data = tx.to_bytes();
-    verify_signature_raw(pk, &data, sig)
-}
-
-/// A generic signed data wrapper for Borsh encode-able data.
-#[derive(
-    Clone, Debug, BorshSerialize, BorshDeserialize, Serialize, Deserialize,
-)]
-pub struct Signed<T: BorshSerialize + BorshDeserialize> {
-    /// Arbitrary data to be signed
-    pub data: T,
-    /// The signature of the data
-    pub sig: Signature,
-}
-
-impl<T> PartialEq for Signed<T>
-where
-    T: BorshSerialize + BorshDeserialize + PartialEq,
-{
-    fn eq(&self, other: &Self) -> bool {
-        self.data == other.data && self.sig == other.sig
-    }
-}
-
-impl<T> Eq for Signed<T> where
-    T: BorshSerialize + BorshDeserialize + Eq + PartialEq
-{
-}
-
-impl<T> Hash for Signed<T>
-where
-    T: BorshSerialize + BorshDeserialize + Hash,
-{
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.data.hash(state);
-        self.sig.hash(state);
-    }
-}
-
-impl<T> PartialOrd for Signed<T>
-where
-    T: BorshSerialize + BorshDeserialize + PartialOrd,
-{
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.data.partial_cmp(&other.data)
-    }
-}
-
-impl<T> Signed<T>
-where
-    T: BorshSerialize + BorshDeserialize,
-{
-    /// Initialize a new signed data.
-    pub fn new(keypair: &Keypair, data: T) -> Self {
-        let to_sign = data
-            .try_to_vec()
-            .expect("Encoding data for signing shouldn't fail");
-        let sig = sign(keypair, &to_sign);
-        Self { data, sig }
-    }
-
-    /// Verify that the data has been signed by the secret key
-    /// counterpart of the given public key.
-    pub fn verify(&self, pk: &PublicKey) -> Result<(), VerifySigError> {
-        let bytes = self
-            .data
-            .try_to_vec()
-            .expect("Encoding data for verifying signature shouldn't fail");
-        verify_signature_raw(pk, &bytes, &self.sig)
+    match sigs {
+        [] => Ok(()),
+        [(pk, data, sig)] => verify_signature_raw(pk, data, sig),
+        _ => {
+            let messages: Vec<&[u8]> =
+                sigs.iter().map(|(_pk, data, _sig)| *data).collect();
+            let signatures: Vec<ed25519_dalek::Signature> =
+                sigs.iter().map(|(_pk, _data, sig)| sig.0).collect();
+            let public_keys: Vec<ed25519_dalek::PublicKey> =
+                sigs.iter().map(|(pk, _data, _sig)| pk.0).collect();
+            ed25519_dalek::verify_batch(&messages, &signatures, &public_keys)
+                .map_err(VerifySigError::SigError)
+        }
     }
 }
 
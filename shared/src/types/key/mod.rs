@@ -1,3 +1,6 @@
 //! Cryptographic keys
 
+pub mod common;
 pub mod ed25519;
+pub mod multisig;
+pub mod secp256k1;
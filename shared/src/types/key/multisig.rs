@@ -0,0 +1,200 @@
+//! Multisignature (k-of-n) accounts.
+//!
+//! A multisig account stores a set of public keys and a threshold in its
+//! storage. A transaction is authorized by the account when it carries a
+//! [`SignedTxData::Multi`] with valid signatures from at least `threshold`
+//! distinct keys from the set.
+//!
+//! The signatures can be collected offline: an unsigned transaction is first
+//! built and shared with the signers, each of whom signs it with
+//! [`sign_tx_offline`]. The signatures are then put together with
+//! [`combine_signatures`] and the resulting transaction can be submitted.
+
+use std::collections::HashSet;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use super::common::{self, PublicKey, Signature, SignedTxData, SigningKey};
+use crate::proto::Tx;
+use crate::types::address::Address;
+use crate::types::storage::{DbKeySeg, Key, KeySeg};
+
+const PKS_STORAGE_KEY: &str = "public_keys";
+const THRESHOLD_STORAGE_KEY: &str = "threshold";
+
+/// Obtain a storage key for a multisig account's public keys.
+pub fn pks_key(owner: &Address) -> Key {
+    Key::from(owner.to_db_key())
+        .push(&PKS_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is a multisig account's public keys. If it
+/// is, returns the owner.
+pub fn is_pks_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(owner), DbKeySeg::StringSeg(key)]
+            if key == PKS_STORAGE_KEY =>
+        {
+            Some(owner)
+        }
+        _ => None,
+    }
+}
+
+/// Obtain a storage key for a multisig account's signature threshold.
+pub fn threshold_key(owner: &Address) -> Key {
+    Key::from(owner.to_db_key())
+        .push(&THRESHOLD_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is a multisig account's signature
+/// threshold. If it is, returns the owner.
+pub fn is_threshold_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(owner), DbKeySeg::StringSeg(key)]
+            if key == THRESHOLD_STORAGE_KEY =>
+        {
+            Some(owner)
+        }
+        _ => None,
+    }
+}
+
+/// Check that the set of public keys and the threshold can be used for a
+/// multisig account, i.e. the keys are unique and the threshold is at least 1
+/// and no more than the number of keys.
+pub fn is_valid_config(public_keys: &[PublicKey], threshold: u64) -> bool {
+    let unique: HashSet<&PublicKey> = public_keys.iter().collect();
+    unique.len() == public_keys.len()
+        && threshold >= 1
+        && threshold <= public_keys.len() as u64
+}
+
+/// Sign an unsigned transaction with one of the multisig account's keys. The
+/// returned public key and signature can be passed to [`combine_signatures`].
+pub fn sign_tx_offline(
+    keypair: &impl SigningKey,
+    tx: &Tx,
+) -> (PublicKey, Signature) {
+    let sig = common::sign(keypair, tx.to_bytes());
+    (keypair.public_key(), sig)
+}
+
+/// Attach the signatures collected with [`sign_tx_offline`] to the unsigned
+/// transaction using [`SignedTxData::Multi`].
+pub fn combine_signatures(tx: Tx, sigs: Vec<(PublicKey, Signature)>) -> Tx {
+    let signed = SignedTxData::Multi {
+        data: tx.data,
+        sigs,
+    }
+    .try_to_vec()
+    .expect("Encoding transaction data shouldn't fail");
+    Tx {
+        code: tx.code,
+        data: Some(signed),
+        timestamp: tx.timestamp,
+    }
+}
+
+/// Count the signatures that are valid according to the given `verify`
+/// function and that have been produced by distinct keys from the given set
+/// of public keys.
+pub fn count_valid_signatures(
+    public_keys: &[PublicKey],
+    sigs: &[(PublicKey, Signature)],
+    verify: impl Fn(&PublicKey, &Signature) -> bool,
+) -> u64 {
+    let mut signers: HashSet<&PublicKey> = HashSet::new();
+    for (pk, sig) in sigs {
+        if public_keys.contains(pk) && !signers.contains(pk) && verify(pk, sig)
+        {
+            signers.insert(pk);
+        }
+    }
+    signers.len() as u64
+}
+
+/// Verify that a transaction has been signed by at least `threshold` distinct
+/// keys from the given set of public keys.
+pub fn verify_tx_sigs(
+    public_keys: &[PublicKey],
+    threshold: u64,
+    tx: &Tx,
+) -> bool {
+    let sigs = match tx
+        .data
+        .as_ref()
+        .and_then(|data| SignedTxData::try_from_slice(&data[..]).ok())
+    {
+        Some(SignedTxData::Multi { sigs, .. }) => sigs,
+        _ => return false,
+    };
+    let valid = count_valid_signatures(public_keys, &sigs, |pk, sig| {
+        common::verify_tx_sig(pk, tx, sig).is_ok()
+    });
+    valid >= threshold
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{ed25519, secp256k1};
+    use super::*;
+
+    #[test]
+    fn test_valid_config() {
+        let pk_1 = ed25519::testing::keypair_1().public_key();
+        let pk_2 = secp256k1::testing::keypair_1().public_key();
+        let pks = vec![pk_1.clone(), pk_2];
+        assert!(is_valid_config(&pks, 1));
+        assert!(is_valid_config(&pks, 2));
+        assert!(!is_valid_config(&pks, 0));
+        assert!(!is_valid_config(&pks, 3));
+        assert!(!is_valid_config(&[pk_1.clone(), pk_1], 1));
+    }
+
+    #[test]
+    fn test_threshold_signatures() {
+        let keypair_1 = ed25519::testing::keypair_1();
+        let keypair_2 = ed25519::testing::keypair_2();
+        let keypair_3 = secp256k1::testing::keypair_1();
+        let outsider = secp256k1::testing::keypair_2();
+        let pks = vec![
+            keypair_1.public_key(),
+            keypair_2.public_key(),
+            keypair_3.public_key(),
+        ];
+        let tx = Tx::new(vec![4, 3, 2, 1, 0], Some(vec![1, 2, 3, 4]));
+
+        let sig_1 = sign_tx_offline(&keypair_1, &tx);
+        let sig_3 = sign_tx_offline(&keypair_3, &tx);
+        let sig_outsider = sign_tx_offline(&outsider, &tx);
+
+        // 2 distinct valid signatures
+        let signed = combine_signatures(tx.clone(), vec![sig_1.clone(), sig_3]);
+        assert!(verify_tx_sigs(&pks, 2, &signed));
+        assert!(!verify_tx_sigs(&pks, 3, &signed));
+
+        // A repeated signature is only counted once
+        let signed =
+            combine_signatures(tx.clone(), vec![sig_1.clone(), sig_1.clone()]);
+        assert!(verify_tx_sigs(&pks, 1, &signed));
+        assert!(!verify_tx_sigs(&pks, 2, &signed));
+
+        // A signature from a key outside of the set is not counted
+        let signed =
+            combine_signatures(tx.clone(), vec![sig_1.clone(), sig_outsider]);
+        assert!(!verify_tx_sigs(&pks, 2, &signed));
+
+        // A signature on a different tx is not counted
+        let other_tx = Tx::new(vec![4, 3, 2, 1, 0], Some(vec![5, 6, 7, 8]));
+        let other_sig = sign_tx_offline(&keypair_2, &other_tx);
+        let signed = combine_signatures(tx.clone(), vec![sig_1, other_sig]);
+        assert!(!verify_tx_sigs(&pks, 2, &signed));
+
+        // A single signature doesn't satisfy a multisig
+        let signed = common::sign_tx(&keypair_3, tx);
+        assert!(!verify_tx_sigs(&pks, 1, &signed));
+    }
+}
@@ -0,0 +1,573 @@
+//! Secp256k1 keys and related functionality
+//!
+//! Like in Ethereum, the signed message is the Keccak-256 hash of the data and
+//! only signatures with a low `s` value are accepted. Unlike in Ethereum, the
+//! implicit address is derived from a SHA-256 hash of the compressed public
+//! key, like the implicit addresses of the other key schemes.
+
+use std::convert::TryInto;
+use std::fmt::{Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::io::{ErrorKind, Write};
+use std::str::FromStr;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::types::{address, hash};
+
+const PUBLIC_KEY_LEN: usize = 33;
+const SECRET_KEY_LEN: usize = 32;
+const SIGNATURE_LEN: usize = 64;
+
+/// Secp256k1 public key
+#[derive(Clone, Debug)]
+pub struct PublicKey(libsecp256k1::PublicKey);
+
+/// Secp256k1 secret key
+#[derive(Clone, Debug)]
+pub struct SecretKey(libsecp256k1::SecretKey);
+
+/// Secp256k1 signature
+#[derive(Clone, Debug)]
+pub struct Signature(libsecp256k1::Signature);
+
+/// Secp256k1 keypair
+#[derive(Clone, Debug)]
+pub struct Keypair {
+    /// The public half of the keypair
+    pub public: PublicKey,
+    /// The secret half of the keypair
+    pub secret: SecretKey,
+}
+
+/// Secp256k1 public key hash
+#[derive(
+    Debug,
+    Clone,
+    BorshSerialize,
+    BorshDeserialize,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+pub struct PublicKeyHash(pub(crate) String);
+
+impl Keypair {
+    /// Derive the keypair from a secret key.
+    pub fn from_secret(secret: SecretKey) -> Self {
+        let public =
+            PublicKey(libsecp256k1::PublicKey::from_secret_key(&secret.0));
+        Self { public, secret }
+    }
+
+    /// Generate a new random keypair.
+    #[cfg(feature = "rand")]
+    pub fn generate<R: rand::Rng>(rng: &mut R) -> Self {
+        let secret = SecretKey(libsecp256k1::SecretKey::random(rng));
+        Self::from_secret(secret)
+    }
+}
+
+/// The message that gets signed is the Keccak-256 hash of the data.
+fn message(data: &[u8]) -> libsecp256k1::Message {
+    libsecp256k1::Message::parse(&hash::keccak256(data))
+}
+
+/// Sign the data with a key.
+pub fn sign(keypair: &Keypair, data: impl AsRef<[u8]>) -> Signature {
+    let (sig, _recovery_id) =
+        libsecp256k1::sign(&message(data.as_ref()), &keypair.secret.0);
+    Signature(sig)
+}
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum VerifySigError {
+    #[error("Signature verification failed: invalid signature")]
+    SigError,
+    #[error("Signature verification failed to encode the data: {0}")]
+    EncodingError(std::io::Error),
+}
+
+/// Check that the public key matches the signature on the given data.
+pub fn verify_signature<T: BorshSerialize + BorshDeserialize>(
+    pk: &PublicKey,
+    data: &T,
+    sig: &Signature,
+) -> Result<(), VerifySigError> {
+    let bytes = data.try_to_vec().map_err(VerifySigError::EncodingError)?;
+    verify_signature_raw(pk, &bytes, sig)
+}
+
+/// Check that the public key matches the signature on the given raw data.
+pub fn verify_signature_raw(
+    pk: &PublicKey,
+    data: &[u8],
+    sig: &Signature,
+) -> Result<(), VerifySigError> {
+    if libsecp256k1::verify(&message(data), &sig.0, &pk.0) {
+        Ok(())
+    } else {
+        Err(VerifySigError::SigError)
+    }
+}
+
+impl BorshDeserialize for PublicKey {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        // deserialize the bytes first
+        let bytes: Vec<u8> =
+            BorshDeserialize::deserialize(buf).map_err(|e| {
+                std::io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Error decoding secp256k1 public key: {}", e),
+                )
+            })?;
+        // convert them to an expected size array
+        let bytes: [u8; PUBLIC_KEY_LEN] =
+            bytes[..].try_into().map_err(|e| {
+                std::io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Error decoding secp256k1 public key: {}", e),
+                )
+            })?;
+        libsecp256k1::PublicKey::parse_compressed(&bytes)
+            .map(PublicKey)
+            .map_err(|e| {
+                std::io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Error decoding secp256k1 public key: {:?}", e),
+                )
+            })
+    }
+}
+
+impl BorshSerialize for PublicKey {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        // We need to turn the key to bytes first..
+        let vec = self.0.serialize_compressed().to_vec();
+        // .. and then encode them with Borsh
+        let bytes = vec
+            .try_to_vec()
+            .expect("Public key bytes encoding shouldn't fail");
+        writer.write_all(&bytes)
+    }
+}
+
+impl BorshDeserialize for SecretKey {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        // deserialize the bytes first
+        let bytes: Vec<u8> =
+            BorshDeserialize::deserialize(buf).map_err(|e| {
+                std::io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Error decoding secp256k1 secret key: {}", e),
+                )
+            })?;
+        // convert them to an expected size array
+        let bytes: [u8; SECRET_KEY_LEN] =
+            bytes[..].try_into().map_err(|e| {
+                std::io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Error decoding secp256k1 secret key: {}", e),
+                )
+            })?;
+        libsecp256k1::SecretKey::parse(&bytes)
+            .map(SecretKey)
+            .map_err(|e| {
+                std::io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Error decoding secp256k1 secret key: {:?}", e),
+                )
+            })
+    }
+}
+
+impl BorshSerialize for SecretKey {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        // We need to turn the key to bytes first..
+        let vec = self.0.serialize().to_vec();
+        // .. and then encode them with Borsh
+        let bytes = vec
+            .try_to_vec()
+            .expect("Secret key bytes encoding shouldn't fail");
+        writer.write_all(&bytes)
+    }
+}
+
+impl BorshDeserialize for Signature {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        // deserialize the bytes first
+        let bytes: Vec<u8> =
+            BorshDeserialize::deserialize(buf).map_err(|e| {
+                std::io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Error decoding secp256k1 signature: {}", e),
+                )
+            })?;
+        if bytes.len() != SIGNATURE_LEN {
+            return Err(std::io::Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Error decoding secp256k1 signature: unexpected length {}",
+                    bytes.len()
+                ),
+            ));
+        }
+        let sig =
+            libsecp256k1::Signature::parse_slice(&bytes).map_err(|e| {
+                std::io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Error decoding secp256k1 signature: {:?}", e),
+                )
+            })?;
+        // The parsing reduces overflowing scalars modulo the curve order and
+        // the verification accepts `s` in either half of its range, so only
+        // the canonical low `s` encoding is accepted to make the signatures
+        // non-malleable
+        if sig.serialize()[..] != bytes[..] {
+            return Err(std::io::Error::new(
+                ErrorKind::InvalidInput,
+                "Error decoding secp256k1 signature: overflowing scalar",
+            ));
+        }
+        if sig.s.is_high() {
+            return Err(std::io::Error::new(
+                ErrorKind::InvalidInput,
+                "Error decoding secp256k1 signature: high s value",
+            ));
+        }
+        Ok(Signature(sig))
+    }
+}
+
+impl BorshSerialize for Signature {
+    fn serialize<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        // We need to turn the signature to bytes first..
+        let vec = self.0.serialize().to_vec();
+        // .. and then encode them with Borsh
+        let bytes = vec
+            .try_to_vec()
+            .expect("Signature bytes encoding shouldn't fail");
+        writer.write_all(&bytes)
+    }
+}
+
+impl PartialEq for PublicKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.serialize_compressed() == other.0.serialize_compressed()
+    }
+}
+
+impl Eq for PublicKey {}
+
+#[allow(clippy::derive_hash_xor_eq)]
+impl Hash for PublicKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.try_to_vec()
+            .expect("Encoding public key shouldn't fail")
+            .hash(state);
+    }
+}
+
+impl PartialOrd for PublicKey {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.try_to_vec()
+            .expect("Encoding public key shouldn't fail")
+            .partial_cmp(
+                &other
+                    .try_to_vec()
+                    .expect("Encoding public key shouldn't fail"),
+            )
+    }
+}
+
+impl Display for PublicKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let vec = self
+            .try_to_vec()
+            .expect("Encoding public key shouldn't fail");
+        write!(f, "{}", hex::encode(&vec))
+    }
+}
+
+impl FromStr for PublicKey {
+    type Err = ParsePublicKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let vec = hex::decode(s).map_err(ParsePublicKeyError::InvalidHex)?;
+        BorshDeserialize::try_from_slice(&vec)
+            .map_err(ParsePublicKeyError::InvalidEncoding)
+    }
+}
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum ParsePublicKeyError {
+    #[error("Invalid public key hex: {0}")]
+    InvalidHex(hex::FromHexError),
+    #[error("Invalid public key encoding: {0}")]
+    InvalidEncoding(std::io::Error),
+}
+
+impl Serialize for PublicKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for PublicKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let string = String::deserialize(deserializer)?;
+        Self::from_str(&string).map_err(de::Error::custom)
+    }
+}
+
+impl Display for SecretKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let vec = self
+            .try_to_vec()
+            .expect("Encoding secret key shouldn't fail");
+        write!(f, "{}", hex::encode(&vec))
+    }
+}
+
+impl FromStr for SecretKey {
+    type Err = ParseSecretKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let vec = hex::decode(s).map_err(ParseSecretKeyError::InvalidHex)?;
+        BorshDeserialize::try_from_slice(&vec)
+            .map_err(ParseSecretKeyError::InvalidEncoding)
+    }
+}
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum ParseSecretKeyError {
+    #[error("Invalid secret key hex: {0}")]
+    InvalidHex(hex::FromHexError),
+    #[error("Invalid secret key encoding: {0}")]
+    InvalidEncoding(std::io::Error),
+}
+
+impl Serialize for SecretKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let string = String::deserialize(deserializer)?;
+        Self::from_str(&string).map_err(de::Error::custom)
+    }
+}
+
+impl PartialEq for Signature {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.serialize() == other.0.serialize()
+    }
+}
+
+impl Eq for Signature {}
+
+#[allow(clippy::derive_hash_xor_eq)]
+impl Hash for Signature {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.try_to_vec()
+            .expect("Encoding signature for hash shouldn't fail")
+            .hash(state);
+    }
+}
+
+impl PartialOrd for Signature {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.try_to_vec()
+            .expect("Encoding signature shouldn't fail")
+            .partial_cmp(
+                &other
+                    .try_to_vec()
+                    .expect("Encoding signature shouldn't fail"),
+            )
+    }
+}
+
+impl Serialize for Signature {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let vec = self
+            .try_to_vec()
+            .expect("Encoding signature shouldn't fail");
+        serializer.serialize_str(&hex::encode(&vec))
+    }
+}
+
+impl<'de> Deserialize<'de> for Signature {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let string = String::deserialize(deserializer)?;
+        let vec = hex::decode(string).map_err(de::Error::custom)?;
+        BorshDeserialize::try_from_slice(&vec).map_err(de::Error::custom)
+    }
+}
+
+impl From<libsecp256k1::PublicKey> for PublicKey {
+    fn from(pk: libsecp256k1::PublicKey) -> Self {
+        Self(pk)
+    }
+}
+
+impl From<PublicKey> for libsecp256k1::PublicKey {
+    fn from(pk: PublicKey) -> Self {
+        pk.0
+    }
+}
+
+impl From<PublicKey> for PublicKeyHash {
+    fn from(pk: PublicKey) -> Self {
+        let pk_bytes =
+            pk.try_to_vec().expect("Public key encoding shouldn't fail");
+        let mut hasher = Sha256::new();
+        hasher.update(pk_bytes);
+        // hex of the first 40 chars of the hash
+        Self(format!(
+            "{:.width$X}",
+            hasher.finalize(),
+            width = address::HASH_LEN
+        ))
+    }
+}
+
+impl From<libsecp256k1::SecretKey> for SecretKey {
+    fn from(sk: libsecp256k1::SecretKey) -> Self {
+        Self(sk)
+    }
+}
+
+impl From<SecretKey> for libsecp256k1::SecretKey {
+    fn from(sk: SecretKey) -> Self {
+        sk.0
+    }
+}
+
+/// Helpers for testing with keys.
+#[cfg(any(test, feature = "testing"))]
+pub mod testing {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// A keypair for tests
+    pub fn keypair_1() -> Keypair {
+        let bytes = [
+            149, 196, 210, 7, 234, 35, 172, 113, 250, 70, 114, 130, 49, 195,
+            60, 53, 106, 104, 28, 64, 20, 117, 154, 117, 65, 217, 13, 183, 33,
+            106, 154, 126,
+        ];
+        let secret = libsecp256k1::SecretKey::parse(&bytes).unwrap();
+        Keypair::from_secret(SecretKey(secret))
+    }
+
+    /// A keypair for tests
+    pub fn keypair_2() -> Keypair {
+        let bytes = [
+            188, 37, 12, 198, 50, 186, 78, 0, 132, 254, 225, 237, 40, 33, 149,
+            167, 22, 45, 191, 172, 97, 84, 95, 106, 49, 173, 219, 32, 187, 177,
+            174, 188,
+        ];
+        let secret = libsecp256k1::SecretKey::parse(&bytes).unwrap();
+        Keypair::from_secret(SecretKey(secret))
+    }
+
+    /// Generate an arbitrary [`Keypair`].
+    pub fn arb_keypair() -> impl Strategy<Value = Keypair> {
+        any::<[u8; 32]>().prop_filter_map("Invalid secret key", |bytes| {
+            libsecp256k1::SecretKey::parse(&bytes)
+                .ok()
+                .map(|secret| Keypair::from_secret(SecretKey(secret)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let keypair = testing::keypair_1();
+        let data = vec![1, 2, 3, 4].repeat(10);
+        let sig = sign(&keypair, &data);
+        assert!(verify_signature_raw(&keypair.public, &data, &sig).is_ok());
+        assert!(verify_signature_raw(&keypair.public, &[0, 1], &sig).is_err());
+
+        let other_keypair = testing::keypair_2();
+        assert!(
+            verify_signature_raw(&other_keypair.public, &data, &sig).is_err()
+        );
+    }
+
+    #[test]
+    fn test_encoding_roundtrip() {
+        let keypair = testing::keypair_1();
+        let pk: PublicKey = keypair.public.to_string().parse().unwrap();
+        assert_eq!(pk, keypair.public);
+        let sk: SecretKey = keypair.secret.to_string().parse().unwrap();
+        assert_eq!(sk.to_string(), keypair.secret.to_string());
+
+        let sig = sign(&keypair, &[1, 2, 3]);
+        let decoded =
+            Signature::try_from_slice(&sig.try_to_vec().unwrap()).unwrap();
+        assert_eq!(decoded, sig);
+    }
+
+    /// Test that a signature cannot be malleated into another valid
+    /// signature of the same data.
+    #[test]
+    fn test_malleated_signature_rejected() {
+        let keypair = testing::keypair_1();
+        let data = [1, 2, 3];
+        let sig = sign(&keypair, &data);
+        assert!(!sig.0.s.is_high());
+
+        // (r, n - s) is also valid for the underlying verification
+        let mut malleated = sig.0.clone();
+        malleated.s = -malleated.s.clone();
+        assert!(libsecp256k1::verify(
+            &message(&data),
+            &malleated,
+            &keypair.public.0
+        ));
+        let encoded = malleated.serialize().to_vec().try_to_vec().unwrap();
+        assert!(Signature::try_from_slice(&encoded).is_err());
+
+        // an `r` that overflows the curve order
+        let mut overflowing = sig.0.serialize();
+        overflowing[..32].copy_from_slice(&[0xff; 32]);
+        let encoded = overflowing.to_vec().try_to_vec().unwrap();
+        assert!(Signature::try_from_slice(&encoded).is_err());
+    }
+}
@@ -1,13 +1,20 @@
 //! Types definitions.
 
 pub mod address;
+pub mod governance;
+pub mod hash;
 #[cfg(feature = "ibc-vp")]
 pub mod ibc;
 pub mod intent;
 pub mod internal;
 pub mod key;
+pub mod pos;
+pub mod recovery;
+pub mod shielded;
 pub mod storage;
 pub mod time;
 pub mod token;
 pub mod transaction;
+pub mod treasury;
 pub mod validity_predicate;
+pub mod vesting;
@@ -0,0 +1,430 @@
+//! Proof-of-stake validators, bonds and their rewards
+
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::types::address::{Address, InternalAddress};
+use crate::types::key::ed25519;
+use crate::types::storage::{DbKeySeg, Epoch, Key, KeySeg};
+use crate::types::token::{self, Amount};
+
+const ADDR: InternalAddress = InternalAddress::PoS;
+const VALIDATOR_STORAGE_KEY: &str = "validator";
+const CONSENSUS_KEY_STORAGE_KEY: &str = "consensus_key";
+const COMMISSION_RATE_STORAGE_KEY: &str = "commission_rate";
+const MAX_COMMISSION_RATE_CHANGE_STORAGE_KEY: &str =
+    "max_commission_rate_change";
+const COMMISSION_EPOCH_STORAGE_KEY: &str = "commission_epoch";
+const STAKE_STORAGE_KEY: &str = "stake";
+const REWARD_ACC_STORAGE_KEY: &str = "reward_acc";
+const COMMISSION_STORAGE_KEY: &str = "commission";
+const REWARD_POINTS_STORAGE_KEY: &str = "reward_points";
+const VOTING_POWER_STORAGE_KEY: &str = "voting_power";
+const JAILED_STORAGE_KEY: &str = "jailed";
+const CONSENSUS_ADDRESS_STORAGE_KEY: &str = "consensus_address";
+const BOND_STORAGE_KEY: &str = "bond";
+const UNBOND_STORAGE_KEY: &str = "unbond";
+const REWARD_EPOCH_STORAGE_KEY: &str = "reward_epoch";
+const REWARD_EPOCH_START_STORAGE_KEY: &str = "reward_epoch_start";
+
+/// The number of basis points in a whole, used for the rates
+pub const BASIS_POINTS: u64 = 10_000;
+
+/// The precision of the validators' reward accumulators, which sum up the
+/// rewards per a bonded micro XAN
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+/// The number of epochs after the epoch in which tokens are unbonded until
+/// they can be withdrawn
+pub const UNBONDING_EPOCHS: u64 = 2;
+
+/// The unbonded amounts of a delegator from a validator by the epoch from
+/// which they can be withdrawn
+pub type Unbonds = BTreeMap<Epoch, Amount>;
+
+/// A bond of XAN from a delegator, or from the validator itself, to a
+/// validator. The bond's rewards are settled whenever its amount changes, so
+/// that the rewards don't have to be written to every bond when they are
+/// distributed.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct Bond {
+    /// The bonded amount
+    pub amount: Amount,
+    /// The validator's reward accumulator at the time the bond's rewards have
+    /// been last settled
+    pub reward_acc: u128,
+    /// The settled rewards that haven't been claimed yet
+    pub unclaimed: Amount,
+}
+
+impl Bond {
+    /// Create a new bond with the current reward accumulator of its validator
+    pub fn new(amount: Amount, reward_acc: u128) -> Self {
+        Self {
+            amount,
+            reward_acc,
+            unclaimed: Amount::default(),
+        }
+    }
+
+    /// Get the rewards that the bond has accrued since they have been last
+    /// settled, given the current reward accumulator of its validator.
+    /// Returns `None` on overflow.
+    pub fn accrued(&self, reward_acc: u128) -> Option<Amount> {
+        let diff = reward_acc.checked_sub(self.reward_acc)?;
+        let amount = u128::from(u64::from(self.amount));
+        let accrued = amount.checked_mul(diff)? / REWARD_PRECISION;
+        u64::try_from(accrued).ok().map(Amount::from)
+    }
+
+    /// Settle the accrued rewards into the unclaimed rewards. Returns `None`
+    /// on overflow.
+    pub fn settle(&mut self, reward_acc: u128) -> Option<()> {
+        let accrued = self.accrued(reward_acc)?;
+        self.unclaimed = self.unclaimed.checked_add(&accrued)?;
+        self.reward_acc = reward_acc;
+        Some(())
+    }
+}
+
+/// Get the address of the proof-of-stake system
+pub fn pos_address() -> Address {
+    Address::Internal(ADDR)
+}
+
+/// Get the voting power of a validator with the given stake, which is its
+/// stake in whole XAN.
+pub fn voting_power(stake: Amount) -> u64 {
+    u64::from(stake) / token::SCALE
+}
+
+/// Get the epoch from which the tokens unbonded in the given epoch can be
+/// withdrawn.
+pub fn withdrawable_epoch(epoch: Epoch) -> Epoch {
+    epoch + UNBONDING_EPOCHS
+}
+
+/// Get the Tendermint address of a validator's consensus key, which is the
+/// upper-case hex of the first 20 bytes of the key's SHA-256 hash.
+pub fn consensus_address(consensus_key: &ed25519::PublicKey) -> String {
+    let pk: ed25519_dalek::PublicKey = consensus_key.clone().into();
+    let mut hasher = Sha256::new();
+    hasher.update(pk.as_bytes());
+    consensus_address_from_bytes(&hasher.finalize()[..20])
+}
+
+/// Get the Tendermint address from its raw bytes, as given in the blocks'
+/// headers and commits.
+pub fn consensus_address_from_bytes(bytes: &[u8]) -> String {
+    hex::encode_upper(bytes)
+}
+
+fn pos_key(key: &str) -> Key {
+    Key::from(pos_address().to_db_key())
+        .push(&key.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key prefix for all the validators.
+pub fn validator_prefix() -> Key {
+    pos_key(VALIDATOR_STORAGE_KEY)
+}
+
+fn validator_key(validator: &Address, key: &str) -> Key {
+    validator_prefix()
+        .push(&validator.to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&key.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+fn is_validator_key<'a>(key: &'a Key, field: &str) -> Option<&'a Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(prefix), DbKeySeg::AddressSeg(validator), DbKeySeg::StringSeg(key)]
+            if addr == &pos_address()
+                && prefix == VALIDATOR_STORAGE_KEY
+                && key == field =>
+        {
+            Some(validator)
+        }
+        _ => None,
+    }
+}
+
+/// Obtain a storage key for a validator's consensus key.
+pub fn consensus_key_key(validator: &Address) -> Key {
+    validator_key(validator, CONSENSUS_KEY_STORAGE_KEY)
+}
+
+/// Obtain a storage key for a validator's commission rate in basis points.
+pub fn commission_rate_key(validator: &Address) -> Key {
+    validator_key(validator, COMMISSION_RATE_STORAGE_KEY)
+}
+
+/// Obtain a storage key for the maximum change of a validator's commission
+/// rate in an epoch, in basis points.
+pub fn max_commission_rate_change_key(validator: &Address) -> Key {
+    validator_key(validator, MAX_COMMISSION_RATE_CHANGE_STORAGE_KEY)
+}
+
+/// Obtain a storage key for the epoch in which a validator's commission rate
+/// has been last set.
+pub fn commission_epoch_key(validator: &Address) -> Key {
+    validator_key(validator, COMMISSION_EPOCH_STORAGE_KEY)
+}
+
+/// Obtain a storage key for the sum of the bonds to a validator.
+pub fn stake_key(validator: &Address) -> Key {
+    validator_key(validator, STAKE_STORAGE_KEY)
+}
+
+/// Obtain a storage key for a validator's reward accumulator, which sums up
+/// the delegators' rewards per a bonded micro XAN, multiplied by the
+/// [`REWARD_PRECISION`].
+pub fn reward_acc_key(validator: &Address) -> Key {
+    validator_key(validator, REWARD_ACC_STORAGE_KEY)
+}
+
+/// Obtain a storage key for a validator's commission that hasn't been claimed
+/// yet.
+pub fn commission_key(validator: &Address) -> Key {
+    validator_key(validator, COMMISSION_STORAGE_KEY)
+}
+
+/// Obtain a storage key for a validator's reward points in the current reward
+/// epoch.
+pub fn reward_points_key(validator: &Address) -> Key {
+    validator_key(validator, REWARD_POINTS_STORAGE_KEY)
+}
+
+/// Obtain a storage key for the voting power of a validator that has been
+/// last given to Tendermint.
+pub fn voting_power_key(validator: &Address) -> Key {
+    validator_key(validator, VOTING_POWER_STORAGE_KEY)
+}
+
+/// Obtain a storage key for the epoch in which a validator has been jailed,
+/// if it's jailed.
+pub fn jailed_key(validator: &Address) -> Key {
+    validator_key(validator, JAILED_STORAGE_KEY)
+}
+
+/// Check if the given storage key is a validator's consensus key key. If it
+/// is, returns the validator.
+pub fn is_consensus_key_key(key: &Key) -> Option<&Address> {
+    is_validator_key(key, CONSENSUS_KEY_STORAGE_KEY)
+}
+
+/// Check if the given storage key is a validator's commission rate key. If it
+/// is, returns the validator.
+pub fn is_commission_rate_key(key: &Key) -> Option<&Address> {
+    is_validator_key(key, COMMISSION_RATE_STORAGE_KEY)
+}
+
+/// Check if the given storage key is a validator's maximum commission rate
+/// change key. If it is, returns the validator.
+pub fn is_max_commission_rate_change_key(key: &Key) -> Option<&Address> {
+    is_validator_key(key, MAX_COMMISSION_RATE_CHANGE_STORAGE_KEY)
+}
+
+/// Check if the given storage key is a validator's commission epoch key. If
+/// it is, returns the validator.
+pub fn is_commission_epoch_key(key: &Key) -> Option<&Address> {
+    is_validator_key(key, COMMISSION_EPOCH_STORAGE_KEY)
+}
+
+/// Check if the given storage key is a validator's reward accumulator key. If
+/// it is, returns the validator.
+pub fn is_reward_acc_key(key: &Key) -> Option<&Address> {
+    is_validator_key(key, REWARD_ACC_STORAGE_KEY)
+}
+
+/// Check if the given storage key is a validator's voting power key. If it
+/// is, returns the validator.
+pub fn is_voting_power_key(key: &Key) -> Option<&Address> {
+    is_validator_key(key, VOTING_POWER_STORAGE_KEY)
+}
+
+/// Check if the given storage key is a validator's jailed key. If it is,
+/// returns the validator.
+pub fn is_jailed_key(key: &Key) -> Option<&Address> {
+    is_validator_key(key, JAILED_STORAGE_KEY)
+}
+
+/// Check if the given storage key is a validator's stake key. If it is,
+/// returns the validator.
+pub fn is_stake_key(key: &Key) -> Option<&Address> {
+    is_validator_key(key, STAKE_STORAGE_KEY)
+}
+
+/// Check if the given storage key is a validator's commission key. If it is,
+/// returns the validator.
+pub fn is_commission_key(key: &Key) -> Option<&Address> {
+    is_validator_key(key, COMMISSION_STORAGE_KEY)
+}
+
+/// Check if the given storage key is a validator's reward points key. If it
+/// is, returns the validator.
+pub fn is_reward_points_key(key: &Key) -> Option<&Address> {
+    is_validator_key(key, REWARD_POINTS_STORAGE_KEY)
+}
+
+/// Obtain a storage key for the validator with the given Tendermint address.
+pub fn consensus_address_key(consensus_address: &str) -> Key {
+    pos_key(CONSENSUS_ADDRESS_STORAGE_KEY)
+        .push(&consensus_address.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key for a bond of a delegator to a validator.
+pub fn bond_key(delegator: &Address, validator: &Address) -> Key {
+    pos_key(BOND_STORAGE_KEY)
+        .push(&delegator.to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&validator.to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key prefix for all the bonds of a delegator.
+pub fn delegator_bonds_prefix(delegator: &Address) -> Key {
+    pos_key(BOND_STORAGE_KEY)
+        .push(&delegator.to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is a key of a validator with the given
+/// Tendermint address. If it is, returns the Tendermint address.
+pub fn is_consensus_address_key(key: &Key) -> Option<&str> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(prefix), DbKeySeg::StringSeg(consensus_address)]
+            if addr == &pos_address()
+                && prefix == CONSENSUS_ADDRESS_STORAGE_KEY =>
+        {
+            Some(consensus_address)
+        }
+        _ => None,
+    }
+}
+
+/// Obtain a storage key for the unbonded amounts of a delegator from a
+/// validator.
+pub fn unbond_key(delegator: &Address, validator: &Address) -> Key {
+    pos_key(UNBOND_STORAGE_KEY)
+        .push(&delegator.to_db_key())
+        .expect("Cannot obtain a storage key")
+        .push(&validator.to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key prefix for all the unbonded amounts of a delegator.
+pub fn delegator_unbonds_prefix(delegator: &Address) -> Key {
+    pos_key(UNBOND_STORAGE_KEY)
+        .push(&delegator.to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is an unbond key. If it is, returns the
+/// delegator and the validator.
+pub fn is_unbond_key(key: &Key) -> Option<(&Address, &Address)> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(prefix), DbKeySeg::AddressSeg(delegator), DbKeySeg::AddressSeg(validator)]
+            if addr == &pos_address() && prefix == UNBOND_STORAGE_KEY =>
+        {
+            Some((delegator, validator))
+        }
+        _ => None,
+    }
+}
+
+/// Check if the given storage key is a bond key. If it is, returns the
+/// delegator and the validator.
+pub fn is_bond_key(key: &Key) -> Option<(&Address, &Address)> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(prefix), DbKeySeg::AddressSeg(delegator), DbKeySeg::AddressSeg(validator)]
+            if addr == &pos_address() && prefix == BOND_STORAGE_KEY =>
+        {
+            Some((delegator, validator))
+        }
+        _ => None,
+    }
+}
+
+/// Obtain a storage key for the epoch in which the current reward points
+/// have been recorded.
+pub fn reward_epoch_key() -> Key {
+    pos_key(REWARD_EPOCH_STORAGE_KEY)
+}
+
+/// Obtain a storage key for the block time at which the epoch of the current
+/// reward points has started.
+pub fn reward_epoch_start_key() -> Key {
+    pos_key(REWARD_EPOCH_START_STORAGE_KEY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::address;
+    use crate::types::key::ed25519::testing::keypair_1;
+
+    #[test]
+    fn test_pos_keys() {
+        let delegator = address::testing::established_address_1();
+        let validator = address::testing::established_address_2();
+        assert_eq!(is_stake_key(&stake_key(&validator)), Some(&validator));
+        assert_eq!(
+            is_reward_points_key(&reward_points_key(&validator)),
+            Some(&validator)
+        );
+        assert_eq!(is_stake_key(&commission_key(&validator)), None);
+        assert_eq!(
+            is_bond_key(&bond_key(&delegator, &validator)),
+            Some((&delegator, &validator))
+        );
+        assert_eq!(is_bond_key(&stake_key(&validator)), None);
+        assert_eq!(
+            is_unbond_key(&unbond_key(&delegator, &validator)),
+            Some((&delegator, &validator))
+        );
+        assert_eq!(is_bond_key(&unbond_key(&delegator, &validator)), None);
+        assert_eq!(
+            is_consensus_address_key(&consensus_address_key("AB12")),
+            Some("AB12")
+        );
+        assert_eq!(is_jailed_key(&jailed_key(&validator)), Some(&validator));
+    }
+
+    #[test]
+    fn test_bond_rewards() {
+        let mut bond = Bond::new(Amount::from(1000), 5 * REWARD_PRECISION);
+        assert_eq!(bond.accrued(4 * REWARD_PRECISION), None);
+        assert_eq!(bond.accrued(5 * REWARD_PRECISION), Some(Amount::from(0)));
+        // half a micro XAN per bonded micro XAN
+        let reward_acc = 5 * REWARD_PRECISION + REWARD_PRECISION / 2;
+        assert_eq!(bond.accrued(reward_acc), Some(Amount::from(500)));
+        bond.settle(reward_acc).unwrap();
+        assert_eq!(bond.unclaimed, Amount::from(500));
+        assert_eq!(bond.accrued(reward_acc), Some(Amount::from(0)));
+    }
+
+    #[test]
+    fn test_consensus_address() {
+        let pk = ed25519::PublicKey::from(keypair_1().public);
+        let address = consensus_address(&pk);
+        assert_eq!(address.len(), 40);
+        assert_eq!(address, address.to_uppercase());
+    }
+}
@@ -0,0 +1,183 @@
+//! Social recovery of accounts' public keys by guardians
+
+use std::collections::BTreeSet;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::types::address::Address;
+use crate::types::key::common::PublicKey;
+use crate::types::storage::{DbKeySeg, Epoch, Key, KeySeg};
+
+const CONFIG_STORAGE_KEY: &str = "recovery";
+const PROPOSAL_STORAGE_KEY: &str = "recovery_proposal";
+
+/// The recovery configuration of an account. The guardians can jointly
+/// propose a new public key for the account, which can replace the account's
+/// key once the proposal has been approved by at least the threshold number of
+/// guardians and the delay has passed, unless the current key vetoes it.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct RecoveryConfig {
+    /// The addresses of the guardians
+    pub guardians: BTreeSet<Address>,
+    /// The minimum number of guardians that have to approve a new key
+    pub threshold: u64,
+    /// The number of epochs after the last approval before the new key can
+    /// replace the current key
+    pub delay: u64,
+}
+
+/// A proposal of a new public key for an account by its guardians
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct RecoveryProposal {
+    /// The proposed public key
+    pub public_key: PublicKey,
+    /// The guardians that approved the proposal
+    pub approvals: BTreeSet<Address>,
+    /// The epoch of the last approval
+    pub epoch: Epoch,
+}
+
+impl RecoveryConfig {
+    /// Check that the threshold can be reached by the guardians and that the
+    /// owner is not one of its own guardians.
+    pub fn is_valid(&self, owner: &Address) -> bool {
+        self.threshold > 0
+            && self.threshold <= self.guardians.len() as u64
+            && !self.guardians.contains(owner)
+    }
+}
+
+impl RecoveryProposal {
+    /// Check if the proposal has been approved by enough guardians of the
+    /// given configuration and the delay has passed in the given epoch, so the
+    /// proposed key can replace the account's key.
+    pub fn is_ready(&self, config: &RecoveryConfig, epoch: Epoch) -> bool {
+        let approvals =
+            self.approvals.intersection(&config.guardians).count() as u64;
+        approvals >= config.threshold
+            && epoch.0 >= self.epoch.0.saturating_add(config.delay)
+    }
+}
+
+/// Obtain a storage key for the recovery configuration of an account.
+pub fn config_key(owner: &Address) -> Key {
+    Key::from(owner.to_db_key())
+        .push(&CONFIG_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key for the recovery proposal of an account.
+pub fn proposal_key(owner: &Address) -> Key {
+    Key::from(owner.to_db_key())
+        .push(&PROPOSAL_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is a recovery configuration key. If it is,
+/// returns the owner.
+pub fn is_config_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(owner), DbKeySeg::StringSeg(key)]
+            if key == CONFIG_STORAGE_KEY =>
+        {
+            Some(owner)
+        }
+        _ => None,
+    }
+}
+
+/// Check if the given storage key is a recovery proposal key. If it is,
+/// returns the owner.
+pub fn is_proposal_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(owner), DbKeySeg::StringSeg(key)]
+            if key == PROPOSAL_STORAGE_KEY =>
+        {
+            Some(owner)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::address;
+    use crate::types::key::common::SigningKey;
+    use crate::types::key::ed25519::testing::keypair_1;
+
+    fn config(threshold: u64) -> RecoveryConfig {
+        RecoveryConfig {
+            guardians: vec![
+                address::testing::established_address_2(),
+                address::xan(),
+            ]
+            .into_iter()
+            .collect(),
+            threshold,
+            delay: 5,
+        }
+    }
+
+    #[test]
+    fn test_valid_config() {
+        let owner = address::testing::established_address_1();
+        assert!(config(1).is_valid(&owner));
+        assert!(config(2).is_valid(&owner));
+        assert!(!config(0).is_valid(&owner));
+        assert!(!config(3).is_valid(&owner));
+        // the owner cannot be its own guardian
+        let mut config = config(1);
+        config.guardians.insert(owner.clone());
+        assert!(!config.is_valid(&owner));
+    }
+
+    #[test]
+    fn test_proposal_ready() {
+        let config = config(2);
+        let mut proposal = RecoveryProposal {
+            public_key: keypair_1().public_key(),
+            approvals: vec![address::testing::established_address_2()]
+                .into_iter()
+                .collect(),
+            epoch: Epoch(10),
+        };
+        // not enough approvals
+        assert!(!proposal.is_ready(&config, Epoch(20)));
+        // approvals of other addresses don't count
+        proposal.approvals.insert(address::btc());
+        assert!(!proposal.is_ready(&config, Epoch(20)));
+        proposal.approvals.insert(address::xan());
+        // the delay has to pass
+        assert!(!proposal.is_ready(&config, Epoch(14)));
+        assert!(proposal.is_ready(&config, Epoch(15)));
+    }
+
+    #[test]
+    fn test_recovery_keys() {
+        let owner = address::testing::established_address_1();
+        assert_eq!(is_config_key(&config_key(&owner)), Some(&owner));
+        assert_eq!(is_proposal_key(&proposal_key(&owner)), Some(&owner));
+        assert_eq!(is_config_key(&proposal_key(&owner)), None);
+        assert_eq!(is_proposal_key(&config_key(&owner)), None);
+    }
+}
@@ -0,0 +1,436 @@
+//! Types of the shielded pool for private token transfers. The notes are
+//! represented by their commitments in a note commitment tree and spent notes
+//! are marked by their nullifiers, which cannot be linked to the commitments.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use bech32::{self, FromBase32, ToBase32, Variant};
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::types::address::{Address, InternalAddress};
+use crate::types::storage::{DbKeySeg, Key, KeySeg};
+use crate::types::token::Amount;
+
+const POOL_ADDR: InternalAddress = InternalAddress::ShieldedPool;
+const COMMITMENT_STORAGE_KEY: &str = "commitment";
+const COMMITMENT_COUNT_STORAGE_KEY: &str = "num_commitments";
+const NULLIFIER_STORAGE_KEY: &str = "nullifier";
+const ANCHOR_STORAGE_KEY: &str = "anchor";
+const TREE_STORAGE_KEY: &str = "tree";
+
+const PAYMENT_ADDRESS_HRP: &str = "zpa";
+const SPENDING_KEY_HRP: &str = "zsk";
+const BECH32_VARIANT: bech32::Variant = Variant::Bech32m;
+
+/// The length of the encoding of the commitments, nullifiers, Merkle roots and
+/// keys, which are all elements of the scalar field of the proof system
+pub const FIELD_ELEMENT_LEN: usize = 32;
+
+/// An encoded element of the scalar field of the proof system
+pub type FieldElement = [u8; FIELD_ELEMENT_LEN];
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum DecodeError {
+    #[error("Error decoding from Bech32m: {0}")]
+    DecodeBech32(bech32::Error),
+    #[error("Error decoding from base32: {0}")]
+    DecodeBase32(bech32::Error),
+    #[error("Unexpected Bech32m human-readable part {0}, expected {1}")]
+    UnexpectedBech32Prefix(String, &'static str),
+    #[error("Unexpected Bech32m variant {0:?}, expected {BECH32_VARIANT:?}")]
+    UnexpectedBech32Variant(bech32::Variant),
+    #[error("Unexpected length {0}, expected {FIELD_ELEMENT_LEN}")]
+    UnexpectedLength(usize),
+}
+
+/// A commitment to a note in the note commitment tree
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct NoteCommitment(pub FieldElement);
+
+/// A nullifier of a spent note
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct Nullifier(pub FieldElement);
+
+/// A root of the note commitment tree. The notes spent in a transaction must be
+/// in the tree with one of the previous roots, which is called the anchor.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct MerkleRoot(pub FieldElement);
+
+/// A shielded payment address is derived from a spending key. The notes owned
+/// by a payment address can only be spent with its spending key.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, BorshSerialize, BorshDeserialize,
+)]
+pub struct PaymentAddress(pub FieldElement);
+
+/// A shielded spending key
+#[derive(Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct SpendingKey(pub FieldElement);
+
+/// A note of the shielded pool holds an amount of a token for its owner. Only
+/// the note's commitment is public, so the note itself has to be sent to its
+/// owner off-chain.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct Note {
+    /// The address of the note's token
+    pub token: Address,
+    /// The amount of the token
+    pub amount: Amount,
+    /// The owner of the note
+    #[serde(with = "serde_bech32")]
+    pub owner: PaymentAddress,
+    /// The unique value from which the note's nullifier is derived
+    pub rho: FieldElement,
+    /// The randomness of the note's commitment
+    pub randomness: FieldElement,
+}
+
+/// A transparent part of a shielded transfer
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct TransparentTransfer {
+    /// The owner of the transparent balance
+    pub owner: Address,
+    /// The address of the transferred token
+    pub token: Address,
+    /// The transferred amount
+    pub amount: Amount,
+}
+
+/// A tx data type for a transfer within the shielded pool, which can also
+/// shield tokens from a transparent balance into the pool and unshield tokens
+/// from the pool into a transparent balance. Every shielded transfer spends
+/// two notes and creates two new notes, some of which may have a zero amount.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct ShieldedTransfer {
+    /// Tokens moved from a transparent balance into the pool
+    pub shield: Option<TransparentTransfer>,
+    /// Tokens moved from the pool into a transparent balance
+    pub unshield: Option<TransparentTransfer>,
+    /// The root of the note commitment tree containing the spent notes
+    pub anchor: MerkleRoot,
+    /// The nullifiers of the spent notes
+    pub nullifiers: [Nullifier; 2],
+    /// The commitments of the new notes
+    pub commitments: [NoteCommitment; 2],
+    /// The proof that the transfer is valid
+    pub proof: Vec<u8>,
+}
+
+impl ShieldedTransfer {
+    /// The token of the transparent parts of the transfer, if any. Returns
+    /// `None` also if the shielded and unshielded tokens are not the same.
+    pub fn transparent_token(&self) -> Option<&Address> {
+        match (&self.shield, &self.unshield) {
+            (Some(shield), Some(unshield))
+                if shield.token == unshield.token =>
+            {
+                Some(&shield.token)
+            }
+            (Some(_), Some(_)) => None,
+            (Some(shield), None) => Some(&shield.token),
+            (None, Some(unshield)) => Some(&unshield.token),
+            (None, None) => None,
+        }
+    }
+}
+
+impl PaymentAddress {
+    /// Encode the payment address with Bech32m encoding
+    pub fn encode(&self) -> String {
+        encode(PAYMENT_ADDRESS_HRP, &self.0)
+    }
+
+    /// Decode the payment address from Bech32m encoding
+    pub fn decode(string: impl AsRef<str>) -> Result<Self, DecodeError> {
+        decode(PAYMENT_ADDRESS_HRP, string).map(Self)
+    }
+}
+
+impl Display for PaymentAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.encode())
+    }
+}
+
+impl FromStr for PaymentAddress {
+    type Err = DecodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::decode(s)
+    }
+}
+
+impl SpendingKey {
+    /// Encode the spending key with Bech32m encoding
+    pub fn encode(&self) -> String {
+        encode(SPENDING_KEY_HRP, &self.0)
+    }
+
+    /// Decode the spending key from Bech32m encoding
+    pub fn decode(string: impl AsRef<str>) -> Result<Self, DecodeError> {
+        decode(SPENDING_KEY_HRP, string).map(Self)
+    }
+}
+
+impl std::fmt::Debug for SpendingKey {
+    /// The key is not printed to prevent it from leaking into logs
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SpendingKey(..)")
+    }
+}
+
+impl FromStr for SpendingKey {
+    type Err = DecodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::decode(s)
+    }
+}
+
+fn encode(hrp: &str, bytes: &FieldElement) -> String {
+    bech32::encode(hrp, bytes.to_base32(), BECH32_VARIANT).unwrap_or_else(
+        |_| {
+            panic!(
+                "The human-readable part {} should never cause a failure",
+                hrp
+            )
+        },
+    )
+}
+
+fn decode(
+    hrp: &'static str,
+    string: impl AsRef<str>,
+) -> Result<FieldElement, DecodeError> {
+    let (prefix, base32, variant) =
+        bech32::decode(string.as_ref()).map_err(DecodeError::DecodeBech32)?;
+    if prefix != hrp {
+        return Err(DecodeError::UnexpectedBech32Prefix(prefix, hrp));
+    }
+    if variant != BECH32_VARIANT {
+        return Err(DecodeError::UnexpectedBech32Variant(variant));
+    }
+    let bytes: Vec<u8> =
+        FromBase32::from_base32(&base32).map_err(DecodeError::DecodeBase32)?;
+    if bytes.len() != FIELD_ELEMENT_LEN {
+        return Err(DecodeError::UnexpectedLength(bytes.len()));
+    }
+    let mut element = [0; FIELD_ELEMENT_LEN];
+    element.copy_from_slice(&bytes);
+    Ok(element)
+}
+
+/// Serialize the payment address of a note in its Bech32m encoding
+mod serde_bech32 {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    use super::PaymentAddress;
+
+    pub fn serialize<S>(
+        address: &PaymentAddress,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&address.encode())
+    }
+
+    pub fn deserialize<'de, D>(
+        deserializer: D,
+    ) -> Result<PaymentAddress, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let string = String::deserialize(deserializer)?;
+        PaymentAddress::decode(string).map_err(de::Error::custom)
+    }
+}
+
+/// The address of the shielded pool
+pub fn pool_address() -> Address {
+    Address::Internal(POOL_ADDR)
+}
+
+fn pool_key(key: &str) -> Key {
+    Key::from(pool_address().to_db_key())
+        .push(&key.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key prefix for all the note commitments.
+pub fn commitment_prefix() -> Key {
+    pool_key(COMMITMENT_STORAGE_KEY)
+}
+
+/// Obtain a storage key for the note commitment at the given position of the
+/// note commitment tree.
+pub fn commitment_key(index: u64) -> Key {
+    commitment_prefix()
+        .push(&index.to_string())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key for the number of note commitments.
+pub fn commitment_count_key() -> Key {
+    pool_key(COMMITMENT_COUNT_STORAGE_KEY)
+}
+
+/// Obtain a storage key for a nullifier of a spent note.
+pub fn nullifier_key(nullifier: &Nullifier) -> Key {
+    pool_key(NULLIFIER_STORAGE_KEY)
+        .push(&hex::encode(nullifier.0))
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key for a root of the note commitment tree that can be
+/// used as an anchor.
+pub fn anchor_key(root: &MerkleRoot) -> Key {
+    pool_key(ANCHOR_STORAGE_KEY)
+        .push(&hex::encode(root.0))
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key for the state of the note commitment tree.
+pub fn tree_key() -> Key {
+    pool_key(TREE_STORAGE_KEY)
+}
+
+/// Check if the given storage key is a note commitment key. If it is, returns
+/// the commitment's position in the tree.
+pub fn is_commitment_key(key: &Key) -> Option<u64> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(prefix), DbKeySeg::StringSeg(index)]
+            if addr == &pool_address() && prefix == COMMITMENT_STORAGE_KEY =>
+        {
+            index.parse().ok()
+        }
+        _ => None,
+    }
+}
+
+/// Check if the given storage key is the key of the number of note
+/// commitments.
+pub fn is_commitment_count_key(key: &Key) -> bool {
+    *key == commitment_count_key()
+}
+
+/// Check if the given storage key is a nullifier key.
+pub fn is_nullifier_key(key: &Key) -> bool {
+    matches!(&key.segments[..],
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(prefix), DbKeySeg::StringSeg(_)]
+            if addr == &pool_address() && prefix == NULLIFIER_STORAGE_KEY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::address;
+
+    #[test]
+    fn test_payment_address_encoding() {
+        let address = PaymentAddress([7; FIELD_ELEMENT_LEN]);
+        let encoded = address.encode();
+        assert!(encoded.starts_with(PAYMENT_ADDRESS_HRP));
+        assert_eq!(PaymentAddress::decode(&encoded).unwrap(), address);
+        // a spending key cannot be decoded as a payment address
+        let key = SpendingKey([7; FIELD_ELEMENT_LEN]).encode();
+        assert!(PaymentAddress::decode(key).is_err());
+    }
+
+    #[test]
+    fn test_note_json() {
+        let note = Note {
+            token: address::xan(),
+            amount: Amount::from(1000),
+            owner: PaymentAddress([1; FIELD_ELEMENT_LEN]),
+            rho: [2; FIELD_ELEMENT_LEN],
+            randomness: [3; FIELD_ELEMENT_LEN],
+        };
+        let json = serde_json::to_string(&note).unwrap();
+        assert!(json.contains(&note.owner.encode()));
+        let decoded: Note = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, note);
+    }
+
+    #[test]
+    fn test_pool_keys() {
+        let key = commitment_key(10);
+        assert_eq!(is_commitment_key(&key), Some(10));
+        assert!(!is_nullifier_key(&key));
+        assert!(is_commitment_count_key(&commitment_count_key()));
+        assert_eq!(is_commitment_key(&commitment_count_key()), None);
+
+        let key = nullifier_key(&Nullifier([1; FIELD_ELEMENT_LEN]));
+        assert!(is_nullifier_key(&key));
+        assert_eq!(is_commitment_key(&key), None);
+        assert!(!is_nullifier_key(&anchor_key(&MerkleRoot(
+            [1; FIELD_ELEMENT_LEN]
+        ))));
+    }
+}
@@ -530,6 +530,8 @@ impl KeySeg for Address {
     Hash,
     BorshSerialize,
     BorshDeserialize,
+    Serialize,
+    Deserialize,
 )]
 pub struct Epoch(pub u64);
 
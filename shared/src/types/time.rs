@@ -2,9 +2,11 @@
 
 use std::convert::{TryFrom, TryInto};
 use std::ops::{Add, Sub};
+use std::str::FromStr;
 
 use borsh::{BorshDeserialize, BorshSerialize};
 pub use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
 
 /// Check if the given `duration` has passed since the given `start.
 pub fn duration_passed(
@@ -27,6 +29,8 @@ pub fn duration_passed(
     Hash,
     BorshSerialize,
     BorshDeserialize,
+    Serialize,
+    Deserialize,
 )]
 pub struct DurationSecs(pub u64);
 
@@ -126,6 +130,36 @@ impl BorshDeserialize for DateTimeUtc {
     }
 }
 
+impl Serialize for DateTimeUtc {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.0.to_rfc3339().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for DateTimeUtc {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let raw = String::deserialize(deserializer)?;
+        raw.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl FromStr for DateTimeUtc {
+    type Err = chrono::ParseError;
+
+    /// Parse a date and time in the RFC 3339 format, e.g.
+    /// `2021-12-31T00:00:00Z`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let actual = DateTime::parse_from_rfc3339(s)?;
+        Ok(Self(actual.into()))
+    }
+}
+
 impl From<DateTime<Utc>> for DateTimeUtc {
     fn from(dt: DateTime<Utc>) -> Self {
         Self(dt)
@@ -1,5 +1,6 @@
 //! A basic fungible token
 
+use std::collections::HashSet;
 use std::fmt::Display;
 use std::str::FromStr;
 
@@ -7,8 +8,8 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::types::address::Address;
-use crate::types::storage::{DbKeySeg, Key, KeySeg};
+use crate::types::address::{Address, InternalAddress};
+use crate::types::storage::{DbKeySeg, Epoch, Key, KeySeg};
 
 /// Amount in micro units. For different granularity another representation
 /// might be more appropriate.
@@ -29,8 +30,15 @@ pub struct Amount {
     micro: u64,
 }
 
-/// Maximum decimal places in a token [`Amount`] and [`Change`].
+/// Maximum decimal places in a token [`Amount`] and [`Change`]. This is also
+/// the default number of decimals of tokens that are not in the token
+/// registry.
 pub const MAX_DECIMAL_PLACES: u32 = 6;
+/// Maximum number of decimals of a token in the token registry, which is the
+/// native denomination. An [`Amount`] of a token in its smallest units is
+/// bounded by `u64::MAX`, so with more decimals the supply of whole tokens
+/// would be too small.
+pub const MAX_TOKEN_DECIMALS: u8 = MAX_DECIMAL_PLACES as u8;
 /// Decimal scale of token [`Amount`] and [`Change`].
 pub const SCALE: u64 = 1_000_000;
 const SCALE_F64: f64 = SCALE as f64;
@@ -47,19 +55,69 @@ impl Amount {
     /// Spend a given amount
     /// Panics when given `amount` > `self.micro` amount.
     pub fn spend(&mut self, amount: &Amount) {
-        self.micro -= amount.micro
+        *self = self
+            .checked_sub(amount)
+            .expect("Cannot spend more than the available amount");
     }
 
     /// Receive a given amount
+    /// Panics when the resulting amount overflows.
     pub fn receive(&mut self, amount: &Amount) {
-        self.micro += amount.micro
+        *self = self
+            .checked_add(amount)
+            .expect("Receiving the amount would overflow");
+    }
+
+    /// Checked addition. Returns `None` on overflow.
+    pub fn checked_add(&self, amount: &Amount) -> Option<Self> {
+        self.micro
+            .checked_add(amount.micro)
+            .map(|micro| Self { micro })
+    }
+
+    /// Checked subtraction. Returns `None` on underflow, i.e. when given
+    /// `amount` > `self`.
+    pub fn checked_sub(&self, amount: &Amount) -> Option<Self> {
+        self.micro
+            .checked_sub(amount.micro)
+            .map(|micro| Self { micro })
+    }
+
+    /// Checked multiplication by a scalar. Returns `None` on overflow.
+    pub fn checked_mul(&self, factor: u64) -> Option<Self> {
+        self.micro.checked_mul(factor).map(|micro| Self { micro })
     }
 
     /// Create a new amount from whole number of tokens
+    /// Panics when the amount in micro units overflows.
     pub fn whole(amount: u64) -> Self {
-        Self {
-            micro: amount * SCALE,
-        }
+        Self::checked_whole(amount)
+            .expect("The whole token amount is too large")
+    }
+
+    /// Create a new amount from whole number of tokens. Returns `None` when the
+    /// amount in micro units overflows.
+    pub fn checked_whole(amount: u64) -> Option<Self> {
+        Self::from(amount).checked_mul(SCALE)
+    }
+
+    /// Parse an amount in decimal notation of a token with the given number
+    /// of decimals into the token's smallest units.
+    pub fn parse_with_decimals(
+        s: &str,
+        decimals: u8,
+    ) -> Result<Self, AmountParseError> {
+        DecimalAmount::from_str(s)?.to_amount(decimals)
+    }
+
+    /// Format the amount in decimal notation of a token with the given number
+    /// of decimals.
+    pub fn to_string_with_decimals(&self, decimals: u8) -> String {
+        let mut decimal = rust_decimal::Decimal::from(self.micro);
+        decimal
+            .set_scale(decimals as u32)
+            .expect("The number of decimals should be within the valid range");
+        decimal.normalize().to_string()
     }
 }
 
@@ -120,9 +178,11 @@ impl From<u64> for Amount {
 
 impl Display for Amount {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let decimal =
-            rust_decimal::Decimal::new(self.micro as i64, 6).normalize();
-        write!(f, "{}", decimal)
+        write!(
+            f,
+            "{}",
+            self.to_string_with_decimals(MAX_DECIMAL_PLACES as u8)
+        )
     }
 }
 
@@ -138,31 +198,74 @@ pub enum AmountParseError {
     ScaleTooLarge(u32),
     #[error("Error decoding token amount, the value is within invalid range.")]
     InvalidRange,
+    #[error(
+        "Error decoding token amount, too many decimal places: {0}. The token \
+         has {1} decimals"
+    )]
+    TooManyDecimals(u32, u8),
 }
 
 impl FromStr for Amount {
     type Err = AmountParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match rust_decimal::Decimal::from_str(s) {
-            Ok(decimal) => {
-                let scale = decimal.scale();
-                if scale > 6 {
-                    return Err(AmountParseError::ScaleTooLarge(scale));
-                }
-                let whole =
-                    decimal * rust_decimal::Decimal::new(SCALE as i64, 0);
-                let micro: u64 =
-                    rust_decimal::prelude::ToPrimitive::to_u64(&whole)
-                        .ok_or(AmountParseError::InvalidRange)?;
-                Ok(Self { micro })
-            }
-            Err(err) => Err(AmountParseError::InvalidDecimal(err)),
+        let decimal = DecimalAmount::from_str(s)?;
+        let scale = decimal.0.scale();
+        if scale > MAX_DECIMAL_PLACES {
+            return Err(AmountParseError::ScaleTooLarge(scale));
         }
+        decimal.to_amount(MAX_DECIMAL_PLACES as u8)
+    }
+}
+
+/// A token amount in decimal notation, e.g. given by a user, that can be
+/// converted into an [`Amount`] once the token's number of decimals is known.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecimalAmount(rust_decimal::Decimal);
+
+impl DecimalAmount {
+    /// Convert into the smallest units of a token with the given number of
+    /// decimals.
+    pub fn to_amount(self, decimals: u8) -> Result<Amount, AmountParseError> {
+        let scale = self.0.scale();
+        if scale > decimals as u32 {
+            return Err(AmountParseError::TooManyDecimals(scale, decimals));
+        }
+        let unit = 10_u64
+            .checked_pow(decimals as u32)
+            .ok_or(AmountParseError::InvalidRange)?;
+        let units = self
+            .0
+            .checked_mul(rust_decimal::Decimal::from(unit))
+            .ok_or(AmountParseError::InvalidRange)?;
+        let micro: u64 = rust_decimal::prelude::ToPrimitive::to_u64(&units)
+            .ok_or(AmountParseError::InvalidRange)?;
+        Ok(Amount { micro })
+    }
+}
+
+impl FromStr for DecimalAmount {
+    type Err = AmountParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        rust_decimal::Decimal::from_str(s)
+            .map(Self)
+            .map_err(AmountParseError::InvalidDecimal)
+    }
+}
+
+impl Display for DecimalAmount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
     }
 }
 
 const BALANCE_STORAGE_KEY: &str = "balance";
+const TOTAL_SUPPLY_STORAGE_KEY: &str = "total_supply";
+const REGISTRY_TOKEN_STORAGE_KEY: &str = "token";
+const REGISTRY_SYMBOL_STORAGE_KEY: &str = "symbol";
+const REGISTRY_ADDR: InternalAddress = InternalAddress::TokenRegistry;
+const ALLOWANCE_STORAGE_KEY: &str = "allowance";
 
 /// Obtain a storage key for user's balance.
 pub fn balance_key(token_addr: &Address, owner: &Address) -> Key {
@@ -209,6 +312,269 @@ pub fn is_any_token_balance_key(key: &Key) -> Option<&Address> {
     }
 }
 
+/// Check if the given storage key is balance key for unspecified token. If it
+/// is, returns the token and the owner.
+pub fn is_any_balance_key(key: &Key) -> Option<(&Address, &Address)> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(token), DbKeySeg::StringSeg(key), DbKeySeg::AddressSeg(owner)]
+            if key == BALANCE_STORAGE_KEY =>
+        {
+            Some((token, owner))
+        }
+        _ => None,
+    }
+}
+
+/// Token transfer, mint and burn errors
+#[allow(missing_docs)]
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum TransferError {
+    #[error(
+        "Insufficient balance of token {token} at {owner}: the balance is \
+         {balance}, but {amount} is required"
+    )]
+    InsufficientBalance {
+        token: Address,
+        owner: Address,
+        balance: Amount,
+        amount: Amount,
+    },
+    #[error(
+        "The balance of token {token} at {owner} would overflow after \
+         receiving {amount}"
+    )]
+    BalanceOverflow {
+        token: Address,
+        owner: Address,
+        amount: Amount,
+    },
+    #[error("The token {0} has no minter, its supply cannot be changed")]
+    NoMinter(Address),
+    #[error(
+        "The total supply of token {token} would overflow after minting \
+         {amount}"
+    )]
+    TotalSupplyOverflow { token: Address, amount: Amount },
+    #[error(
+        "The total supply of token {token} is {total_supply}, {amount} cannot \
+         be burned"
+    )]
+    TotalSupplyUnderflow {
+        token: Address,
+        total_supply: Amount,
+        amount: Amount,
+    },
+    #[error(
+        "Insufficient allowance of token {token} from {owner} to {spender}: \
+         the allowance is {allowance}, but {amount} is required"
+    )]
+    InsufficientAllowance {
+        token: Address,
+        owner: Address,
+        spender: Address,
+        allowance: Amount,
+        amount: Amount,
+    },
+    #[error(
+        "The allowance of token {token} from {owner} to {spender} has expired \
+         in epoch {expiry}"
+    )]
+    AllowanceExpired {
+        token: Address,
+        owner: Address,
+        spender: Address,
+        expiry: Epoch,
+    },
+}
+
+/// Obtain a storage key for a token's total supply.
+pub fn total_supply_key(token_addr: &Address) -> Key {
+    Key::from(token_addr.to_db_key())
+        .push(&TOTAL_SUPPLY_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is a total supply key. If it is, returns
+/// the token's address.
+pub fn is_total_supply_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(token), DbKeySeg::StringSeg(key)]
+            if key == TOTAL_SUPPLY_STORAGE_KEY =>
+        {
+            Some(token)
+        }
+        _ => None,
+    }
+}
+
+/// Get the change of an amount from its encoded values before and after a
+/// transaction. A missing value is a zero amount. Returns `None` if either
+/// value cannot be decoded.
+pub fn amount_change(
+    pre: Option<&[u8]>,
+    post: Option<&[u8]>,
+) -> Option<Change> {
+    let decode = |bytes: Option<&[u8]>| match bytes {
+        Some(bytes) => Amount::try_from_slice(bytes).ok(),
+        None => Some(Amount::default()),
+    };
+    Some(decode(post)?.change() - decode(pre)?.change())
+}
+
+/// Validate the changes of a token's balances and total supply, shared by the
+/// native and the wasm token validity predicates. The sum of the balance
+/// changes must be equal to the change of the token's total supply, which can
+/// only be increased with the token's minter in the verifiers. Any debited
+/// owner must be in the verifiers, which is enough to burn its tokens. The
+/// supply of a token without a minter cannot change. Any other keys are
+/// denied.
+///
+/// The `read_change` function returns the change of the amount at the given
+/// key, or `None` if it cannot be decoded, in which case the changes are
+/// rejected. The `read_minter` function returns the token's minter.
+pub fn validate_changes<E>(
+    token: &Address,
+    keys_changed: &HashSet<Key>,
+    verifiers: &HashSet<Address>,
+    mut read_change: impl FnMut(&Key) -> Result<Option<Change>, E>,
+    read_minter: impl FnOnce() -> Result<Option<Address>, E>,
+) -> Result<bool, E> {
+    let mut change: Change = 0;
+    let mut supply_change: Change = 0;
+    for key in keys_changed {
+        if let Some(owner) = is_balance_key(token, key) {
+            let this_change = match read_change(key)? {
+                Some(this_change) => this_change,
+                None => return Ok(false),
+            };
+            // reject the changes if their sum overflows
+            change = match change.checked_add(this_change) {
+                Some(change) => change,
+                None => return Ok(false),
+            };
+            // make sure that the spender approved the transaction
+            if this_change < 0 && !verifiers.contains(owner) {
+                return Ok(false);
+            }
+        } else if is_total_supply_key(key) == Some(token) {
+            supply_change = match read_change(key)? {
+                Some(supply_change) => supply_change,
+                None => return Ok(false),
+            };
+        } else {
+            // deny any other keys
+            return Ok(false);
+        }
+    }
+    if change != supply_change {
+        return Ok(false);
+    }
+    if supply_change == 0 {
+        return Ok(true);
+    }
+    Ok(match read_minter()? {
+        // make sure that the minter approved an increase of the total
+        // supply, the burned tokens have been approved by their owners
+        Some(minter) => supply_change < 0 || verifiers.contains(&minter),
+        None => false,
+    })
+}
+
+/// Metadata of a token in the token registry
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct TokenInfo {
+    /// A short ticker symbol, e.g. "XAN". The symbols are unique in the
+    /// registry regardless of their letter case.
+    pub symbol: String,
+    /// A human-readable name of the token
+    pub name: String,
+    /// The number of decimal places used to display the token's amounts. An
+    /// [`Amount`] of the token is in its smallest units.
+    pub decimals: u8,
+    /// An account that may mint and burn the token, if any. Tokens without a
+    /// minter have a fixed supply. The minter may also be an internal address,
+    /// so that the supply is controlled by its native validity predicate.
+    pub minter: Option<Address>,
+}
+
+/// Maximum length of a token symbol
+pub const MAX_SYMBOL_LEN: usize = 12;
+/// Maximum length of a token name
+pub const MAX_NAME_LEN: usize = 64;
+
+impl TokenInfo {
+    /// Check that the token info can be put into the registry: the symbol is
+    /// a non-empty ASCII alphanumeric string, the name is non-empty, and the
+    /// number of decimals is within [`MAX_TOKEN_DECIMALS`].
+    pub fn is_valid(&self) -> bool {
+        !self.symbol.is_empty()
+            && self.symbol.len() <= MAX_SYMBOL_LEN
+            && self.symbol.chars().all(|c| c.is_ascii_alphanumeric())
+            && !self.name.trim().is_empty()
+            && self.name.len() <= MAX_NAME_LEN
+            && self.decimals <= MAX_TOKEN_DECIMALS
+    }
+}
+
+/// Obtain a storage key for a token's info in the token registry.
+pub fn registry_key(token_addr: &Address) -> Key {
+    registry_prefix()
+        .push(&token_addr.to_db_key())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key prefix for all tokens' info in the token registry.
+pub fn registry_prefix() -> Key {
+    Key::from(Address::Internal(REGISTRY_ADDR).to_db_key())
+        .push(&REGISTRY_TOKEN_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is a token's info in the token registry. If
+/// it is, returns the token's address.
+pub fn is_registry_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(Address::Internal(REGISTRY_ADDR)), DbKeySeg::StringSeg(key), DbKeySeg::AddressSeg(token)]
+            if key == REGISTRY_TOKEN_STORAGE_KEY =>
+        {
+            Some(token)
+        }
+        _ => None,
+    }
+}
+
+/// Obtain a storage key for a token symbol in the token registry, which maps
+/// the symbol to the token's address. The symbol is case-insensitive.
+pub fn registry_symbol_key(symbol: &str) -> Key {
+    Key::from(Address::Internal(REGISTRY_ADDR).to_db_key())
+        .push(&REGISTRY_SYMBOL_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+        .push(&symbol.to_lowercase())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is a token symbol in the token registry. If
+/// it is, returns the lowercase symbol.
+pub fn is_registry_symbol_key(key: &Key) -> Option<&String> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(Address::Internal(REGISTRY_ADDR)), DbKeySeg::StringSeg(key), DbKeySeg::StringSeg(symbol)]
+            if key == REGISTRY_SYMBOL_STORAGE_KEY =>
+        {
+            Some(symbol)
+        }
+        _ => None,
+    }
+}
+
 /// A simple bilateral token transfer
 #[derive(
     Debug,
@@ -233,6 +599,146 @@ pub struct Transfer {
     pub amount: Amount,
 }
 
+/// Mint new tokens to the target. The token's minter must authorize it.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Hash,
+    Eq,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+)]
+pub struct Mint {
+    /// Target address will receive the minted tokens
+    pub target: Address,
+    /// Token's address
+    pub token: Address,
+    /// The amount of tokens
+    pub amount: Amount,
+}
+
+/// Burn tokens from the source. Only the source must authorize it, but the
+/// token must have a minter, otherwise its supply is fixed.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Hash,
+    Eq,
+    PartialOrd,
+    Serialize,
+    Deserialize,
+)]
+pub struct Burn {
+    /// Source address will lose the burned tokens
+    pub source: Address,
+    /// Token's address
+    pub token: Address,
+    /// The amount of tokens
+    pub amount: Amount,
+}
+
+/// Obtain a storage key for the allowance given by the owner to the spender
+/// to transfer the owner's tokens. The allowance is stored in the owner's
+/// storage sub-space and its modifications are checked by the owner's VP. The
+/// token and the spender are encoded as string segments, so that the key
+/// doesn't trigger their VPs.
+pub fn allowance_key(
+    owner: &Address,
+    token: &Address,
+    spender: &Address,
+) -> Key {
+    Key::from(owner.to_db_key())
+        .push(&ALLOWANCE_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+        .push(&token.encode())
+        .expect("Cannot obtain a storage key")
+        .push(&spender.encode())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is an allowance key. If it is, returns the
+/// owner, the token and the spender.
+pub fn is_allowance_key(key: &Key) -> Option<(&Address, Address, Address)> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(owner), DbKeySeg::StringSeg(key), DbKeySeg::StringSeg(token), DbKeySeg::StringSeg(spender)]
+            if key == ALLOWANCE_STORAGE_KEY =>
+        {
+            let token = Address::decode(token).ok()?;
+            let spender = Address::decode(spender).ok()?;
+            Some((owner, token, spender))
+        }
+        _ => None,
+    }
+}
+
+/// An allowance for a spender to transfer up to the amount of the owner's
+/// tokens, optionally limited to the epochs before the expiry epoch
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, BorshSerialize, BorshDeserialize,
+)]
+pub struct Allowance {
+    /// The remaining amount that can be transferred by the spender
+    pub amount: Amount,
+    /// The first epoch in which the allowance can no longer be used
+    pub expiry: Option<Epoch>,
+}
+
+impl Allowance {
+    /// Check if the allowance cannot be used in the given epoch.
+    pub fn is_expired(&self, epoch: Epoch) -> bool {
+        matches!(self.expiry, Some(expiry) if epoch >= expiry)
+    }
+}
+
+/// Approve the spender to transfer up to the amount of the owner's tokens. It
+/// replaces any previous allowance of the spender for the token.
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct Approve {
+    /// The owner of the tokens
+    pub owner: Address,
+    /// The address allowed to transfer the owner's tokens
+    pub spender: Address,
+    /// Token's address
+    pub token: Address,
+    /// The maximum amount of tokens
+    pub amount: Amount,
+    /// The first epoch in which the allowance can no longer be used, if any
+    pub expiry: Option<Epoch>,
+}
+
+/// Revoke the spender's allowance to transfer the owner's tokens
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct Revoke {
+    /// The owner of the tokens
+    pub owner: Address,
+    /// The address whose allowance is revoked
+    pub spender: Address,
+    /// Token's address
+    pub token: Address,
+}
+
+/// A transfer of the owner's tokens by a spender with an allowance
+#[derive(Debug, Clone, PartialEq, BorshSerialize, BorshDeserialize)]
+pub struct TransferFrom {
+    /// The address spending the allowance, it has to authorize the transfer
+    pub spender: Address,
+    /// The owner of the tokens
+    pub owner: Address,
+    /// Target address will receive the tokens
+    pub target: Address,
+    /// Token's address
+    pub token: Address,
+    /// The amount of tokens
+    pub amount: Amount,
+}
+
 #[cfg(test)]
 mod tests {
     use proptest::prelude::*;
@@ -250,5 +756,114 @@ mod tests {
                 let identity = Amount::from(float);
                 assert_eq!(amount, identity);
         }
+
+        /// Checked addition should only fail when the sum overflows `u64`.
+        #[test]
+        fn test_token_amount_checked_add(a: u64, b: u64) {
+            let sum = Amount::from(a).checked_add(&Amount::from(b));
+            match a.checked_add(b) {
+                Some(expected) => {
+                    assert_eq!(sum, Some(Amount::from(expected)));
+                    // Adding and subtracting back should be an identity
+                    let diff = sum.unwrap().checked_sub(&Amount::from(b));
+                    assert_eq!(diff, Some(Amount::from(a)));
+                }
+                None => assert_eq!(sum, None),
+            }
+        }
+
+        /// Checked subtraction should only fail when the subtrahend is
+        /// greater than the amount.
+        #[test]
+        fn test_token_amount_checked_sub(a: u64, b: u64) {
+            let diff = Amount::from(a).checked_sub(&Amount::from(b));
+            if b > a {
+                assert_eq!(diff, None);
+            } else {
+                assert_eq!(diff, Some(Amount::from(a - b)));
+            }
+        }
+
+        /// Checked multiplication should only fail when the product
+        /// overflows `u64`.
+        #[test]
+        fn test_token_amount_checked_mul(a: u64, factor: u64) {
+            let product = Amount::from(a).checked_mul(factor);
+            assert_eq!(product, a.checked_mul(factor).map(Amount::from));
+        }
+
+        /// Whole token amounts should only fail when the amount in micro
+        /// units overflows `u64`.
+        #[test]
+        fn test_token_amount_checked_whole(whole: u64) {
+            let amount = Amount::checked_whole(whole);
+            if whole > u64::MAX / SCALE {
+                assert_eq!(amount, None);
+            } else {
+                assert_eq!(amount, Some(Amount::from(whole * SCALE)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_token_amount_edge_cases() {
+        let max = Amount::from(u64::MAX);
+        let one = Amount::from(1);
+        let zero = Amount::default();
+
+        assert_eq!(max.checked_add(&one), None);
+        assert_eq!(max.checked_add(&zero), Some(max));
+        assert_eq!(zero.checked_sub(&one), None);
+        assert_eq!(max.checked_sub(&max), Some(zero));
+        assert_eq!(max.checked_mul(2), None);
+        assert_eq!(max.checked_mul(1), Some(max));
+        assert_eq!(max.checked_mul(0), Some(zero));
+        assert_eq!(Amount::checked_whole(u64::MAX / SCALE + 1), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_token_amount_spend_underflow() {
+        let mut amount = Amount::from(1);
+        amount.spend(&Amount::from(2));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_token_amount_receive_overflow() {
+        let mut amount = Amount::from(u64::MAX);
+        amount.receive(&Amount::from(1));
+    }
+
+    #[test]
+    fn test_allowance_key() {
+        let owner = crate::types::address::testing::established_address_1();
+        let spender = crate::types::address::testing::established_address_2();
+        let token = crate::types::address::xan();
+        let key = allowance_key(&owner, &token, &spender);
+        assert_eq!(
+            is_allowance_key(&key),
+            Some((&owner, token.clone(), spender.clone()))
+        );
+        // Only the owner's VP is triggered by the key
+        assert_eq!(key.find_addresses(), vec![owner.clone()]);
+        // The key survives a round-trip through its string representation
+        let parsed = Key::parse(key.to_string()).unwrap();
+        assert_eq!(parsed, key);
+        assert_eq!(is_any_token_balance_key(&key), None);
+        let key = balance_key(&token, &owner);
+        assert_eq!(is_allowance_key(&key), None);
+    }
+
+    #[test]
+    fn test_allowance_expiry() {
+        let allowance = Allowance {
+            amount: Amount::from(1),
+            expiry: Some(Epoch(2)),
+        };
+        assert!(!allowance.is_expired(Epoch(1)));
+        assert!(allowance.is_expired(Epoch(2)));
+        assert!(allowance.is_expired(Epoch(3)));
+        assert!(!Allowance::default().is_expired(Epoch(u64::MAX)));
     }
 }
@@ -1,9 +1,16 @@
 //! Types that are used in transactions.
 
+use std::collections::BTreeMap;
+
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
-use super::key::ed25519::PublicKey;
+use super::governance::{ProposalContent, Vote};
+use super::key::common::PublicKey;
+use super::key::ed25519;
+use super::recovery::RecoveryConfig;
+use super::token::{self, TokenInfo};
+use super::vesting::VestingSchedule;
 use crate::types::address::Address;
 
 /// A tx data type to update an account's validity predicate
@@ -23,6 +30,77 @@ pub struct UpdateVp {
     pub vp_code: Vec<u8>,
 }
 
+/// A tx data type to update an account's public key
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct UpdatePk {
+    /// An address of the account
+    pub addr: Address,
+    /// The new public key that replaces the account's current key
+    pub public_key: PublicKey,
+}
+
+/// A tx data type to set or remove an account's recovery configuration
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct UpdateRecovery {
+    /// An address of the account
+    pub addr: Address,
+    /// The new recovery configuration or `None` to disable the recovery
+    pub config: Option<RecoveryConfig>,
+}
+
+/// A tx data type for a guardian's approval of a new public key for an
+/// account. A proposal of a different key replaces any previous proposal.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct ProposeRecovery {
+    /// An address of the recovered account
+    pub owner: Address,
+    /// An address of the approving guardian
+    pub guardian: Address,
+    /// The proposed public key
+    pub public_key: PublicKey,
+}
+
+/// A tx data type to veto an account's recovery proposal with its current
+/// key or to replace the account's key with the key of a ready recovery
+/// proposal
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct Recovery {
+    /// An address of the recovered account
+    pub owner: Address,
+}
+
 /// A tx data type to initialize a new established account
 #[derive(
     Debug,
@@ -41,3 +119,283 @@ pub struct InitAccount {
     /// The VP code
     pub vp_code: Vec<u8>,
 }
+
+/// A tx data type to initialize a new established multisig account
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct InitMultisigAccount {
+    /// Public keys to be written into the account's storage. Transactions for
+    /// the newly created account have to be signed by at least `threshold`
+    /// of these keys.
+    pub public_keys: Vec<PublicKey>,
+    /// The minimum number of signatures required to authorize a transaction
+    pub threshold: u64,
+    /// The VP code
+    pub vp_code: Vec<u8>,
+}
+
+/// A tx data type to initialize a new established vesting account
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct InitVestingAccount {
+    /// Public key to be written into the account's storage. This can be used
+    /// for signature verification of transactions for the newly created
+    /// account.
+    pub public_key: PublicKey,
+    /// The vesting schedule of the account's tokens, which cannot be changed
+    /// after the account is created
+    pub schedule: VestingSchedule,
+    /// The VP code
+    pub vp_code: Vec<u8>,
+}
+
+/// A tx data type to initialize a new token account and add it to the token
+/// registry
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct InitToken {
+    /// The token's metadata for the registry
+    pub info: TokenInfo,
+    /// The VP code of the token's account. The registered tokens are
+    /// validated by the native token VP, so this VP is not being run, but
+    /// every account must have one.
+    pub vp_code: Vec<u8>,
+}
+
+/// A tx data type to submit a new governance proposal
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct InitProposal {
+    /// The address that submits the proposal and pays the deposit
+    pub author: Address,
+    /// The proposed change
+    pub content: ProposalContent,
+    /// The amount of XAN to deposit with the proposal
+    pub deposit: token::Amount,
+}
+
+/// A tx data type to vote on a governance proposal
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct VoteProposal {
+    /// The proposal's identifier
+    pub id: u64,
+    /// The address whose voting power is used for the vote
+    pub voter: Address,
+    /// The vote
+    pub vote: Vote,
+}
+
+/// A tx data type to spend the treasury funds, which has to be authorized by
+/// the treasury's admin
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct TreasurySpend {
+    /// The recipient of the funds
+    pub target: Address,
+    /// The transferred token
+    pub token: Address,
+    /// The transferred amount
+    pub amount: token::Amount,
+}
+
+/// A tx data type to claim the rewards of a bond to a validator. When the
+/// delegator is the validator itself, its commission is claimed too.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct ClaimRewards {
+    /// The owner of the bond, who gets the rewards
+    pub delegator: Address,
+    /// The validator to which the XAN is bonded
+    pub validator: Address,
+}
+
+/// A tx data type to register a new validator
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct BecomeValidator {
+    /// The validator's account
+    pub address: Address,
+    /// The key that the validator uses to sign the blocks
+    pub consensus_key: ed25519::PublicKey,
+    /// The validator's commission rate in basis points
+    pub commission_rate: u64,
+    /// The maximum change of the validator's commission rate in an epoch, in
+    /// basis points
+    pub max_commission_rate_change: u64,
+}
+
+/// A tx data type to bond XAN to a validator
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct Bond {
+    /// The owner of the bond
+    pub delegator: Address,
+    /// The validator to which the XAN is bonded
+    pub validator: Address,
+    /// The bonded amount
+    pub amount: token::Amount,
+}
+
+/// A tx data type to unbond XAN from a validator. The unbonded XAN can be
+/// withdrawn after the unbonding epochs.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct Unbond {
+    /// The owner of the bond
+    pub delegator: Address,
+    /// The validator from which the XAN is unbonded
+    pub validator: Address,
+    /// The unbonded amount
+    pub amount: token::Amount,
+}
+
+/// A tx data type to withdraw all the withdrawable XAN unbonded from a
+/// validator
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct Withdraw {
+    /// The owner of the unbonded XAN
+    pub delegator: Address,
+    /// The validator from which the XAN has been unbonded
+    pub validator: Address,
+}
+
+/// A tx data type to change a validator's commission rate
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct ChangeCommission {
+    /// The validator
+    pub validator: Address,
+    /// The new commission rate in basis points
+    pub rate: u64,
+}
+
+/// A tx data type to unjail a validator
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct Unjail {
+    /// The jailed validator
+    pub validator: Address,
+}
+
+/// A custom event emitted by a transaction. If the transaction is accepted, the
+/// event is included in the block results, from where it can be queried by
+/// clients.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct TxEvent {
+    /// The type of the event
+    pub event_type: String,
+    /// The event's attributes
+    pub attributes: BTreeMap<String, String>,
+}
+
+impl TxEvent {
+    /// Get the size of the event's type and attributes in bytes
+    pub fn size(&self) -> usize {
+        self.attributes
+            .iter()
+            .fold(self.event_type.len(), |acc, (key, value)| {
+                acc + key.len() + value.len()
+            })
+    }
+}
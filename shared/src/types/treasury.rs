@@ -0,0 +1,20 @@
+//! The community treasury, which holds the protocol-owned funds
+
+use crate::types::address::{Address, InternalAddress};
+use crate::types::storage::{Key, KeySeg};
+
+const ADDR: InternalAddress = InternalAddress::Treasury;
+const ADMIN_STORAGE_KEY: &str = "admin";
+
+/// Get the address of the treasury
+pub fn treasury_address() -> Address {
+    Address::Internal(ADDR)
+}
+
+/// Obtain a storage key for the address of the account that can spend the
+/// treasury funds, if any, in addition to the governance proposals.
+pub fn admin_key() -> Key {
+    Key::from(treasury_address().to_db_key())
+        .push(&ADMIN_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
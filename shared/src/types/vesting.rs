@@ -0,0 +1,184 @@
+//! Vesting schedules of time-locked accounts
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::types::address::Address;
+use crate::types::storage::{DbKeySeg, Epoch, Key, KeySeg};
+use crate::types::time::DateTimeUtc;
+use crate::types::token::Amount;
+
+const VESTING_STORAGE_KEY: &str = "vesting";
+
+/// A vesting schedule of an account's tokens. Before the cliff epoch, the
+/// whole amount is locked. From the cliff epoch, the amount unlocks linearly
+/// from the start epoch until it's fully unlocked at the end epoch. A schedule
+/// with the cliff equal to the end unlocks all at once at the cliff. A
+/// schedule may also have a cliff time, before which the whole amount is locked
+/// regardless of the epoch.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct VestingSchedule {
+    /// The vested token's address
+    pub token: Address,
+    /// The total amount of tokens subject to the schedule
+    pub amount: Amount,
+    /// The epoch from which the amount starts to unlock linearly
+    pub start: Epoch,
+    /// The first epoch in which any tokens are unlocked
+    pub cliff: Epoch,
+    /// The first epoch in which all the tokens are unlocked
+    pub end: Epoch,
+    /// The block time before which the whole amount is locked, if any
+    pub cliff_time: Option<DateTimeUtc>,
+}
+
+impl VestingSchedule {
+    /// Check that the epochs are ordered as start <= cliff <= end.
+    pub fn is_valid(&self) -> bool {
+        self.start <= self.cliff && self.cliff <= self.end
+    }
+
+    /// Get the amount that is still locked in the given epoch and at the
+    /// given block time. When the schedule has a cliff time and the block time
+    /// isn't known, the whole amount is locked.
+    pub fn locked_amount(
+        &self,
+        epoch: Epoch,
+        time: Option<DateTimeUtc>,
+    ) -> Amount {
+        if epoch < self.cliff {
+            return self.amount;
+        }
+        if let Some(cliff_time) = self.cliff_time {
+            if time.map_or(true, |time| time < cliff_time) {
+                return self.amount;
+            }
+        }
+        if epoch >= self.end {
+            return Amount::default();
+        }
+        // the cliff is before the end, so the start is too
+        let elapsed = (epoch.0 - self.start.0) as u128;
+        let duration = (self.end.0 - self.start.0) as u128;
+        let total = u64::from(self.amount) as u128;
+        let unlocked = (total * elapsed / duration) as u64;
+        Amount::from(u64::from(self.amount) - unlocked)
+    }
+}
+
+/// Obtain a storage key for the vesting schedule of an account.
+pub fn vesting_key(owner: &Address) -> Key {
+    Key::from(owner.to_db_key())
+        .push(&VESTING_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is a vesting schedule key. If it is,
+/// returns the owner.
+pub fn is_vesting_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(owner), DbKeySeg::StringSeg(key)]
+            if key == VESTING_STORAGE_KEY =>
+        {
+            Some(owner)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::address;
+    use crate::types::time::{Duration, TimeZone, Utc};
+
+    fn new_schedule(start: u64, cliff: u64, end: u64) -> VestingSchedule {
+        VestingSchedule {
+            token: address::xan(),
+            amount: Amount::from(1000),
+            start: Epoch(start),
+            cliff: Epoch(cliff),
+            end: Epoch(end),
+            cliff_time: None,
+        }
+    }
+
+    #[test]
+    fn test_linear_vesting() {
+        let schedule = new_schedule(10, 10, 20);
+        assert!(schedule.is_valid());
+        assert_eq!(schedule.locked_amount(Epoch(0), None), Amount::from(1000));
+        assert_eq!(schedule.locked_amount(Epoch(10), None), Amount::from(1000));
+        assert_eq!(schedule.locked_amount(Epoch(11), None), Amount::from(900));
+        assert_eq!(schedule.locked_amount(Epoch(15), None), Amount::from(500));
+        assert_eq!(schedule.locked_amount(Epoch(20), None), Amount::default());
+        assert_eq!(schedule.locked_amount(Epoch(100), None), Amount::default());
+    }
+
+    #[test]
+    fn test_vesting_with_cliff() {
+        // linear vesting with a cliff
+        let schedule = new_schedule(10, 15, 20);
+        assert!(schedule.is_valid());
+        assert_eq!(schedule.locked_amount(Epoch(14), None), Amount::from(1000));
+        assert_eq!(schedule.locked_amount(Epoch(15), None), Amount::from(500));
+        assert_eq!(schedule.locked_amount(Epoch(16), None), Amount::from(400));
+
+        // everything unlocks at the cliff
+        let schedule = new_schedule(10, 20, 20);
+        assert!(schedule.is_valid());
+        assert_eq!(schedule.locked_amount(Epoch(19), None), Amount::from(1000));
+        assert_eq!(schedule.locked_amount(Epoch(20), None), Amount::default());
+    }
+
+    #[test]
+    fn test_vesting_with_cliff_time() {
+        let cliff_time: DateTimeUtc =
+            Utc.ymd(2022, 1, 1).and_hms(0, 0, 0).into();
+        let schedule = VestingSchedule {
+            cliff_time: Some(cliff_time),
+            ..new_schedule(10, 10, 20)
+        };
+        assert!(schedule.is_valid());
+        let before = cliff_time - Duration::seconds(1);
+        // everything is locked before the cliff time or if the time is unknown
+        assert_eq!(
+            schedule.locked_amount(Epoch(15), Some(before)),
+            Amount::from(1000)
+        );
+        assert_eq!(schedule.locked_amount(Epoch(15), None), Amount::from(1000));
+        // from the cliff time, the tokens unlock by the epochs
+        assert_eq!(
+            schedule.locked_amount(Epoch(15), Some(cliff_time)),
+            Amount::from(500)
+        );
+        assert_eq!(
+            schedule.locked_amount(Epoch(5), Some(cliff_time)),
+            Amount::from(1000)
+        );
+    }
+
+    #[test]
+    fn test_invalid_schedule() {
+        assert!(!new_schedule(10, 5, 20).is_valid());
+        assert!(!new_schedule(10, 25, 20).is_valid());
+    }
+
+    #[test]
+    fn test_vesting_key() {
+        let owner = address::testing::established_address_1();
+        let key = vesting_key(&owner);
+        assert_eq!(is_vesting_key(&key), Some(&owner));
+        let other = key.push(&"other".to_owned()).unwrap();
+        assert_eq!(is_vesting_key(&other), None);
+    }
+}
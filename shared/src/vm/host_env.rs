@@ -15,9 +15,14 @@ use crate::ledger::storage::{self, Storage, StorageHasher};
 use crate::ledger::vp_env;
 use crate::proto::Tx;
 use crate::types::address::{self, Address};
+use crate::types::hash;
 use crate::types::internal::HostEnvResult;
-use crate::types::key::ed25519::{verify_tx_sig, PublicKey, Signature};
+use crate::types::key::common::{
+    verify_all_signatures_raw, verify_signature_raw, verify_tx_sig, PublicKey,
+    Signature,
+};
 use crate::types::storage::Key;
+use crate::types::transaction::TxEvent;
 use crate::vm::memory::VmMemory;
 use crate::vm::prefix_iter::{PrefixIteratorId, PrefixIterators};
 use crate::vm::types::KeyVal;
@@ -27,6 +32,9 @@ use crate::vm::{
 
 const VERIFY_TX_SIG_GAS_COST: u64 = 1000;
 const WASM_VALIDATION_GAS_PER_BYTE: u64 = 1;
+const VERIFY_SIG_GAS_COST: u64 = 1000;
+const HASH_GAS_COST: u64 = 100;
+const HASH_GAS_PER_BYTE: u64 = 1;
 
 /// These runtime errors will abort tx WASM execution immediately
 #[allow(missing_docs)]
@@ -57,6 +65,10 @@ pub enum TxRuntimeError {
     NumConversionError(TryFromIntError),
     #[error("Memory error: {0}")]
     MemoryError(Box<dyn std::error::Error + Sync + Send + 'static>),
+    #[error("Transaction panicked: {0}")]
+    Panic(String),
+    #[error("Transaction ran out of memory allocating {0} bytes")]
+    OutOfMemory(u64),
 }
 
 type TxResult<T> = std::result::Result<T, TxRuntimeError>;
@@ -1287,6 +1299,35 @@ where
     Ok(epoch.0)
 }
 
+/// Getting the block time function exposed to the wasm VM Tx environment. The
+/// time is that of the block to which the current transaction is being
+/// applied. If the time is available, it will be placed in the result buffer
+/// and its size is returned, otherwise returns -1.
+pub fn tx_get_block_time<MEM, DB, H>(env: &TxEnv<MEM, DB, H>) -> TxResult<i64>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    let storage = unsafe { env.ctx.storage.get() };
+    let (time, gas) = storage.get_block_time();
+    tx_add_gas(env, gas)?;
+    Ok(match time {
+        Some(time) => {
+            let value =
+                time.try_to_vec().map_err(TxRuntimeError::EncodingError)?;
+            let len: i64 = value
+                .len()
+                .try_into()
+                .map_err(TxRuntimeError::NumConversionError)?;
+            let result_buffer = unsafe { env.ctx.result_buffer.get() };
+            result_buffer.replace(value);
+            len
+        }
+        None => HostEnvResult::Fail.to_i64(),
+    })
+}
+
 /// Getting the chain ID function exposed to the wasm VM VP environment.
 pub fn vp_get_chain_id<MEM, DB, H, EVAL>(
     env: &VpEnv<MEM, DB, H, EVAL>,
@@ -1366,6 +1407,66 @@ where
     Ok(epoch.0)
 }
 
+/// Getting the block time function exposed to the wasm VM VP environment. The
+/// time is that of the block to which the current transaction is being
+/// applied. If the time is available, it will be placed in the result buffer
+/// and its size is returned, otherwise returns -1.
+pub fn vp_get_block_time<MEM, DB, H, EVAL>(
+    env: &VpEnv<MEM, DB, H, EVAL>,
+) -> vp_env::Result<i64>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    EVAL: VpEvaluator,
+{
+    let gas_meter = unsafe { env.ctx.gas_meter.get() };
+    let storage = unsafe { env.ctx.storage.get() };
+    let time = vp_env::get_block_time(gas_meter, storage)?;
+    Ok(match time {
+        Some(time) => {
+            let value = time
+                .try_to_vec()
+                .map_err(vp_env::RuntimeError::EncodingError)?;
+            let len: i64 = value
+                .len()
+                .try_into()
+                .map_err(vp_env::RuntimeError::NumConversionError)?;
+            let result_buffer = unsafe { env.ctx.result_buffer.get() };
+            result_buffer.replace(value);
+            len
+        }
+        None => HostEnvResult::Fail.to_i64(),
+    })
+}
+
+/// Getting the transaction timestamp function exposed to the wasm VM VP
+/// environment. The timestamp will be placed in the result buffer and its size
+/// is returned.
+pub fn vp_get_tx_timestamp<MEM, DB, H, EVAL>(
+    env: &VpEnv<MEM, DB, H, EVAL>,
+) -> vp_env::Result<i64>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    EVAL: VpEvaluator,
+{
+    let gas_meter = unsafe { env.ctx.gas_meter.get() };
+    let tx = unsafe { env.ctx.tx.get() };
+    let timestamp = vp_env::get_tx_timestamp(gas_meter, tx)?;
+    let value = timestamp
+        .try_to_vec()
+        .map_err(vp_env::RuntimeError::EncodingError)?;
+    let len: i64 = value
+        .len()
+        .try_into()
+        .map_err(vp_env::RuntimeError::NumConversionError)?;
+    let result_buffer = unsafe { env.ctx.result_buffer.get() };
+    result_buffer.replace(value);
+    Ok(len)
+}
+
 /// Verify a transaction signature.
 pub fn vp_verify_tx_signature<MEM, DB, H, EVAL>(
     env: &VpEnv<MEM, DB, H, EVAL>,
@@ -1402,6 +1503,307 @@ where
     Ok(HostEnvResult::from(verify_tx_sig(&pk, tx, &sig).is_ok()).to_i64())
 }
 
+/// SHA-256 hashing function exposed to the wasm VM VP environment. The 32
+/// bytes hash of the data is written to the given result pointer.
+pub fn vp_sha256<MEM, DB, H, EVAL>(
+    env: &VpEnv<MEM, DB, H, EVAL>,
+    data_ptr: u64,
+    data_len: u64,
+    result_ptr: u64,
+) -> vp_env::Result<()>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    EVAL: VpEvaluator,
+{
+    vp_hash(env, hash::sha256, data_ptr, data_len, result_ptr)
+}
+
+/// Blake2b-256 hashing function exposed to the wasm VM VP environment. The 32
+/// bytes hash of the data is written to the given result pointer.
+pub fn vp_blake2b<MEM, DB, H, EVAL>(
+    env: &VpEnv<MEM, DB, H, EVAL>,
+    data_ptr: u64,
+    data_len: u64,
+    result_ptr: u64,
+) -> vp_env::Result<()>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    EVAL: VpEvaluator,
+{
+    vp_hash(env, hash::blake2b, data_ptr, data_len, result_ptr)
+}
+
+/// Keccak-256 hashing function exposed to the wasm VM VP environment. The 32
+/// bytes hash of the data is written to the given result pointer.
+pub fn vp_keccak256<MEM, DB, H, EVAL>(
+    env: &VpEnv<MEM, DB, H, EVAL>,
+    data_ptr: u64,
+    data_len: u64,
+    result_ptr: u64,
+) -> vp_env::Result<()>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    EVAL: VpEvaluator,
+{
+    vp_hash(env, hash::keccak256, data_ptr, data_len, result_ptr)
+}
+
+/// Read the data from memory, hash it with the given function and write the
+/// hash to the result pointer.
+fn vp_hash<MEM, DB, H, EVAL>(
+    env: &VpEnv<MEM, DB, H, EVAL>,
+    hash_fn: fn(&[u8]) -> hash::Hash,
+    data_ptr: u64,
+    data_len: u64,
+    result_ptr: u64,
+) -> vp_env::Result<()>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    EVAL: VpEvaluator,
+{
+    let (data, gas) = env
+        .memory
+        .read_bytes(data_ptr, data_len as _)
+        .map_err(|e| vp_env::RuntimeError::MemoryError(Box::new(e)))?;
+    let gas_meter = unsafe { env.ctx.gas_meter.get() };
+    vp_env::add_gas(gas_meter, gas)?;
+    vp_env::add_gas(gas_meter, hash_gas_cost(data_len))?;
+    let hash = hash_fn(&data);
+    let gas = env
+        .memory
+        .write_bytes(result_ptr, hash)
+        .map_err(|e| vp_env::RuntimeError::MemoryError(Box::new(e)))?;
+    vp_env::add_gas(gas_meter, gas)
+}
+
+/// Verify a signature of arbitrary data with the given public key.
+pub fn vp_verify_signature<MEM, DB, H, EVAL>(
+    env: &VpEnv<MEM, DB, H, EVAL>,
+    pk_ptr: u64,
+    pk_len: u64,
+    sig_ptr: u64,
+    sig_len: u64,
+    data_ptr: u64,
+    data_len: u64,
+) -> vp_env::Result<i64>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    EVAL: VpEvaluator,
+{
+    let (pk, gas) = env
+        .memory
+        .read_bytes(pk_ptr, pk_len as _)
+        .map_err(|e| vp_env::RuntimeError::MemoryError(Box::new(e)))?;
+    let gas_meter = unsafe { env.ctx.gas_meter.get() };
+    vp_env::add_gas(gas_meter, gas)?;
+    let pk: PublicKey = BorshDeserialize::try_from_slice(&pk)
+        .map_err(vp_env::RuntimeError::EncodingError)?;
+
+    let (sig, gas) = env
+        .memory
+        .read_bytes(sig_ptr, sig_len as _)
+        .map_err(|e| vp_env::RuntimeError::MemoryError(Box::new(e)))?;
+    vp_env::add_gas(gas_meter, gas)?;
+    let sig: Signature = BorshDeserialize::try_from_slice(&sig)
+        .map_err(vp_env::RuntimeError::EncodingError)?;
+
+    let (data, gas) = env
+        .memory
+        .read_bytes(data_ptr, data_len as _)
+        .map_err(|e| vp_env::RuntimeError::MemoryError(Box::new(e)))?;
+    vp_env::add_gas(gas_meter, gas)?;
+
+    vp_env::add_gas(gas_meter, VERIFY_SIG_GAS_COST)?;
+    Ok(
+        HostEnvResult::from(verify_signature_raw(&pk, &data, &sig).is_ok())
+            .to_i64(),
+    )
+}
+
+/// Verify a list of signatures of arbitrary data, one after another. The list
+/// is a borsh encoded list of public keys with the signed data and the
+/// signatures. Only succeeds if all the signatures are valid.
+pub fn vp_verify_all_signatures<MEM, DB, H, EVAL>(
+    env: &VpEnv<MEM, DB, H, EVAL>,
+    sigs_ptr: u64,
+    sigs_len: u64,
+) -> vp_env::Result<i64>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    EVAL: VpEvaluator,
+{
+    let (sigs, gas) = env
+        .memory
+        .read_bytes(sigs_ptr, sigs_len as _)
+        .map_err(|e| vp_env::RuntimeError::MemoryError(Box::new(e)))?;
+    let gas_meter = unsafe { env.ctx.gas_meter.get() };
+    vp_env::add_gas(gas_meter, gas)?;
+    let sigs: Vec<(PublicKey, Vec<u8>, Signature)> =
+        BorshDeserialize::try_from_slice(&sigs)
+            .map_err(vp_env::RuntimeError::EncodingError)?;
+
+    vp_env::add_gas(gas_meter, VERIFY_SIG_GAS_COST * sigs.len() as u64)?;
+    Ok(HostEnvResult::from(verify_all_signatures_raw(&sigs).is_ok()).to_i64())
+}
+
+/// SHA-256 hashing function exposed to the wasm VM Tx environment. The 32
+/// bytes hash of the data is written to the given result pointer.
+pub fn tx_sha256<MEM, DB, H>(
+    env: &TxEnv<MEM, DB, H>,
+    data_ptr: u64,
+    data_len: u64,
+    result_ptr: u64,
+) -> TxResult<()>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    tx_hash(env, hash::sha256, data_ptr, data_len, result_ptr)
+}
+
+/// Blake2b-256 hashing function exposed to the wasm VM Tx environment. The 32
+/// bytes hash of the data is written to the given result pointer.
+pub fn tx_blake2b<MEM, DB, H>(
+    env: &TxEnv<MEM, DB, H>,
+    data_ptr: u64,
+    data_len: u64,
+    result_ptr: u64,
+) -> TxResult<()>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    tx_hash(env, hash::blake2b, data_ptr, data_len, result_ptr)
+}
+
+/// Keccak-256 hashing function exposed to the wasm VM Tx environment. The 32
+/// bytes hash of the data is written to the given result pointer.
+pub fn tx_keccak256<MEM, DB, H>(
+    env: &TxEnv<MEM, DB, H>,
+    data_ptr: u64,
+    data_len: u64,
+    result_ptr: u64,
+) -> TxResult<()>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    tx_hash(env, hash::keccak256, data_ptr, data_len, result_ptr)
+}
+
+/// Read the data from memory, hash it with the given function and write the
+/// hash to the result pointer.
+fn tx_hash<MEM, DB, H>(
+    env: &TxEnv<MEM, DB, H>,
+    hash_fn: fn(&[u8]) -> hash::Hash,
+    data_ptr: u64,
+    data_len: u64,
+    result_ptr: u64,
+) -> TxResult<()>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    let (data, gas) = env
+        .memory
+        .read_bytes(data_ptr, data_len as _)
+        .map_err(|e| TxRuntimeError::MemoryError(Box::new(e)))?;
+    tx_add_gas(env, gas)?;
+    tx_add_gas(env, hash_gas_cost(data_len))?;
+    let hash = hash_fn(&data);
+    let gas = env
+        .memory
+        .write_bytes(result_ptr, hash)
+        .map_err(|e| TxRuntimeError::MemoryError(Box::new(e)))?;
+    tx_add_gas(env, gas)
+}
+
+/// Verify a signature of arbitrary data with the given public key.
+pub fn tx_verify_signature<MEM, DB, H>(
+    env: &TxEnv<MEM, DB, H>,
+    pk_ptr: u64,
+    pk_len: u64,
+    sig_ptr: u64,
+    sig_len: u64,
+    data_ptr: u64,
+    data_len: u64,
+) -> TxResult<i64>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    let (pk, gas) = env
+        .memory
+        .read_bytes(pk_ptr, pk_len as _)
+        .map_err(|e| TxRuntimeError::MemoryError(Box::new(e)))?;
+    tx_add_gas(env, gas)?;
+    let pk: PublicKey = BorshDeserialize::try_from_slice(&pk)
+        .map_err(TxRuntimeError::EncodingError)?;
+
+    let (sig, gas) = env
+        .memory
+        .read_bytes(sig_ptr, sig_len as _)
+        .map_err(|e| TxRuntimeError::MemoryError(Box::new(e)))?;
+    tx_add_gas(env, gas)?;
+    let sig: Signature = BorshDeserialize::try_from_slice(&sig)
+        .map_err(TxRuntimeError::EncodingError)?;
+
+    let (data, gas) = env
+        .memory
+        .read_bytes(data_ptr, data_len as _)
+        .map_err(|e| TxRuntimeError::MemoryError(Box::new(e)))?;
+    tx_add_gas(env, gas)?;
+
+    tx_add_gas(env, VERIFY_SIG_GAS_COST)?;
+    Ok(
+        HostEnvResult::from(verify_signature_raw(&pk, &data, &sig).is_ok())
+            .to_i64(),
+    )
+}
+
+/// Verify a list of signatures of arbitrary data, one after another. The list
+/// is a borsh encoded list of public keys with the signed data and the
+/// signatures. Only succeeds if all the signatures are valid.
+pub fn tx_verify_all_signatures<MEM, DB, H>(
+    env: &TxEnv<MEM, DB, H>,
+    sigs_ptr: u64,
+    sigs_len: u64,
+) -> TxResult<i64>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    let (sigs, gas) = env
+        .memory
+        .read_bytes(sigs_ptr, sigs_len as _)
+        .map_err(|e| TxRuntimeError::MemoryError(Box::new(e)))?;
+    tx_add_gas(env, gas)?;
+    let sigs: Vec<(PublicKey, Vec<u8>, Signature)> =
+        BorshDeserialize::try_from_slice(&sigs)
+            .map_err(TxRuntimeError::EncodingError)?;
+
+    tx_add_gas(env, VERIFY_SIG_GAS_COST * sigs.len() as u64)?;
+    Ok(HostEnvResult::from(verify_all_signatures_raw(&sigs).is_ok()).to_i64())
+}
+
 /// Log a string from exposed to the wasm VM Tx environment. The message will be
 /// printed at the [`tracing::Level::INFO`]. This function is for development
 /// only.
@@ -1423,6 +1825,68 @@ where
     Ok(())
 }
 
+/// Emit a custom event from a transaction. The event is only included in the
+/// block results if the transaction is accepted.
+pub fn tx_emit_event<MEM, DB, H>(
+    env: &TxEnv<MEM, DB, H>,
+    event_ptr: u64,
+    event_len: u64,
+) -> TxResult<()>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    let (event, gas) = env
+        .memory
+        .read_bytes(event_ptr, event_len as _)
+        .map_err(|e| TxRuntimeError::MemoryError(Box::new(e)))?;
+    tx_add_gas(env, gas)?;
+    let event = TxEvent::try_from_slice(&event[..])
+        .map_err(TxRuntimeError::EncodingError)?;
+    tracing::debug!("tx_emit_event {:?}", event);
+
+    let write_log = unsafe { env.ctx.write_log.get() };
+    let gas = write_log.emit_event(event);
+    tx_add_gas(env, gas)
+}
+
+/// Abort the transaction execution with a panic message. This is called from
+/// the panic hook installed in the transaction's entrypoint.
+pub fn tx_panic<MEM, DB, H>(
+    env: &TxEnv<MEM, DB, H>,
+    msg_ptr: u64,
+    msg_len: u64,
+) -> TxResult<()>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    let (msg, _gas) = env
+        .memory
+        .read_string(msg_ptr, msg_len as _)
+        .map_err(|e| TxRuntimeError::MemoryError(Box::new(e)))?;
+    tracing::info!("WASM Transaction panicked: {}", msg);
+    Err(TxRuntimeError::Panic(msg))
+}
+
+/// Abort the transaction execution, because the guest failed to allocate the
+/// given number of bytes. This is called from the transaction's global
+/// allocator.
+pub fn tx_out_of_memory<MEM, DB, H>(
+    _env: &TxEnv<MEM, DB, H>,
+    size: u64,
+) -> TxResult<()>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+{
+    tracing::info!("WASM Transaction ran out of memory");
+    Err(TxRuntimeError::OutOfMemory(size))
+}
+
 /// Evaluate a validity predicate with the given input data.
 pub fn vp_eval<MEM, DB, H, EVAL>(
     env: &VpEnv<'static, MEM, DB, H, EVAL>,
@@ -1478,6 +1942,44 @@ where
     Ok(())
 }
 
+/// Abort the validity predicate execution with a panic message. This is called
+/// from the panic hook installed in the validity predicate's entrypoint.
+pub fn vp_panic<MEM, DB, H, EVAL>(
+    env: &VpEnv<MEM, DB, H, EVAL>,
+    msg_ptr: u64,
+    msg_len: u64,
+) -> vp_env::Result<()>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    EVAL: VpEvaluator,
+{
+    let (msg, _gas) = env
+        .memory
+        .read_string(msg_ptr, msg_len as _)
+        .map_err(|e| vp_env::RuntimeError::MemoryError(Box::new(e)))?;
+    tracing::info!("WASM Validity predicate panicked: {}", msg);
+    Err(vp_env::RuntimeError::Panic(msg))
+}
+
+/// Abort the validity predicate execution, because the guest failed to
+/// allocate the given number of bytes. This is called from the validity
+/// predicate's global allocator.
+pub fn vp_out_of_memory<MEM, DB, H, EVAL>(
+    _env: &VpEnv<MEM, DB, H, EVAL>,
+    size: u64,
+) -> vp_env::Result<()>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    EVAL: VpEvaluator,
+{
+    tracing::info!("WASM Validity predicate ran out of memory");
+    Err(vp_env::RuntimeError::OutOfMemory(size))
+}
+
 /// Remove given intents from the matchmaker's mempool
 pub fn mm_remove_intents<MEM, MM>(
     env: &MatchmakerEnv<MEM, MM>,
@@ -1535,6 +2037,136 @@ pub fn mm_update_state<MEM, MM>(
     mm.update_state(data);
 }
 
+/// SHA-256 hashing function exposed to the wasm VM matchmaker environment.
+/// The 32 bytes hash of the data is written to the given result pointer.
+pub fn mm_sha256<MEM, MM>(
+    env: &MatchmakerEnv<MEM, MM>,
+    data_ptr: u64,
+    data_len: u64,
+    result_ptr: u64,
+) where
+    MEM: VmMemory,
+    MM: MmHost,
+{
+    mm_hash(env, hash::sha256, data_ptr, data_len, result_ptr)
+}
+
+/// Blake2b-256 hashing function exposed to the wasm VM matchmaker
+/// environment. The 32 bytes hash of the data is written to the given result
+/// pointer.
+pub fn mm_blake2b<MEM, MM>(
+    env: &MatchmakerEnv<MEM, MM>,
+    data_ptr: u64,
+    data_len: u64,
+    result_ptr: u64,
+) where
+    MEM: VmMemory,
+    MM: MmHost,
+{
+    mm_hash(env, hash::blake2b, data_ptr, data_len, result_ptr)
+}
+
+/// Keccak-256 hashing function exposed to the wasm VM matchmaker environment.
+/// The 32 bytes hash of the data is written to the given result pointer.
+pub fn mm_keccak256<MEM, MM>(
+    env: &MatchmakerEnv<MEM, MM>,
+    data_ptr: u64,
+    data_len: u64,
+    result_ptr: u64,
+) where
+    MEM: VmMemory,
+    MM: MmHost,
+{
+    mm_hash(env, hash::keccak256, data_ptr, data_len, result_ptr)
+}
+
+/// Read the data from memory, hash it with the given function and write the
+/// hash to the result pointer.
+fn mm_hash<MEM, MM>(
+    env: &MatchmakerEnv<MEM, MM>,
+    hash_fn: fn(&[u8]) -> hash::Hash,
+    data_ptr: u64,
+    data_len: u64,
+    result_ptr: u64,
+) where
+    MEM: VmMemory,
+    MM: MmHost,
+{
+    let (data, _gas) = env
+        .memory
+        .read_bytes(data_ptr, data_len as _)
+        .expect("TODO: handle runtime errors");
+    let hash = hash_fn(&data);
+    env.memory
+        .write_bytes(result_ptr, hash)
+        .expect("TODO: handle runtime errors");
+}
+
+/// Verify a signature of arbitrary data with the given public key.
+pub fn mm_verify_signature<MEM, MM>(
+    env: &MatchmakerEnv<MEM, MM>,
+    pk_ptr: u64,
+    pk_len: u64,
+    sig_ptr: u64,
+    sig_len: u64,
+    data_ptr: u64,
+    data_len: u64,
+) -> i64
+where
+    MEM: VmMemory,
+    MM: MmHost,
+{
+    let (pk, _gas) = env
+        .memory
+        .read_bytes(pk_ptr, pk_len as _)
+        .expect("TODO: handle runtime errors");
+    let pk: PublicKey = match BorshDeserialize::try_from_slice(&pk) {
+        Ok(pk) => pk,
+        Err(_) => return HostEnvResult::Fail.to_i64(),
+    };
+
+    let (sig, _gas) = env
+        .memory
+        .read_bytes(sig_ptr, sig_len as _)
+        .expect("TODO: handle runtime errors");
+    let sig: Signature = match BorshDeserialize::try_from_slice(&sig) {
+        Ok(sig) => sig,
+        Err(_) => return HostEnvResult::Fail.to_i64(),
+    };
+
+    let (data, _gas) = env
+        .memory
+        .read_bytes(data_ptr, data_len as _)
+        .expect("TODO: handle runtime errors");
+
+    HostEnvResult::from(verify_signature_raw(&pk, &data, &sig).is_ok()).to_i64()
+}
+
+/// Verify a list of signatures of arbitrary data, one after another. The list
+/// is a borsh encoded list of public keys with the signed data and the
+/// signatures. Only succeeds if all the signatures are valid.
+pub fn mm_verify_all_signatures<MEM, MM>(
+    env: &MatchmakerEnv<MEM, MM>,
+    sigs_ptr: u64,
+    sigs_len: u64,
+) -> i64
+where
+    MEM: VmMemory,
+    MM: MmHost,
+{
+    let (sigs, _gas) = env
+        .memory
+        .read_bytes(sigs_ptr, sigs_len as _)
+        .expect("TODO: handle runtime errors");
+    let sigs: Vec<(PublicKey, Vec<u8>, Signature)> =
+        match BorshDeserialize::try_from_slice(&sigs) {
+            Ok(sigs) => sigs,
+            Err(_) => return HostEnvResult::Fail.to_i64(),
+        };
+
+    HostEnvResult::from(verify_all_signatures_raw(&sigs).is_ok()).to_i64()
+}
+
 /// Log a string from exposed to the wasm VM matchmaker environment. The message
 /// will be printed at the [`tracing::Level::INFO`]. This function is for
 /// development only.
@@ -1570,6 +2202,11 @@ pub fn mm_filter_log_string<MEM>(
     tracing::info!("WASM Filter log: {}", str);
 }
 
+/// The gas cost of hashing data of the given length
+fn hash_gas_cost(data_len: u64) -> u64 {
+    HASH_GAS_COST + HASH_GAS_PER_BYTE * data_len
+}
+
 /// A helper module for testing
 #[cfg(feature = "testing")]
 pub mod testing {
@@ -97,7 +97,16 @@ where
             "anoma_tx_get_block_height" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_get_block_height),
             "anoma_tx_get_block_hash" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_get_block_hash),
             "anoma_tx_get_block_epoch" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_get_block_epoch),
+            "anoma_tx_get_block_time" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_get_block_time),
+            "anoma_tx_sha256" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_sha256),
+            "anoma_tx_blake2b" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_blake2b),
+            "anoma_tx_keccak256" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_keccak256),
+            "anoma_tx_verify_signature" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_verify_signature),
+            "anoma_tx_verify_all_signatures" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_verify_all_signatures),
+            "anoma_tx_emit_event" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_emit_event),
             "anoma_tx_log_string" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_log_string),
+            "anoma_tx_panic" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_panic),
+            "anoma_tx_out_of_memory" => Function::new_native_with_env(wasm_store, env.clone(), host_env::tx_out_of_memory),
         },
     }
 }
@@ -131,9 +140,18 @@ where
             "anoma_vp_get_block_height" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_get_block_height),
             "anoma_vp_get_block_hash" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_get_block_hash),
             "anoma_vp_get_block_epoch" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_get_block_epoch),
+            "anoma_vp_get_block_time" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_get_block_time),
+            "anoma_vp_get_tx_timestamp" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_get_tx_timestamp),
             "anoma_vp_verify_tx_signature" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_verify_tx_signature),
+            "anoma_vp_sha256" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_sha256),
+            "anoma_vp_blake2b" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_blake2b),
+            "anoma_vp_keccak256" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_keccak256),
+            "anoma_vp_verify_signature" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_verify_signature),
+            "anoma_vp_verify_all_signatures" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_verify_all_signatures),
             "anoma_vp_eval" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_eval),
             "anoma_vp_log_string" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_log_string),
+            "anoma_vp_panic" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_panic),
+            "anoma_vp_out_of_memory" => Function::new_native_with_env(wasm_store, env.clone(), host_env::vp_out_of_memory),
         },
     }
 }
@@ -159,6 +177,11 @@ where
             "anoma_mm_send_match" => Function::new_native_with_env(wasm_store, env.clone(), host_env::mm_send_match),
             "anoma_mm_update_state" => Function::new_native_with_env(wasm_store, env.clone(), host_env::mm_update_state),
             "anoma_mm_remove_intents" => Function::new_native_with_env(wasm_store, env.clone(), host_env::mm_remove_intents),
+            "anoma_mm_sha256" => Function::new_native_with_env(wasm_store, env.clone(), host_env::mm_sha256),
+            "anoma_mm_blake2b" => Function::new_native_with_env(wasm_store, env.clone(), host_env::mm_blake2b),
+            "anoma_mm_keccak256" => Function::new_native_with_env(wasm_store, env.clone(), host_env::mm_keccak256),
+            "anoma_mm_verify_signature" => Function::new_native_with_env(wasm_store, env.clone(), host_env::mm_verify_signature),
+            "anoma_mm_verify_all_signatures" => Function::new_native_with_env(wasm_store, env.clone(), host_env::mm_verify_all_signatures),
             "anoma_mm_log_string" => Function::new_native_with_env(wasm_store, env, host_env::mm_log_string),
         },
     }
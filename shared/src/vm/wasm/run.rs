@@ -11,14 +11,15 @@ use wasmer::BaseTunables;
 
 use super::memory::{Limit, WasmMemory};
 use crate::gossip::mm::MmHost;
-use crate::ledger::gas::{BlockGasMeter, VpGasMeter};
+use crate::ledger::gas::{self, BlockGasMeter, VpGasMeter};
 use crate::ledger::storage::write_log::WriteLog;
 use crate::ledger::storage::{self, Storage, StorageHasher};
+use crate::ledger::vp_env;
 use crate::proto::Tx;
 use crate::types::address::Address;
 use crate::types::internal::HostEnvResult;
 use crate::types::storage::Key;
-use crate::vm::host_env::{TxEnv, VpCtx, VpEnv, VpEvaluator};
+use crate::vm::host_env::{TxEnv, TxRuntimeError, VpCtx, VpEnv, VpEvaluator};
 use crate::vm::prefix_iter::PrefixIterators;
 use crate::vm::types::VpInput;
 use crate::vm::wasm::host_env::{
@@ -32,6 +33,8 @@ const VP_ENTRYPOINT: &str = "_validate_tx";
 const MATCHMAKER_ENTRYPOINT: &str = "_match_intent";
 const FILTER_ENTRYPOINT: &str = "_validate_intent";
 const WASM_STACK_LIMIT: u32 = u16::MAX as u32;
+/// The name of the export of the global injected by the stack-height limiter
+const STACK_HEIGHT_EXPORT: &str = "__anoma_stack_height";
 
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
@@ -54,6 +57,14 @@ pub enum Error {
     MissingModuleEntrypoint(wasmer::ExportError),
     #[error("Failed running wasm with: {0}")]
     RuntimeError(wasmer::RuntimeError),
+    #[error("Wasm panicked: {0}")]
+    Panic(String),
+    #[error("Wasm ran out of gas: {0}")]
+    OutOfGas(gas::Error),
+    #[error("Wasm ran out of memory")]
+    OutOfMemory,
+    #[error("Wasm exceeded the stack-height limit")]
+    StackOverflow,
     #[error("Failed instantiating wasm module with: {0}")]
     InstantiationError(wasmer::InstantiationError),
     #[error(
@@ -139,7 +150,7 @@ where
         })?;
     apply_tx
         .call(tx_data_ptr, tx_data_len)
-        .map_err(Error::RuntimeError)?;
+        .map_err(|error| runtime_error(&instance, error))?;
 
     Ok(verifiers)
 }
@@ -271,7 +282,7 @@ fn run_vp(
             verifiers_ptr,
             verifiers_len,
         )
-        .map_err(Error::RuntimeError)?;
+        .map_err(|error| runtime_error(&instance, error))?;
     tracing::debug!("is_valid {}", is_valid);
     Ok(is_valid == 1)
 }
@@ -465,6 +476,80 @@ pub fn matchmaker_filter(
     Ok(found_match == 0)
 }
 
+/// Turn a runtime error from a transaction or a validity predicate execution
+/// into a more specific error, if its cause can be determined.
+fn runtime_error(
+    instance: &wasmer::Instance,
+    error: wasmer::RuntimeError,
+) -> Error {
+    let error = match error.downcast::<TxRuntimeError>() {
+        Ok(TxRuntimeError::Panic(msg)) => return Error::Panic(msg),
+        Ok(TxRuntimeError::OutOfGas(err)) => return Error::OutOfGas(err),
+        Ok(TxRuntimeError::OutOfMemory(_)) => return Error::OutOfMemory,
+        Ok(TxRuntimeError::MemoryError(err)) if is_out_of_memory(&*err) => {
+            return Error::OutOfMemory;
+        }
+        Ok(err) => {
+            return Error::RuntimeError(wasmer::RuntimeError::user(Box::new(
+                err,
+            )));
+        }
+        Err(error) => error,
+    };
+    let error = match error.downcast::<vp_env::RuntimeError>() {
+        Ok(vp_env::RuntimeError::Panic(msg)) => return Error::Panic(msg),
+        Ok(vp_env::RuntimeError::OutOfGas(err)) => return Error::OutOfGas(err),
+        Ok(vp_env::RuntimeError::OutOfMemory(_)) => return Error::OutOfMemory,
+        Ok(vp_env::RuntimeError::MemoryError(err))
+            if is_out_of_memory(&*err) =>
+        {
+            return Error::OutOfMemory;
+        }
+        Ok(err) => {
+            return Error::RuntimeError(wasmer::RuntimeError::user(Box::new(
+                err,
+            )));
+        }
+        Err(error) => error,
+    };
+    match error.clone().to_trap() {
+        Some(wasmer_vm::TrapCode::StackOverflow) => Error::StackOverflow,
+        // The stack-height limiter traps with `unreachable` when the limit is
+        // exceeded
+        Some(wasmer_vm::TrapCode::UnreachableCodeReached)
+            if is_stack_height_exceeded(instance) =>
+        {
+            Error::StackOverflow
+        }
+        _ => Error::RuntimeError(error),
+    }
+}
+
+/// Check if a memory error from a host function has been caused by the wasm
+/// memory limit.
+fn is_out_of_memory(
+    error: &(dyn std::error::Error + Sync + Send + 'static),
+) -> bool {
+    matches!(
+        error.downcast_ref::<memory::Error>(),
+        Some(memory::Error::MemoryOutOfBounds(
+            wasmer::MemoryError::CouldNotGrow { .. }
+        ))
+    )
+}
+
+/// Check if the stack-height counter injected into the wasm code is over the
+/// limit.
+fn is_stack_height_exceeded(instance: &wasmer::Instance) -> bool {
+    instance
+        .exports
+        .get_global(STACK_HEIGHT_EXPORT)
+        .ok()
+        .and_then(|global| global.get().i32())
+        .map(|height| height as u32 > WASM_STACK_LIMIT)
+        .unwrap_or_default()
+}
+
 /// Prepare a wasm store for untrusted code.
 fn untrusted_wasm_store(limit: Limit<BaseTunables>) -> wasmer::Store {
     // Use Singlepass compiler with the default settings
@@ -491,12 +576,30 @@ fn prepare_wasm_code<T: AsRef<[u8]>>(code: T) -> Result<Vec<u8>> {
     let module =
         pwasm_utils::inject_gas_counter(module, &get_gas_rules(), "env")
             .map_err(|_original_module| Error::GasMeterInjection)?;
-    let module =
+    let mut module =
         pwasm_utils::stack_height::inject_limiter(module, WASM_STACK_LIMIT)
             .map_err(|_original_module| Error::StackLimiterInjection)?;
+    export_stack_height(&mut module);
     elements::serialize(module).map_err(Error::SerializationError)
 }
 
+/// Export the global used by the stack-height limiter, so that a stack
+/// overflow can be told apart from other `unreachable` traps. This must be
+/// called right after the limiter injection, which appends the global as the
+/// last one.
+fn export_stack_height(module: &mut elements::Module) {
+    let global_index = match module.globals_space().checked_sub(1) {
+        Some(index) => index as u32,
+        None => return,
+    };
+    if let Some(exports) = module.export_section_mut() {
+        exports.entries_mut().push(elements::ExportEntry::new(
+            STACK_HEIGHT_EXPORT.to_string(),
+            elements::Internal::Global(global_index),
+        ));
+    }
+}
+
 /// Get the gas rules used to meter wasm operations
 fn get_gas_rules() -> rules::Set {
     rules::Set::default().with_grow_cost(1)
@@ -533,13 +636,13 @@ mod tests {
         let loops = WASM_STACK_LIMIT / 3 - 1;
 
         let error = loop_in_tx_wasm(loops).expect_err(&format!(
-            "Expecting runtime error \"unreachable\" caused by stack-height \
-             overflow, loops {}. Got",
+            "Expecting stack-height overflow error, loops {}. Got",
             loops,
         ));
-        assert_eq!(
-            get_trap_code(&error),
-            Either::Left(wasmer_vm::TrapCode::UnreachableCodeReached),
+        assert!(
+            matches!(error, Error::StackOverflow),
+            "Expected stack overflow, got {:?}",
+            error
         );
 
         // one less loop shouldn't go over the limit
@@ -556,13 +659,12 @@ mod tests {
         // one from this value, we should be just under the limit.
         let loops = WASM_STACK_LIMIT / 3 - 1;
 
-        let error = loop_in_vp_wasm(loops).expect_err(
-            "Expecting runtime error \"unreachable\" caused by stack-height \
-             overflow. Got",
-        );
-        assert_eq!(
-            get_trap_code(&error),
-            Either::Left(wasmer_vm::TrapCode::UnreachableCodeReached),
+        let error = loop_in_vp_wasm(loops)
+            .expect_err("Expecting stack-height overflow error. Got");
+        assert!(
+            matches!(error, Error::StackOverflow),
+            "Expected stack overflow, got {:?}",
+            error
         );
 
         // one less loop shouldn't go over the limit
@@ -570,6 +672,87 @@ mod tests {
         assert!(result.is_ok(), "Expected success. Got {:?}", result);
     }
 
+    /// Test that when a transaction wasm panics, the execution is aborted with
+    /// the panic message.
+    #[test]
+    fn test_tx_panic() {
+        let tx_code = wasmer::wat2wasm(
+            br#"
+            (module
+                (import "env" "anoma_tx_panic" (func $panic (param i64 i64)))
+                (type (;0;) (func (param i64 i64)))
+
+                (func $_apply_tx (type 0) (param i64 i64)
+                (call $panic (i64.const 1024) (i64.const 11)))
+
+                (memory (;0;) 16)
+                (data (i32.const 1024) "tx panicked")
+                (export "memory" (memory 0))
+                (export "_apply_tx" (func $_apply_tx)))
+            "#,
+        )
+        .expect("unexpected error converting wat2wasm")
+        .into_owned();
+
+        let storage = TestStorage::default();
+        let mut write_log = WriteLog::default();
+        let mut gas_meter = BlockGasMeter::default();
+        let error =
+            tx(&storage, &mut write_log, &mut gas_meter, tx_code, vec![])
+                .expect_err("Expected the tx to panic");
+        match error {
+            Error::Panic(msg) => assert_eq!(msg, "tx panicked"),
+            _ => panic!("Expected a panic, got {:?}", error),
+        }
+    }
+
+    /// Test that when a validity predicate wasm panics, the execution is
+    /// aborted with the panic message.
+    #[test]
+    fn test_vp_panic() {
+        let vp_code = wasmer::wat2wasm(
+            br#"
+            (module
+                (import "env" "anoma_vp_panic" (func $panic (param i64 i64)))
+                (type (;0;) (func (param i64 i64 i64 i64 i64 i64 i64 i64) (result i64)))
+
+                (func $_validate_tx (type 0) (param i64 i64 i64 i64 i64 i64 i64 i64) (result i64)
+                (call $panic (i64.const 1024) (i64.const 11))
+                (i64.const 1))
+
+                (memory (;0;) 16)
+                (data (i32.const 1024) "vp panicked")
+                (export "memory" (memory 0))
+                (export "_validate_tx" (func $_validate_tx)))
+            "#,
+        )
+        .expect("unexpected error converting wat2wasm")
+        .into_owned();
+
+        let tx = Tx::new(vec![], None);
+        let mut storage = TestStorage::default();
+        let addr = storage.address_gen.generate_address("rng seed");
+        let write_log = WriteLog::default();
+        let mut gas_meter = VpGasMeter::new(0);
+        let keys_changed = HashSet::new();
+        let verifiers = HashSet::new();
+        let error = vp(
+            vp_code,
+            &tx,
+            &addr,
+            &storage,
+            &write_log,
+            &mut gas_meter,
+            &keys_changed,
+            &verifiers,
+        )
+        .expect_err("Expected the VP to panic");
+        match error {
+            Error::Panic(msg) => assert_eq!(msg, "vp panicked"),
+            _ => panic!("Expected a panic, got {:?}", error),
+        }
+    }
+
     /// Test that when a transaction wasm goes over the memory limit inside the
     /// wasm execution, the execution is aborted.
     #[test]
@@ -603,6 +786,8 @@ mod tests {
         let error =
             tx(&storage, &mut write_log, &mut gas_meter, tx_code, tx_data)
                 .expect_err("Expected to run out of memory");
+        // The test wasm is built without the out-of-memory hook, so its
+        // allocator traps with `unreachable`
         assert_eq!(
             get_trap_code(&error),
             Either::Left(wasmer_vm::TrapCode::UnreachableCodeReached),
@@ -731,6 +916,8 @@ mod tests {
         )
         .expect_err("Expected to run out of memory");
 
+        // The test wasm is built without the out-of-memory hook, so its
+        // allocator traps with `unreachable`
         assert_eq!(
             get_trap_code(&error),
             Either::Left(wasmer_vm::TrapCode::UnreachableCodeReached),
@@ -840,6 +1027,8 @@ mod tests {
             tx_data,
         )
         .expect_err("Expected to run out of memory");
+        // The test wasm is built without the out-of-memory hook, so its
+        // allocator traps with `unreachable`
         assert_eq!(
             get_trap_code(&error),
             Either::Left(wasmer_vm::TrapCode::UnreachableCodeReached),
@@ -885,6 +1074,8 @@ mod tests {
             &verifiers,
         )
         .expect_err("Expected to run out of memory");
+        // The test wasm is built without the out-of-memory hook, so its
+        // allocator traps with `unreachable`
         assert_eq!(
             get_trap_code(&error),
             Either::Left(wasmer_vm::TrapCode::UnreachableCodeReached),
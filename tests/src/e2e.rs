@@ -283,8 +283,9 @@ mod tests {
                 "--source", 
                 BERTHA,
                 "--public-key", 
-                // Value obtained from `anoma::types::key::ed25519::tests::gen_keypair`
-                "200000001be519a321e29020fa3cbfbfd01bd5e92db134305609270b71dace25b5a21168",
+                // Value obtained from `anoma::types::key::ed25519::tests::gen_keypair`,
+                // prefixed with the ed25519 scheme tag of `key::common::PublicKey`
+                "00200000001be519a321e29020fa3cbfbfd01bd5e92db134305609270b71dace25b5a21168",
                 "--code-path",
                 VP_USER_WASM
             ],
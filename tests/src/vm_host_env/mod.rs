@@ -15,14 +15,19 @@ pub mod vp;
 
 #[cfg(test)]
 mod tests {
+    use anoma::ledger::storage::testing::block_header;
     use anoma::proto::Tx;
-    use anoma::types::key::ed25519::SignedTxData;
+    use anoma::types::key::common::{SignedTxData, SigningKey};
     use anoma::types::storage::{Key, KeySeg};
-    use anoma::types::{address, key};
+    use anoma::types::time::{DateTimeUtc, TimeZone, Utc};
+    use anoma::types::{address, hash, key, token};
+    use anoma_vm_env::token::tx as token_tx;
     use anoma_vm_env::tx_prelude::{
         BorshDeserialize, BorshSerialize, KeyValIterator,
     };
-    use anoma_vm_env::vp_prelude::{PostKeyValIterator, PreKeyValIterator};
+    use anoma_vm_env::vp_prelude::{
+        key as key_vp, PostKeyValIterator, PreKeyValIterator,
+    };
     use itertools::Itertools;
     use test_env_log::test;
 
@@ -207,6 +212,284 @@ mod tests {
             tx_host_env::get_block_epoch(),
             env.storage.get_block_epoch().0
         );
+        assert_eq!(
+            tx_host_env::get_block_time(),
+            env.storage.get_block_time().0
+        );
+    }
+
+    #[test]
+    fn test_tx_get_block_time() {
+        let mut env = TestTxEnv::default();
+        let time = DateTimeUtc(Utc.ymd(2021, 9, 1).and_hms(12, 0, 0));
+        env.set_block_time(time);
+        // The environment must be initialized first
+        init_tx_env(&mut env);
+
+        assert_eq!(tx_host_env::get_block_time(), Some(time));
+    }
+
+    #[test]
+    fn test_tx_crypto() {
+        // The environment must be initialized first
+        let mut env = TestTxEnv::default();
+        init_tx_env(&mut env);
+
+        let data = vec![1, 2, 3, 4].repeat(10);
+        assert_eq!(tx_host_env::sha256(&data), hash::sha256(&data));
+        assert_eq!(tx_host_env::blake2b(&data), hash::blake2b(&data));
+        assert_eq!(tx_host_env::keccak256(&data), hash::keccak256(&data));
+
+        let keypair = key::ed25519::testing::keypair_1();
+        let pk = keypair.public_key();
+        let sig = key::common::sign(&keypair, &data);
+        assert!(tx_host_env::verify_signature(&pk, &data, &sig));
+        assert!(!tx_host_env::verify_signature(&pk, &[0, 1], &sig));
+
+        let other_keypair = key::secp256k1::testing::keypair_1();
+        let other_pk = other_keypair.public_key();
+        let other_sig = key::common::sign(&other_keypair, &data);
+        assert!(tx_host_env::verify_all_signatures(&[
+            (pk.clone(), data.clone(), sig.clone()),
+            (other_pk.clone(), data.clone(), other_sig),
+        ]));
+        assert!(!tx_host_env::verify_all_signatures(&[
+            (pk, data.clone(), sig),
+            (other_pk, data, key::common::sign(&other_keypair, &[0, 1])),
+        ]));
+    }
+
+    #[test]
+    fn test_tx_token_transfer() {
+        let mut env = TestTxEnv::default();
+        let token = address::xan();
+        let source = address::testing::established_address_1();
+        let target = address::testing::established_address_2();
+        env.spawn_accounts(&[&token, &source, &target]);
+
+        // The environment must be initialized first
+        init_tx_env(&mut env);
+
+        let balance = |owner: &address::Address| -> token::Amount {
+            let key = token::balance_key(&token, owner).to_string();
+            tx_host_env::read(&key).unwrap_or_default()
+        };
+        let source_key = token::balance_key(&token, &source).to_string();
+        tx_host_env::write(&source_key, token::Amount::from(100));
+
+        // A transfer within the balance succeeds
+        token_tx::transfer(&source, &target, &token, 60.into()).unwrap();
+        assert_eq!(balance(&source), token::Amount::from(40));
+        assert_eq!(balance(&target), token::Amount::from(60));
+
+        // A transfer over the balance fails without modifying the balances
+        let err = token_tx::transfer(&source, &target, &token, 41.into())
+            .unwrap_err();
+        assert_eq!(
+            err,
+            token::TransferError::InsufficientBalance {
+                token: token.clone(),
+                owner: source.clone(),
+                balance: 40.into(),
+                amount: 41.into(),
+            }
+        );
+        assert_eq!(balance(&source), token::Amount::from(40));
+        assert_eq!(balance(&target), token::Amount::from(60));
+
+        // A transfer from an address without any balance fails
+        let err = token_tx::transfer(&target, &source, &token, 61.into())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            token::TransferError::InsufficientBalance { .. }
+        ));
+
+        // A transfer that would overflow the target's balance fails
+        tx_host_env::write(&source_key, token::Amount::from(u64::MAX));
+        let err = token_tx::transfer(&source, &target, &token, u64::MAX.into())
+            .unwrap_err();
+        assert!(matches!(err, token::TransferError::BalanceOverflow { .. }));
+        assert_eq!(balance(&source), token::Amount::from(u64::MAX));
+        assert_eq!(balance(&target), token::Amount::from(60));
+
+        // A transfer to self doesn't change the balance
+        token_tx::transfer(&source, &source, &token, 10.into()).unwrap();
+        assert_eq!(balance(&source), token::Amount::from(u64::MAX));
+    }
+
+    #[test]
+    fn test_tx_token_mint_and_burn() {
+        let mut env = TestTxEnv::default();
+        let token = address::xan();
+        let minter = address::testing::established_address_1();
+        let owner = address::testing::established_address_2();
+        env.spawn_accounts(&[&token, &minter, &owner]);
+        register_token(&mut env, &token, Some(minter.clone()));
+
+        // The environment must be initialized first
+        init_tx_env(&mut env);
+
+        let balance = || -> token::Amount {
+            let key = token::balance_key(&token, &owner).to_string();
+            tx_host_env::read(&key).unwrap_or_default()
+        };
+        let total_supply = || -> token::Amount {
+            let key = token::total_supply_key(&token).to_string();
+            tx_host_env::read(&key).unwrap_or_default()
+        };
+
+        // Minting increases both the balance and the total supply and it
+        // requires the minter's approval
+        token_tx::mint(&owner, &token, 100.into()).unwrap();
+        assert_eq!(balance(), token::Amount::from(100));
+        assert_eq!(total_supply(), token::Amount::from(100));
+        assert!(env.verifiers.contains(&minter));
+
+        // Burning decreases both the balance and the total supply
+        token_tx::burn(&owner, &token, 40.into()).unwrap();
+        assert_eq!(balance(), token::Amount::from(60));
+        assert_eq!(total_supply(), token::Amount::from(60));
+
+        // Burning more than the balance fails without any changes
+        let err = token_tx::burn(&owner, &token, 61.into()).unwrap_err();
+        assert!(matches!(
+            err,
+            token::TransferError::InsufficientBalance { .. }
+        ));
+        assert_eq!(balance(), token::Amount::from(60));
+        assert_eq!(total_supply(), token::Amount::from(60));
+
+        // Minting that would overflow the total supply fails without any
+        // changes
+        let err = token_tx::mint(&minter, &token, u64::MAX.into()).unwrap_err();
+        assert!(matches!(
+            err,
+            token::TransferError::TotalSupplyOverflow { .. }
+        ));
+        assert_eq!(total_supply(), token::Amount::from(60));
+    }
+
+    #[test]
+    fn test_tx_token_mint_without_minter() {
+        let mut env = TestTxEnv::default();
+        let token = address::xan();
+        let owner = address::testing::established_address_1();
+        env.spawn_accounts(&[&token, &owner]);
+        register_token(&mut env, &token, None);
+
+        // The environment must be initialized first
+        init_tx_env(&mut env);
+
+        let err = token_tx::mint(&owner, &token, 100.into()).unwrap_err();
+        assert_eq!(err, token::TransferError::NoMinter(token.clone()));
+        let err = token_tx::burn(&owner, &token, 0.into()).unwrap_err();
+        assert_eq!(err, token::TransferError::NoMinter(token));
+        assert!(env.all_touched_storage_keys().is_empty());
+    }
+
+    #[test]
+    fn test_vp_token_total_supply() {
+        let token = address::xan();
+        let minter = address::testing::established_address_1();
+        let owner = address::testing::established_address_2();
+        let new_tx_env = || {
+            let mut tx_env = TestTxEnv::default();
+            tx_env.spawn_accounts(&[&minter, &owner]);
+            register_token(&mut tx_env, &token, Some(minter.clone()));
+            tx_env
+        };
+        let supply_key = token::total_supply_key(&token).to_string();
+        let balance_key = token::balance_key(&token, &owner).to_string();
+        let token_vp = |vp_env: &TestVpEnv| {
+            anoma_vm_env::token::vp::vp(
+                &token,
+                &vp_env.keys_changed,
+                &vp_env.verifiers,
+            )
+        };
+
+        // A mint with the minter's approval is accepted
+        let vp_env = init_vp_env_from_tx(token.clone(), new_tx_env(), |_| {
+            token_tx::mint(&owner, &token, 100.into()).unwrap();
+        });
+        assert!(token_vp(&vp_env));
+
+        // The owner has 100 tokens out of the total supply of 100
+        let new_funded_tx_env = |minter: Option<address::Address>| {
+            let mut tx_env = new_tx_env();
+            register_token(&mut tx_env, &token, minter);
+            for key in &[&balance_key, &supply_key] {
+                let key = Key::parse(key).unwrap();
+                let value = token::Amount::from(100).try_to_vec().unwrap();
+                tx_env.storage.write(&key, value).unwrap();
+            }
+            tx_env
+        };
+
+        // A burn by the owner is accepted without the minter
+        let tx_env = new_funded_tx_env(Some(minter.clone()));
+        let vp_env = init_vp_env_from_tx(token.clone(), tx_env, |_| {
+            token_tx::burn(&owner, &token, 40.into()).unwrap();
+        });
+        assert!(!vp_env.verifiers.contains(&minter));
+        assert!(token_vp(&vp_env));
+
+        // The supply of a token without a minter cannot be decreased
+        let tx_env = new_funded_tx_env(None);
+        let vp_env = init_vp_env_from_tx(token.clone(), tx_env, |_| {
+            tx_host_env::write(&balance_key, token::Amount::from(0));
+            tx_host_env::write(&supply_key, token::Amount::from(0));
+            tx_host_env::insert_verifier(owner.clone());
+        });
+        assert!(!token_vp(&vp_env));
+
+        // Changing the total supply without the minter is rejected
+        let vp_env = init_vp_env_from_tx(token.clone(), new_tx_env(), |_| {
+            tx_host_env::write(&balance_key, token::Amount::from(100));
+            tx_host_env::write(&supply_key, token::Amount::from(100));
+        });
+        assert!(!token_vp(&vp_env));
+
+        // Changing the balance without the total supply is rejected, even
+        // with the minter's approval
+        let vp_env = init_vp_env_from_tx(token.clone(), new_tx_env(), |_| {
+            tx_host_env::write(&balance_key, token::Amount::from(100));
+            tx_host_env::insert_verifier(minter.clone());
+        });
+        assert!(!token_vp(&vp_env));
+
+        // The total supply change must match the balance changes
+        let vp_env = init_vp_env_from_tx(token.clone(), new_tx_env(), |_| {
+            tx_host_env::write(&balance_key, token::Amount::from(100));
+            tx_host_env::write(&supply_key, token::Amount::from(101));
+            tx_host_env::insert_verifier(minter.clone());
+        });
+        assert!(!token_vp(&vp_env));
+
+        // A balance that cannot be decoded is rejected
+        let vp_env = init_vp_env_from_tx(token.clone(), new_tx_env(), |_| {
+            tx_host_env::write(&balance_key, 0xff_u8);
+            tx_host_env::insert_verifier(owner.clone());
+        });
+        assert!(!token_vp(&vp_env));
+    }
+
+    /// Add the token with the given minter into the token registry
+    fn register_token(
+        env: &mut TestTxEnv,
+        token: &address::Address,
+        minter: Option<address::Address>,
+    ) {
+        let info = token::TokenInfo {
+            symbol: "ABC".to_owned(),
+            name: "Some token".to_owned(),
+            decimals: 6,
+            minter,
+        };
+        env.storage
+            .write(&token::registry_key(token), info.try_to_vec().unwrap())
+            .unwrap();
     }
 
     /// An example how to write a VP host environment integration test
@@ -364,14 +647,31 @@ mod tests {
 
     #[test]
     fn test_vp_verify_tx_signature() {
+        check_vp_verify_tx_signature(
+            &key::ed25519::testing::keypair_1(),
+            &key::ed25519::testing::keypair_2(),
+        );
+        check_vp_verify_tx_signature(
+            &key::secp256k1::testing::keypair_1(),
+            &key::secp256k1::testing::keypair_2(),
+        );
+        check_vp_verify_tx_signature(
+            &key::ed25519::testing::keypair_1(),
+            &key::secp256k1::testing::keypair_1(),
+        );
+    }
+
+    fn check_vp_verify_tx_signature(
+        keypair: &impl SigningKey,
+        other_keypair: &impl SigningKey,
+    ) {
         let mut env = TestVpEnv::default();
 
         let addr = address::testing::established_address_1();
 
         // Write the public key to storage
-        let pk_key = key::ed25519::pk_key(&addr);
-        let keypair = key::ed25519::testing::keypair_1();
-        let pk = key::ed25519::PublicKey::from(keypair.public);
+        let pk_key = key::common::pk_key(&addr);
+        let pk = keypair.public_key();
         env.storage
             .write(&pk_key, pk.try_to_vec().unwrap())
             .unwrap();
@@ -381,26 +681,106 @@ mod tests {
         // Use some arbitrary data
         let data = vec![1, 2, 3, 4].repeat(10);
 
-        env.tx = Tx::new(code, Some(data.clone())).sign(&keypair);
+        env.tx = Tx::new(code, Some(data.clone())).sign(keypair);
         // Initialize the environment
         init_vp_env(&mut env);
 
         let tx_data = env.tx.data.expect("data should exist");
-        let signed_tx_data = match SignedTxData::try_from_slice(&tx_data[..]) {
-            Ok(data) => data,
+        let (signed_data, sig) =
+            match SignedTxData::try_from_slice(&tx_data[..]) {
+                Ok(SignedTxData::Single { data, sig }) => (data, sig),
+                _ => panic!("decoding failed"),
+            };
+        assert_eq!(signed_data, Some(data));
+        assert!(vp_host_env::verify_tx_signature(&pk, &sig));
+
+        let other_pk = other_keypair.public_key();
+        assert!(!vp_host_env::verify_tx_signature(&other_pk, &sig));
+    }
+
+    #[test]
+    fn test_vp_verify_multisig_tx_signatures() {
+        let mut env = TestVpEnv::default();
+
+        let addr = address::testing::established_address_1();
+        let keypair_1 = key::ed25519::testing::keypair_1();
+        let keypair_2 = key::secp256k1::testing::keypair_1();
+        let keypair_3 = key::ed25519::testing::keypair_2();
+        let outsider = key::secp256k1::testing::keypair_2();
+
+        // Write the public keys and a 2-of-3 threshold to storage
+        let pks = vec![
+            keypair_1.public_key(),
+            keypair_2.public_key(),
+            keypair_3.public_key(),
+        ];
+        let pks_key = key::multisig::pks_key(&addr);
+        env.storage
+            .write(&pks_key, pks.try_to_vec().unwrap())
+            .unwrap();
+        let threshold_key = key::multisig::threshold_key(&addr);
+        env.storage
+            .write(&threshold_key, 2_u64.try_to_vec().unwrap())
+            .unwrap();
+
+        // Use some arbitrary bytes for tx code
+        let code = vec![4, 3, 2, 1, 0];
+        // Use some arbitrary data
+        let data = vec![1, 2, 3, 4].repeat(10);
+        let tx = Tx::new(code, Some(data));
+        let sig_1 = key::multisig::sign_tx_offline(&keypair_1, &tx);
+        let sig_2 = key::multisig::sign_tx_offline(&keypair_2, &tx);
+        let sig_outsider = key::multisig::sign_tx_offline(&outsider, &tx);
+
+        env.tx = key::multisig::combine_signatures(tx, vec![sig_1, sig_2]);
+        // Initialize the environment
+        init_vp_env(&mut env);
+
+        let tx_data = env.tx.data.clone().expect("data should exist");
+        let sigs = match SignedTxData::try_from_slice(&tx_data[..]) {
+            Ok(SignedTxData::Multi { sigs, .. }) => sigs,
             _ => panic!("decoding failed"),
         };
-        assert_eq!(signed_tx_data.data, Some(data));
-        assert!(vp_host_env::verify_tx_signature(&pk, &signed_tx_data.sig));
-
-        let other_keypair = key::ed25519::testing::keypair_2();
-        let other_pk = key::ed25519::PublicKey::from(other_keypair.public);
-        assert!(!vp_host_env::verify_tx_signature(
-            &other_pk,
-            &signed_tx_data.sig
+        assert!(key_vp::multisig::verify_threshold_sigs(&addr, &sigs));
+        // The threshold is not met with a single signature
+        assert!(!key_vp::multisig::verify_threshold_sigs(&addr, &sigs[..1]));
+        // A signature from a key that's not in the set is not counted
+        assert!(!key_vp::multisig::verify_threshold_sigs(
+            &addr,
+            &[sigs[0].clone(), sig_outsider]
         ));
     }
 
+    #[test]
+    fn test_vp_crypto() {
+        // The environment must be initialized first
+        let mut env = TestVpEnv::default();
+        init_vp_env(&mut env);
+
+        let data = vec![1, 2, 3, 4].repeat(10);
+        assert_eq!(vp_host_env::sha256(&data), hash::sha256(&data));
+        assert_eq!(vp_host_env::blake2b(&data), hash::blake2b(&data));
+        assert_eq!(vp_host_env::keccak256(&data), hash::keccak256(&data));
+
+        let keypair = key::ed25519::testing::keypair_1();
+        let pk = keypair.public_key();
+        let sig = key::common::sign(&keypair, &data);
+        assert!(vp_host_env::verify_signature(&pk, &data, &sig));
+        let other_keypair = key::secp256k1::testing::keypair_1();
+        let other_pk = other_keypair.public_key();
+        assert!(!vp_host_env::verify_signature(&other_pk, &data, &sig));
+
+        let other_sig = key::common::sign(&other_keypair, &data);
+        assert!(vp_host_env::verify_all_signatures(&[
+            (pk.clone(), data.clone(), sig.clone()),
+            (other_pk.clone(), data.clone(), other_sig),
+        ]));
+        assert!(!vp_host_env::verify_all_signatures(&[
+            (pk, data.clone(), sig.clone()),
+            (other_pk, data, sig),
+        ]));
+    }
+
     #[test]
     fn test_vp_get_metadata() {
         // The environment must be initialized first
@@ -420,6 +800,26 @@ mod tests {
             vp_host_env::get_block_epoch(),
             env.storage.get_block_epoch().0
         );
+        assert_eq!(
+            vp_host_env::get_block_time(),
+            env.storage.get_block_time().0
+        );
+        assert_eq!(vp_host_env::get_tx_timestamp(), env.tx.timestamp);
+    }
+
+    #[test]
+    fn test_vp_get_block_time() {
+        let mut env = TestVpEnv::default();
+        let block_time = DateTimeUtc(Utc.ymd(2021, 9, 1).and_hms(12, 0, 0));
+        env.storage.set_header(block_header(block_time)).unwrap();
+        // The transaction was created before the block
+        let tx_timestamp = DateTimeUtc(Utc.ymd(2021, 9, 1).and_hms(11, 59, 0));
+        env.tx.timestamp = tx_timestamp;
+        // The environment must be initialized first
+        init_vp_env(&mut env);
+
+        assert_eq!(vp_host_env::get_block_time(), Some(block_time));
+        assert_eq!(vp_host_env::get_tx_timestamp(), tx_timestamp);
     }
 
     #[test]
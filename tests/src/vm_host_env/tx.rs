@@ -2,10 +2,11 @@ use std::collections::HashSet;
 
 use anoma::ledger::gas::BlockGasMeter;
 use anoma::ledger::storage::mockdb::MockDB;
-use anoma::ledger::storage::testing::TestStorage;
+use anoma::ledger::storage::testing::{block_header, TestStorage};
 use anoma::ledger::storage::write_log::WriteLog;
 use anoma::types::address::Address;
 use anoma::types::storage::Key;
+use anoma::types::time::DateTimeUtc;
 use anoma::vm;
 use anoma::vm::prefix_iter::PrefixIterators;
 
@@ -46,6 +47,21 @@ impl TestTxEnv {
     pub fn all_touched_storage_keys(&self) -> HashSet<Key> {
         self.write_log.get_keys()
     }
+
+    /// Fake the existence of the given accounts by writing an empty validity
+    /// predicate for them, because it's used to check if an address exists
+    /// when we write into its storage.
+    pub fn spawn_accounts(&mut self, addresses: &[&Address]) {
+        for address in addresses {
+            let vp_key = Key::validity_predicate(address);
+            self.storage.write(&vp_key, vec![]).unwrap();
+        }
+    }
+
+    /// Set the time of the current block, which is otherwise not available.
+    pub fn set_block_time(&mut self, time: DateTimeUtc) {
+        self.storage.set_header(block_header(time)).unwrap();
+    }
 }
 
 /// Initialize the host environment inside the [`tx_host_env`] module.
@@ -163,5 +179,28 @@ mod native_tx_host_env {
     native_host_fn!(tx_get_block_height() -> u64);
     native_host_fn!(tx_get_block_hash(result_ptr: u64));
     native_host_fn!(tx_get_block_epoch() -> u64);
+    native_host_fn!(tx_get_block_time() -> i64);
+    native_host_fn!(tx_emit_event(event_ptr: u64, event_len: u64));
+    native_host_fn!(tx_sha256(data_ptr: u64, data_len: u64, result_ptr: u64));
+    native_host_fn!(tx_blake2b(data_ptr: u64, data_len: u64, result_ptr: u64));
+    native_host_fn!(tx_keccak256(
+        data_ptr: u64,
+        data_len: u64,
+        result_ptr: u64
+    ));
+    native_host_fn!(tx_verify_signature(
+            pk_ptr: u64,
+            pk_len: u64,
+            sig_ptr: u64,
+            sig_len: u64,
+            data_ptr: u64,
+            data_len: u64,
+        ) -> i64);
+    native_host_fn!(tx_verify_all_signatures(
+            sigs_ptr: u64,
+            sigs_len: u64,
+        ) -> i64);
     native_host_fn!(tx_log_string(str_ptr: u64, str_len: u64));
+    native_host_fn!(tx_panic(msg_ptr: u64, msg_len: u64));
+    native_host_fn!(tx_out_of_memory(size: u64));
 }
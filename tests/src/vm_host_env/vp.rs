@@ -6,6 +6,8 @@ use anoma::ledger::storage::testing::TestStorage;
 use anoma::ledger::storage::write_log::WriteLog;
 use anoma::proto::Tx;
 use anoma::types::address::{self, Address};
+use anoma::types::key::common::SigningKey;
+use anoma::types::key::multisig;
 use anoma::types::storage::{self, Key};
 use anoma::vm;
 use anoma::vm::prefix_iter::PrefixIterators;
@@ -58,6 +60,38 @@ impl Default for TestVpEnv {
     }
 }
 
+impl TestVpEnv {
+    /// Sign the environment's transaction with the given key and re-initialize
+    /// the host environment with it. Returns the signed transaction data,
+    /// which is the input of the VP.
+    pub fn sign_tx(&mut self, keypair: &impl SigningKey) -> Vec<u8> {
+        let tx = std::mem::replace(&mut self.tx, Tx::new(vec![], None));
+        self.tx = Tx::new(tx.code, tx.data).sign(keypair);
+        init_vp_env(self);
+        self.tx.data.clone().unwrap_or_default()
+    }
+
+    /// Sign the environment's transaction offline with the given keys of a
+    /// multisig account and re-initialize the host environment with it.
+    /// Returns the signed transaction data, which is the input of the VP.
+    pub fn sign_tx_multisig(
+        &mut self,
+        keypairs: &[&dyn SigningKey],
+    ) -> Vec<u8> {
+        let tx = std::mem::replace(&mut self.tx, Tx::new(vec![], None));
+        let tx = Tx::new(tx.code, tx.data);
+        let sigs = keypairs
+            .iter()
+            .map(|keypair| {
+                (keypair.public_key(), keypair.sign_bytes(&tx.to_bytes()))
+            })
+            .collect();
+        self.tx = multisig::combine_signatures(tx, sigs);
+        init_vp_env(self);
+        self.tx.data.clone().unwrap_or_default()
+    }
+}
+
 /// Initialize the host environment inside the [`vp_host_env`] module by running
 /// a transaction. The transaction is expected to modify the given address
 /// `addr` or to add it to the set of verifiers using
@@ -235,6 +269,8 @@ mod native_vp_host_env {
     native_host_fn!(vp_get_block_height() -> u64);
     native_host_fn!(vp_get_block_hash(result_ptr: u64));
     native_host_fn!(vp_get_block_epoch() -> u64);
+    native_host_fn!(vp_get_block_time() -> i64);
+    native_host_fn!(vp_get_tx_timestamp() -> i64);
     native_host_fn!(vp_verify_tx_signature(
             pk_ptr: u64,
             pk_len: u64,
@@ -247,5 +283,26 @@ mod native_vp_host_env {
             input_data_ptr: u64,
             input_data_len: u64,
         ) -> i64);
+    native_host_fn!(vp_sha256(data_ptr: u64, data_len: u64, result_ptr: u64));
+    native_host_fn!(vp_blake2b(data_ptr: u64, data_len: u64, result_ptr: u64));
+    native_host_fn!(vp_keccak256(
+        data_ptr: u64,
+        data_len: u64,
+        result_ptr: u64
+    ));
+    native_host_fn!(vp_verify_signature(
+            pk_ptr: u64,
+            pk_len: u64,
+            sig_ptr: u64,
+            sig_len: u64,
+            data_ptr: u64,
+            data_len: u64,
+        ) -> i64);
+    native_host_fn!(vp_verify_all_signatures(
+            sigs_ptr: u64,
+            sigs_len: u64,
+        ) -> i64);
     native_host_fn!(vp_log_string(str_ptr: u64, str_len: u64));
+    native_host_fn!(vp_panic(msg_ptr: u64, msg_len: u64));
+    native_host_fn!(vp_out_of_memory(size: u64));
 }
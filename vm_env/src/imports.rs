@@ -16,6 +16,16 @@ fn read_from_buffer<T: BorshDeserialize>(
     read_result: i64,
     result_buffer: unsafe extern "C" fn(u64),
 ) -> Option<T> {
+    let target = read_bytes_from_buffer(read_result, result_buffer)?;
+    T::try_from_slice(&target[..]).ok()
+}
+
+/// This function is a helper to handle the second step of reading var-len
+/// values from the host, without decoding them.
+fn read_bytes_from_buffer(
+    read_result: i64,
+    result_buffer: unsafe extern "C" fn(u64),
+) -> Option<Vec<u8>> {
     if HostEnvResult::is_fail(read_result) {
         None
     } else {
@@ -28,7 +38,7 @@ fn read_from_buffer<T: BorshDeserialize>(
         let target = unsafe {
             Vec::from_raw_parts(offset as _, read_result as _, read_result as _)
         };
-        T::try_from_slice(&target[..]).ok()
+        Some(target)
     }
 }
 
@@ -50,15 +60,20 @@ fn read_key_val_from_buffer<T: BorshDeserialize>(
 /// Transaction environment imports
 pub mod tx {
     use core::slice;
+    use std::collections::BTreeMap;
     use std::convert::TryFrom;
     use std::marker::PhantomData;
 
     use anoma::types::address;
     use anoma::types::address::Address;
+    use anoma::types::hash::{Hash, HASH_LENGTH};
     use anoma::types::internal::HostEnvResult;
+    use anoma::types::key::common::{PublicKey, Signature};
     use anoma::types::storage::{
         BlockHash, BlockHeight, Epoch, BLOCK_HASH_LENGTH, CHAIN_ID_LENGTH,
     };
+    use anoma::types::time::DateTimeUtc;
+    use anoma::types::transaction::TxEvent;
     pub use borsh::{BorshDeserialize, BorshSerialize};
 
     #[derive(Debug)]
@@ -202,6 +217,105 @@ pub mod tx {
         Epoch(unsafe { anoma_tx_get_block_epoch() })
     }
 
+    /// Get time of the current block. This is not available when the
+    /// transaction is not being applied in a block (e.g. in a dry run).
+    pub fn get_block_time() -> Option<DateTimeUtc> {
+        let read_result = unsafe { anoma_tx_get_block_time() };
+        super::read_from_buffer(read_result, anoma_tx_result_buffer)
+    }
+
+    /// Emit a custom event with the given type and attributes. The event will
+    /// be included in the block results only if the transaction is accepted.
+    pub fn emit_event<T: AsRef<str>>(
+        event_type: T,
+        attributes: BTreeMap<String, String>,
+    ) {
+        let event = TxEvent {
+            event_type: event_type.as_ref().to_string(),
+            attributes,
+        };
+        let event = event.try_to_vec().unwrap();
+        unsafe {
+            anoma_tx_emit_event(event.as_ptr() as _, event.len() as _);
+        }
+    }
+
+    /// Compute a SHA-256 hash of the given data
+    pub fn sha256(data: impl AsRef<[u8]>) -> Hash {
+        let data = data.as_ref();
+        let mut result = [0; HASH_LENGTH];
+        unsafe {
+            anoma_tx_sha256(
+                data.as_ptr() as _,
+                data.len() as _,
+                result.as_mut_ptr() as _,
+            );
+        }
+        result
+    }
+
+    /// Compute a Blake2b-256 hash of the given data
+    pub fn blake2b(data: impl AsRef<[u8]>) -> Hash {
+        let data = data.as_ref();
+        let mut result = [0; HASH_LENGTH];
+        unsafe {
+            anoma_tx_blake2b(
+                data.as_ptr() as _,
+                data.len() as _,
+                result.as_mut_ptr() as _,
+            );
+        }
+        result
+    }
+
+    /// Compute a Keccak-256 hash of the given data
+    pub fn keccak256(data: impl AsRef<[u8]>) -> Hash {
+        let data = data.as_ref();
+        let mut result = [0; HASH_LENGTH];
+        unsafe {
+            anoma_tx_keccak256(
+                data.as_ptr() as _,
+                data.len() as _,
+                result.as_mut_ptr() as _,
+            );
+        }
+        result
+    }
+
+    /// Verify a signature of arbitrary data with the given public key.
+    pub fn verify_signature(
+        pk: &PublicKey,
+        data: impl AsRef<[u8]>,
+        sig: &Signature,
+    ) -> bool {
+        let pk = BorshSerialize::try_to_vec(pk).unwrap();
+        let sig = BorshSerialize::try_to_vec(sig).unwrap();
+        let data = data.as_ref();
+        let valid = unsafe {
+            anoma_tx_verify_signature(
+                pk.as_ptr() as _,
+                pk.len() as _,
+                sig.as_ptr() as _,
+                sig.len() as _,
+                data.as_ptr() as _,
+                data.len() as _,
+            )
+        };
+        HostEnvResult::is_success(valid)
+    }
+
+    /// Verify a list of signatures of arbitrary data, one after another.
+    /// Returns `true` only if all the signatures are valid.
+    pub fn verify_all_signatures(
+        sigs: &[(PublicKey, Vec<u8>, Signature)],
+    ) -> bool {
+        let sigs = BorshSerialize::try_to_vec(sigs).unwrap();
+        let valid = unsafe {
+            anoma_tx_verify_all_signatures(sigs.as_ptr() as _, sigs.len() as _)
+        };
+        HostEnvResult::is_success(valid)
+    }
+
     /// Log a string. The message will be printed at the `tracing::Level::Info`.
     pub fn log_string<T: AsRef<str>>(msg: T) {
         let msg = msg.as_ref();
@@ -210,6 +324,29 @@ pub mod tx {
         }
     }
 
+    /// Install a panic hook that aborts the transaction execution and passes
+    /// the panic message to the host. This is called from the entrypoint
+    /// generated by the [`transaction`] macro.
+    ///
+    /// [`transaction`]: anoma_vm_macro::transaction
+    pub fn set_panic_hook() {
+        std::panic::set_hook(Box::new(|info| {
+            let msg = info.to_string();
+            unsafe {
+                anoma_tx_panic(msg.as_ptr() as _, msg.len() as _);
+            }
+        }));
+    }
+
+    /// Abort the transaction execution, because an allocation of the given
+    /// size failed. This is called from the global allocator generated by the
+    /// [`transaction`] macro.
+    ///
+    /// [`transaction`]: anoma_vm_macro::transaction
+    pub fn out_of_memory(size: usize) {
+        unsafe { anoma_tx_out_of_memory(size as _) }
+    }
+
     /// These host functions are implemented in the Anoma's [`host_env`]
     /// module. The environment provides calls to them via this C interface.
     extern "C" {
@@ -272,8 +409,44 @@ pub mod tx {
         // Get the current block epoch
         fn anoma_tx_get_block_epoch() -> u64;
 
+        // Get the current block time, returns the size of the value, or -1
+        // if it's not available. If a value is found, it will be placed in
+        // the result buffer.
+        fn anoma_tx_get_block_time() -> i64;
+
+        // Emit a custom event
+        fn anoma_tx_emit_event(event_ptr: u64, event_len: u64);
+
+        // Write a SHA-256 hash of the data to the result pointer
+        fn anoma_tx_sha256(data_ptr: u64, data_len: u64, result_ptr: u64);
+
+        // Write a Blake2b-256 hash of the data to the result pointer
+        fn anoma_tx_blake2b(data_ptr: u64, data_len: u64, result_ptr: u64);
+
+        // Write a Keccak-256 hash of the data to the result pointer
+        fn anoma_tx_keccak256(data_ptr: u64, data_len: u64, result_ptr: u64);
+
+        // Verify a signature of arbitrary data
+        fn anoma_tx_verify_signature(
+            pk_ptr: u64,
+            pk_len: u64,
+            sig_ptr: u64,
+            sig_len: u64,
+            data_ptr: u64,
+            data_len: u64,
+        ) -> i64;
+
+        // Verify a list of signatures of arbitrary data
+        fn anoma_tx_verify_all_signatures(sigs_ptr: u64, sigs_len: u64) -> i64;
+
         // Requires a node running with "Info" log level
         fn anoma_tx_log_string(str_ptr: u64, str_len: u64);
+
+        // Abort the execution with the given panic message
+        fn anoma_tx_panic(msg_ptr: u64, msg_len: u64);
+
+        // Abort the execution, because the guest ran out of memory
+        fn anoma_tx_out_of_memory(size: u64);
     }
 }
 
@@ -283,11 +456,13 @@ pub mod vp {
     use std::convert::TryFrom;
     use std::marker::PhantomData;
 
+    use anoma::types::hash::{Hash, HASH_LENGTH};
     use anoma::types::internal::HostEnvResult;
-    use anoma::types::key::ed25519::{PublicKey, Signature};
+    use anoma::types::key::common::{PublicKey, Signature};
     use anoma::types::storage::{
         BlockHash, BlockHeight, Epoch, BLOCK_HASH_LENGTH, CHAIN_ID_LENGTH,
     };
+    use anoma::types::time::DateTimeUtc;
     pub use borsh::{BorshDeserialize, BorshSerialize};
 
     pub struct PreKeyValIterator<T>(pub u64, pub PhantomData<T>);
@@ -303,6 +478,15 @@ pub mod vp {
         super::read_from_buffer(read_result, anoma_vp_result_buffer)
     }
 
+    /// Try to read the raw bytes of a value at the given key from storage
+    /// before transaction execution.
+    pub fn read_bytes_pre(key: impl AsRef<str>) -> Option<Vec<u8>> {
+        let key = key.as_ref();
+        let read_result =
+            unsafe { anoma_vp_read_pre(key.as_ptr() as _, key.len() as _) };
+        super::read_bytes_from_buffer(read_result, anoma_vp_result_buffer)
+    }
+
     /// Try to read a variable-length value at the given key from storage after
     /// transaction execution.
     pub fn read_post<T: BorshDeserialize>(key: impl AsRef<str>) -> Option<T> {
@@ -312,6 +496,15 @@ pub mod vp {
         super::read_from_buffer(read_result, anoma_vp_result_buffer)
     }
 
+    /// Try to read the raw bytes of a value at the given key from storage
+    /// after transaction execution.
+    pub fn read_bytes_post(key: impl AsRef<str>) -> Option<Vec<u8>> {
+        let key = key.as_ref();
+        let read_result =
+            unsafe { anoma_vp_read_post(key.as_ptr() as _, key.len() as _) };
+        super::read_bytes_from_buffer(read_result, anoma_vp_result_buffer)
+    }
+
     /// Check if the given key was present in storage before transaction
     /// execution.
     pub fn has_key_pre(key: impl AsRef<str>) -> bool {
@@ -403,9 +596,24 @@ pub mod vp {
         Epoch(unsafe { anoma_vp_get_block_epoch() })
     }
 
+    /// Get time of the current block. This is not available when the
+    /// transaction is not being applied in a block (e.g. in a dry run).
+    pub fn get_block_time() -> Option<DateTimeUtc> {
+        let read_result = unsafe { anoma_vp_get_block_time() };
+        super::read_from_buffer(read_result, anoma_vp_result_buffer)
+    }
+
+    /// Get the timestamp of the transaction that triggered this validity
+    /// predicate
+    pub fn get_tx_timestamp() -> DateTimeUtc {
+        let read_result = unsafe { anoma_vp_get_tx_timestamp() };
+        super::read_from_buffer(read_result, anoma_vp_result_buffer)
+            .expect("Cannot read the transaction timestamp")
+    }
+
     /// Verify a transaction signature. The signature is expected to have been
     /// produced on the encoded transaction [`anoma::proto::Tx`]
-    /// using [`anoma::types::key::ed25519::sign_tx`].
+    /// using [`anoma::types::key::common::sign_tx`].
     pub fn verify_tx_signature(pk: &PublicKey, sig: &Signature) -> bool {
         let pk = BorshSerialize::try_to_vec(pk).unwrap();
         let sig = BorshSerialize::try_to_vec(sig).unwrap();
@@ -420,6 +628,82 @@ pub mod vp {
         HostEnvResult::is_success(valid)
     }
 
+    /// Compute a SHA-256 hash of the given data
+    pub fn sha256(data: impl AsRef<[u8]>) -> Hash {
+        let data = data.as_ref();
+        let mut result = [0; HASH_LENGTH];
+        unsafe {
+            anoma_vp_sha256(
+                data.as_ptr() as _,
+                data.len() as _,
+                result.as_mut_ptr() as _,
+            );
+        }
+        result
+    }
+
+    /// Compute a Blake2b-256 hash of the given data
+    pub fn blake2b(data: impl AsRef<[u8]>) -> Hash {
+        let data = data.as_ref();
+        let mut result = [0; HASH_LENGTH];
+        unsafe {
+            anoma_vp_blake2b(
+                data.as_ptr() as _,
+                data.len() as _,
+                result.as_mut_ptr() as _,
+            );
+        }
+        result
+    }
+
+    /// Compute a Keccak-256 hash of the given data
+    pub fn keccak256(data: impl AsRef<[u8]>) -> Hash {
+        let data = data.as_ref();
+        let mut result = [0; HASH_LENGTH];
+        unsafe {
+            anoma_vp_keccak256(
+                data.as_ptr() as _,
+                data.len() as _,
+                result.as_mut_ptr() as _,
+            );
+        }
+        result
+    }
+
+    /// Verify a signature of arbitrary data with the given public key.
+    pub fn verify_signature(
+        pk: &PublicKey,
+        data: impl AsRef<[u8]>,
+        sig: &Signature,
+    ) -> bool {
+        let pk = BorshSerialize::try_to_vec(pk).unwrap();
+        let sig = BorshSerialize::try_to_vec(sig).unwrap();
+        let data = data.as_ref();
+        let valid = unsafe {
+            anoma_vp_verify_signature(
+                pk.as_ptr() as _,
+                pk.len() as _,
+                sig.as_ptr() as _,
+                sig.len() as _,
+                data.as_ptr() as _,
+                data.len() as _,
+            )
+        };
+        HostEnvResult::is_success(valid)
+    }
+
+    /// Verify a list of signatures of arbitrary data, one after another.
+    /// Returns `true` only if all the signatures are valid.
+    pub fn verify_all_signatures(
+        sigs: &[(PublicKey, Vec<u8>, Signature)],
+    ) -> bool {
+        let sigs = BorshSerialize::try_to_vec(sigs).unwrap();
+        let valid = unsafe {
+            anoma_vp_verify_all_signatures(sigs.as_ptr() as _, sigs.len() as _)
+        };
+        HostEnvResult::is_success(valid)
+    }
+
     /// Log a string. The message will be printed at the `tracing::Level::Info`.
     pub fn log_string<T: AsRef<str>>(msg: T) {
         let msg = msg.as_ref();
@@ -428,6 +712,29 @@ pub mod vp {
         }
     }
 
+    /// Install a panic hook that aborts the validity predicate execution and
+    /// passes the panic message to the host. This is called from the
+    /// entrypoint generated by the [`validity_predicate`] macro.
+    ///
+    /// [`validity_predicate`]: anoma_vm_macro::validity_predicate
+    pub fn set_panic_hook() {
+        std::panic::set_hook(Box::new(|info| {
+            let msg = info.to_string();
+            unsafe {
+                anoma_vp_panic(msg.as_ptr() as _, msg.len() as _);
+            }
+        }));
+    }
+
+    /// Abort the validity predicate execution, because an allocation of the
+    /// given size failed. This is called from the global allocator
+    /// generated by the [`validity_predicate`] macro.
+    ///
+    /// [`validity_predicate`]: anoma_vm_macro::validity_predicate
+    pub fn out_of_memory(size: usize) {
+        unsafe { anoma_vp_out_of_memory(size as _) }
+    }
+
     /// Evaluate a validity predicate with given data. The address, changed
     /// storage keys and verifiers will have the same values as the input to
     /// caller's validity predicate.
@@ -501,6 +808,15 @@ pub mod vp {
         // Get the current block epoch
         fn anoma_vp_get_block_epoch() -> u64;
 
+        // Get the current block time, returns the size of the value, or -1
+        // if it's not available. If a value is found, it will be placed in
+        // the result buffer.
+        fn anoma_vp_get_block_time() -> i64;
+
+        // Get the timestamp of the transaction, returns the size of the value.
+        // The value will be placed in the result buffer.
+        fn anoma_vp_get_tx_timestamp() -> i64;
+
         // Verify a transaction signature
         fn anoma_vp_verify_tx_signature(
             pk_ptr: u64,
@@ -509,9 +825,37 @@ pub mod vp {
             sig_len: u64,
         ) -> i64;
 
+        // Write a SHA-256 hash of the data to the result pointer
+        fn anoma_vp_sha256(data_ptr: u64, data_len: u64, result_ptr: u64);
+
+        // Write a Blake2b-256 hash of the data to the result pointer
+        fn anoma_vp_blake2b(data_ptr: u64, data_len: u64, result_ptr: u64);
+
+        // Write a Keccak-256 hash of the data to the result pointer
+        fn anoma_vp_keccak256(data_ptr: u64, data_len: u64, result_ptr: u64);
+
+        // Verify a signature of arbitrary data
+        fn anoma_vp_verify_signature(
+            pk_ptr: u64,
+            pk_len: u64,
+            sig_ptr: u64,
+            sig_len: u64,
+            data_ptr: u64,
+            data_len: u64,
+        ) -> i64;
+
+        // Verify a list of signatures of arbitrary data
+        fn anoma_vp_verify_all_signatures(sigs_ptr: u64, sigs_len: u64) -> i64;
+
         // Requires a node running with "Info" log level
         fn anoma_vp_log_string(str_ptr: u64, str_len: u64);
 
+        // Abort the execution with the given panic message
+        fn anoma_vp_panic(msg_ptr: u64, msg_len: u64);
+
+        // Abort the execution, because the guest ran out of memory
+        fn anoma_vp_out_of_memory(size: u64);
+
         fn anoma_vp_eval(
             vp_code_ptr: u64,
             vp_code_len: u64,
@@ -525,6 +869,9 @@ pub mod vp {
 pub mod matchmaker {
     use std::collections::HashSet;
 
+    use anoma::types::hash::{Hash, HASH_LENGTH};
+    use anoma::types::internal::HostEnvResult;
+    use anoma::types::key::common::{PublicKey, Signature};
     pub use borsh::{BorshDeserialize, BorshSerialize};
 
     /// Send a transaction with the `tx_data` and the `tx_code` to the ledger
@@ -554,6 +901,82 @@ pub mod matchmaker {
         };
     }
 
+    /// Compute a SHA-256 hash of the given data
+    pub fn sha256(data: impl AsRef<[u8]>) -> Hash {
+        let data = data.as_ref();
+        let mut result = [0; HASH_LENGTH];
+        unsafe {
+            anoma_mm_sha256(
+                data.as_ptr() as _,
+                data.len() as _,
+                result.as_mut_ptr() as _,
+            );
+        }
+        result
+    }
+
+    /// Compute a Blake2b-256 hash of the given data
+    pub fn blake2b(data: impl AsRef<[u8]>) -> Hash {
+        let data = data.as_ref();
+        let mut result = [0; HASH_LENGTH];
+        unsafe {
+            anoma_mm_blake2b(
+                data.as_ptr() as _,
+                data.len() as _,
+                result.as_mut_ptr() as _,
+            );
+        }
+        result
+    }
+
+    /// Compute a Keccak-256 hash of the given data
+    pub fn keccak256(data: impl AsRef<[u8]>) -> Hash {
+        let data = data.as_ref();
+        let mut result = [0; HASH_LENGTH];
+        unsafe {
+            anoma_mm_keccak256(
+                data.as_ptr() as _,
+                data.len() as _,
+                result.as_mut_ptr() as _,
+            );
+        }
+        result
+    }
+
+    /// Verify a signature of arbitrary data with the given public key.
+    pub fn verify_signature(
+        pk: &PublicKey,
+        data: impl AsRef<[u8]>,
+        sig: &Signature,
+    ) -> bool {
+        let pk = BorshSerialize::try_to_vec(pk).unwrap();
+        let sig = BorshSerialize::try_to_vec(sig).unwrap();
+        let data = data.as_ref();
+        let valid = unsafe {
+            anoma_mm_verify_signature(
+                pk.as_ptr() as _,
+                pk.len() as _,
+                sig.as_ptr() as _,
+                sig.len() as _,
+                data.as_ptr() as _,
+                data.len() as _,
+            )
+        };
+        HostEnvResult::is_success(valid)
+    }
+
+    /// Verify a list of signatures of arbitrary data, one after another.
+    /// Returns `true` only if all the signatures are valid.
+    pub fn verify_all_signatures(
+        sigs: &[(PublicKey, Vec<u8>, Signature)],
+    ) -> bool {
+        let sigs = BorshSerialize::try_to_vec(sigs).unwrap();
+        let valid = unsafe {
+            anoma_mm_verify_all_signatures(sigs.as_ptr() as _, sigs.len() as _)
+        };
+        HostEnvResult::is_success(valid)
+    }
+
     /// Log a string. The message will be printed at the `tracing::Level::Info`.
     pub fn log_string<T: AsRef<str>>(msg: T) {
         let msg = msg.as_ref();
@@ -572,6 +995,28 @@ pub mod matchmaker {
 
         fn anoma_mm_remove_intents(intents_id_ptr: u64, intents_id_len: u64);
 
+        // Write a SHA-256 hash of the data to the result pointer
+        fn anoma_mm_sha256(data_ptr: u64, data_len: u64, result_ptr: u64);
+
+        // Write a Blake2b-256 hash of the data to the result pointer
+        fn anoma_mm_blake2b(data_ptr: u64, data_len: u64, result_ptr: u64);
+
+        // Write a Keccak-256 hash of the data to the result pointer
+        fn anoma_mm_keccak256(data_ptr: u64, data_len: u64, result_ptr: u64);
+
+        // Verify a signature of arbitrary data
+        fn anoma_mm_verify_signature(
+            pk_ptr: u64,
+            pk_len: u64,
+            sig_ptr: u64,
+            sig_len: u64,
+            data_ptr: u64,
+            data_len: u64,
+        ) -> i64;
+
+        // Verify a list of signatures of arbitrary data
+        fn anoma_mm_verify_all_signatures(sigs_ptr: u64, sigs_len: u64) -> i64;
+
         // Requires a node running with "Info" log level
         fn anoma_mm_log_string(str_ptr: u64, str_len: u64);
     }
@@ -1,7 +1,8 @@
 use std::collections::HashSet;
 
+use anoma::types::hash::Hash;
 use anoma::types::intent;
-use anoma::types::key::ed25519::{Signature, Signed};
+use anoma::types::key::common::Signed;
 
 /// Tx imports and functions.
 pub mod tx {
@@ -11,9 +12,9 @@ pub mod tx {
     pub fn invalidate_exchange(intent: &Signed<Exchange>) {
         use crate::imports::tx;
         let key = intent::invalid_intent_key(&intent.data.addr);
-        let mut invalid_intent: HashSet<Signature> =
+        let mut invalid_intent: HashSet<Hash> =
             tx::read(&key.to_string()).unwrap_or_default();
-        invalid_intent.insert(intent.sig.clone());
+        invalid_intent.insert(intent::exchange_hash(&intent.data));
         tx::write(&key.to_string(), &invalid_intent)
     }
 }
@@ -27,12 +28,13 @@ pub mod vp {
     pub fn vp_exchange(intent: &Signed<Exchange>) -> bool {
         use crate::imports::vp;
         let key = intent::invalid_intent_key(&intent.data.addr);
+        let hash = intent::exchange_hash(&intent.data);
 
-        let invalid_intent_pre: HashSet<Signature> =
+        let invalid_intent_pre: HashSet<Hash> =
             vp::read_pre(&key.to_string()).unwrap_or_default();
-        let invalid_intent_post: HashSet<Signature> =
+        let invalid_intent_post: HashSet<Hash> =
             vp::read_post(&key.to_string()).unwrap_or_default();
-        !invalid_intent_pre.contains(&intent.sig)
-            && invalid_intent_post.contains(&intent.sig)
+        !invalid_intent_pre.contains(&hash)
+            && invalid_intent_post.contains(&hash)
     }
 }
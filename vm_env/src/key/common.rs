@@ -0,0 +1,16 @@
+use anoma::types::address::Address;
+use anoma::types::key::common;
+
+/// Vp imports and functions.
+pub mod vp {
+    pub use anoma::types::key::common::*;
+
+    use super::*;
+    use crate::imports::vp;
+
+    /// Get the public key associated with the given address, if any.
+    pub fn get(owner: &Address) -> Option<PublicKey> {
+        let key = common::pk_key(owner).to_string();
+        vp::read_pre(&key)
+    }
+}
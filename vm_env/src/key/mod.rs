@@ -1 +1,4 @@
+pub mod common;
 pub mod ed25519;
+pub mod multisig;
+pub mod secp256k1;
@@ -0,0 +1,44 @@
+use anoma::types::address::Address;
+use anoma::types::key::common::{PublicKey, Signature};
+use anoma::types::key::multisig;
+
+/// Vp imports and functions.
+pub mod vp {
+    pub use anoma::types::key::multisig::*;
+
+    use super::*;
+    use crate::imports::vp;
+
+    /// Get the public keys of the multisig account with the given address.
+    pub fn get_pks(owner: &Address) -> Option<Vec<PublicKey>> {
+        let key = multisig::pks_key(owner).to_string();
+        vp::read_pre(&key)
+    }
+
+    /// Get the signature threshold of the multisig account with the given
+    /// address.
+    pub fn get_threshold(owner: &Address) -> Option<u64> {
+        let key = multisig::threshold_key(owner).to_string();
+        vp::read_pre(&key)
+    }
+
+    /// Check that the transaction has been signed by at least the threshold
+    /// number of distinct keys of the multisig account with the given
+    /// address. An invalid stored config never verifies.
+    pub fn verify_threshold_sigs(
+        owner: &Address,
+        sigs: &[(PublicKey, Signature)],
+    ) -> bool {
+        match (get_pks(owner), get_threshold(owner)) {
+            (Some(pks), Some(threshold))
+                if multisig::is_valid_config(&pks, threshold) =>
+            {
+                let valid = count_valid_signatures(&pks, sigs, |pk, sig| {
+                    vp::verify_tx_signature(pk, sig)
+                });
+                valid >= threshold
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,4 @@
+/// Vp imports and functions.
+pub mod vp {
+    pub use anoma::types::key::secp256k1::*;
+}
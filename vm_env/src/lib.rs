@@ -7,7 +7,9 @@
 pub mod imports;
 pub mod intent;
 pub mod key;
+pub mod recovery;
 pub mod token;
+pub mod vesting;
 
 pub mod tx_prelude {
     pub use anoma::types::address::Address;
@@ -30,9 +32,14 @@ pub mod vp_prelude {
     pub use crate::imports::vp::*;
     pub use crate::intent::vp as intent;
     pub mod key {
+        pub use crate::key::common::vp as common;
         pub use crate::key::ed25519::vp as ed25519;
+        pub use crate::key::multisig::vp as multisig;
+        pub use crate::key::secp256k1::vp as secp256k1;
     }
+    pub use crate::recovery::vp as recovery;
     pub use crate::token::vp as token;
+    pub use crate::vesting::vp as vesting;
 }
 
 pub mod matchmaker_prelude {
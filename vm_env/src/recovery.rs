@@ -0,0 +1,22 @@
+use anoma::types::address::Address;
+use anoma::types::recovery;
+
+/// Vp imports and functions.
+pub mod vp {
+    pub use anoma::types::recovery::*;
+
+    use super::*;
+    use crate::imports::vp;
+
+    /// Get the recovery configuration of the account with the given address.
+    pub fn get_config(owner: &Address) -> Option<RecoveryConfig> {
+        let key = recovery::config_key(owner).to_string();
+        vp::read_pre(&key)
+    }
+
+    /// Get the recovery proposal of the account with the given address.
+    pub fn get_proposal(owner: &Address) -> Option<RecoveryProposal> {
+        let key = recovery::proposal_key(owner).to_string();
+        vp::read_pre(&key)
+    }
+}
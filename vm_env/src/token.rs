@@ -1,45 +1,47 @@
 use std::collections::HashSet;
 
 use anoma::types::address::Address;
-use anoma::types::storage::Key;
+use anoma::types::storage::{Epoch, Key};
 use anoma::types::token;
 
 /// Vp imports and functions.
 pub mod vp {
+    use std::convert::Infallible;
+
     pub use anoma::types::token::*;
 
     use super::*;
     use crate::imports::vp;
 
-    /// A token validity predicate.
+    /// A token validity predicate. See [`token::validate_changes`].
     pub fn vp(
         token: &Address,
         keys_changed: &HashSet<Key>,
         verifiers: &HashSet<Address>,
     ) -> bool {
-        let mut change: Change = 0;
-        let all_checked = keys_changed.iter().all(|key| {
-            match token::is_balance_key(token, key) {
-                None => {
-                    // deny any other keys
-                    false
-                }
-                Some(owner) => {
-                    // accumulate the change
-                    let key = key.to_string();
-                    let pre: Amount = vp::read_pre(&key).unwrap_or_default();
-                    let post: Amount = vp::read_post(&key).unwrap_or_default();
-                    let this_change = post.change() - pre.change();
-                    change += this_change;
-                    // make sure that the spender approved the transaction
-                    if this_change < 0 {
-                        return verifiers.contains(owner);
-                    }
-                    true
-                }
-            }
-        });
-        all_checked && change == 0
+        let result: Result<bool, Infallible> = token::validate_changes(
+            token,
+            keys_changed,
+            verifiers,
+            |key| {
+                let key = key.to_string();
+                let pre = vp::read_bytes_pre(&key);
+                let post = vp::read_bytes_post(&key);
+                Ok(token::amount_change(pre.as_deref(), post.as_deref()))
+            },
+            || Ok(read_minter(token)),
+        );
+        match result {
+            Ok(accept) => accept,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Read the token's minter from the token registry.
+    fn read_minter(token: &Address) -> Option<Address> {
+        let info: Option<TokenInfo> =
+            vp::read_pre(&token::registry_key(token).to_string());
+        info.and_then(|info| info.minter)
     }
 }
 
@@ -50,29 +52,192 @@ pub mod tx {
     use super::*;
     use crate::imports::tx;
 
-    /// A token transfer that can be used in a transaction.
+    /// A token transfer that can be used in a transaction. Returns an error
+    /// if the source's balance is insufficient or if the target's balance
+    /// would overflow, in which case no changes are written.
     pub fn transfer(
         src: &Address,
         dest: &Address,
         token: &Address,
         amount: Amount,
-    ) {
+    ) -> Result<(), TransferError> {
         let src_key = token::balance_key(token, src);
         let dest_key = token::balance_key(token, dest);
-        let src_bal: Option<Amount> = tx::read(&src_key.to_string());
-        match src_bal {
-            None => {
-                tx::log_string(format!("src {} has no balance", src));
-                unreachable!()
+        let src_bal: Amount =
+            tx::read(&src_key.to_string()).unwrap_or_default();
+        let new_src_bal = src_bal.checked_sub(&amount).ok_or_else(|| {
+            TransferError::InsufficientBalance {
+                token: token.clone(),
+                owner: src.clone(),
+                balance: src_bal,
+                amount,
+            }
+        })?;
+        if src == dest {
+            // The balance doesn't change
+            return Ok(());
+        }
+        let dest_bal: Amount =
+            tx::read(&dest_key.to_string()).unwrap_or_default();
+        let new_dest_bal = dest_bal.checked_add(&amount).ok_or_else(|| {
+            TransferError::BalanceOverflow {
+                token: token.clone(),
+                owner: dest.clone(),
+                amount,
+            }
+        })?;
+        tx::write(&src_key.to_string(), new_src_bal);
+        tx::write(&dest_key.to_string(), new_dest_bal);
+        Ok(())
+    }
+
+    /// Mint new tokens to the target and increase the token's total supply.
+    /// The token's minter is inserted into the verifiers to authorize it.
+    /// Returns an error if the token has no minter or if the target's balance
+    /// or the total supply would overflow, in which case no changes are
+    /// written.
+    pub fn mint(
+        target: &Address,
+        token: &Address,
+        amount: Amount,
+    ) -> Result<(), TransferError> {
+        let minter = read_minter(token)?;
+        let target_key = token::balance_key(token, target);
+        let supply_key = token::total_supply_key(token);
+        let target_bal: Amount =
+            tx::read(&target_key.to_string()).unwrap_or_default();
+        let new_target_bal =
+            target_bal.checked_add(&amount).ok_or_else(|| {
+                TransferError::BalanceOverflow {
+                    token: token.clone(),
+                    owner: target.clone(),
+                    amount,
+                }
+            })?;
+        let supply: Amount =
+            tx::read(&supply_key.to_string()).unwrap_or_default();
+        let new_supply = supply.checked_add(&amount).ok_or_else(|| {
+            TransferError::TotalSupplyOverflow {
+                token: token.clone(),
+                amount,
+            }
+        })?;
+        tx::write(&target_key.to_string(), new_target_bal);
+        tx::write(&supply_key.to_string(), new_supply);
+        tx::insert_verifier(minter);
+        Ok(())
+    }
+
+    /// Burn tokens from the source and decrease the token's total supply. Only
+    /// the source has to authorize it, the token's minter isn't needed.
+    /// Returns an error if the token has no minter or if the source's balance
+    /// is insufficient, in which case no changes are written.
+    pub fn burn(
+        source: &Address,
+        token: &Address,
+        amount: Amount,
+    ) -> Result<(), TransferError> {
+        // tokens with a fixed supply cannot be burned
+        read_minter(token)?;
+        let source_key = token::balance_key(token, source);
+        let supply_key = token::total_supply_key(token);
+        let source_bal: Amount =
+            tx::read(&source_key.to_string()).unwrap_or_default();
+        let new_source_bal =
+            source_bal.checked_sub(&amount).ok_or_else(|| {
+                TransferError::InsufficientBalance {
+                    token: token.clone(),
+                    owner: source.clone(),
+                    balance: source_bal,
+                    amount,
+                }
+            })?;
+        let supply: Amount =
+            tx::read(&supply_key.to_string()).unwrap_or_default();
+        let new_supply = supply.checked_sub(&amount).ok_or_else(|| {
+            TransferError::TotalSupplyUnderflow {
+                token: token.clone(),
+                total_supply: supply,
+                amount,
             }
-            Some(mut src_bal) => {
-                src_bal.spend(&amount);
-                let mut dest_bal: Amount =
-                    tx::read(&dest_key.to_string()).unwrap_or_default();
-                dest_bal.receive(&amount);
-                tx::write(&src_key.to_string(), src_bal);
-                tx::write(&dest_key.to_string(), dest_bal);
+        })?;
+        tx::write(&source_key.to_string(), new_source_bal);
+        tx::write(&supply_key.to_string(), new_supply);
+        Ok(())
+    }
+
+    /// Approve the spender to transfer up to the amount of the owner's
+    /// tokens, replacing any previous allowance. The owner's VP must accept
+    /// it.
+    pub fn approve(
+        owner: &Address,
+        token: &Address,
+        spender: &Address,
+        amount: Amount,
+        expiry: Option<Epoch>,
+    ) {
+        let key = token::allowance_key(owner, token, spender);
+        tx::write(&key.to_string(), Allowance { amount, expiry });
+    }
+
+    /// Revoke the spender's allowance to transfer the owner's tokens. The
+    /// owner's VP must accept it.
+    pub fn revoke(owner: &Address, token: &Address, spender: &Address) {
+        let key = token::allowance_key(owner, token, spender);
+        tx::delete(&key.to_string());
+    }
+
+    /// A token transfer by a spender using its allowance from the owner. The
+    /// spender is inserted into the verifiers to authorize it. Returns an
+    /// error if the allowance is expired or insufficient or if the transfer
+    /// fails, in which case no changes are written.
+    pub fn transfer_from(
+        spender: &Address,
+        owner: &Address,
+        target: &Address,
+        token: &Address,
+        amount: Amount,
+    ) -> Result<(), TransferError> {
+        let key = token::allowance_key(owner, token, spender);
+        let allowance: Allowance =
+            tx::read(&key.to_string()).unwrap_or_default();
+        if let Some(expiry) = allowance.expiry {
+            if allowance.is_expired(tx::get_block_epoch()) {
+                return Err(TransferError::AllowanceExpired {
+                    token: token.clone(),
+                    owner: owner.clone(),
+                    spender: spender.clone(),
+                    expiry,
+                });
             }
         }
+        let remaining =
+            allowance.amount.checked_sub(&amount).ok_or_else(|| {
+                TransferError::InsufficientAllowance {
+                    token: token.clone(),
+                    owner: owner.clone(),
+                    spender: spender.clone(),
+                    allowance: allowance.amount,
+                    amount,
+                }
+            })?;
+        transfer(owner, target, token, amount)?;
+        tx::write(
+            &key.to_string(),
+            Allowance {
+                amount: remaining,
+                ..allowance
+            },
+        );
+        tx::insert_verifier(spender.clone());
+        Ok(())
+    }
+
+    /// Read the token's minter from the token registry.
+    fn read_minter(token: &Address) -> Result<Address, TransferError> {
+        let info: Option<TokenInfo> =
+            tx::read(&token::registry_key(token).to_string());
+        info.and_then(|info| info.minter)
+            .ok_or_else(|| TransferError::NoMinter(token.clone()))
     }
 }
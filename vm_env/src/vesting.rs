@@ -0,0 +1,16 @@
+use anoma::types::address::Address;
+use anoma::types::vesting;
+
+/// Vp imports and functions.
+pub mod vp {
+    pub use anoma::types::vesting::*;
+
+    use super::*;
+    use crate::imports::vp;
+
+    /// Get the vesting schedule of the account with the given address.
+    pub fn get_schedule(owner: &Address) -> Option<VestingSchedule> {
+        let key = vesting::vesting_key(owner).to_string();
+        vp::read_pre(&key)
+    }
+}
@@ -15,15 +15,33 @@ pub fn transaction(_attr: TokenStream, input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as ItemFn);
     let ident = &ast.sig.ident;
     let gen = quote! {
-        // Use `wee_alloc` as the global allocator.
+        // Use `wee_alloc` as the global allocator. Allocation failures are
+        // reported to the host, which aborts the execution.
+        struct WasmAlloc(wee_alloc::WeeAlloc<'static>);
+
+        unsafe impl core::alloc::GlobalAlloc for WasmAlloc {
+            unsafe fn alloc(&self, layout: core::alloc::Layout) -> *mut u8 {
+                let ptr = core::alloc::GlobalAlloc::alloc(&self.0, layout);
+                if ptr.is_null() {
+                    out_of_memory(layout.size());
+                }
+                ptr
+            }
+
+            unsafe fn dealloc(&self, ptr: *mut u8, layout: core::alloc::Layout) {
+                core::alloc::GlobalAlloc::dealloc(&self.0, ptr, layout)
+            }
+        }
+
         #[global_allocator]
-        static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
+        static ALLOC: WasmAlloc = WasmAlloc(wee_alloc::WeeAlloc::INIT);
 
         #ast
 
         // The module entrypoint callable by wasm runtime
         #[no_mangle]
         extern "C" fn _apply_tx(tx_data_ptr: u64, tx_data_len: u64) {
+            set_panic_hook();
             let slice = unsafe {
                 core::slice::from_raw_parts(
                     tx_data_ptr as *const u8,
@@ -55,9 +73,26 @@ pub fn validity_predicate(
     let ast = parse_macro_input!(input as ItemFn);
     let ident = &ast.sig.ident;
     let gen = quote! {
-        // Use `wee_alloc` as the global allocator.
+        // Use `wee_alloc` as the global allocator. Allocation failures are
+        // reported to the host, which aborts the execution.
+        struct WasmAlloc(wee_alloc::WeeAlloc<'static>);
+
+        unsafe impl core::alloc::GlobalAlloc for WasmAlloc {
+            unsafe fn alloc(&self, layout: core::alloc::Layout) -> *mut u8 {
+                let ptr = core::alloc::GlobalAlloc::alloc(&self.0, layout);
+                if ptr.is_null() {
+                    out_of_memory(layout.size());
+                }
+                ptr
+            }
+
+            unsafe fn dealloc(&self, ptr: *mut u8, layout: core::alloc::Layout) {
+                core::alloc::GlobalAlloc::dealloc(&self.0, ptr, layout)
+            }
+        }
+
         #[global_allocator]
-        static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
+        static ALLOC: WasmAlloc = WasmAlloc(wee_alloc::WeeAlloc::INIT);
 
         #ast
 
@@ -75,6 +110,7 @@ pub fn validity_predicate(
             verifiers_ptr: u64,
             verifiers_len: u64,
         ) -> u64 {
+            set_panic_hook();
             let slice = unsafe {
                 core::slice::from_raw_parts(addr_ptr as *const u8, addr_len as _)
             };
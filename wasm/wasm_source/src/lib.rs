@@ -1,9 +1,15 @@
 #[cfg(feature = "mm_token_exch")]
 pub mod mm_token_exch;
 
+#[cfg(feature = "vp_multisig")]
+pub mod vp_multisig;
+
 #[cfg(feature = "vp_user")]
 pub mod vp_user;
 
+#[cfg(feature = "vp_vesting")]
+pub mod vp_vesting;
+
 /// A tx to initialize a new established address with a given public key and
 /// a validity predicate.
 #[cfg(feature = "tx_init_account")]
@@ -13,23 +19,122 @@ pub mod tx_init_account {
     #[transaction]
     fn apply_tx(tx_data: Vec<u8>) {
         let signed =
-            key::ed25519::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
-        let tx_data =
-            transaction::InitAccount::try_from_slice(&signed.data.unwrap()[..])
-                .unwrap();
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let tx_data = transaction::InitAccount::try_from_slice(
+            &signed.into_data().unwrap()[..],
+        )
+        .unwrap();
         log_string(
             "apply_tx called to init a new established account".to_string(),
         );
 
         let address = init_account(&tx_data.vp_code);
-        let pk_key = key::ed25519::pk_key(&address);
+        let pk_key = key::common::pk_key(&address);
         write(&pk_key.to_string(), &tx_data.public_key);
     }
 }
 
+/// A tx to initialize a new established multisig address with a given set of
+/// public keys, a signature threshold and a validity predicate.
+#[cfg(feature = "tx_init_multisig_account")]
+pub mod tx_init_multisig_account {
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let tx_data = transaction::InitMultisigAccount::try_from_slice(
+            &signed.into_data().unwrap()[..],
+        )
+        .unwrap();
+        log_string(
+            "apply_tx called to init a new established multisig account"
+                .to_string(),
+        );
+        if !key::multisig::is_valid_config(
+            &tx_data.public_keys,
+            tx_data.threshold,
+        ) {
+            panic!(
+                "Invalid multisig account: the keys must be unique and the \
+                 threshold must be between 1 and the number of keys"
+            );
+        }
+
+        let address = init_account(&tx_data.vp_code);
+        let pks_key = key::multisig::pks_key(&address);
+        write(&pks_key.to_string(), &tx_data.public_keys);
+        let threshold_key = key::multisig::threshold_key(&address);
+        write(&threshold_key.to_string(), tx_data.threshold);
+    }
+}
+
+/// A tx to initialize a new established vesting address with a given public
+/// key, a vesting schedule and a validity predicate.
+#[cfg(feature = "tx_init_vesting_account")]
+pub mod tx_init_vesting_account {
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let tx_data = transaction::InitVestingAccount::try_from_slice(
+            &signed.into_data().unwrap()[..],
+        )
+        .unwrap();
+        log_string(
+            "apply_tx called to init a new established vesting account"
+                .to_string(),
+        );
+        if !tx_data.schedule.is_valid() {
+            panic!(
+                "Invalid vesting schedule: the epochs must be ordered as \
+                 start <= cliff <= end"
+            );
+        }
+
+        let address = init_account(&tx_data.vp_code);
+        let pk_key = key::common::pk_key(&address);
+        write(&pk_key.to_string(), &tx_data.public_key);
+        let vesting_key = vesting::vesting_key(&address);
+        write(&vesting_key.to_string(), &tx_data.schedule);
+    }
+}
+
+/// A tx to initialize a new token account with a given validity predicate and
+/// add it to the token registry. The token's total supply starts at zero.
+#[cfg(feature = "tx_init_token")]
+pub mod tx_init_token {
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let tx_data = transaction::InitToken::try_from_slice(
+            &signed.into_data().unwrap()[..],
+        )
+        .unwrap();
+        log_string(format!(
+            "apply_tx called to init a new token {}",
+            tx_data.info.symbol
+        ));
+
+        let address = init_account(&tx_data.vp_code);
+        let registry_key = token::registry_key(&address);
+        write(&registry_key.to_string(), &tx_data.info);
+        let symbol_key = token::registry_symbol_key(&tx_data.info.symbol);
+        write(&symbol_key.to_string(), &address);
+        let total_supply_key = token::total_supply_key(&address);
+        write(&total_supply_key.to_string(), token::Amount::default());
+    }
+}
+
 /// A tx for a token transfer crafted by matchmaker from intents.
 /// This tx uses `intent::IntentTransfers` wrapped inside
-/// `key::ed25519::SignedTxData` as its input as declared in `shared` crate.
+/// `key::common::SignedTxData` as its input as declared in `shared` crate.
 #[cfg(feature = "tx_from_intent")]
 pub mod tx_from_intent {
     use anoma_vm_env::tx_prelude::*;
@@ -37,10 +142,11 @@ pub mod tx_from_intent {
     #[transaction]
     fn apply_tx(tx_data: Vec<u8>) {
         let signed =
-            key::ed25519::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
 
-        let tx_data =
-            intent::IntentTransfers::try_from_slice(&signed.data.unwrap()[..]);
+        let tx_data = intent::IntentTransfers::try_from_slice(
+            &signed.into_data().unwrap()[..],
+        );
 
         let tx_data = tx_data.unwrap();
 
@@ -54,7 +160,8 @@ pub mod tx_from_intent {
             amount,
         } in tx_data.transfers
         {
-            token::transfer(&source, &target, &token, amount);
+            token::transfer(&source, &target, &token, amount)
+                .unwrap_or_else(|err| panic!("Transfer failed: {}", err));
         }
 
         tx_data
@@ -66,7 +173,7 @@ pub mod tx_from_intent {
 }
 
 /// A tx for token transfer.
-/// This tx uses `token::Transfer` wrapped inside `key::ed25519::SignedTxData`
+/// This tx uses `token::Transfer` wrapped inside `key::common::SignedTxData`
 /// as its input as declared in `shared` crate.
 #[cfg(feature = "tx_transfer")]
 pub mod tx_transfer {
@@ -75,9 +182,10 @@ pub mod tx_transfer {
     #[transaction]
     fn apply_tx(tx_data: Vec<u8>) {
         let signed =
-            key::ed25519::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
         let transfer =
-            token::Transfer::try_from_slice(&signed.data.unwrap()[..]).unwrap();
+            token::Transfer::try_from_slice(&signed.into_data().unwrap()[..])
+                .unwrap();
         log_string(format!("apply_tx called with transfer: {:#?}", transfer));
         let token::Transfer {
             source,
@@ -86,11 +194,194 @@ pub mod tx_transfer {
             amount,
         } = transfer;
         token::transfer(&source, &target, &token, amount)
+            .unwrap_or_else(|err| panic!("Transfer failed: {}", err))
+    }
+}
+
+/// A tx for approving a spender to transfer up to an amount of the owner's
+/// tokens. This tx uses `token::Approve` wrapped inside
+/// `key::common::SignedTxData` as its input as declared in `shared` crate.
+#[cfg(feature = "tx_approve")]
+pub mod tx_approve {
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let approve =
+            token::Approve::try_from_slice(&signed.into_data().unwrap()[..])
+                .unwrap();
+        log_string(format!("apply_tx called with approve: {:#?}", approve));
+        let token::Approve {
+            owner,
+            spender,
+            token,
+            amount,
+            expiry,
+        } = approve;
+        token::approve(&owner, &token, &spender, amount, expiry)
+    }
+}
+
+/// A tx for revoking a spender's allowance to transfer the owner's tokens.
+/// This tx uses `token::Revoke` wrapped inside `key::common::SignedTxData`
+/// as its input as declared in `shared` crate.
+#[cfg(feature = "tx_revoke")]
+pub mod tx_revoke {
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let revoke =
+            token::Revoke::try_from_slice(&signed.into_data().unwrap()[..])
+                .unwrap();
+        log_string(format!("apply_tx called with revoke: {:#?}", revoke));
+        let token::Revoke {
+            owner,
+            spender,
+            token,
+        } = revoke;
+        token::revoke(&owner, &token, &spender)
+    }
+}
+
+/// A tx for a token transfer by a spender using its allowance from the
+/// owner. This tx uses `token::TransferFrom` wrapped inside
+/// `key::common::SignedTxData` as its input as declared in `shared` crate.
+#[cfg(feature = "tx_transfer_from")]
+pub mod tx_transfer_from {
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let transfer = token::TransferFrom::try_from_slice(
+            &signed.into_data().unwrap()[..],
+        )
+        .unwrap();
+        log_string(format!(
+            "apply_tx called with transfer from: {:#?}",
+            transfer
+        ));
+        let token::TransferFrom {
+            spender,
+            owner,
+            target,
+            token,
+            amount,
+        } = transfer;
+        token::transfer_from(&spender, &owner, &target, &token, amount)
+            .unwrap_or_else(|err| panic!("Transfer failed: {}", err))
+    }
+}
+
+/// A tx for minting new tokens by the token's minter.
+/// This tx uses `token::Mint` wrapped inside `key::common::SignedTxData`
+/// as its input as declared in `shared` crate.
+#[cfg(feature = "tx_mint")]
+pub mod tx_mint {
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let mint =
+            token::Mint::try_from_slice(&signed.into_data().unwrap()[..])
+                .unwrap();
+        log_string(format!("apply_tx called with mint: {:#?}", mint));
+        let token::Mint {
+            target,
+            token,
+            amount,
+        } = mint;
+        token::mint(&target, &token, amount)
+            .unwrap_or_else(|err| panic!("Mint failed: {}", err))
+    }
+}
+
+/// A tx for burning tokens, authorized by the source and the token's minter.
+/// This tx uses `token::Burn` wrapped inside `key::common::SignedTxData`
+/// as its input as declared in `shared` crate.
+#[cfg(feature = "tx_burn")]
+pub mod tx_burn {
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let burn =
+            token::Burn::try_from_slice(&signed.into_data().unwrap()[..])
+                .unwrap();
+        log_string(format!("apply_tx called with burn: {:#?}", burn));
+        let token::Burn {
+            source,
+            token,
+            amount,
+        } = burn;
+        token::burn(&source, &token, amount)
+            .unwrap_or_else(|err| panic!("Burn failed: {}", err))
+    }
+}
+
+/// A tx for a shielded transfer, which may also shield tokens into or unshield
+/// tokens out of the shielded pool.
+/// This tx uses `shielded::ShieldedTransfer` wrapped inside
+/// `key::common::SignedTxData` as its input as declared in `shared` crate.
+#[cfg(feature = "tx_shielded_transfer")]
+pub mod tx_shielded_transfer {
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let transfer = shielded::ShieldedTransfer::try_from_slice(
+            &signed.into_data().unwrap()[..],
+        )
+        .unwrap();
+        log_string(format!(
+            "apply_tx called with shielded transfer: {:#?}",
+            transfer
+        ));
+        let pool = shielded::pool_address();
+        if let Some(shield) = &transfer.shield {
+            token::transfer(&shield.owner, &pool, &shield.token, shield.amount)
+                .unwrap_or_else(|err| panic!("Shielding failed: {}", err));
+        }
+        if let Some(unshield) = &transfer.unshield {
+            token::transfer(
+                &pool,
+                &unshield.owner,
+                &unshield.token,
+                unshield.amount,
+            )
+            .unwrap_or_else(|err| panic!("Unshielding failed: {}", err));
+        }
+        for nullifier in &transfer.nullifiers {
+            let key = shielded::nullifier_key(nullifier).to_string();
+            if has_key(&key) {
+                panic!("The note has already been spent: {:?}", nullifier);
+            }
+            write(&key, ());
+        }
+        let count_key = shielded::commitment_count_key().to_string();
+        let count: u64 = read(&count_key).unwrap_or_default();
+        for (index, commitment) in transfer.commitments.iter().enumerate() {
+            let key = shielded::commitment_key(count + index as u64);
+            write(&key.to_string(), *commitment);
+        }
+        write(&count_key, count + transfer.commitments.len() as u64);
     }
 }
 
 /// A tx for updating an account's validity predicate.
-/// This tx wraps the validity predicate inside `key::ed25519::SignedTxData` as
+/// This tx wraps the validity predicate inside `key::common::SignedTxData` as
 /// its input as declared in `shared` crate.
 #[cfg(feature = "tx_update_vp")]
 pub mod tx_update_vp {
@@ -99,16 +390,523 @@ pub mod tx_update_vp {
     #[transaction]
     fn apply_tx(tx_data: Vec<u8>) {
         let signed =
-            key::ed25519::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
-        let update_vp =
-            transaction::UpdateVp::try_from_slice(&signed.data.unwrap()[..])
-                .unwrap();
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let update_vp = transaction::UpdateVp::try_from_slice(
+            &signed.into_data().unwrap()[..],
+        )
+        .unwrap();
         log_string(format!("update VP for: {:#?}", update_vp.addr));
         update_validity_predicate(update_vp.addr, update_vp.vp_code)
     }
 }
 
-/// A VP for a token.
+/// A tx for updating an account's public key.
+/// This tx wraps `transaction::UpdatePk` inside `key::common::SignedTxData` as
+/// its input as declared in `shared` crate.
+#[cfg(feature = "tx_update_pk")]
+pub mod tx_update_pk {
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let update_pk = transaction::UpdatePk::try_from_slice(
+            &signed.into_data().unwrap()[..],
+        )
+        .unwrap();
+        log_string(format!("update public key for: {:#?}", update_pk.addr));
+        let pk_key = key::common::pk_key(&update_pk.addr);
+        write(&pk_key.to_string(), &update_pk.public_key);
+    }
+}
+
+/// A tx for setting or removing an account's recovery configuration.
+/// This tx wraps `transaction::UpdateRecovery` inside
+/// `key::common::SignedTxData` as its input as declared in `shared` crate.
+#[cfg(feature = "tx_update_recovery")]
+pub mod tx_update_recovery {
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let update = transaction::UpdateRecovery::try_from_slice(
+            &signed.into_data().unwrap()[..],
+        )
+        .unwrap();
+        log_string(format!("update recovery: {:#?}", update));
+        let config_key = recovery::config_key(&update.addr).to_string();
+        match update.config {
+            Some(config) => write(&config_key, config),
+            None => delete(&config_key),
+        }
+    }
+}
+
+/// A tx for a guardian's approval of a new public key for an account.
+/// This tx wraps `transaction::ProposeRecovery` inside
+/// `key::common::SignedTxData` as its input as declared in `shared` crate.
+#[cfg(feature = "tx_propose_recovery")]
+pub mod tx_propose_recovery {
+    use std::collections::BTreeSet;
+
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let propose = transaction::ProposeRecovery::try_from_slice(
+            &signed.into_data().unwrap()[..],
+        )
+        .unwrap();
+        log_string(format!("propose recovery: {:#?}", propose));
+        let proposal_key = recovery::proposal_key(&propose.owner).to_string();
+        let proposal: Option<recovery::RecoveryProposal> = read(&proposal_key);
+        // the approvals of a different key are discarded
+        let mut approvals = match proposal {
+            Some(proposal) if proposal.public_key == propose.public_key => {
+                proposal.approvals
+            }
+            _ => BTreeSet::new(),
+        };
+        approvals.insert(propose.guardian);
+        let proposal = recovery::RecoveryProposal {
+            public_key: propose.public_key,
+            approvals,
+            epoch: get_block_epoch(),
+        };
+        write(&proposal_key, proposal);
+    }
+}
+
+/// A tx for vetoing an account's recovery proposal with its current key.
+/// This tx wraps `transaction::Recovery` inside `key::common::SignedTxData` as
+/// its input as declared in `shared` crate.
+#[cfg(feature = "tx_veto_recovery")]
+pub mod tx_veto_recovery {
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let veto = transaction::Recovery::try_from_slice(
+            &signed.into_data().unwrap()[..],
+        )
+        .unwrap();
+        log_string(format!("veto recovery for: {:#?}", veto.owner));
+        delete(&recovery::proposal_key(&veto.owner).to_string());
+    }
+}
+
+/// A tx for replacing an account's public key with the key of its ready
+/// recovery proposal.
+/// This tx wraps `transaction::Recovery` inside `key::common::SignedTxData` as
+/// its input as declared in `shared` crate.
+#[cfg(feature = "tx_recover")]
+pub mod tx_recover {
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let recover = transaction::Recovery::try_from_slice(
+            &signed.into_data().unwrap()[..],
+        )
+        .unwrap();
+        log_string(format!("recover account: {:#?}", recover.owner));
+        let proposal_key = recovery::proposal_key(&recover.owner).to_string();
+        let proposal: recovery::RecoveryProposal = read(&proposal_key)
+            .unwrap_or_else(|| {
+                panic!("No recovery proposal for {}", recover.owner)
+            });
+        let pk_key = key::common::pk_key(&recover.owner);
+        write(&pk_key.to_string(), &proposal.public_key);
+        delete(&proposal_key);
+    }
+}
+
+/// A tx for submitting a new governance proposal with a deposit.
+/// This tx wraps `transaction::InitProposal` inside
+/// `key::common::SignedTxData` as its input as declared in `shared` crate.
+#[cfg(feature = "tx_init_proposal")]
+pub mod tx_init_proposal {
+    use std::collections::BTreeSet;
+
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let init = transaction::InitProposal::try_from_slice(
+            &signed.into_data().unwrap()[..],
+        )
+        .unwrap();
+        log_string(format!("init proposal: {:#?}", init));
+        let parameters: governance::GovernanceParameters =
+            read(&governance::parameters_key().to_string())
+                .expect("Missing the governance parameters");
+        let counter_key = governance::counter_key().to_string();
+        let id: u64 = read(&counter_key).unwrap_or_default();
+        let voting_start_epoch = get_block_epoch();
+        let voting_end_epoch =
+            storage::Epoch(voting_start_epoch.0 + parameters.voting_period);
+
+        token::transfer(
+            &init.author,
+            &governance::governance_address(),
+            &address::xan(),
+            init.deposit,
+        )
+        .unwrap_or_else(|err| panic!("Deposit failed: {}", err));
+
+        let proposal = governance::Proposal {
+            id,
+            author: init.author.clone(),
+            content: init.content,
+            deposit: init.deposit,
+            voting_start_epoch,
+            voting_end_epoch,
+        };
+        write(&governance::proposal_key(id).to_string(), proposal);
+        let active_key = governance::active_proposals_key().to_string();
+        let mut active: BTreeSet<u64> = read(&active_key).unwrap_or_default();
+        active.insert(id);
+        write(&active_key, active);
+        write(&counter_key, id + 1);
+        insert_verifier(init.author);
+    }
+}
+
+/// A tx for voting on a governance proposal.
+/// This tx wraps `transaction::VoteProposal` inside
+/// `key::common::SignedTxData` as its input as declared in `shared` crate.
+#[cfg(feature = "tx_vote_proposal")]
+pub mod tx_vote_proposal {
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let vote = transaction::VoteProposal::try_from_slice(
+            &signed.into_data().unwrap()[..],
+        )
+        .unwrap();
+        log_string(format!("vote proposal: {:#?}", vote));
+        let vote_key = governance::vote_key(vote.id, &vote.voter).to_string();
+        write(&vote_key, vote.vote);
+        insert_verifier(vote.voter);
+    }
+}
+
+/// A tx for spending the treasury funds, which has to be signed by the keys
+/// of the treasury's admin account. This tx wraps
+/// `transaction::TreasurySpend` inside `key::common::SignedTxData` as its
+/// input as declared in `shared` crate.
+#[cfg(feature = "tx_treasury_spend")]
+pub mod tx_treasury_spend {
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let spend = transaction::TreasurySpend::try_from_slice(
+            &signed.into_data().unwrap()[..],
+        )
+        .unwrap();
+        log_string(format!("treasury spend: {:#?}", spend));
+        let admin: Address = read(&treasury::admin_key().to_string())
+            .expect("The treasury has no admin");
+        token::transfer(
+            &treasury::treasury_address(),
+            &spend.target,
+            &spend.token,
+            spend.amount,
+        )
+        .unwrap_or_else(|err| panic!("Transfer failed: {}", err));
+        insert_verifier(admin);
+    }
+}
+
+/// A tx for claiming the rewards of a bond to a validator, which has to be
+/// signed by the delegator's key. When the delegator is the validator itself,
+/// its commission is claimed too. This tx wraps
+/// `transaction::ClaimRewards` inside `key::common::SignedTxData` as its
+/// input as declared in `shared` crate.
+#[cfg(feature = "tx_claim_rewards")]
+pub mod tx_claim_rewards {
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let claim = transaction::ClaimRewards::try_from_slice(
+            &signed.into_data().unwrap()[..],
+        )
+        .unwrap();
+        log_string(format!("claim rewards: {:#?}", claim));
+        let bond_key =
+            pos::bond_key(&claim.delegator, &claim.validator).to_string();
+        let mut bond: pos::Bond = read(&bond_key).expect("The bond is missing");
+        let reward_acc: u128 =
+            read(&pos::reward_acc_key(&claim.validator).to_string())
+                .unwrap_or_default();
+        bond.settle(reward_acc).expect("The rewards overflowed");
+        let mut amount = bond.unclaimed;
+        bond.unclaimed = token::Amount::default();
+        write(&bond_key, bond);
+        if claim.delegator == claim.validator {
+            let commission_key =
+                pos::commission_key(&claim.validator).to_string();
+            let commission: Option<token::Amount> = read(&commission_key);
+            if let Some(commission) = commission {
+                amount = amount
+                    .checked_add(&commission)
+                    .expect("The rewards overflowed");
+                write(&commission_key, token::Amount::default());
+            }
+        }
+        token::transfer(
+            &pos::pos_address(),
+            &claim.delegator,
+            &address::xan(),
+            amount,
+        )
+        .unwrap_or_else(|err| panic!("Transfer failed: {}", err));
+        insert_verifier(claim.delegator);
+    }
+}
+
+#[cfg(feature = "tx_become_validator")]
+pub mod tx_become_validator {
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let data = transaction::BecomeValidator::try_from_slice(
+            &signed.into_data().unwrap()[..],
+        )
+        .unwrap();
+        log_string(format!("become validator: {:#?}", data));
+        let validator = &data.address;
+        let consensus_address = pos::consensus_address(&data.consensus_key);
+        write(
+            pos::consensus_key_key(validator).to_string(),
+            data.consensus_key.clone(),
+        );
+        write(
+            pos::consensus_address_key(&consensus_address).to_string(),
+            validator.clone(),
+        );
+        write(
+            pos::commission_rate_key(validator).to_string(),
+            data.commission_rate,
+        );
+        write(
+            pos::max_commission_rate_change_key(validator).to_string(),
+            data.max_commission_rate_change,
+        );
+        write(
+            pos::commission_epoch_key(validator).to_string(),
+            get_block_epoch(),
+        );
+        write(pos::reward_acc_key(validator).to_string(), 0_u128);
+        insert_verifier(data.address);
+    }
+}
+
+#[cfg(feature = "tx_bond")]
+pub mod tx_bond {
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let bond =
+            transaction::Bond::try_from_slice(&signed.into_data().unwrap()[..])
+                .unwrap();
+        log_string(format!("bond: {:#?}", bond));
+        let bond_key =
+            pos::bond_key(&bond.delegator, &bond.validator).to_string();
+        let reward_acc: u128 =
+            read(&pos::reward_acc_key(&bond.validator).to_string())
+                .unwrap_or_default();
+        let current: Option<pos::Bond> = read(&bond_key);
+        let new = match current {
+            Some(mut current) => {
+                // the rewards are settled before the amount changes
+                current.settle(reward_acc).expect("The rewards overflowed");
+                current.amount = current
+                    .amount
+                    .checked_add(&bond.amount)
+                    .expect("The bond overflowed");
+                current
+            }
+            None => pos::Bond::new(bond.amount, reward_acc),
+        };
+        write(&bond_key, new);
+        let stake_key = pos::stake_key(&bond.validator).to_string();
+        let stake: token::Amount = read(&stake_key).unwrap_or_default();
+        write(
+            &stake_key,
+            stake
+                .checked_add(&bond.amount)
+                .expect("The stake overflowed"),
+        );
+        token::transfer(
+            &bond.delegator,
+            &pos::pos_address(),
+            &address::xan(),
+            bond.amount,
+        )
+        .unwrap_or_else(|err| panic!("Transfer failed: {}", err));
+    }
+}
+
+#[cfg(feature = "tx_unbond")]
+pub mod tx_unbond {
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let unbond = transaction::Unbond::try_from_slice(
+            &signed.into_data().unwrap()[..],
+        )
+        .unwrap();
+        log_string(format!("unbond: {:#?}", unbond));
+        let bond_key =
+            pos::bond_key(&unbond.delegator, &unbond.validator).to_string();
+        let mut bond: pos::Bond = read(&bond_key).expect("The bond is missing");
+        let reward_acc: u128 =
+            read(&pos::reward_acc_key(&unbond.validator).to_string())
+                .unwrap_or_default();
+        // the rewards are settled before the amount changes
+        bond.settle(reward_acc).expect("The rewards overflowed");
+        bond.amount = bond
+            .amount
+            .checked_sub(&unbond.amount)
+            .expect("The unbonded amount exceeds the bond");
+        write(&bond_key, bond);
+        let stake_key = pos::stake_key(&unbond.validator).to_string();
+        let stake: token::Amount = read(&stake_key).unwrap_or_default();
+        write(
+            &stake_key,
+            stake
+                .checked_sub(&unbond.amount)
+                .expect("The unbonded amount exceeds the stake"),
+        );
+        let unbond_key =
+            pos::unbond_key(&unbond.delegator, &unbond.validator).to_string();
+        let mut unbonds: pos::Unbonds = read(&unbond_key).unwrap_or_default();
+        let unbonded = unbonds
+            .entry(pos::withdrawable_epoch(get_block_epoch()))
+            .or_default();
+        *unbonded = unbonded
+            .checked_add(&unbond.amount)
+            .expect("The unbonded amount overflowed");
+        write(&unbond_key, unbonds);
+        insert_verifier(unbond.delegator);
+    }
+}
+
+#[cfg(feature = "tx_withdraw")]
+pub mod tx_withdraw {
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let withdraw = transaction::Withdraw::try_from_slice(
+            &signed.into_data().unwrap()[..],
+        )
+        .unwrap();
+        log_string(format!("withdraw: {:#?}", withdraw));
+        let unbond_key =
+            pos::unbond_key(&withdraw.delegator, &withdraw.validator)
+                .to_string();
+        let unbonds: pos::Unbonds = read(&unbond_key).unwrap_or_default();
+        let epoch = get_block_epoch();
+        let (withdrawable, unbonding): (pos::Unbonds, pos::Unbonds) = unbonds
+            .into_iter()
+            .partition(|(unbond_epoch, _)| *unbond_epoch <= epoch);
+        let amount = withdrawable
+            .values()
+            .try_fold(token::Amount::default(), |sum, amount| {
+                sum.checked_add(amount)
+            })
+            .expect("The withdrawn amount overflowed");
+        write(&unbond_key, unbonding);
+        token::transfer(
+            &pos::pos_address(),
+            &withdraw.delegator,
+            &address::xan(),
+            amount,
+        )
+        .unwrap_or_else(|err| panic!("Transfer failed: {}", err));
+        insert_verifier(withdraw.delegator);
+    }
+}
+
+#[cfg(feature = "tx_change_commission")]
+pub mod tx_change_commission {
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let change = transaction::ChangeCommission::try_from_slice(
+            &signed.into_data().unwrap()[..],
+        )
+        .unwrap();
+        log_string(format!("change commission: {:#?}", change));
+        write(
+            pos::commission_rate_key(&change.validator).to_string(),
+            change.rate,
+        );
+        write(
+            pos::commission_epoch_key(&change.validator).to_string(),
+            get_block_epoch(),
+        );
+        insert_verifier(change.validator);
+    }
+}
+
+#[cfg(feature = "tx_unjail")]
+pub mod tx_unjail {
+    use anoma_vm_env::tx_prelude::*;
+
+    #[transaction]
+    fn apply_tx(tx_data: Vec<u8>) {
+        let signed =
+            key::common::SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+        let unjail = transaction::Unjail::try_from_slice(
+            &signed.into_data().unwrap()[..],
+        )
+        .unwrap();
+        log_string(format!("unjail: {:#?}", unjail));
+        delete(pos::jailed_key(&unjail.validator).to_string());
+        insert_verifier(unjail.validator);
+    }
+}
+
+/// A VP for a token. The tokens in the token registry are validated by the
+/// native token VP instead, so this VP is only being run for unregistered
+/// token accounts.
 #[cfg(feature = "vp_token")]
 pub mod vp_token {
     use anoma_vm_env::vp_prelude::*;
@@ -3,7 +3,7 @@ use std::collections::{HashMap, VecDeque};
 use anoma_vm_env::matchmaker_prelude::intent::{
     Exchange, FungibleTokenIntent, IntentTransfers,
 };
-use anoma_vm_env::matchmaker_prelude::key::ed25519::Signed;
+use anoma_vm_env::matchmaker_prelude::key::common::Signed;
 use anoma_vm_env::matchmaker_prelude::{token, *};
 use good_lp::{
     constraint, default_solver, variable, variables, Expression,
@@ -0,0 +1,307 @@
+//! A k-of-n multisig VP.
+//! This VP requires the transaction to be signed by at least a threshold
+//! number of distinct keys from the account's set of public keys for sending
+//! tokens and for any other modification of the account's storage (receiving
+//! tokens is permissive). The account's public keys and threshold can only be
+//! replaced with a valid multisig configuration.
+
+use anoma_vm_env::vp_prelude::key::common::SignedTxData;
+use anoma_vm_env::vp_prelude::*;
+
+enum KeyType<'a> {
+    Token(&'a Address),
+    Config(&'a Address),
+    Unknown,
+}
+
+impl<'a> From<&'a storage::Key> for KeyType<'a> {
+    fn from(key: &'a storage::Key) -> KeyType<'a> {
+        if let Some((_token, owner)) = token::is_any_balance_key(key) {
+            Self::Token(owner)
+        } else if let Some(owner) = key::multisig::is_pks_key(key) {
+            Self::Config(owner)
+        } else if let Some(owner) = key::multisig::is_threshold_key(key) {
+            Self::Config(owner)
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+#[validity_predicate]
+fn validate_tx(
+    tx_data: Vec<u8>,
+    addr: Address,
+    keys_changed: HashSet<storage::Key>,
+    verifiers: HashSet<Address>,
+) -> bool {
+    log_string(format!(
+        "validate_tx called with multisig addr: {}, key_changed: {:#?}, \
+         verifiers: {:?}",
+        addr, keys_changed, verifiers
+    ));
+
+    // TODO memoize?
+    let valid_sigs = match SignedTxData::try_from_slice(&tx_data[..]) {
+        Ok(SignedTxData::Multi { sigs, .. }) => {
+            key::multisig::verify_threshold_sigs(&addr, &sigs)
+        }
+        _ => false,
+    };
+
+    log_string(format!("signatures valid {}, {}", valid_sigs, &addr));
+
+    for key in keys_changed.iter() {
+        let is_valid = match KeyType::from(key) {
+            KeyType::Token(owner) if owner == &addr => {
+                let key = key.to_string();
+                let pre: token::Amount = read_pre(&key).unwrap_or_default();
+                let post: token::Amount = read_post(&key).unwrap_or_default();
+                let change = post.change() - pre.change();
+                // debit has to signed, credit doesn't
+                let is_valid = change >= 0 || valid_sigs;
+                log_string(format!(
+                    "token key: {}, change: {}, valid_sigs: {}, valid \
+                     modification: {}",
+                    key, change, valid_sigs, is_valid
+                ));
+                is_valid
+            }
+            KeyType::Token(_owner) => {
+                log_string(format!(
+                    "Token: key {} is not of owner, valid_sigs {}, owner: {}, \
+                     address: {}",
+                    key, valid_sigs, _owner, addr
+                ));
+                valid_sigs
+            }
+            KeyType::Config(owner) if owner == &addr => {
+                // the new keys and threshold have to be signed with the
+                // current ones and the account must remain spendable
+                let pks: Option<Vec<key::common::PublicKey>> =
+                    read_post(&key::multisig::pks_key(&addr).to_string());
+                let threshold: Option<u64> =
+                    read_post(&key::multisig::threshold_key(&addr).to_string());
+                let valid_config = match (pks, threshold) {
+                    (Some(pks), Some(threshold)) => {
+                        key::multisig::is_valid_config(&pks, threshold)
+                    }
+                    _ => false,
+                };
+                log_string(format!(
+                    "multisig config key: {}, valid_sigs: {}, valid_config: \
+                     {}",
+                    key, valid_sigs, valid_config
+                ));
+                valid_sigs && valid_config
+            }
+            KeyType::Config(_) | KeyType::Unknown => {
+                log_string(format!(
+                    "Unknown key modified, valid sigs {}",
+                    valid_sigs
+                ));
+                valid_sigs
+            }
+        };
+        if !is_valid {
+            log_string(format!("key {} modification failed vp", key));
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use anoma_tests::tx::{tx_host_env, TestTxEnv};
+    use anoma_tests::vp::*;
+    use anoma_vm_env::token::tx as token_tx;
+    use anoma_vm_env::vp_prelude::key::common::SigningKey;
+    use anoma_vm_env::vp_prelude::key::{ed25519, secp256k1};
+
+    use super::*;
+
+    /// Test that no-op transaction (i.e. no storage modifications) is deemed
+    /// valid.
+    #[test]
+    fn test_no_op_transaction() {
+        let mut env = TestVpEnv::default();
+        init_vp_env(&mut env);
+
+        let tx_data: Vec<u8> = vec![];
+        let addr: Address = env.addr;
+        let keys_changed: HashSet<storage::Key> = HashSet::default();
+        let verifiers: HashSet<Address> = HashSet::default();
+
+        let valid = validate_tx(tx_data, addr, keys_changed, verifiers);
+
+        assert!(valid);
+    }
+
+    /// The keys of a 2-of-3 multisig account
+    fn keypairs() -> (ed25519::Keypair, secp256k1::Keypair, ed25519::Keypair) {
+        (
+            ed25519::testing::keypair_1(),
+            secp256k1::testing::keypair_1(),
+            ed25519::testing::keypair_2(),
+        )
+    }
+
+    /// Initialize a tx environment in which the owner is a 2-of-3 multisig
+    /// account with the keys from [`keypairs`] and a balance of 100 tokens.
+    fn init_multisig_env(owner: &Address, token: &Address) -> TestTxEnv {
+        let mut tx_env = TestTxEnv::default();
+        tx_env.spawn_accounts(&[owner, token]);
+        let (keypair_1, keypair_2, keypair_3) = keypairs();
+        let pks = vec![
+            keypair_1.public_key(),
+            keypair_2.public_key(),
+            keypair_3.public_key(),
+        ];
+        tx_env
+            .storage
+            .write(&key::multisig::pks_key(owner), pks.try_to_vec().unwrap())
+            .unwrap();
+        tx_env
+            .storage
+            .write(
+                &key::multisig::threshold_key(owner),
+                2_u64.try_to_vec().unwrap(),
+            )
+            .unwrap();
+        tx_env
+            .storage
+            .write(
+                &token::balance_key(token, owner),
+                token::Amount::from(100).try_to_vec().unwrap(),
+            )
+            .unwrap();
+        tx_env
+    }
+
+    /// Test that a debit from the account requires the threshold number of
+    /// signatures from the account's keys.
+    #[test]
+    fn test_debit_with_threshold_sigs() {
+        let vp_owner = address::testing::established_address_1();
+        let target = address::testing::established_address_2();
+        let token = address::xan();
+        let (keypair_1, keypair_2, _keypair_3) = keypairs();
+        let validate = |signers: &[&dyn SigningKey]| {
+            let mut tx_env = init_multisig_env(&vp_owner, &token);
+            tx_env.spawn_accounts(&[&target]);
+            let mut vp_env =
+                init_vp_env_from_tx(vp_owner.clone(), tx_env, |_| {
+                    token_tx::transfer(
+                        &vp_owner,
+                        &target,
+                        &token,
+                        token::Amount::from(30),
+                    )
+                    .unwrap();
+                });
+            let tx_data = vp_env.sign_tx_multisig(signers);
+            validate_tx(
+                tx_data,
+                vp_owner.clone(),
+                vp_env.keys_changed,
+                vp_env.verifiers,
+            )
+        };
+
+        assert!(validate(&[&keypair_1, &keypair_2]));
+        assert!(!validate(&[&keypair_1]));
+        // the same key doesn't count twice
+        assert!(!validate(&[&keypair_1, &keypair_1]));
+        // a key from outside of the account's keys isn't counted
+        assert!(!validate(&[&keypair_1, &secp256k1::testing::keypair_2()]));
+    }
+
+    /// Test that the account can receive tokens and that its balance can be
+    /// written without a change, without any signatures.
+    #[test]
+    fn test_unsigned_credit() {
+        let vp_owner = address::testing::established_address_1();
+        let token = address::xan();
+        let balance_key = token::balance_key(&token, &vp_owner);
+        for balance in &[100, 150] {
+            let tx_env = init_multisig_env(&vp_owner, &token);
+            let vp_env = init_vp_env_from_tx(vp_owner.clone(), tx_env, |_| {
+                tx_host_env::write(
+                    &balance_key.to_string(),
+                    token::Amount::from(*balance),
+                );
+            });
+            let tx_data: Vec<u8> = vec![];
+            assert!(validate_tx(
+                tx_data,
+                vp_owner.clone(),
+                vp_env.keys_changed,
+                vp_env.verifiers
+            ));
+        }
+
+        // a debit isn't accepted without signatures
+        let tx_env = init_multisig_env(&vp_owner, &token);
+        let vp_env = init_vp_env_from_tx(vp_owner.clone(), tx_env, |_| {
+            tx_host_env::write(
+                &balance_key.to_string(),
+                token::Amount::from(50),
+            );
+        });
+        let tx_data: Vec<u8> = vec![];
+        assert!(!validate_tx(
+            tx_data,
+            vp_owner,
+            vp_env.keys_changed,
+            vp_env.verifiers
+        ));
+    }
+
+    /// Test that the account's keys and threshold can only be changed with
+    /// the threshold number of signatures and into a valid configuration.
+    #[test]
+    fn test_config_change() {
+        let vp_owner = address::testing::established_address_1();
+        let token = address::xan();
+        let (keypair_1, keypair_2, keypair_3) = keypairs();
+        let validate = |pks: Vec<key::common::PublicKey>,
+                        threshold: u64,
+                        signers: &[&dyn SigningKey]| {
+            let tx_env = init_multisig_env(&vp_owner, &token);
+            let mut vp_env =
+                init_vp_env_from_tx(vp_owner.clone(), tx_env, |_| {
+                    tx_host_env::write(
+                        &key::multisig::pks_key(&vp_owner).to_string(),
+                        &pks,
+                    );
+                    tx_host_env::write(
+                        &key::multisig::threshold_key(&vp_owner).to_string(),
+                        threshold,
+                    );
+                });
+            let tx_data = vp_env.sign_tx_multisig(signers);
+            validate_tx(
+                tx_data,
+                vp_owner.clone(),
+                vp_env.keys_changed,
+                vp_env.verifiers,
+            )
+        };
+        let pks = || vec![keypair_1.public_key(), keypair_3.public_key()];
+
+        assert!(validate(pks(), 1, &[&keypair_1, &keypair_2]));
+        assert!(validate(pks(), 2, &[&keypair_2, &keypair_3]));
+        // the current threshold of signatures is required
+        assert!(!validate(pks(), 1, &[&keypair_1]));
+        assert!(!validate(pks(), 1, &[]));
+        // a zero threshold would let anyone spend from the account
+        assert!(!validate(pks(), 0, &[&keypair_1, &keypair_2]));
+        // the threshold cannot be above the number of keys
+        assert!(!validate(pks(), 3, &[&keypair_1, &keypair_2]));
+        // the keys must be unique
+        let duplicate_pks =
+            vec![keypair_1.public_key(), keypair_1.public_key()];
+        assert!(!validate(duplicate_pks, 2, &[&keypair_1, &keypair_2]));
+    }
+}
@@ -1,26 +1,52 @@
 //! A basic user VP.
 //! This VP currently provides a signature verification against a public key for
-//! sending tokens (receiving tokens is permissive).
+//! sending tokens (receiving tokens is permissive). Tokens can also be sent by
+//! a spender using an unexpired allowance given by this account. The public key
+//! can only be replaced with a valid key with a signature of the current key,
+//! or by a recovery proposal approved by the account's guardians, once its
+//! delay has passed.
+
+use std::collections::BTreeSet;
 
 use anoma_vm_env::vp_prelude::intent::{
     Exchange, FungibleTokenIntent, IntentTransfers,
 };
-use anoma_vm_env::vp_prelude::key::ed25519::{Signed, SignedTxData};
+use anoma_vm_env::vp_prelude::key::common::{Signed, SignedTxData};
 use anoma_vm_env::vp_prelude::*;
 use rust_decimal::prelude::*;
 
 enum KeyType<'a> {
-    Token(&'a Address),
+    Token {
+        token: &'a Address,
+        owner: &'a Address,
+    },
+    Allowance {
+        owner: &'a Address,
+        spender: Address,
+    },
     InvalidIntentSet(&'a Address),
+    PublicKey(&'a Address),
+    RecoveryConfig(&'a Address),
+    RecoveryProposal(&'a Address),
     Unknown,
 }
 
 impl<'a> From<&'a storage::Key> for KeyType<'a> {
     fn from(key: &'a storage::Key) -> KeyType<'a> {
-        if let Some(address) = token::is_any_token_balance_key(key) {
-            Self::Token(address)
+        if let Some((token, owner)) = token::is_any_balance_key(key) {
+            Self::Token { token, owner }
+        } else if let Some((owner, _token, spender)) =
+            token::is_allowance_key(key)
+        {
+            Self::Allowance { owner, spender }
         } else if let Some(address) = intent::is_invalid_intent_key(key) {
             Self::InvalidIntentSet(address)
+        } else if let Some(owner) = key::common::is_pk_key(key) {
+            Self::PublicKey(owner)
+        } else if let Some(owner) = recovery::is_config_key(key) {
+            Self::RecoveryConfig(owner)
+        } else if let Some(owner) = recovery::is_proposal_key(key) {
+            Self::RecoveryProposal(owner)
         } else {
             Self::Unknown
         }
@@ -41,16 +67,7 @@ fn validate_tx(
     ));
 
     // TODO memoize?
-    let transfer_valid_sig = match SignedTxData::try_from_slice(&tx_data[..]) {
-        Ok(tx) => {
-            let pk = key::ed25519::get(&addr);
-            match pk {
-                Some(pk) => verify_tx_signature(&pk, &tx.sig),
-                None => false,
-            }
-        }
-        _ => false,
-    };
+    let transfer_valid_sig = is_signed_by(&addr, &tx_data[..]);
 
     log_string(format!("signature valid {}, {}", transfer_valid_sig, &addr));
 
@@ -63,37 +80,94 @@ fn validate_tx(
 
     for key in keys_changed.iter() {
         let is_valid = match KeyType::from(key) {
-            KeyType::Token(owner) if owner == &addr => {
+            KeyType::Token { token, owner } if owner == &addr => {
                 let key = key.to_string();
                 let pre: token::Amount = read_pre(&key).unwrap_or_default();
                 let post: token::Amount = read_post(&key).unwrap_or_default();
                 let change = post.change() - pre.change();
+                // debit has to signed or covered by allowances, credit
+                // doesn't
+                let is_valid = change >= 0
+                    || transfer_valid_sig
+                    || valid_intent
+                    || allowances_cover_debit(
+                        &addr,
+                        token,
+                        -change,
+                        &keys_changed,
+                        &verifiers,
+                    );
                 log_string(format!(
                     "token key: {}, change: {}, transfer_valid_sig: {}, \
                      valid_intent: {}, valid modification: {}",
-                    key,
-                    change,
-                    transfer_valid_sig,
-                    valid_intent,
-                    (change < 0 && (transfer_valid_sig || valid_intent))
-                        || change > 0
+                    key, change, transfer_valid_sig, valid_intent, is_valid
+                ));
+                is_valid
+            }
+            KeyType::Allowance { owner, spender } if owner == &addr => {
+                // an allowance has to be signed, unless it's being used by
+                // the spender
+                let is_valid = transfer_valid_sig
+                    || used_allowance(key, &spender, &verifiers).is_some();
+                log_string(format!(
+                    "allowance key: {}, transfer_valid_sig: {}, valid \
+                     modification: {}",
+                    key, transfer_valid_sig, is_valid
                 ));
-                // debit has to signed, credit doesn't
-                (change < 0 && (transfer_valid_sig || valid_intent))
-                    || change > 0
+                is_valid
             }
             KeyType::InvalidIntentSet(owner) if owner == &addr => {
                 let key = key.to_string();
-                let pre: Vec<Vec<u8>> = read_pre(&key).unwrap_or_default();
-                let post: Vec<Vec<u8>> = read_post(&key).unwrap_or_default();
-                // only one sig is added, intent is already checked
+                let pre: HashSet<hash::Hash> =
+                    read_pre(&key).unwrap_or_default();
+                let post: HashSet<hash::Hash> =
+                    read_post(&key).unwrap_or_default();
+                // only one hash is added, intent is already checked
                 log_string(format!(
-                    "intent sig set key: {}, valid modification: {}",
+                    "intent hash set key: {}, valid modification: {}",
                     key,
                     pre.len() + 1 != post.len()
                 ));
                 pre.len() + 1 == post.len()
             }
+            KeyType::PublicKey(owner) if owner == &addr => {
+                // the key has to be signed by the current key and replaced
+                // with a valid key, so that the account cannot be locked
+                let new_pk: Option<key::common::PublicKey> =
+                    read_post(&key.to_string());
+                let is_valid = (transfer_valid_sig && new_pk.is_some())
+                    || is_recovery_executed(&addr);
+                log_string(format!(
+                    "public key: {}, transfer_valid_sig: {}, new key: {:?}, \
+                     valid modification: {}",
+                    key, transfer_valid_sig, new_pk, is_valid
+                ));
+                is_valid
+            }
+            KeyType::RecoveryConfig(owner) if owner == &addr => {
+                let config: Option<recovery::RecoveryConfig> =
+                    read_post(&key.to_string());
+                let is_valid = transfer_valid_sig
+                    && config.map_or(true, |config| config.is_valid(&addr));
+                log_string(format!(
+                    "recovery config: {}, transfer_valid_sig: {}, valid \
+                     modification: {}",
+                    key, transfer_valid_sig, is_valid
+                ));
+                is_valid
+            }
+            KeyType::RecoveryProposal(owner) if owner == &addr => {
+                // the current key can veto a proposal
+                let is_valid = transfer_valid_sig
+                    || is_valid_approval(&addr, &tx_data[..], key)
+                    || is_recovery_executed(&addr);
+                log_string(format!(
+                    "recovery proposal: {}, transfer_valid_sig: {}, valid \
+                     modification: {}",
+                    key, transfer_valid_sig, is_valid
+                ));
+                is_valid
+            }
             KeyType::InvalidIntentSet(_owner) => {
                 log_string(format!(
                     "InvalidIntentSet: key {} is not of owner, \
@@ -102,7 +176,7 @@ fn validate_tx(
                 ));
                 transfer_valid_sig
             }
-            KeyType::Token(_owner) => {
+            KeyType::Token { owner: _owner, .. } => {
                 log_string(format!(
                     "Token: key {} is not of owner, transfer_valid_sig {}, \
                      owner: {}, address: {}",
@@ -110,6 +184,31 @@ fn validate_tx(
                 ));
                 transfer_valid_sig
             }
+            KeyType::Allowance { owner: _owner, .. } => {
+                log_string(format!(
+                    "Allowance: key {} is not of owner, transfer_valid_sig \
+                     {}, owner: {}, address: {}",
+                    key, transfer_valid_sig, _owner, addr
+                ));
+                transfer_valid_sig
+            }
+            KeyType::PublicKey(_owner) => {
+                log_string(format!(
+                    "PublicKey: key {} is not of owner, transfer_valid_sig \
+                     {}, owner: {}, address: {}",
+                    key, transfer_valid_sig, _owner, addr
+                ));
+                transfer_valid_sig
+            }
+            KeyType::RecoveryConfig(_owner)
+            | KeyType::RecoveryProposal(_owner) => {
+                log_string(format!(
+                    "Recovery: key {} is not of owner, transfer_valid_sig {}, \
+                     owner: {}, address: {}",
+                    key, transfer_valid_sig, _owner, addr
+                ));
+                transfer_valid_sig
+            }
             KeyType::Unknown => {
                 log_string(format!(
                     "Unknown key modified, valid sig {}",
@@ -126,12 +225,121 @@ fn validate_tx(
     true
 }
 
+/// Check that the tx is signed by the public key of the given address.
+fn is_signed_by(signer: &Address, tx_data: &[u8]) -> bool {
+    match SignedTxData::try_from_slice(tx_data) {
+        Ok(SignedTxData::Single { sig, .. }) => {
+            let pk = key::common::get(signer);
+            match pk {
+                Some(pk) => verify_tx_signature(&pk, &sig),
+                None => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// Check a guardian's approval of a recovery proposal. The tx must be signed
+/// by a single guardian, who is added to the proposal's approvals in the
+/// current epoch. The approvals are reset when a different key is proposed.
+fn is_valid_approval(
+    addr: &Address,
+    tx_data: &[u8],
+    key: &storage::Key,
+) -> bool {
+    let config = match recovery::get_config(addr) {
+        Some(config) => config,
+        None => return false,
+    };
+    let key = key.to_string();
+    let post: recovery::RecoveryProposal = match read_post(&key) {
+        Some(post) => post,
+        None => return false,
+    };
+    let pre: Option<recovery::RecoveryProposal> = read_pre(&key);
+    let pre_approvals = match pre {
+        Some(pre) if pre.public_key == post.public_key => pre.approvals,
+        _ => BTreeSet::new(),
+    };
+    if !post.approvals.is_superset(&pre_approvals)
+        || post.epoch != get_block_epoch()
+    {
+        return false;
+    }
+    let mut added = post.approvals.difference(&pre_approvals);
+    match (added.next(), added.next()) {
+        (Some(guardian), None) => {
+            config.guardians.contains(guardian)
+                && is_signed_by(guardian, tx_data)
+        }
+        _ => false,
+    }
+}
+
+/// Check that the account's public key is replaced with the key of a ready
+/// recovery proposal, which is removed.
+fn is_recovery_executed(addr: &Address) -> bool {
+    let (config, proposal) =
+        match (recovery::get_config(addr), recovery::get_proposal(addr)) {
+            (Some(config), Some(proposal)) => (config, proposal),
+            _ => return false,
+        };
+    let pk_key = key::common::pk_key(addr).to_string();
+    let new_pk: Option<key::common::PublicKey> = read_post(&pk_key);
+    let proposal_key = recovery::proposal_key(addr).to_string();
+    proposal.is_ready(&config, get_block_epoch())
+        && new_pk.as_ref() == Some(&proposal.public_key)
+        && !has_key_post(&proposal_key)
+}
+
+/// Get the amount of an allowance used in this transaction. The allowance must
+/// be unexpired, it can only decrease without changing its expiry and the
+/// spender must be in the verifiers to authorize its use.
+fn used_allowance(
+    key: &storage::Key,
+    spender: &Address,
+    verifiers: &HashSet<Address>,
+) -> Option<token::Amount> {
+    if !verifiers.contains(spender) {
+        return None;
+    }
+    let key = key.to_string();
+    let pre: token::Allowance = read_pre(&key)?;
+    let post: token::Allowance = read_post(&key)?;
+    if pre.is_expired(get_block_epoch()) || pre.expiry != post.expiry {
+        return None;
+    }
+    pre.amount.checked_sub(&post.amount)
+}
+
+/// Check that the owner's debit of the token is covered by the allowances
+/// used by spenders in this transaction.
+fn allowances_cover_debit(
+    owner: &Address,
+    token: &Address,
+    debit: token::Change,
+    keys_changed: &HashSet<storage::Key>,
+    verifiers: &HashSet<Address>,
+) -> bool {
+    let used: token::Change = keys_changed
+        .iter()
+        .filter_map(|key| match token::is_allowance_key(key) {
+            Some((key_owner, key_token, spender))
+                if key_owner == owner && &key_token == token =>
+            {
+                used_allowance(key, &spender, verifiers)
+            }
+            _ => None,
+        })
+        .map(|amount| amount.change())
+        .sum();
+    used >= debit
+}
+
 fn check_intent_transfers(addr: &Address, tx_data: &[u8]) -> bool {
     match SignedTxData::try_from_slice(tx_data) {
         Ok(tx) => {
-            match IntentTransfers::try_from_slice(
-                &tx.data.as_ref().unwrap()[..],
-            ) {
+            match IntentTransfers::try_from_slice(&tx.data().unwrap()[..]) {
                 Ok(tx_data) => {
                     log_string(format!(
                         "tx_data.exchanges: {:?}, {}",
@@ -148,7 +356,7 @@ fn check_intent_transfers(addr: &Address, tx_data: &[u8]) -> bool {
                             addr,
                             exchange,
                             intent_data,
-                            &tx.data.unwrap(),
+                            &tx.into_data().unwrap(),
                         )
                     } else {
                         log_string(
@@ -171,7 +379,7 @@ fn check_intent(
     intent_transfers: &[u8],
 ) -> bool {
     // verify signature
-    let pk = key::ed25519::get(addr);
+    let pk = key::common::get(addr);
     if let Some(pk) = pk {
         if intent.verify(&pk).is_err() {
             log_string("invalid sig".to_string());
@@ -217,19 +425,29 @@ fn check_intent(
     ));
 
     let token_sell_key = token::balance_key(token_sell, addr).to_string();
-    let mut sell_difference: token::Amount =
-        read_pre(&token_sell_key).unwrap_or_default();
+    let sell_pre: token::Amount = read_pre(&token_sell_key).unwrap_or_default();
     let sell_post: token::Amount =
         read_post(token_sell_key).unwrap_or_default();
 
-    sell_difference.spend(&sell_post);
+    let sell_difference = match sell_pre.checked_sub(&sell_post) {
+        Some(difference) => difference,
+        None => {
+            log_string("the sold token balance has increased".to_string());
+            return false;
+        }
+    };
 
     let token_buy_key = token::balance_key(token_buy, addr).to_string();
     let buy_pre: token::Amount = read_pre(&token_buy_key).unwrap_or_default();
-    let mut buy_difference: token::Amount =
-        read_post(token_buy_key).unwrap_or_default();
+    let buy_post: token::Amount = read_post(token_buy_key).unwrap_or_default();
 
-    buy_difference.spend(&buy_pre);
+    let buy_difference = match buy_post.checked_sub(&buy_pre) {
+        Some(difference) => difference,
+        None => {
+            log_string("the bought token balance has decreased".to_string());
+            return false;
+        }
+    };
 
     let sell_diff: Decimal = sell_difference.change().into(); // -> how many token I sold
     let buy_diff: Decimal = buy_difference.change().into(); // -> how many token I got
@@ -268,7 +486,15 @@ fn check_intent(
 
 #[cfg(test)]
 mod tests {
+    use anoma_tests::tx::{tx_host_env, TestTxEnv};
     use anoma_tests::vp::*;
+    use anoma_vm_env::token::tx as token_tx;
+    use anoma_vm_env::vp_prelude::key::common::SigningKey;
+    use anoma_vm_env::vp_prelude::key::ed25519::testing::{
+        keypair_1, keypair_2,
+    };
+    use anoma_vm_env::vp_prelude::key::ed25519::Keypair;
+    use anoma_vm_env::vp_prelude::storage::Epoch;
 
     use super::*;
 
@@ -288,4 +514,536 @@ mod tests {
 
         assert!(valid);
     }
+
+    /// Initialize a tx environment in which the owner has a balance of 100
+    /// tokens and has given the spender an allowance of 50 tokens. The owner
+    /// has the public key of `keypair_1` and the spender of `keypair_2`.
+    fn init_allowance_env(
+        owner: &Address,
+        spender: &Address,
+        token: &Address,
+        expiry: Option<Epoch>,
+    ) -> TestTxEnv {
+        let mut tx_env = TestTxEnv::default();
+        tx_env.spawn_accounts(&[owner, spender, token]);
+        for (addr, keypair) in &[(owner, keypair_1()), (spender, keypair_2())] {
+            let pk_key = key::common::pk_key(addr);
+            tx_env
+                .storage
+                .write(&pk_key, keypair.public_key().try_to_vec().unwrap())
+                .unwrap();
+        }
+        let balance_key = token::balance_key(token, owner);
+        tx_env
+            .storage
+            .write(&balance_key, token::Amount::from(100).try_to_vec().unwrap())
+            .unwrap();
+        let allowance_key = token::allowance_key(owner, token, spender);
+        let allowance = token::Allowance {
+            amount: token::Amount::from(50),
+            expiry,
+        };
+        tx_env
+            .storage
+            .write(&allowance_key, allowance.try_to_vec().unwrap())
+            .unwrap();
+        tx_env
+    }
+
+    /// Test that a spender can transfer the owner's tokens within its
+    /// allowance without the owner's signature.
+    #[test]
+    fn test_transfer_from_within_allowance() {
+        let vp_owner = address::testing::established_address_1();
+        let spender = address::testing::established_address_2();
+        let token = address::xan();
+        let tx_env = init_allowance_env(&vp_owner, &spender, &token, None);
+
+        let vp_env = init_vp_env_from_tx(vp_owner.clone(), tx_env, |_| {
+            token_tx::transfer_from(
+                &spender,
+                &vp_owner,
+                &spender,
+                &token,
+                token::Amount::from(30),
+            )
+            .unwrap();
+        });
+
+        let tx_data: Vec<u8> = vec![];
+        assert!(validate_tx(
+            tx_data,
+            vp_owner,
+            vp_env.keys_changed,
+            vp_env.verifiers
+        ));
+    }
+
+    /// Test that a debit over the used allowance is rejected.
+    #[test]
+    fn test_transfer_from_over_allowance() {
+        let vp_owner = address::testing::established_address_1();
+        let spender = address::testing::established_address_2();
+        let token = address::xan();
+        let tx_env = init_allowance_env(&vp_owner, &spender, &token, None);
+
+        let vp_env = init_vp_env_from_tx(vp_owner.clone(), tx_env, |_| {
+            token_tx::transfer_from(
+                &spender,
+                &vp_owner,
+                &spender,
+                &token,
+                token::Amount::from(30),
+            )
+            .unwrap();
+            // Transfer more than the used allowance
+            let balance_key = token::balance_key(&token, &vp_owner);
+            tx_host_env::write(
+                &balance_key.to_string(),
+                token::Amount::from(60),
+            );
+        });
+
+        let tx_data: Vec<u8> = vec![];
+        assert!(!validate_tx(
+            tx_data,
+            vp_owner,
+            vp_env.keys_changed,
+            vp_env.verifiers
+        ));
+    }
+
+    /// Test that an expired allowance cannot be used.
+    #[test]
+    fn test_transfer_from_expired_allowance() {
+        let vp_owner = address::testing::established_address_1();
+        let spender = address::testing::established_address_2();
+        let token = address::xan();
+        let mut tx_env =
+            init_allowance_env(&vp_owner, &spender, &token, Some(Epoch(5)));
+        tx_env.storage.current_epoch = Epoch(5);
+
+        let vp_env = init_vp_env_from_tx(vp_owner.clone(), tx_env, |_| {
+            // The tx would fail, so modify the storage directly
+            let balance_key = token::balance_key(&token, &vp_owner);
+            tx_host_env::write(
+                &balance_key.to_string(),
+                token::Amount::from(70),
+            );
+            let allowance_key =
+                token::allowance_key(&vp_owner, &token, &spender);
+            tx_host_env::write(
+                &allowance_key.to_string(),
+                token::Allowance {
+                    amount: token::Amount::from(20),
+                    expiry: Some(Epoch(5)),
+                },
+            );
+            tx_host_env::insert_verifier(spender.clone());
+        });
+
+        let tx_data: Vec<u8> = vec![];
+        assert!(!validate_tx(
+            tx_data,
+            vp_owner,
+            vp_env.keys_changed,
+            vp_env.verifiers
+        ));
+    }
+
+    /// Test that an allowance cannot be given without the owner's signature.
+    #[test]
+    fn test_unsigned_approve() {
+        let vp_owner = address::testing::established_address_1();
+        let spender = address::testing::established_address_2();
+        let token = address::xan();
+        let tx_env = init_allowance_env(&vp_owner, &spender, &token, None);
+
+        let vp_env = init_vp_env_from_tx(vp_owner.clone(), tx_env, |_| {
+            token_tx::approve(
+                &vp_owner,
+                &token,
+                &spender,
+                token::Amount::from(100),
+                None,
+            );
+        });
+
+        let tx_data: Vec<u8> = vec![];
+        assert!(!validate_tx(
+            tx_data,
+            vp_owner,
+            vp_env.keys_changed,
+            vp_env.verifiers
+        ));
+    }
+
+    /// Test that the owner can burn its tokens of a token with a different
+    /// minter with only its own signature.
+    #[test]
+    fn test_burn_signed_by_source() {
+        let vp_owner = address::testing::established_address_1();
+        let minter = address::testing::established_address_2();
+        let token = address::xan();
+        let mut tx_env = init_pk_env(&vp_owner, &keypair_1());
+        tx_env.spawn_accounts(&[&minter, &token]);
+        let info = token::TokenInfo {
+            symbol: "ABC".to_owned(),
+            name: "Some token".to_owned(),
+            decimals: 6,
+            minter: Some(minter.clone()),
+        };
+        tx_env
+            .storage
+            .write(&token::registry_key(&token), info.try_to_vec().unwrap())
+            .unwrap();
+        for key in &[
+            token::balance_key(&token, &vp_owner),
+            token::total_supply_key(&token),
+        ] {
+            tx_env
+                .storage
+                .write(key, token::Amount::from(100).try_to_vec().unwrap())
+                .unwrap();
+        }
+
+        let mut vp_env = init_vp_env_from_tx(vp_owner.clone(), tx_env, |_| {
+            token_tx::burn(&vp_owner, &token, token::Amount::from(40)).unwrap();
+        });
+
+        // The minter's VP, which would require its signature, isn't triggered
+        assert!(!vp_env.verifiers.contains(&minter));
+        let tx_data = vp_env.sign_tx(&keypair_1());
+        assert!(validate_tx(
+            tx_data,
+            vp_owner,
+            vp_env.keys_changed,
+            vp_env.verifiers
+        ));
+    }
+
+    /// Run the tx signed by the given keypair and validate it with the VPs of
+    /// all the addresses that it triggers, i.e. this VP for the accounts and
+    /// the token VP for the token. Returns the triggered addresses if all the
+    /// VPs accept it.
+    fn validate_with_triggered_vps<F>(
+        new_tx_env: impl Fn() -> TestTxEnv,
+        token: &Address,
+        signer: &Keypair,
+        mut apply_tx: F,
+    ) -> Option<HashSet<Address>>
+    where
+        F: FnMut(&Address),
+    {
+        let mut tx_env = new_tx_env();
+        anoma_tests::tx::init_tx_env(&mut tx_env);
+        apply_tx(token);
+        let triggered: HashSet<Address> = tx_env
+            .write_log
+            .verifiers_changed_keys(&tx_env.verifiers)
+            .keys()
+            .cloned()
+            .collect();
+
+        let all_valid = triggered.iter().all(|addr| {
+            let mut vp_env =
+                init_vp_env_from_tx(addr.clone(), new_tx_env(), &mut apply_tx);
+            if addr == token {
+                token::vp(token, &vp_env.keys_changed, &vp_env.verifiers)
+            } else {
+                let tx_data = vp_env.sign_tx(signer);
+                validate_tx(
+                    tx_data,
+                    addr.clone(),
+                    vp_env.keys_changed,
+                    vp_env.verifiers,
+                )
+            }
+        });
+        if all_valid {
+            Some(triggered)
+        } else {
+            None
+        }
+    }
+
+    /// Test that the allowance txs are accepted by all the VPs that they
+    /// trigger.
+    #[test]
+    fn test_allowance_txs_triggered_vps() {
+        let owner = address::testing::established_address_1();
+        let spender = address::testing::established_address_2();
+        let token = address::xan();
+        let new_tx_env = || init_allowance_env(&owner, &spender, &token, None);
+
+        // Approve and revoke only trigger the owner's VP
+        let triggered = validate_with_triggered_vps(
+            new_tx_env,
+            &token,
+            &keypair_1(),
+            |_| {
+                token_tx::approve(
+                    &owner,
+                    &token,
+                    &spender,
+                    token::Amount::from(100),
+                    None,
+                );
+            },
+        );
+        assert_eq!(triggered, Some([owner.clone()].iter().cloned().collect()));
+        let triggered = validate_with_triggered_vps(
+            new_tx_env,
+            &token,
+            &keypair_1(),
+            |_| {
+                token_tx::revoke(&owner, &token, &spender);
+            },
+        );
+        assert_eq!(triggered, Some([owner.clone()].iter().cloned().collect()));
+
+        // A transfer to the spender signed by the spender is accepted by the
+        // owner, the spender and the token
+        let triggered = validate_with_triggered_vps(
+            new_tx_env,
+            &token,
+            &keypair_2(),
+            |_| {
+                token_tx::transfer_from(
+                    &spender,
+                    &owner,
+                    &spender,
+                    &token,
+                    token::Amount::from(30),
+                )
+                .unwrap();
+            },
+        );
+        assert_eq!(
+            triggered,
+            Some([owner, spender, token].iter().cloned().collect())
+        );
+    }
+
+    /// Initialize a tx environment in which the owner has the public key of
+    /// the given keypair.
+    fn init_pk_env(owner: &Address, keypair: &Keypair) -> TestTxEnv {
+        let mut tx_env = TestTxEnv::default();
+        tx_env.spawn_accounts(&[owner]);
+        let pk_key = key::common::pk_key(owner);
+        tx_env
+            .storage
+            .write(&pk_key, keypair.public_key().try_to_vec().unwrap())
+            .unwrap();
+        tx_env
+    }
+
+    /// Run a tx that replaces the owner's public key with the public key of
+    /// `keypair_2`, optionally signed by the given keypair, and validate it.
+    fn validate_update_pk(signer: Option<&Keypair>) -> bool {
+        let vp_owner = address::testing::established_address_1();
+        let tx_env = init_pk_env(&vp_owner, &keypair_1());
+
+        let mut vp_env = init_vp_env_from_tx(vp_owner.clone(), tx_env, |_| {
+            let pk_key = key::common::pk_key(&vp_owner);
+            tx_host_env::write(&pk_key.to_string(), keypair_2().public_key());
+        });
+
+        let tx_data = match signer {
+            Some(keypair) => vp_env.sign_tx(keypair),
+            None => vec![],
+        };
+        validate_tx(tx_data, vp_owner, vp_env.keys_changed, vp_env.verifiers)
+    }
+
+    /// Test that the public key can be rotated with a signature of the current
+    /// key.
+    #[test]
+    fn test_update_pk() {
+        assert!(validate_update_pk(Some(&keypair_1())));
+    }
+
+    /// Test that the public key cannot be rotated without a signature of the
+    /// current key.
+    #[test]
+    fn test_unauthorized_update_pk() {
+        assert!(!validate_update_pk(None));
+        // The new key cannot authorize the rotation
+        assert!(!validate_update_pk(Some(&keypair_2())));
+    }
+
+    /// Test that the public key cannot be deleted, even with a valid
+    /// signature.
+    #[test]
+    fn test_delete_pk() {
+        let vp_owner = address::testing::established_address_1();
+        let keypair = keypair_1();
+        let tx_env = init_pk_env(&vp_owner, &keypair);
+
+        let mut vp_env = init_vp_env_from_tx(vp_owner.clone(), tx_env, |_| {
+            let pk_key = key::common::pk_key(&vp_owner);
+            tx_host_env::delete(&pk_key.to_string());
+        });
+
+        let tx_data = vp_env.sign_tx(&keypair);
+        assert!(!validate_tx(
+            tx_data,
+            vp_owner,
+            vp_env.keys_changed,
+            vp_env.verifiers
+        ));
+    }
+
+    /// Initialize a tx environment in which the owner has the public key of
+    /// `keypair_1` and a recovery configuration with a threshold of 2 of
+    /// the given guardian, whose public key is of `keypair_2`, and another
+    /// guardian and a delay of 5 epochs.
+    fn init_recovery_env(
+        owner: &Address,
+        guardian: &Address,
+        epoch: Epoch,
+        proposal: Option<recovery::RecoveryProposal>,
+    ) -> TestTxEnv {
+        let mut tx_env = init_pk_env(owner, &keypair_1());
+        tx_env.spawn_accounts(&[guardian]);
+        tx_env.storage.current_epoch = epoch;
+        let pk_key = key::common::pk_key(guardian);
+        tx_env
+            .storage
+            .write(&pk_key, keypair_2().public_key().try_to_vec().unwrap())
+            .unwrap();
+        let config = recovery::RecoveryConfig {
+            guardians: vec![guardian.clone(), address::xan()]
+                .into_iter()
+                .collect(),
+            threshold: 2,
+            delay: 5,
+        };
+        tx_env
+            .storage
+            .write(&recovery::config_key(owner), config.try_to_vec().unwrap())
+            .unwrap();
+        if let Some(proposal) = proposal {
+            tx_env
+                .storage
+                .write(
+                    &recovery::proposal_key(owner),
+                    proposal.try_to_vec().unwrap(),
+                )
+                .unwrap();
+        }
+        tx_env
+    }
+
+    /// A recovery proposal of the public key of `keypair_2` approved by the
+    /// given guardians in epoch 10
+    fn recovery_proposal(
+        approvals: Vec<Address>,
+    ) -> recovery::RecoveryProposal {
+        recovery::RecoveryProposal {
+            public_key: keypair_2().public_key(),
+            approvals: approvals.into_iter().collect(),
+            epoch: Epoch(10),
+        }
+    }
+
+    /// Run a tx with the given approver's approval of a new key, optionally
+    /// signed by the given keypair, and validate it.
+    fn validate_approval(approver: Address, signer: Option<&Keypair>) -> bool {
+        let vp_owner = address::testing::established_address_1();
+        let guardian = address::testing::established_address_2();
+        let tx_env = init_recovery_env(&vp_owner, &guardian, Epoch(10), None);
+
+        let mut vp_env = init_vp_env_from_tx(vp_owner.clone(), tx_env, |_| {
+            let proposal_key = recovery::proposal_key(&vp_owner);
+            tx_host_env::write(
+                &proposal_key.to_string(),
+                recovery_proposal(vec![approver.clone()]),
+            );
+        });
+
+        let tx_data = match signer {
+            Some(keypair) => vp_env.sign_tx(keypair),
+            None => vec![],
+        };
+        validate_tx(tx_data, vp_owner, vp_env.keys_changed, vp_env.verifiers)
+    }
+
+    /// Test that a guardian can approve a new key with its own signature.
+    #[test]
+    fn test_recovery_approval() {
+        let guardian = address::testing::established_address_2();
+        assert!(validate_approval(guardian.clone(), Some(&keypair_2())));
+        // The approval must be signed by the guardian
+        assert!(!validate_approval(guardian, None));
+        // Only the guardians can approve
+        assert!(!validate_approval(address::btc(), Some(&keypair_2())));
+    }
+
+    /// Run a tx that replaces the owner's key with the key of a recovery
+    /// proposal with the given approvals in the given epoch and validate it.
+    fn validate_recovery(approvals: Vec<Address>, epoch: Epoch) -> bool {
+        let vp_owner = address::testing::established_address_1();
+        let guardian = address::testing::established_address_2();
+        let proposal = recovery_proposal(approvals);
+        let tx_env =
+            init_recovery_env(&vp_owner, &guardian, epoch, Some(proposal));
+
+        let vp_env = init_vp_env_from_tx(vp_owner.clone(), tx_env, |_| {
+            let pk_key = key::common::pk_key(&vp_owner);
+            tx_host_env::write(&pk_key.to_string(), keypair_2().public_key());
+            let proposal_key = recovery::proposal_key(&vp_owner);
+            tx_host_env::delete(&proposal_key.to_string());
+        });
+
+        // The recovery doesn't have to be signed
+        let tx_data: Vec<u8> = vec![];
+        validate_tx(tx_data, vp_owner, vp_env.keys_changed, vp_env.verifiers)
+    }
+
+    /// Test that the key can be recovered only when the threshold of
+    /// guardians approved it and the delay has passed.
+    #[test]
+    fn test_recovery() {
+        let guardian = address::testing::established_address_2();
+        let approvals = vec![guardian.clone(), address::xan()];
+        assert!(!validate_recovery(approvals.clone(), Epoch(14)));
+        assert!(validate_recovery(approvals, Epoch(15)));
+        assert!(!validate_recovery(vec![guardian], Epoch(15)));
+    }
+
+    /// Test that only the current key can veto a recovery proposal.
+    #[test]
+    fn test_veto_recovery() {
+        for (signer, expected) in
+            vec![(keypair_1(), true), (keypair_2(), false)]
+        {
+            let vp_owner = address::testing::established_address_1();
+            let guardian = address::testing::established_address_2();
+            let proposal = recovery_proposal(vec![guardian.clone()]);
+            let tx_env = init_recovery_env(
+                &vp_owner,
+                &guardian,
+                Epoch(11),
+                Some(proposal),
+            );
+
+            let mut vp_env =
+                init_vp_env_from_tx(vp_owner.clone(), tx_env, |_| {
+                    let proposal_key = recovery::proposal_key(&vp_owner);
+                    tx_host_env::delete(&proposal_key.to_string());
+                });
+
+            let tx_data = vp_env.sign_tx(&signer);
+            assert_eq!(
+                validate_tx(
+                    tx_data,
+                    vp_owner,
+                    vp_env.keys_changed,
+                    vp_env.verifiers
+                ),
+                expected
+            );
+        }
+    }
 }
@@ -0,0 +1,398 @@
+//! A vesting VP.
+//! This VP is a variant of the user VP for accounts with time-locked tokens. In
+//! addition to the signature verification against a public key for sending
+//! tokens (receiving tokens is permissive), it rejects debits of the vested
+//! token that would take the balance below the amount that is still locked by
+//! the account's vesting schedule at the current block epoch and time. The
+//! schedule cannot be modified and the VP cannot be updated until all the
+//! tokens are unlocked.
+
+use anoma_vm_env::vp_prelude::key::common::SignedTxData;
+use anoma_vm_env::vp_prelude::*;
+
+enum KeyType<'a> {
+    Token {
+        token: &'a Address,
+        owner: &'a Address,
+    },
+    Vesting,
+    Vp(&'a Address),
+    Unknown,
+}
+
+impl<'a> From<&'a storage::Key> for KeyType<'a> {
+    fn from(key: &'a storage::Key) -> KeyType<'a> {
+        if let Some((token, owner)) = token::is_any_balance_key(key) {
+            Self::Token { token, owner }
+        } else if vesting::is_vesting_key(key).is_some() {
+            Self::Vesting
+        } else if let Some(address) = key.is_validity_predicate() {
+            Self::Vp(address)
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+#[validity_predicate]
+fn validate_tx(
+    tx_data: Vec<u8>,
+    addr: Address,
+    keys_changed: HashSet<storage::Key>,
+    verifiers: HashSet<Address>,
+) -> bool {
+    log_string(format!(
+        "validate_tx called with vesting addr: {}, key_changed: {:#?}, \
+         verifiers: {:?}",
+        addr, keys_changed, verifiers
+    ));
+
+    let valid_sig = match SignedTxData::try_from_slice(&tx_data[..]) {
+        Ok(SignedTxData::Single { sig, .. }) => {
+            let pk = key::common::get(&addr);
+            match pk {
+                Some(pk) => verify_tx_signature(&pk, &sig),
+                None => false,
+            }
+        }
+        _ => false,
+    };
+
+    log_string(format!("signature valid {}, {}", valid_sig, &addr));
+
+    let schedule = vesting::get_schedule(&addr);
+    let locked = schedule
+        .as_ref()
+        .map(|schedule| {
+            schedule.locked_amount(get_block_epoch(), get_block_time())
+        })
+        .unwrap_or_default();
+
+    log_string(format!(
+        "vesting schedule {:?}, locked {}",
+        schedule, locked
+    ));
+
+    for key in keys_changed.iter() {
+        let is_valid = match KeyType::from(key) {
+            KeyType::Token { token, owner } if owner == &addr => {
+                let key = key.to_string();
+                let pre: token::Amount = read_pre(&key).unwrap_or_default();
+                let post: token::Amount = read_post(&key).unwrap_or_default();
+                let change = post.change() - pre.change();
+                let is_vested_token = matches!(
+                    &schedule,
+                    Some(schedule) if &schedule.token == token
+                );
+                // debit has to signed and it cannot spend the locked tokens,
+                // credit doesn't
+                let is_valid = change >= 0
+                    || (valid_sig && (!is_vested_token || post >= locked));
+                log_string(format!(
+                    "token key: {}, change: {}, valid_sig: {}, locked: {}, \
+                     valid modification: {}",
+                    key, change, valid_sig, locked, is_valid
+                ));
+                is_valid
+            }
+            KeyType::Vesting => {
+                log_string(format!("Vesting: key {} cannot be modified", key));
+                false
+            }
+            KeyType::Vp(owner) if owner == &addr => {
+                // the VP cannot be replaced while there are locked tokens
+                let is_valid = valid_sig && locked == token::Amount::default();
+                log_string(format!(
+                    "VP key: {}, valid_sig: {}, locked: {}, valid \
+                     modification: {}",
+                    key, valid_sig, locked, is_valid
+                ));
+                is_valid
+            }
+            KeyType::Token { .. } | KeyType::Vp(_) | KeyType::Unknown => {
+                log_string(format!(
+                    "Unknown key modified, valid sig {}",
+                    valid_sig
+                ));
+                valid_sig
+            }
+        };
+        if !is_valid {
+            log_string(format!("key {} modification failed vp", key));
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use anoma_tests::tx::{tx_host_env, TestTxEnv};
+    use anoma_tests::vp::*;
+    use anoma_vm_env::token::tx as token_tx;
+    use anoma_vm_env::vp_prelude::key::common::SigningKey;
+    use anoma_vm_env::vp_prelude::key::ed25519::testing::keypair_1;
+    use anoma_vm_env::vp_prelude::key::ed25519::Keypair;
+    use anoma_vm_env::vp_prelude::storage::Epoch;
+    use anoma_vm_env::vp_prelude::time::{
+        DateTimeUtc, Duration, TimeZone, Utc,
+    };
+
+    use super::*;
+
+    const VP_ALWAYS_TRUE_WASM: &str =
+        "../../wasm_for_tests/vp_always_true.wasm";
+
+    /// Initialize a tx environment with a vesting account that has a balance
+    /// of 1000 tokens, which unlock linearly from epoch 10 until epoch 20.
+    fn init_vesting_env(
+        vp_owner: &Address,
+        target: &Address,
+        token: &Address,
+        keypair: &Keypair,
+        epoch: Epoch,
+    ) -> TestTxEnv {
+        let mut tx_env = TestTxEnv::default();
+        tx_env.spawn_accounts(&[vp_owner, target, token]);
+        tx_env.storage.current_epoch = epoch;
+        let pk_key = key::common::pk_key(vp_owner);
+        let pk = keypair.public_key();
+        tx_env
+            .storage
+            .write(&pk_key, pk.try_to_vec().unwrap())
+            .unwrap();
+        let schedule = vesting::VestingSchedule {
+            token: token.clone(),
+            amount: token::Amount::from(1000),
+            start: Epoch(10),
+            cliff: Epoch(10),
+            end: Epoch(20),
+            cliff_time: None,
+        };
+        tx_env
+            .storage
+            .write(
+                &vesting::vesting_key(vp_owner),
+                schedule.try_to_vec().unwrap(),
+            )
+            .unwrap();
+        tx_env
+            .storage
+            .write(
+                &token::balance_key(token, vp_owner),
+                token::Amount::from(1000).try_to_vec().unwrap(),
+            )
+            .unwrap();
+        tx_env
+    }
+
+    /// Run a signed transfer from the vesting account in the given epoch and
+    /// validate it.
+    fn validate_transfer(epoch: Epoch, amount: u64) -> bool {
+        let vp_owner = address::testing::established_address_1();
+        let target = address::testing::established_address_2();
+        let token = address::xan();
+        let keypair = keypair_1();
+        let tx_env =
+            init_vesting_env(&vp_owner, &target, &token, &keypair, epoch);
+
+        let mut vp_env = init_vp_env_from_tx(vp_owner.clone(), tx_env, |_| {
+            token_tx::transfer(
+                &vp_owner,
+                &target,
+                &token,
+                token::Amount::from(amount),
+            )
+            .unwrap();
+        });
+
+        let tx_data = vp_env.sign_tx(&keypair);
+        validate_tx(tx_data, vp_owner, vp_env.keys_changed, vp_env.verifiers)
+    }
+
+    /// Test that no-op transaction (i.e. no storage modifications) is deemed
+    /// valid.
+    #[test]
+    fn test_no_op_transaction() {
+        let mut env = TestVpEnv::default();
+        init_vp_env(&mut env);
+
+        let tx_data: Vec<u8> = vec![];
+        let addr: Address = env.addr;
+        let keys_changed: HashSet<storage::Key> = HashSet::default();
+        let verifiers: HashSet<Address> = HashSet::default();
+
+        let valid = validate_tx(tx_data, addr, keys_changed, verifiers);
+
+        assert!(valid);
+    }
+
+    /// Test that the locked tokens cannot be transferred.
+    #[test]
+    fn test_transfer_locked_tokens() {
+        // Nothing is unlocked before the start
+        assert!(!validate_transfer(Epoch(5), 1));
+        // A half of the tokens is unlocked halfway through
+        assert!(validate_transfer(Epoch(15), 500));
+        assert!(!validate_transfer(Epoch(15), 501));
+        // Everything is unlocked at the end
+        assert!(validate_transfer(Epoch(20), 1000));
+    }
+
+    /// Test that the tokens are locked until the schedule's cliff time, even
+    /// after its cliff epoch.
+    #[test]
+    fn test_transfer_before_cliff_time() {
+        let cliff_time: DateTimeUtc =
+            Utc.ymd(2022, 1, 1).and_hms(0, 0, 0).into();
+        for (block_time, expected) in vec![
+            (None, false),
+            (Some(cliff_time - Duration::seconds(1)), false),
+            (Some(cliff_time), true),
+        ] {
+            let vp_owner = address::testing::established_address_1();
+            let target = address::testing::established_address_2();
+            let token = address::xan();
+            let keypair = keypair_1();
+            let mut tx_env = init_vesting_env(
+                &vp_owner,
+                &target,
+                &token,
+                &keypair,
+                Epoch(15),
+            );
+            let schedule = vesting::VestingSchedule {
+                token: token.clone(),
+                amount: token::Amount::from(1000),
+                start: Epoch(10),
+                cliff: Epoch(10),
+                end: Epoch(20),
+                cliff_time: Some(cliff_time),
+            };
+            tx_env
+                .storage
+                .write(
+                    &vesting::vesting_key(&vp_owner),
+                    schedule.try_to_vec().unwrap(),
+                )
+                .unwrap();
+            if let Some(block_time) = block_time {
+                tx_env.set_block_time(block_time);
+            }
+
+            let mut vp_env =
+                init_vp_env_from_tx(vp_owner.clone(), tx_env, |_| {
+                    token_tx::transfer(
+                        &vp_owner,
+                        &target,
+                        &token,
+                        token::Amount::from(500),
+                    )
+                    .unwrap();
+                });
+
+            let tx_data = vp_env.sign_tx(&keypair);
+            assert_eq!(
+                validate_tx(
+                    tx_data,
+                    vp_owner,
+                    vp_env.keys_changed,
+                    vp_env.verifiers
+                ),
+                expected
+            );
+        }
+    }
+
+    /// Test that a transfer of the unlocked tokens has to be signed.
+    #[test]
+    fn test_unsigned_transfer() {
+        let vp_owner = address::testing::established_address_1();
+        let target = address::testing::established_address_2();
+        let token = address::xan();
+        let tx_env = init_vesting_env(
+            &vp_owner,
+            &target,
+            &token,
+            &keypair_1(),
+            Epoch(20),
+        );
+
+        let vp_env = init_vp_env_from_tx(vp_owner.clone(), tx_env, |_| {
+            token_tx::transfer(
+                &vp_owner,
+                &target,
+                &token,
+                token::Amount::from(10),
+            )
+            .unwrap();
+        });
+
+        let tx_data: Vec<u8> = vec![];
+        assert!(!validate_tx(
+            tx_data,
+            vp_owner,
+            vp_env.keys_changed,
+            vp_env.verifiers
+        ));
+    }
+
+    /// Test that the vesting schedule cannot be modified, even with a valid
+    /// signature.
+    #[test]
+    fn test_modify_schedule() {
+        let vp_owner = address::testing::established_address_1();
+        let target = address::testing::established_address_2();
+        let token = address::xan();
+        let keypair = keypair_1();
+        let tx_env =
+            init_vesting_env(&vp_owner, &target, &token, &keypair, Epoch(5));
+
+        let mut vp_env = init_vp_env_from_tx(vp_owner.clone(), tx_env, |_| {
+            let key = vesting::vesting_key(&vp_owner);
+            tx_host_env::delete(&key.to_string());
+        });
+
+        let tx_data = vp_env.sign_tx(&keypair);
+        assert!(!validate_tx(
+            tx_data,
+            vp_owner,
+            vp_env.keys_changed,
+            vp_env.verifiers
+        ));
+    }
+
+    /// Test that the VP can only be updated once all the tokens are unlocked.
+    #[test]
+    fn test_update_vp() {
+        let vp_code =
+            std::fs::read(VP_ALWAYS_TRUE_WASM).expect("cannot load wasm");
+        for (epoch, expected) in vec![(Epoch(15), false), (Epoch(20), true)] {
+            let vp_owner = address::testing::established_address_1();
+            let target = address::testing::established_address_2();
+            let token = address::xan();
+            let keypair = keypair_1();
+            let tx_env =
+                init_vesting_env(&vp_owner, &target, &token, &keypair, epoch);
+
+            let mut vp_env =
+                init_vp_env_from_tx(vp_owner.clone(), tx_env, |_| {
+                    tx_host_env::update_validity_predicate(
+                        vp_owner.clone(),
+                        &vp_code,
+                    );
+                });
+
+            let tx_data = vp_env.sign_tx(&keypair);
+            assert_eq!(
+                validate_tx(
+                    tx_data,
+                    vp_owner,
+                    vp_env.keys_changed,
+                    vp_env.verifiers
+                ),
+                expected
+            );
+        }
+    }
+}